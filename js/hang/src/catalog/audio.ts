@@ -42,8 +42,17 @@ export const AudioConfigSchema = z.object({
 	// ex: AAC often uses 1024 samples per frame, so at 44100Hz, this would be 1024/44100 = 23ms
 	jitter: z.optional(u53Schema),
 
+	// The total duration of the track in milliseconds, for file-backed (VOD) broadcasts.
+	// Absent for live content, where the end isn't known.
+	duration: z.optional(u53Schema),
+
 	// The companion timeline track indexing this rendition's groups, if the publisher offers one.
 	timeline: z.optional(TimelineSchema),
+
+	// The alternate group of this rendition, as in MSF's altGroup.
+	// Renditions sharing a group are the same content at different qualities.
+	// If absent, every rendition in the section is an alternative.
+	altGroup: z.optional(u53Schema),
 });
 
 /** Schema for the catalog audio section: a map of track name to rendition config. */
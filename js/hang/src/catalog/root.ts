@@ -1,8 +1,12 @@
 import * as z from "zod/mini";
 
 import { AudioSchema } from "./audio";
+import { u53Schema } from "./integers";
 import { VideoSchema } from "./video";
 
+/** The catalog schema version written by this package, matching `hang::Catalog::VERSION`. */
+export const VERSION = 1;
+
 /**
  * The root catalog: the base media sections every hang broadcast carries.
  *
@@ -12,6 +16,8 @@ import { VideoSchema } from "./video";
  * built via `z.extend(RootSchema, { ... })`.
  */
 export const RootSchema = z.looseObject({
+	/** The catalog schema version; absent on catalogs written before it existed. See {@link VERSION}. */
+	version: z.optional(u53Schema),
 	video: z.optional(VideoSchema),
 	audio: z.optional(AudioSchema),
 });
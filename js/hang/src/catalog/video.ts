@@ -8,6 +8,33 @@ const TrackSchema = z.object({
 	name: z.string(),
 });
 
+/** Schema for the color description of a video rendition, as ITU-T H.273 (CICP) code points. */
+export const ColorSpaceSchema = z.object({
+	primaries: z.number(),
+	transfer: z.number(),
+	matrix: z.number(),
+	fullRange: z.boolean(),
+});
+
+// A CIE 1931 [x, y] chromaticity coordinate.
+const ChromaticitySchema = z.tuple([z.number(), z.number()]);
+
+/** Schema for the mastering display color volume (SMPTE ST 2086). Luminance is in cd/m². */
+export const MasteringDisplaySchema = z.object({
+	red: ChromaticitySchema,
+	green: ChromaticitySchema,
+	blue: ChromaticitySchema,
+	whitePoint: ChromaticitySchema,
+	maxLuminance: z.number(),
+	minLuminance: z.number(),
+});
+
+/** Schema for the content light level (CTA-861.3), in cd/m². */
+export const ContentLightLevelSchema = z.object({
+	maxCll: z.number(),
+	maxFall: z.number(),
+});
+
 /** Schema for a single video rendition's decoder config. Mirrors WebCodecs VideoDecoderConfig. */
 export const VideoConfigSchema = z.object({
 	// See: https://w3c.github.io/webcodecs/codec_registry.html
@@ -32,6 +59,16 @@ export const VideoConfigSchema = z.object({
 	displayAspectWidth: z.optional(u53Schema),
 	displayAspectHeight: z.optional(u53Schema),
 
+	// The color description as ITU-T H.273 (CICP) code points.
+	// If not provided, the decoder assumes whatever the bitstream signals (usually BT.709 SDR).
+	colorSpace: z.optional(ColorSpaceSchema),
+
+	// The mastering display color volume (SMPTE ST 2086), for HDR content.
+	masteringDisplay: z.optional(MasteringDisplaySchema),
+
+	// The content light level (CTA-861.3), for HDR content.
+	contentLightLevel: z.optional(ContentLightLevelSchema),
+
 	// The frame rate of the video in frames per second
 	framerate: z.optional(z.number()),
 
@@ -53,8 +90,17 @@ export const VideoConfigSchema = z.object({
 	// - If frames are buffered into 2s segments, this would be 2s.
 	jitter: z.optional(u53Schema),
 
+	// The total duration of the track in milliseconds, for file-backed (VOD) broadcasts.
+	// Absent for live content, where the end isn't known.
+	duration: z.optional(u53Schema),
+
 	// The companion timeline track indexing this rendition's groups, if the publisher offers one.
 	timeline: z.optional(TimelineSchema),
+
+	// The alternate group of this rendition, as in MSF's altGroup.
+	// Renditions sharing a group are the same content at different qualities.
+	// If absent, every rendition in the section is an alternative.
+	altGroup: z.optional(u53Schema),
 });
 
 /**
@@ -111,3 +157,9 @@ export const VideoSchema = z.union([
 export type Video = z.infer<typeof VideoSchema>;
 /** Decoder config for a single video rendition. */
 export type VideoConfig = z.infer<typeof VideoConfigSchema>;
+/** The color description of a video rendition. */
+export type ColorSpace = z.infer<typeof ColorSpaceSchema>;
+/** The mastering display color volume of a video rendition. */
+export type MasteringDisplay = z.infer<typeof MasteringDisplaySchema>;
+/** The content light level of a video rendition. */
+export type ContentLightLevel = z.infer<typeof ContentLightLevelSchema>;
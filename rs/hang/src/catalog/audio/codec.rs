@@ -5,6 +5,9 @@ use derive_more::{Display, From};
 use std::str::FromStr;
 
 /// Supported audio codec mimetypes.
+///
+/// [`Display`](std::fmt::Display) writes the WebCodecs codec string (e.g. `mp4a.40.2`),
+/// assembled from the parsed fields, and [`FromStr`] parses it back.
 #[derive(Debug, Clone, PartialEq, Eq, Display, From)]
 #[non_exhaustive]
 pub enum AudioCodec {
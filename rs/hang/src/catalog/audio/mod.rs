@@ -97,10 +97,22 @@ pub struct AudioConfig {
 	#[serde(default)]
 	pub jitter: Option<moq_net::Time>,
 
+	/// The total duration of the track, for file-backed (VOD) broadcasts.
+	/// Absent for live content, where the end isn't known.
+	#[serde(default)]
+	pub duration: Option<moq_net::Time>,
+
 	/// The companion timeline track indexing this rendition's groups, if the publisher
 	/// offers one. See [`Timeline`](crate::catalog::Timeline).
 	#[serde(default)]
 	pub timeline: Option<crate::catalog::Timeline>,
+
+	/// The alternate group of this rendition, as in MSF's `altGroup`.
+	///
+	/// Renditions sharing a group are the same content at different qualities, so a player
+	/// switches between them. If absent, every rendition in the section is an alternative.
+	#[serde(default)]
+	pub alt_group: Option<u32>,
 }
 
 impl AudioConfig {
@@ -119,7 +131,17 @@ impl AudioConfig {
 			description: None,
 			container: Container::default(),
 			jitter: None,
+			duration: None,
 			timeline: None,
+			alt_group: None,
 		}
 	}
+
+	/// A generous upper bound on a single frame of this track in bytes, or `None` without a bitrate.
+	///
+	/// Meant to catch wildly implausible frame sizes from a misbehaving publisher, ex. via
+	/// [`moq_net::TrackProducer::set_max_frame_size`], not to police the encoder.
+	pub fn max_frame_size(&self) -> Option<u64> {
+		Some(crate::catalog::container::max_frame_size(self.bitrate?, self.jitter))
+	}
 }
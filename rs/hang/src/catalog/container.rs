@@ -44,6 +44,23 @@ pub enum Container {
 	Loc,
 }
 
+/// How many times the average rate a single frame may carry before it's implausible.
+///
+/// Keyframes run well above the average and VBR encoders burst, so this is deliberately loose.
+const FRAME_SIZE_SLACK: u64 = 16;
+
+/// A generous upper bound on one moq frame, given the track bitrate and jitter.
+///
+/// A frame can hold a whole fragment (a CMAF moof+mdat spans up to `jitter`), so the bound
+/// is [`FRAME_SIZE_SLACK`] times the data of at least one second, or of `jitter` if longer.
+pub(crate) fn max_frame_size(bitrate: u64, jitter: Option<moq_net::Time>) -> u64 {
+	let micros = jitter.map(|j| j.as_micros()).unwrap_or_default().max(1_000_000);
+	let bytes = bitrate as u128 * micros / 8 / 1_000_000;
+	u64::try_from(bytes)
+		.unwrap_or(u64::MAX)
+		.saturating_mul(FRAME_SIZE_SLACK)
+}
+
 #[cfg(test)]
 mod tests {
 	use super::*;
@@ -56,4 +73,20 @@ mod tests {
 		let json = serde_json::to_string(&parsed).unwrap();
 		assert_eq!(json, r#"{"kind":"loc"}"#);
 	}
+
+	#[test]
+	fn max_frame_size_scales_with_bitrate_and_jitter() {
+		// 2 Mbps: 250 KB per second, with 16x slack.
+		assert_eq!(max_frame_size(2_000_000, None), 4_000_000);
+		// Sub-second jitter still allows a full second of data.
+		assert_eq!(
+			max_frame_size(2_000_000, Some(moq_net::Time::from_millis_unchecked(33))),
+			4_000_000
+		);
+		// A 4s CMAF fragment can carry 4s of data.
+		assert_eq!(
+			max_frame_size(2_000_000, Some(moq_net::Time::from_secs_unchecked(4))),
+			16_000_000
+		);
+	}
 }
@@ -14,6 +14,14 @@ use serde::{Deserialize, Serialize};
 #[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Default)]
 #[serde(default, rename_all = "camelCase")]
 pub struct Catalog {
+	/// The schema version this catalog was written with.
+	///
+	/// Catalogs that predate the field decode as `0` and are upgraded by [`Catalog::migrate`].
+	/// A value above [`Catalog::VERSION`] comes from a newer producer; fields this crate
+	/// doesn't know are ignored rather than treated as errors.
+	#[serde(default)]
+	pub version: u32,
+
 	/// Video track information with multiple renditions.
 	///
 	/// Contains a map of video track renditions that the viewer can choose from
@@ -39,20 +47,51 @@ impl Catalog {
 	/// both tracks; a consumer reads whichever it prefers.
 	pub const COMPRESSED_NAME: &str = "catalog.json.z";
 
+	/// The track name for the audio-only catalog: just the `audio` section, the `video` one left empty.
+	///
+	/// Republished only when the audio section changes, so a client that only plays audio can
+	/// join without fetching (or waking for) every video rendition of a large broadcast.
+	pub const AUDIO_NAME: &str = "catalog.audio.json";
+
+	/// The track name for the video-only catalog, the counterpart of [`AUDIO_NAME`](Self::AUDIO_NAME).
+	pub const VIDEO_NAME: &str = "catalog.video.json";
+
+	/// The catalog schema version written by this crate.
+	///
+	/// - `0`: unversioned catalogs, written before the `version` field existed.
+	/// - `1`: adds the `version` field; otherwise identical to `0`.
+	pub const VERSION: u32 = 1;
+
+	/// Upgrade an older catalog in place to [`Catalog::VERSION`].
+	///
+	/// Newer catalogs are left untouched. The parse helpers call this automatically.
+	pub fn migrate(&mut self) {
+		// 0 -> 1: only the version marker was added, so there is nothing to rewrite.
+		if self.version < Self::VERSION {
+			self.version = Self::VERSION;
+		}
+	}
+
 	/// Parse a catalog from a string.
 	#[allow(clippy::should_implement_trait)]
 	pub fn from_str(s: &str) -> Result<Self> {
-		Ok(serde_json::from_str(s)?)
+		let mut catalog: Self = serde_json::from_str(s)?;
+		catalog.migrate();
+		Ok(catalog)
 	}
 
 	/// Parse a catalog from a slice of bytes.
 	pub fn from_slice(v: &[u8]) -> Result<Self> {
-		Ok(serde_json::from_slice(v)?)
+		let mut catalog: Self = serde_json::from_slice(v)?;
+		catalog.migrate();
+		Ok(catalog)
 	}
 
 	/// Parse a catalog from a reader.
 	pub fn from_reader(reader: impl std::io::Read) -> Result<Self> {
-		Ok(serde_json::from_reader(reader)?)
+		let mut catalog: Self = serde_json::from_reader(reader)?;
+		catalog.migrate();
+		Ok(catalog)
 	}
 
 	/// Serialize the catalog to a string.
@@ -83,6 +122,16 @@ impl Catalog {
 	pub fn compressed_track() -> moq_net::Track {
 		moq_net::Track::new(Catalog::COMPRESSED_NAME).with_priority(100)
 	}
+
+	/// The track carrying the audio-only catalog ([`AUDIO_NAME`](Self::AUDIO_NAME)).
+	pub fn audio_track() -> moq_net::Track {
+		moq_net::Track::new(Catalog::AUDIO_NAME).with_priority(100)
+	}
+
+	/// The track carrying the video-only catalog ([`VIDEO_NAME`](Self::VIDEO_NAME)).
+	pub fn video_track() -> moq_net::Track {
+		moq_net::Track::new(Catalog::VIDEO_NAME).with_priority(100)
+	}
 }
 
 #[cfg(test)]
@@ -96,6 +145,7 @@ mod test {
 	#[test]
 	fn simple() {
 		let mut encoded = r#"{
+			"version": 1,
 			"video": {
 				"renditions": {
 					"video": {
@@ -147,6 +197,7 @@ mod test {
 		audio_renditions.insert("audio".to_string(), audio_config);
 
 		let decoded = Catalog {
+			version: Catalog::VERSION,
 			video: Video {
 				renditions: video_renditions,
 				display: None,
@@ -189,11 +240,34 @@ mod test {
 		let json = serde_json::to_string(&app).expect("failed to encode");
 
 		// A base consumer ignores the unknown section.
-		let base = Catalog::from_str(&json).expect("failed to decode base");
+		let mut base = Catalog::from_str(&json).expect("failed to decode base");
+		assert_eq!(base.version, Catalog::VERSION);
+		base.version = 0;
 		assert_eq!(base, Catalog::default());
 
 		// The extended consumer round-trips its own section.
 		let decoded: AppCatalog = serde_json::from_str(&json).expect("failed to decode app");
 		assert_eq!(decoded, app);
 	}
+
+	#[test]
+	fn migrate_unversioned() {
+		// A catalog written before the version field decodes as version 0 and is upgraded.
+		let fixture = include_str!("test_data/unversioned.json");
+		let mut legacy: Catalog = serde_json::from_str(fixture).unwrap();
+		assert_eq!(legacy.version, 0);
+
+		let catalog = Catalog::from_str(fixture).unwrap();
+		assert_eq!(catalog.version, Catalog::VERSION);
+		assert_eq!(catalog.video.renditions["video"].coded_width, Some(1280));
+		assert_eq!(catalog.audio.renditions["audio"].sample_rate, 48_000);
+
+		// Only the version changes.
+		legacy.migrate();
+		assert_eq!(legacy, catalog);
+
+		// A newer catalog keeps its version and ignores fields we don't know.
+		let newer = Catalog::from_str(r#"{"version":99,"future":true}"#).unwrap();
+		assert_eq!(newer.version, 99);
+	}
 }
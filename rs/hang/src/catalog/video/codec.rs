@@ -7,6 +7,9 @@ use derive_more::{Display, From};
 use crate::Error;
 
 /// Supported video codec mimetypes.
+///
+/// [`Display`](std::fmt::Display) writes the WebCodecs codec string (e.g. `avc1.640028`),
+/// assembled from the parsed fields, and [`FromStr`] parses it back.
 // TODO implement serde for convience
 #[derive(Debug, Clone, PartialEq, Eq, Display, From)]
 #[non_exhaustive]
@@ -0,0 +1,117 @@
+use serde::{Deserialize, Serialize};
+
+/// The color description of a video track, as ITU-T H.273 (CICP) code points.
+///
+/// Every codec signals these the same way (H.264/H.265 VUI, the AV1 sequence header,
+/// the VP9 `vpcC` and the ISO BMFF `colr` box), so the catalog carries them once,
+/// independent of the codec string.
+///
+/// ex. HDR10 is `primaries: 9` (BT.2020), `transfer: 16` (PQ), `matrix: 9` (BT.2020 NCL).
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct ColorSpace {
+	/// Color primaries (H.273 table 2).
+	pub primaries: u8,
+
+	/// Transfer characteristics (H.273 table 3).
+	pub transfer: u8,
+
+	/// Matrix coefficients (H.273 table 4).
+	pub matrix: u8,
+
+	/// Whether the samples use the full range (true) or the limited "video" range (false).
+	pub full_range: bool,
+}
+
+/// The color volume of the display the content was mastered on (SMPTE ST 2086).
+///
+/// Chromaticities are CIE 1931 `[x, y]` coordinates and luminance is in cd/m².
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct MasteringDisplay {
+	/// The chromaticity of the red primary.
+	pub red: [f64; 2],
+
+	/// The chromaticity of the green primary.
+	pub green: [f64; 2],
+
+	/// The chromaticity of the blue primary.
+	pub blue: [f64; 2],
+
+	/// The chromaticity of the white point.
+	pub white_point: [f64; 2],
+
+	/// The display's peak luminance.
+	pub max_luminance: f64,
+
+	/// The display's black level.
+	pub min_luminance: f64,
+}
+
+/// The content light level of the track (CTA-861.3), in cd/m².
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct ContentLightLevel {
+	/// The brightest pixel in the entire track (MaxCLL).
+	pub max_cll: u16,
+
+	/// The brightest frame average in the entire track (MaxFALL).
+	pub max_fall: u16,
+}
+
+#[cfg(test)]
+mod test {
+	use crate::catalog::{H264, VideoConfig};
+
+	use super::*;
+
+	#[test]
+	fn test_hdr10_round_trip() {
+		let mut config = VideoConfig::new(H264 {
+			profile: 0x64,
+			constraints: 0x00,
+			level: 0x28,
+			inline: false,
+		});
+		config.color_space = Some(ColorSpace {
+			primaries: 9,
+			transfer: 16,
+			matrix: 9,
+			full_range: false,
+		});
+		config.mastering_display = Some(MasteringDisplay {
+			red: [0.708, 0.292],
+			green: [0.17, 0.797],
+			blue: [0.131, 0.046],
+			white_point: [0.3127, 0.329],
+			max_luminance: 1000.0,
+			min_luminance: 0.0001,
+		});
+		config.content_light_level = Some(ContentLightLevel {
+			max_cll: 1000,
+			max_fall: 400,
+		});
+
+		let json = serde_json::to_value(&config).unwrap();
+		assert_eq!(json["colorSpace"]["transfer"], 16);
+		assert_eq!(json["masteringDisplay"]["whitePoint"][0], 0.3127);
+		assert_eq!(json["contentLightLevel"]["maxFall"], 400);
+
+		let decoded: VideoConfig = serde_json::from_value(json).unwrap();
+		assert_eq!(decoded, config);
+	}
+
+	#[test]
+	fn test_sdr_omits_fields() {
+		let config = VideoConfig::new(H264 {
+			profile: 0x42,
+			constraints: 0xc0,
+			level: 0x1e,
+			inline: false,
+		});
+
+		let json = serde_json::to_value(&config).unwrap();
+		assert!(json.get("colorSpace").is_none());
+		assert!(json.get("masteringDisplay").is_none());
+	}
+}
@@ -1,11 +1,13 @@
 mod av1;
 mod codec;
+mod color;
 mod h264;
 mod h265;
 mod vp9;
 
 pub use av1::*;
 pub use codec::*;
+pub use color::*;
 pub use h264::*;
 pub use h265::*;
 pub use vp9::*;
@@ -117,7 +119,20 @@ pub struct VideoConfig {
 	pub display_ratio_width: Option<u32>,
 	pub display_ratio_height: Option<u32>,
 
-	// TODO color space
+	/// The color description of the media.
+	///
+	/// If not provided, the decoder assumes whatever the bitstream signals (usually BT.709 SDR).
+	#[serde(default)]
+	pub color_space: Option<ColorSpace>,
+
+	/// The mastering display color volume, for HDR content.
+	#[serde(default)]
+	pub mastering_display: Option<MasteringDisplay>,
+
+	/// The content light level, for HDR content.
+	#[serde(default)]
+	pub content_light_level: Option<ContentLightLevel>,
+
 	/// The maximum bitrate of the video track, if known.
 	#[serde(default)]
 	pub bitrate: Option<u64>,
@@ -148,10 +163,22 @@ pub struct VideoConfig {
 	#[serde(default)]
 	pub jitter: Option<moq_net::Time>,
 
+	/// The total duration of the track, for file-backed (VOD) broadcasts.
+	/// Absent for live content, where the end isn't known.
+	#[serde(default)]
+	pub duration: Option<moq_net::Time>,
+
 	/// The companion timeline track indexing this rendition's groups, if the publisher
 	/// offers one. See [`Timeline`](crate::catalog::Timeline).
 	#[serde(default)]
 	pub timeline: Option<crate::catalog::Timeline>,
+
+	/// The alternate group of this rendition, as in MSF's `altGroup`.
+	///
+	/// Renditions sharing a group are the same content at different qualities, so a player
+	/// switches between them. If absent, every rendition in the section is an alternative.
+	#[serde(default)]
+	pub alt_group: Option<u32>,
 }
 
 impl VideoConfig {
@@ -169,12 +196,25 @@ impl VideoConfig {
 			coded_height: None,
 			display_ratio_width: None,
 			display_ratio_height: None,
+			color_space: None,
+			mastering_display: None,
+			content_light_level: None,
 			bitrate: None,
 			framerate: None,
 			optimize_for_latency: None,
 			container: Container::default(),
 			jitter: None,
+			duration: None,
 			timeline: None,
+			alt_group: None,
 		}
 	}
+
+	/// A generous upper bound on a single frame of this track in bytes, or `None` without a bitrate.
+	///
+	/// Meant to catch wildly implausible frame sizes from a misbehaving publisher, ex. via
+	/// [`moq_net::TrackProducer::set_max_frame_size`], not to police the encoder.
+	pub fn max_frame_size(&self) -> Option<u64> {
+		Some(crate::catalog::container::max_frame_size(self.bitrate?, self.jitter))
+	}
 }
@@ -13,7 +13,7 @@
 //!   `rtmp`, `srt`, `rtc`). Exactly one per invocation, so "which endpoint" is
 //!   unambiguous and there's no silently-ignored flag.
 
-use std::time::Duration;
+use std::{path::PathBuf, time::Duration};
 
 use clap::{ArgGroup, Args, Parser, Subcommand};
 
@@ -87,13 +87,13 @@ pub struct Import {
 #[derive(Subcommand, Clone)]
 pub enum ImportSource {
 	/// Raw H.264 Annex-B from stdin.
-	Avc3,
-	/// Fragmented MP4 / CMAF from stdin.
-	Fmp4,
+	Avc3(Stdin),
+	/// Fragmented MP4 / CMAF from stdin, or from `--input` files.
+	Fmp4(Fmp4Import),
 	/// MPEG-TS from stdin.
-	Ts,
+	Ts(Stdin),
 	/// FLV / RTMP container from stdin.
-	Flv,
+	Flv(Stdin),
 	/// Pull a remote HLS / LL-HLS playlist (http/https URL or local file) into MoQ.
 	Hls(crate::hls::ImportArgs),
 	/// RTMP: pull a remote play (`--connect`) or accept incoming publishes (`--listen`).
@@ -105,18 +105,40 @@ pub enum ImportSource {
 }
 
 impl ImportSource {
-	/// The stdin container format, when this source is one of the container formats.
-	pub fn stdin_format(&self) -> Option<PublishFormat> {
+	/// The stdin container format plus its options, when this source is one of the
+	/// container formats.
+	pub fn stdin(&self) -> Option<(PublishFormat, &Stdin)> {
 		Some(match self {
-			Self::Avc3 => PublishFormat::Avc3,
-			Self::Fmp4 => PublishFormat::Fmp4,
-			Self::Ts => PublishFormat::Ts,
-			Self::Flv => PublishFormat::Flv,
+			Self::Avc3(args) => (PublishFormat::Avc3, args),
+			Self::Fmp4(args) => (PublishFormat::Fmp4, &args.stdin),
+			Self::Ts(args) => (PublishFormat::Ts, args),
+			Self::Flv(args) => (PublishFormat::Flv, args),
 			_ => return None,
 		})
 	}
 }
 
+/// Options shared by every stdin container source.
+#[derive(Args, Clone)]
+pub struct Stdin {
+	/// Publish at the media's own pace (1x) rather than as fast as it's read,
+	/// replaying a file as if it were live.
+	#[arg(long)]
+	pub realtime: bool,
+}
+
+/// The fmp4 import: [`Stdin`] plus input files.
+#[derive(Args, Clone)]
+pub struct Fmp4Import {
+	#[command(flatten)]
+	pub stdin: Stdin,
+
+	/// Read these files instead of stdin, merging their tracks into one broadcast, e.g. an
+	/// audio track per language stored separately. Repeat for each file.
+	#[arg(long = "input")]
+	pub inputs: Vec<PathBuf>,
+}
+
 // ------------------------------------------------------------------ export
 
 /// export = MoQ -> one sink.
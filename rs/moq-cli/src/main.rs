@@ -99,9 +99,13 @@ async fn run_import(moq: MoqSide, import: Import, net: Net) -> anyhow::Result<()
 	}
 
 	// Foreign side: the single source.
-	if let Some(format) = import.source.stdin_format() {
+	if let Some((format, stdin)) = import.source.stdin() {
 		warn_if_missing_format(&name);
-		let publish = Publish::new(&format)?;
+		let publish = match &import.source {
+			ImportSource::Fmp4(fmp4) if !fmp4.inputs.is_empty() => Publish::fmp4_files(fmp4.inputs.clone())?,
+			_ => Publish::new(&format)?,
+		}
+		.with_realtime(stdin.realtime);
 		anyhow::ensure!(
 			origin.publish_broadcast(&name, publish.consume()),
 			"failed to publish broadcast"
@@ -145,7 +149,7 @@ async fn run_import(moq: MoqSide, import: Import, net: Net) -> anyhow::Result<()
 					tasks.spawn(rtc::connect_import(origin.clone(), url, name));
 				}
 			}
-			_ => unreachable!("container formats are handled by stdin_format above"),
+			_ => unreachable!("container formats are handled by stdin above"),
 		}
 	}
 
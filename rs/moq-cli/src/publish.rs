@@ -1,3 +1,6 @@
+use std::path::PathBuf;
+
+use anyhow::Context;
 use hang::moq_net;
 use moq_mux::container::{flv, fmp4, ts};
 
@@ -49,9 +52,52 @@ impl PublishDecoder {
 	}
 }
 
+/// Holds the input back so a pre-recorded broadcast is published at 1x.
+///
+/// The clock starts at the first frame written. The importers write each frame as soon as its
+/// bytes are read, so after every read the input waits until the newest frame it wrote is due:
+/// frames go out at their own timestamps, at most one read early, rather than a group at a time.
+struct Realtime {
+	// The wall clock instant of the first frame, and its timestamp.
+	start: Option<(tokio::time::Instant, moq_mux::container::Timestamp)>,
+}
+
+impl Realtime {
+	fn new() -> Self {
+		Self { start: None }
+	}
+
+	/// Sleep until `newest`, the newest frame an input has written, is due.
+	async fn wait(&mut self, newest: Option<moq_mux::container::Timestamp>) {
+		let Some(newest) = newest else {
+			return;
+		};
+		let (start, first) = *self.start.get_or_insert((tokio::time::Instant::now(), newest));
+		if let Ok(ahead) = newest.checked_sub(first) {
+			tokio::time::sleep_until(start + std::time::Duration::from(ahead)).await;
+		}
+	}
+}
+
+/// Where a decoder's bytes are read from.
+enum Input {
+	Stdin,
+	File(PathBuf),
+}
+
+/// One input and the importer it feeds.
+struct Source {
+	input: Input,
+	decoder: PublishDecoder,
+	// The newest timestamp this input has written, to read the one furthest behind first.
+	progress: moq_mux::container::Progress,
+}
+
 pub struct Publish {
-	source: PublishDecoder,
+	// Every input decodes into the same broadcast and catalog.
+	sources: Vec<Source>,
 	broadcast: moq_net::BroadcastProducer,
+	realtime: bool,
 }
 
 impl Publish {
@@ -67,10 +113,15 @@ impl Publish {
 				&mut broadcast,
 				moq_mux::catalog::hang::Catalog::<ts::catalog::Ext>::default(),
 			)?;
-			let ts = ts::Import::new(broadcast.clone(), catalog);
+			let ts = ts::Import::new(broadcast.clone(), catalog.clone());
 			return Ok(Self {
-				source: PublishDecoder::Ts(Box::new(ts)),
+				sources: vec![Source {
+					input: Input::Stdin,
+					decoder: PublishDecoder::Ts(Box::new(ts)),
+					progress: catalog.progress(),
+				}],
 				broadcast,
+				realtime: false,
 			});
 		}
 
@@ -92,7 +143,51 @@ impl Publish {
 			}
 		};
 
-		Ok(Self { source, broadcast })
+		Ok(Self {
+			sources: vec![Source {
+				input: Input::Stdin,
+				decoder: source,
+				progress: catalog.progress(),
+			}],
+			broadcast,
+			realtime: false,
+		})
+	}
+
+	/// Import each fMP4 file into one broadcast instead of reading stdin.
+	///
+	/// The importers share the broadcast and its catalog, so track names stay unique across
+	/// files and the catalog lists every file's renditions. Each read goes to the file whose
+	/// newest frame is the oldest, so [`with_realtime`](Self::with_realtime) paces them on one
+	/// clock and none runs ahead of the others.
+	pub fn fmp4_files(paths: Vec<PathBuf>) -> anyhow::Result<Self> {
+		let mut broadcast = moq_net::Broadcast::new().produce();
+		let catalog = moq_mux::catalog::Producer::new(&mut broadcast)?;
+
+		let sources = paths
+			.into_iter()
+			.map(|path| {
+				let fmp4 = fmp4::Import::new(broadcast.clone(), catalog.clone());
+				Source {
+					input: Input::File(path),
+					progress: fmp4.progress(),
+					decoder: PublishDecoder::Fmp4(Box::new(fmp4)),
+				}
+			})
+			.collect();
+
+		Ok(Self {
+			sources,
+			broadcast,
+			realtime: false,
+		})
+	}
+
+	/// Publish at the media's own pace instead of as fast as stdin is read, to replay a file as
+	/// if it were live. See [`Realtime`].
+	pub fn with_realtime(mut self, realtime: bool) -> Self {
+		self.realtime = realtime;
+		self
 	}
 
 	pub fn consume(&self) -> moq_net::BroadcastConsumer {
@@ -100,20 +195,177 @@ impl Publish {
 	}
 
 	pub async fn run(self) -> anyhow::Result<()> {
-		let mut decoder = self.source;
+		let mut realtime = self.realtime.then(Realtime::new);
+
+		let mut readers = Vec::with_capacity(self.sources.len());
+		for source in self.sources {
+			let reader: Box<dyn tokio::io::AsyncRead + Send + Unpin> = match source.input {
+				Input::Stdin => Box::new(tokio::io::stdin()),
+				Input::File(path) => Box::new(
+					tokio::fs::File::open(&path)
+						.await
+						.with_context(|| format!("failed to open {}", path.display()))?,
+				),
+			};
+			readers.push((reader, source.decoder, source.progress));
+		}
 
-		let mut stdin = tokio::io::stdin();
 		let mut buffer = bytes::BytesMut::new();
 
-		loop {
+		// Read from the input furthest behind, one that has written nothing yet first.
+		while let Some(index) = (0..readers.len()).min_by_key(|&index| readers[index].2.newest()) {
+			let (reader, decoder, progress) = &mut readers[index];
+
 			buffer.clear();
-			let n = tokio::io::AsyncReadExt::read_buf(&mut stdin, &mut buffer).await?;
+			let n = tokio::io::AsyncReadExt::read_buf(reader, &mut buffer).await?;
 			if n == 0 {
-				// EOF: flush the importer's buffered trailing frame and close the tracks.
+				// EOF: flush the importer's buffered trailing frame and close its tracks.
 				decoder.finish()?;
-				return Ok(());
+				readers.remove(index);
+				continue;
 			}
 			decoder.decode_chunk(&buffer)?;
+
+			if let Some(realtime) = &mut realtime {
+				realtime.wait(progress.newest()).await;
+			}
+		}
+
+		Ok(())
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	const BBB: &str = concat!(
+		env!("CARGO_MANIFEST_DIR"),
+		"/../moq-mux/src/container/fmp4/test_data/bbb.mp4"
+	);
+
+	/// Publish `bbb.mp4`, returning how long that took on the paused clock and the newest timestamp.
+	async fn replay(realtime: bool) -> (std::time::Duration, std::time::Duration) {
+		let publish = Publish::fmp4_files(vec![BBB.into()]).unwrap().with_realtime(realtime);
+		let progress = publish.sources[0].progress.clone();
+
+		let start = tokio::time::Instant::now();
+		publish.run().await.unwrap();
+		(start.elapsed(), progress.newest().unwrap().into())
+	}
+
+	#[tokio::test(start_paused = true)]
+	async fn realtime_paces_to_the_media() {
+		// The file starts at zero, so playing it out takes until its newest frame is due,
+		// rounded up to the timer's millisecond.
+		let (elapsed, newest) = replay(true).await;
+		assert!(!newest.is_zero());
+		assert!(elapsed >= newest && elapsed - newest < std::time::Duration::from_millis(1));
+	}
+
+	#[tokio::test(start_paused = true)]
+	async fn reads_as_fast_as_possible_by_default() {
+		let (elapsed, _) = replay(false).await;
+		assert!(elapsed.is_zero());
+	}
+
+	/// `bbb.mp4` played `loops` times, a loop every [`LOOP`], without its video if `video` is false.
+	fn looped(loops: u32, video: bool) -> Vec<u8> {
+		use mp4_atom::{Any, DecodeMaybe, Encode};
+
+		let data = std::fs::read(BBB).unwrap();
+		let mut cursor = std::io::Cursor::new(data.as_slice());
+
+		let mut output = Vec::new();
+		let mut moov = None;
+		let mut moof = None;
+		let mut fragments = Vec::new();
+		while let Some(atom) = Any::decode_maybe(&mut cursor).unwrap_or(None) {
+			match atom {
+				Any::Ftyp(ftyp) => ftyp.encode(&mut output).unwrap(),
+				Any::Moov(atom) => {
+					atom.encode(&mut output).unwrap();
+					moov = Some(atom);
+				}
+				Any::Moof(atom) => moof = Some(atom),
+				Any::Mdat(mdat) => fragments.extend(moof.take().map(|moof| (moof, mdat))),
+				_ => {}
+			}
+		}
+
+		let moov = moov.unwrap();
+		let trak = |id: u32| moov.trak.iter().find(|trak| trak.tkhd.track_id == id).unwrap();
+
+		for index in 0..loops {
+			for (moof, mdat) in &fragments {
+				let mut moof = moof.clone();
+				let trak = trak(moof.traf[0].tfhd.track_id);
+				if !video && trak.mdia.hdlr.handler == b"vide".into() {
+					continue;
+				}
+
+				let shift = LOOP.as_secs_f64() * index as f64 * trak.mdia.mdhd.timescale as f64;
+				let tfdt = moof.traf[0].tfdt.as_mut().unwrap();
+				tfdt.base_media_decode_time += shift as u64;
+
+				moof.encode(&mut output).unwrap();
+				mdat.encode(&mut output).unwrap();
+			}
+		}
+
+		output
+	}
+
+	const LOOP: std::time::Duration = std::time::Duration::from_millis(100);
+
+	#[tokio::test(start_paused = true)]
+	async fn realtime_paces_each_input() {
+		// Audio alone packs far more media into each read than audio and video together, so
+		// reading the files in turn would run it ahead and hold the other back.
+		let dir = std::env::temp_dir().join(format!("moq-cli-publish-test-{}", std::process::id()));
+		std::fs::create_dir_all(&dir).unwrap();
+		let inputs = [(dir.join("av.mp4"), true), (dir.join("audio.mp4"), false)];
+		for (path, video) in &inputs {
+			std::fs::write(path, looped(20, *video)).unwrap();
+		}
+
+		let publish = Publish::fmp4_files(inputs.iter().map(|(path, _)| path.clone()).collect())
+			.unwrap()
+			.with_realtime(true);
+		let progress: Vec<_> = publish.sources.iter().map(|source| source.progress.clone()).collect();
+
+		let start = tokio::time::Instant::now();
+		let mut run = tokio::spawn(publish.run());
+
+		// Sample both inputs as the clock runs: neither may fall behind it by more than a loop.
+		let mut samples = Vec::new();
+		loop {
+			tokio::select! {
+				res = &mut run => {
+					res.unwrap().unwrap();
+					break;
+				}
+				_ = tokio::time::sleep(std::time::Duration::from_millis(1)) => {
+					let newest: Vec<_> = progress.iter().map(|progress| progress.newest()).collect();
+					samples.push((start.elapsed(), newest));
+				}
+			}
+		}
+		std::fs::remove_dir_all(&dir).unwrap();
+
+		let last: Vec<std::time::Duration> = progress
+			.iter()
+			.map(|progress| progress.newest().unwrap().into())
+			.collect();
+		assert!(last.iter().all(|last| *last > LOOP * 19));
+
+		for (elapsed, newest) in samples {
+			for (newest, last) in newest.into_iter().zip(&last) {
+				let newest = newest.map(std::time::Duration::from).unwrap_or_default();
+				if newest < *last {
+					assert!(newest + LOOP > elapsed, "input at {newest:?} fell behind {elapsed:?}");
+				}
+			}
 		}
 	}
 }
@@ -268,6 +268,7 @@ async fn publish_media_aac_populates_description() {
 		profile: 2,
 		sample_rate: 44_100,
 		channel_count: 2,
+		extension: None,
 	};
 	let init = config.encode();
 	let _media = broadcast.publish_media("aac".into(), init.to_vec()).unwrap();
@@ -0,0 +1,219 @@
+//! Catalog change events.
+//!
+//! [`Changes`] wraps any [`Stream`] and diffs each snapshot against the previous one,
+//! yielding a [`Change`] per rendition added, removed or reconfigured. A player can
+//! create and tear down decoders from these events instead of comparing whole catalogs.
+
+use std::collections::{BTreeMap, VecDeque};
+use std::task::{Poll, ready};
+
+use hang::catalog::{AudioConfig, VideoConfig};
+
+use super::Stream;
+
+/// A rendition added, removed or reconfigured between two catalog snapshots.
+#[derive(Debug, Clone, PartialEq)]
+#[non_exhaustive]
+pub enum Change {
+	/// A video rendition appeared.
+	VideoAdded { name: String, config: VideoConfig },
+	/// A video rendition's config changed, e.g. a new resolution or init segment.
+	VideoChanged { name: String, config: VideoConfig },
+	/// A video rendition disappeared.
+	VideoRemoved { name: String },
+	/// An audio rendition appeared.
+	AudioAdded { name: String, config: AudioConfig },
+	/// An audio rendition's config changed.
+	AudioChanged { name: String, config: AudioConfig },
+	/// An audio rendition disappeared.
+	AudioRemoved { name: String },
+}
+
+/// Yields the rendition changes between successive snapshots of a catalog [`Stream`].
+///
+/// Renditions are matched by track name. The first snapshot is diffed against an empty
+/// catalog, so every rendition in it is reported as added. Within a snapshot, removals
+/// come first, then changes, then additions, so a player can free a decoder before
+/// creating its replacement. A snapshot that changes nothing yields nothing.
+///
+/// Build one with [`Stream::changes`](super::Stream::changes) or [`Changes::new`].
+pub struct Changes<S: Stream> {
+	inner: S,
+	video: BTreeMap<String, VideoConfig>,
+	audio: BTreeMap<String, AudioConfig>,
+	pending: VecDeque<Change>,
+}
+
+impl<S: Stream> Changes<S> {
+	/// Wrap `inner`, diffing each of its snapshots against the last.
+	pub fn new(inner: S) -> Self {
+		Self {
+			inner,
+			video: BTreeMap::new(),
+			audio: BTreeMap::new(),
+			pending: VecDeque::new(),
+		}
+	}
+
+	/// Poll for the next change, returning `None` once the catalog ends.
+	pub fn poll_next(&mut self, waiter: &kio::Waiter) -> Poll<crate::Result<Option<Change>>> {
+		loop {
+			if let Some(change) = self.pending.pop_front() {
+				return Poll::Ready(Ok(Some(change)));
+			}
+
+			let Some(catalog) = ready!(self.inner.poll_next(waiter))? else {
+				return Poll::Ready(Ok(None));
+			};
+
+			let video = catalog.video.renditions;
+			let audio = catalog.audio.renditions;
+
+			let mut removed = Vec::new();
+			let mut changed = Vec::new();
+			let mut added = Vec::new();
+
+			for (name, diff) in diff(&self.video, &video) {
+				match diff {
+					Diff::Removed => removed.push(Change::VideoRemoved { name }),
+					Diff::Changed(config) => changed.push(Change::VideoChanged { name, config }),
+					Diff::Added(config) => added.push(Change::VideoAdded { name, config }),
+				}
+			}
+			for (name, diff) in diff(&self.audio, &audio) {
+				match diff {
+					Diff::Removed => removed.push(Change::AudioRemoved { name }),
+					Diff::Changed(config) => changed.push(Change::AudioChanged { name, config }),
+					Diff::Added(config) => added.push(Change::AudioAdded { name, config }),
+				}
+			}
+
+			self.pending.extend(removed);
+			self.pending.extend(changed);
+			self.pending.extend(added);
+
+			self.video = video;
+			self.audio = audio;
+		}
+	}
+
+	/// Wait for the next change, returning `None` once the catalog ends.
+	pub async fn next(&mut self) -> crate::Result<Option<Change>> {
+		kio::wait(|waiter| self.poll_next(waiter)).await
+	}
+}
+
+enum Diff<C> {
+	Added(C),
+	Changed(C),
+	Removed,
+}
+
+// The per-name difference from `old` to `new`, in name order.
+fn diff<C: Clone + PartialEq>(old: &BTreeMap<String, C>, new: &BTreeMap<String, C>) -> Vec<(String, Diff<C>)> {
+	let removed = old
+		.keys()
+		.filter(|name| !new.contains_key(*name))
+		.map(|name| (name.clone(), Diff::Removed));
+
+	let updated = new.iter().filter_map(|(name, config)| match old.get(name) {
+		None => Some((name.clone(), Diff::Added(config.clone()))),
+		Some(previous) if previous != config => Some((name.clone(), Diff::Changed(config.clone()))),
+		Some(_) => None,
+	});
+
+	removed.chain(updated).collect()
+}
+
+#[cfg(test)]
+mod tests {
+	use hang::catalog::{AudioCodec, Container, H264};
+
+	use super::super::hang::Catalog;
+	use super::*;
+
+	/// Yields queued snapshots, then ends.
+	struct Queue(VecDeque<Catalog>);
+
+	impl Stream for Queue {
+		type Ext = ();
+
+		fn poll_next(&mut self, _: &kio::Waiter) -> Poll<crate::Result<Option<Catalog>>> {
+			Poll::Ready(Ok(self.0.pop_front()))
+		}
+	}
+
+	fn h264(level: u8) -> VideoConfig {
+		let mut config = VideoConfig::new(H264 {
+			profile: 0x42,
+			constraints: 0,
+			level,
+			inline: false,
+		});
+		config.container = Container::Legacy;
+		config
+	}
+
+	fn opus() -> AudioConfig {
+		let mut config = AudioConfig::new(AudioCodec::Opus, 48_000, 2);
+		config.container = Container::Legacy;
+		config
+	}
+
+	fn catalog(video: &[(&str, u8)], audio: &[&str]) -> Catalog {
+		let mut catalog = Catalog::default();
+		for (name, level) in video {
+			catalog.video.renditions.insert(name.to_string(), h264(*level));
+		}
+		for name in audio {
+			catalog.audio.renditions.insert(name.to_string(), opus());
+		}
+		catalog
+	}
+
+	fn drain(changes: &mut Changes<Queue>) -> Vec<Change> {
+		let mut out = Vec::new();
+		while let Poll::Ready(Some(change)) = changes.poll_next(&kio::Waiter::noop()).map(|res| res.unwrap()) {
+			out.push(change);
+		}
+		out
+	}
+
+	#[test]
+	fn diffs_successive_snapshots() {
+		let snapshots = VecDeque::from([
+			catalog(&[("video1", 0x1e)], &["audio1"]),
+			// No change.
+			catalog(&[("video1", 0x1e)], &["audio1"]),
+			catalog(&[("video1", 0x1f), ("video2", 0x1e)], &["audio2"]),
+		]);
+		let mut changes = Queue(snapshots).changes();
+
+		assert_eq!(
+			drain(&mut changes),
+			vec![
+				Change::VideoAdded {
+					name: "video1".into(),
+					config: h264(0x1e),
+				},
+				Change::AudioAdded {
+					name: "audio1".into(),
+					config: opus(),
+				},
+				Change::AudioRemoved { name: "audio1".into() },
+				Change::VideoChanged {
+					name: "video1".into(),
+					config: h264(0x1f),
+				},
+				Change::VideoAdded {
+					name: "video2".into(),
+					config: h264(0x1e),
+				},
+				Change::AudioAdded {
+					name: "audio2".into(),
+					config: opus(),
+				},
+			]
+		);
+	}
+}
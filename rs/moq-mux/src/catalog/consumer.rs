@@ -44,6 +44,23 @@ impl<E: CatalogExt> Consumer<E> {
 			}
 		})
 	}
+
+	/// Subscribe to just the audio section, on the [`hang::Catalog::AUDIO_NAME`] track.
+	///
+	/// Snapshots carry no video renditions and no extension sections, and only arrive when the
+	/// audio section changes, so an audio-only client joins a large broadcast faster.
+	pub fn audio_only(broadcast: &moq_net::BroadcastConsumer) -> Result<Self, crate::Error> {
+		let track = broadcast.subscribe_track(&hang::Catalog::audio_track())?;
+		Ok(Self::Hang(super::hang::Consumer::new(track)))
+	}
+
+	/// Subscribe to just the video section, on the [`hang::Catalog::VIDEO_NAME`] track.
+	///
+	/// The counterpart of [`audio_only`](Self::audio_only): snapshots carry no audio renditions.
+	pub fn video_only(broadcast: &moq_net::BroadcastConsumer) -> Result<Self, crate::Error> {
+		let track = broadcast.subscribe_track(&hang::Catalog::video_track())?;
+		Ok(Self::Hang(super::hang::Consumer::new(track)))
+	}
 }
 
 impl<E: CatalogExt> Stream for Consumer<E> {
@@ -59,6 +76,7 @@ impl<E: CatalogExt> Stream for Consumer<E> {
 					Err(err) => return Poll::Ready(Err(err)),
 				};
 				Poll::Ready(Ok(media.map(|m| Catalog::<E> {
+					version: hang::Catalog::VERSION,
 					video: m.video,
 					audio: m.audio,
 					ext: E::default(),
@@ -0,0 +1,256 @@
+//! Catalog-driven track subscription.
+//!
+//! [`Follow`] watches a catalog [`Stream`] and keeps a subscription to one
+//! rendition of a role (video or audio). Each snapshot's renditions are handed
+//! back to the caller, who picks one (e.g. with [`highest_bitrate`]) and passes
+//! it to [`Follow::select`]. When the pick, or the picked rendition's config,
+//! changes, `select` subscribes to the new track, so a player never has to know
+//! track names up front.
+
+use std::collections::BTreeMap;
+use std::task::{Poll, ready};
+
+use hang::catalog::{AudioConfig, VideoConfig};
+
+use super::Stream;
+use super::hang::{Catalog, CatalogExt};
+
+/// A catalog section [`Follow`] can pick renditions from.
+///
+/// Implemented for [`VideoConfig`] and [`AudioConfig`].
+pub trait Section: Clone + PartialEq + Send + 'static {
+	/// The renditions of this role in `catalog`, keyed by track name.
+	fn renditions<E: CatalogExt>(catalog: &Catalog<E>) -> &BTreeMap<String, Self>;
+
+	/// The advertised bitrate in bits per second, if any.
+	fn bitrate(&self) -> Option<u64>;
+}
+
+impl Section for VideoConfig {
+	fn renditions<E: CatalogExt>(catalog: &Catalog<E>) -> &BTreeMap<String, Self> {
+		&catalog.video.renditions
+	}
+
+	fn bitrate(&self) -> Option<u64> {
+		self.bitrate
+	}
+}
+
+impl Section for AudioConfig {
+	fn renditions<E: CatalogExt>(catalog: &Catalog<E>) -> &BTreeMap<String, Self> {
+		&catalog.audio.renditions
+	}
+
+	fn bitrate(&self) -> Option<u64> {
+		self.bitrate
+	}
+}
+
+/// Pick the rendition with the highest advertised bitrate.
+///
+/// Renditions without a bitrate rank lowest; ties go to the first by name.
+pub fn highest_bitrate<C: Section>(renditions: &BTreeMap<String, C>) -> Option<String> {
+	renditions
+		.iter()
+		.rev()
+		.max_by_key(|(_, config)| config.bitrate())
+		.map(|(name, _)| name.clone())
+}
+
+/// A rendition picked through [`Follow::select`], along with its subscription.
+pub struct Followed<C> {
+	/// The rendition's track name.
+	pub name: String,
+
+	/// The rendition's config at the time it was picked.
+	pub config: C,
+
+	/// The subscription to the rendition's track.
+	pub track: moq_net::TrackConsumer,
+}
+
+/// Follows the picked rendition of one role as the catalog changes.
+///
+/// [`next`](Self::next) yields the role's renditions from each catalog snapshot.
+/// Choose one and pass its name to [`select`](Self::select), which subscribes
+/// whenever the pick (or the picked rendition's config) differs from the last one.
+/// The previous subscription is the caller's to drop.
+///
+/// Build one with [`video`](Follow::video) or [`audio`](Follow::audio).
+pub struct Follow<S: Stream, C: Section> {
+	catalog: S,
+	broadcast: moq_net::BroadcastConsumer,
+	renditions: BTreeMap<String, C>,
+	current: Option<(String, C)>,
+}
+
+impl<S: Stream> Follow<S, VideoConfig> {
+	/// Follow the video renditions in each snapshot of `catalog`.
+	pub fn video(broadcast: &moq_net::BroadcastConsumer, catalog: S) -> Self {
+		Self::new(broadcast, catalog)
+	}
+}
+
+impl<S: Stream> Follow<S, AudioConfig> {
+	/// Follow the audio renditions in each snapshot of `catalog`.
+	pub fn audio(broadcast: &moq_net::BroadcastConsumer, catalog: S) -> Self {
+		Self::new(broadcast, catalog)
+	}
+}
+
+impl<S: Stream, C: Section> Follow<S, C> {
+	fn new(broadcast: &moq_net::BroadcastConsumer, catalog: S) -> Self {
+		Self {
+			catalog,
+			broadcast: broadcast.clone(),
+			renditions: BTreeMap::new(),
+			current: None,
+		}
+	}
+
+	/// Poll for the next snapshot's renditions, returning `None` once the catalog ends.
+	pub fn poll_next(&mut self, waiter: &kio::Waiter) -> Poll<crate::Result<Option<BTreeMap<String, C>>>> {
+		let Some(catalog) = ready!(self.catalog.poll_next(waiter))? else {
+			return Poll::Ready(Ok(None));
+		};
+
+		self.renditions = C::renditions(&catalog).clone();
+		Poll::Ready(Ok(Some(self.renditions.clone())))
+	}
+
+	/// Wait for the next snapshot's renditions, returning `None` once the catalog ends.
+	pub async fn next(&mut self) -> crate::Result<Option<BTreeMap<String, C>>> {
+		kio::wait(|waiter| self.poll_next(waiter)).await
+	}
+
+	/// Follow the rendition called `name` in the latest snapshot.
+	///
+	/// Returns `None` when `name` is already followed with the same config, or isn't
+	/// in the latest snapshot.
+	pub fn select(&mut self, name: &str) -> crate::Result<Option<Followed<C>>> {
+		let Some(config) = self.renditions.get(name) else {
+			return Ok(None);
+		};
+
+		if self
+			.current
+			.as_ref()
+			.is_some_and(|current| (current.0.as_str(), &current.1) == (name, config))
+		{
+			return Ok(None);
+		}
+
+		let track = self.broadcast.subscribe_track(&moq_net::Track::new(name))?;
+		self.current = Some((name.to_string(), config.clone()));
+
+		Ok(Some(Followed {
+			name: name.to_string(),
+			config: config.clone(),
+			track,
+		}))
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use std::collections::VecDeque;
+
+	use hang::catalog::{Container, H264};
+
+	use super::*;
+
+	/// Yields queued snapshots, then ends.
+	struct Queue(VecDeque<Catalog>);
+
+	impl Stream for Queue {
+		type Ext = ();
+
+		fn poll_next(&mut self, _: &kio::Waiter) -> Poll<crate::Result<Option<Catalog>>> {
+			Poll::Ready(Ok(self.0.pop_front()))
+		}
+	}
+
+	fn h264(bitrate: Option<u64>) -> VideoConfig {
+		let mut config = VideoConfig::new(H264 {
+			profile: 0x42,
+			constraints: 0,
+			level: 0x1e,
+			inline: false,
+		});
+		config.container = Container::Legacy;
+		config.bitrate = bitrate;
+		config
+	}
+
+	fn catalog(video: &[(&str, Option<u64>)]) -> Catalog {
+		let mut catalog = Catalog::default();
+		for (name, bitrate) in video {
+			catalog.video.renditions.insert(name.to_string(), h264(*bitrate));
+		}
+		catalog
+	}
+
+	/// Take the next snapshot and follow its highest-bitrate rendition.
+	fn next(follow: &mut Follow<Queue, VideoConfig>) -> Option<Option<Followed<VideoConfig>>> {
+		let renditions = match follow.poll_next(&kio::Waiter::noop()) {
+			Poll::Ready(res) => res.unwrap()?,
+			Poll::Pending => panic!("queue never blocks"),
+		};
+		let name = highest_bitrate(&renditions)?;
+		Some(follow.select(&name).unwrap())
+	}
+
+	#[test]
+	fn picks_highest_bitrate() {
+		let renditions = catalog(&[("a", None), ("b", Some(500)), ("c", Some(2_000))])
+			.video
+			.renditions;
+		assert_eq!(highest_bitrate(&renditions).as_deref(), Some("c"));
+
+		let renditions = catalog(&[("b", None), ("a", None)]).video.renditions;
+		assert_eq!(highest_bitrate(&renditions).as_deref(), Some("a"));
+	}
+
+	#[test]
+	fn resubscribes_when_the_pick_changes() {
+		let mut broadcast = moq_net::Broadcast::new().produce();
+		let _lo = broadcast.create_track(moq_net::Track::new("lo")).unwrap();
+		let _hi = broadcast.create_track(moq_net::Track::new("hi")).unwrap();
+		let snapshots = VecDeque::from([
+			catalog(&[("lo", Some(500))]),
+			// An unrelated rendition leaves the pick alone.
+			catalog(&[("lo", Some(500)), ("thumb", None)]),
+			catalog(&[("lo", Some(500)), ("hi", Some(2_000))]),
+		]);
+		let mut follow = Follow::video(&broadcast.consume(), Queue(snapshots));
+
+		let followed = next(&mut follow).unwrap().unwrap();
+		assert_eq!(followed.name, "lo");
+		assert_eq!(followed.track.name, "lo");
+
+		assert!(next(&mut follow).unwrap().is_none());
+
+		let followed = next(&mut follow).unwrap().unwrap();
+		assert_eq!(followed.name, "hi");
+		assert_eq!(followed.config.bitrate, Some(2_000));
+
+		assert!(next(&mut follow).is_none());
+	}
+
+	#[test]
+	fn caller_picks() {
+		let mut broadcast = moq_net::Broadcast::new().produce();
+		let _lo = broadcast.create_track(moq_net::Track::new("lo")).unwrap();
+		let snapshots = VecDeque::from([catalog(&[("lo", Some(500)), ("hi", Some(2_000))])]);
+		let mut follow = Follow::video(&broadcast.consume(), Queue(snapshots));
+
+		let Poll::Ready(Ok(Some(renditions))) = follow.poll_next(&kio::Waiter::noop()) else {
+			panic!("expected a snapshot");
+		};
+		assert_eq!(renditions.keys().collect::<Vec<_>>(), ["hi", "lo"]);
+
+		assert_eq!(follow.select("lo").unwrap().unwrap().name, "lo");
+		// Not in the catalog, so there is nothing to follow.
+		assert!(follow.select("missing").unwrap().is_none());
+	}
+}
@@ -36,7 +36,12 @@ impl<E: CatalogExt> Consumer<E> {
 	/// Poll for the next catalog update.
 	pub fn poll_next(&mut self, waiter: &kio::Waiter) -> Poll<Result<Option<Catalog<E>>>> {
 		let result = ready!(self.inner.poll_next(waiter));
-		Poll::Ready(result.map_err(Into::into))
+		Poll::Ready(result.map_err(Into::into).map(|catalog| {
+			catalog.map(|mut catalog| {
+				catalog.migrate();
+				catalog
+			})
+		}))
 	}
 
 	/// Get the next catalog update.
@@ -47,7 +52,10 @@ impl<E: CatalogExt> Consumer<E> {
 	where
 		Catalog<E>: Unpin,
 	{
-		Ok(self.inner.next().await?)
+		Ok(self.inner.next().await?.map(|mut catalog| {
+			catalog.migrate();
+			catalog
+		}))
 	}
 }
 
@@ -74,7 +82,10 @@ mod test {
 
 	// Build a base catalog distinguished by an audio rendition named `name`, plus its JSON payload.
 	fn catalog_payload(name: &str) -> (Catalog, String) {
-		let mut catalog = Catalog::default();
+		let mut catalog = Catalog {
+			version: hang::Catalog::VERSION,
+			..Default::default()
+		};
 		catalog.audio.renditions.insert(
 			name.to_string(),
 			hang::catalog::AudioConfig::new(hang::catalog::AudioCodec::Opus, 48_000, 2),
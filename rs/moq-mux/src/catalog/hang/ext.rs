@@ -95,6 +95,10 @@ impl Extra {
 #[derive(Serialize, Deserialize, Clone, Default, Debug, PartialEq)]
 #[serde(bound(serialize = "E: Serialize", deserialize = "E: DeserializeOwned"))]
 pub struct Catalog<E: CatalogExt = ()> {
+	/// The schema version, see [`hang::Catalog::version`].
+	#[serde(default)]
+	pub version: u32,
+
 	#[serde(default)]
 	pub video: hang::catalog::Video,
 
@@ -109,10 +113,25 @@ impl<E: CatalogExt> Catalog<E> {
 	/// The base catalog carrying just the media sections, used to derive the MSF track.
 	pub(crate) fn media(&self) -> hang::Catalog {
 		hang::Catalog {
+			version: self.version,
 			video: self.video.clone(),
 			audio: self.audio.clone(),
 		}
 	}
+
+	/// Upgrade an older catalog in place, see [`hang::Catalog::migrate`].
+	pub fn migrate(&mut self) {
+		let mut base = hang::Catalog {
+			version: self.version,
+			video: std::mem::take(&mut self.video),
+			audio: std::mem::take(&mut self.audio),
+		};
+		base.migrate();
+
+		self.version = base.version;
+		self.video = base.video;
+		self.audio = base.audio;
+	}
 }
 
 impl Catalog<Extra> {
@@ -226,4 +245,20 @@ mod test {
 		);
 		assert_eq!(catalog.sections().count(), 1);
 	}
+
+	#[test]
+	fn migrate_unversioned() {
+		// A pre-version catalog, plus an extension section alongside it.
+		let fixture = include_str!("test_data/unversioned.json");
+		let mut json: serde_json::Value = serde_json::from_str(fixture).unwrap();
+		json["scte35"] = serde_json::json!({ "splice_id": 7 });
+
+		let mut catalog: Catalog<Scte35Ext> = serde_json::from_value(json).unwrap();
+		assert_eq!(catalog.version, 0);
+		catalog.migrate();
+
+		// Migrates the media sections exactly like the base catalog, keeping the extension.
+		assert_eq!(catalog.media(), hang::Catalog::from_str(fixture).unwrap());
+		assert_eq!(catalog.scte35, Some(Scte35 { splice_id: 7 }));
+	}
 }
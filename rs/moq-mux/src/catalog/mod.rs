@@ -18,19 +18,25 @@
 //! [`Catalog<E>`](hang::Catalog) snapshots. Wrap it with [`Select`] (driven by a
 //! [`select::Broadcast`](crate::select::Broadcast)) to narrow the set before
 //! handing it to an exporter; both also implement [`Stream`] so they compose
-//! either direction.
+//! either direction. [`Follow`] goes one step further for players: it hands back
+//! each snapshot's renditions and subscribes to the one the caller picks, while [`Changes`]
+//! reports each rendition added, removed or reconfigured between snapshots.
 
 pub mod hang;
 pub mod msf;
 
+mod changes;
 mod consumer;
+mod follow;
 mod format;
 mod producer;
 mod select;
 mod stream;
 mod tracks;
 
+pub use changes::{Change, Changes};
 pub use consumer::Consumer;
+pub use follow::{Follow, Followed, Section, highest_bitrate};
 pub use format::*;
 pub use producer::{Guard, Producer};
 pub use select::Select;
@@ -82,11 +82,11 @@ impl From<moq_net::TrackConsumer> for Consumer {
 /// description, custom roles), or with packaging other than [`moq_msf::Packaging::Loc`],
 /// [`moq_msf::Packaging::Cmaf`], or [`moq_msf::Packaging::Legacy`] are skipped with a warning.
 ///
-/// Both [`moq_msf::Packaging::Loc`] and [`moq_msf::Packaging::Legacy`] map to
-/// [`Container::Legacy`]. [`moq_msf::Packaging::Cmaf`] requires `init_data` to be present
+/// [`moq_msf::Packaging::Loc`] maps to [`Container::Loc`] and [`moq_msf::Packaging::Legacy`]
+/// to [`Container::Legacy`]. [`moq_msf::Packaging::Cmaf`] requires `init_data` to be present
 /// (base64-encoded ftyp+moov); a missing or malformed init segment is an error.
 ///
-/// Fields with no representation in `hang::Catalog` (`is_live`, `render_group`, `alt_group`,
+/// Fields with no representation in `hang::Catalog` (`is_live`, `render_group`,
 /// `max_grp_sap_starting_type`, `max_obj_sap_starting_type`) are dropped.
 pub(crate) fn from_msf(msf: &moq_msf::Catalog) -> Result<hang::Catalog> {
 	let mut catalog = hang::Catalog::default();
@@ -143,8 +143,10 @@ pub(crate) fn from_msf(msf: &moq_msf::Catalog) -> Result<hang::Catalog> {
 /// segment, and silently skipping it would mask a publisher bug.
 fn container_from_msf(track: &moq_msf::Track) -> Result<Option<Container>> {
 	match &track.packaging {
-		// Both LOC and Legacy represent raw payloads without ISO-BMFF boxing.
-		moq_msf::Packaging::Loc | moq_msf::Packaging::Legacy => Ok(Some(Container::Legacy)),
+		// Both carry the raw codec bitstream without ISO-BMFF boxing, but frame the
+		// timestamp differently: LOC as a property block, Legacy as a bare varint.
+		moq_msf::Packaging::Loc => Ok(Some(Container::Loc)),
+		moq_msf::Packaging::Legacy => Ok(Some(Container::Legacy)),
 		moq_msf::Packaging::Cmaf => {
 			let init = decode_init_data(track)?.ok_or_else(|| Error::MissingCmafInit(track.name.clone()))?;
 			Ok(Some(Container::Cmaf {
@@ -215,6 +217,7 @@ fn video_config_from_msf(track: &moq_msf::Track) -> Result<Option<VideoConfig>>
 	config.framerate = track.framerate;
 	config.container = container;
 	config.jitter = track.jitter.and_then(|j| moq_net::Time::try_from(j).ok());
+	config.alt_group = track.alt_group;
 	Ok(Some(config))
 }
 
@@ -254,6 +257,7 @@ fn audio_config_from_msf(track: &moq_msf::Track) -> Result<Option<AudioConfig>>
 	config.description = legacy_description(track)?;
 	config.container = container;
 	config.jitter = track.jitter.and_then(|j| moq_net::Time::try_from(j).ok());
+	config.alt_group = track.alt_group;
 	Ok(Some(config))
 }
 
@@ -425,7 +429,7 @@ mod test {
 	}
 
 	#[test]
-	fn loc_audio_yields_legacy_container() {
+	fn loc_audio_yields_loc_container() {
 		let msf = moq_msf::Catalog {
 			tracks: vec![audio_track("audio0", moq_msf::Packaging::Loc)],
 		};
@@ -433,7 +437,7 @@ mod test {
 		let catalog = from_msf(&msf).expect("LOC audio should convert");
 		let audio = catalog.audio.renditions.get("audio0").expect("audio0 rendition");
 
-		assert_eq!(audio.container, Container::Legacy);
+		assert_eq!(audio.container, Container::Loc);
 		assert_eq!(audio.codec, AudioCodec::Opus);
 		assert_eq!(audio.sample_rate, 48_000);
 		assert_eq!(audio.channel_count, 2);
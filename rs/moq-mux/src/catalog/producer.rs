@@ -17,6 +17,8 @@ use super::hang::{Catalog, CatalogExt, Consumer, Extra};
 /// The JSON catalog is updated when tracks are added/removed but is *not* automatically published.
 /// You'll have to call [`lock`](Self::lock) to update and publish the catalog.
 /// Both the hang (`catalog.json`) and MSF (`catalog`) tracks are published on drop of the guard.
+/// The media sections are also split onto `catalog.audio.json` and `catalog.video.json`, each
+/// republished only when its own section changes, for consumers that need just one of them.
 ///
 /// The hang track is published through [`moq_json`], which currently emits one snapshot per
 /// group (deltas disabled). This routes catalog publishing through the JSON merge-patch helper
@@ -24,6 +26,8 @@ use super::hang::{Catalog, CatalogExt, Consumer, Extra};
 pub struct Producer<E: CatalogExt = ()> {
 	hang: moq_json::snapshot::Producer<Catalog<E>>,
 	hangz: moq_json::snapshot::Producer<Catalog<E>>,
+	hang_audio: moq_json::snapshot::Producer<hang::Catalog>,
+	hang_video: moq_json::snapshot::Producer<hang::Catalog>,
 	msf_track: moq_net::TrackProducer,
 
 	current: Arc<Mutex<Catalog<E>>>,
@@ -33,6 +37,9 @@ pub struct Producer<E: CatalogExt = ()> {
 	/// a caller has none land on one timeline and audio/video stay in sync.
 	clock: crate::Clock,
 
+	/// The newest frame timestamp written to any rendition. See [`progress`](Self::progress).
+	progress: crate::container::Progress,
+
 	/// A clone of the broadcast, retained so per-rendition timeline tracks can be created
 	/// lazily when a rendition is registered (the codec importers hold only their media
 	/// track, not the broadcast).
@@ -41,6 +48,13 @@ pub struct Producer<E: CatalogExt = ()> {
 	/// The per-rendition timeline producers, memoized by media-track name so the catalog
 	/// section and the media track's group recorder share one track. See [`media_producer`](Self::media_producer).
 	timelines: Arc<Mutex<BTreeMap<String, crate::timeline::Producer>>>,
+
+	/// The per-rendition in-memory seek indexes, memoized by media-track name. Kept apart from
+	/// `timelines` so an importer without a timeline track (fMP4 passthrough) can still feed one.
+	indexes: Arc<Mutex<BTreeMap<String, crate::timeline::Index>>>,
+
+	/// The bound of each new [`Index`](crate::timeline::Index), or `None` to keep every group.
+	index_capacity: Option<usize>,
 }
 
 // Manual Clone so a producer is cheaply clonable regardless of whether `E` is.
@@ -49,11 +63,16 @@ impl<E: CatalogExt> Clone for Producer<E> {
 		Self {
 			hang: self.hang.clone(),
 			hangz: self.hangz.clone(),
+			hang_audio: self.hang_audio.clone(),
+			hang_video: self.hang_video.clone(),
 			msf_track: self.msf_track.clone(),
 			current: self.current.clone(),
 			clock: self.clock,
+			progress: self.progress.clone(),
 			broadcast: self.broadcast.clone(),
 			timelines: self.timelines.clone(),
+			indexes: self.indexes.clone(),
+			index_capacity: self.index_capacity,
 		}
 	}
 }
@@ -77,12 +96,16 @@ impl<E: CatalogExt> Producer<E> {
 	) -> Result<Self, moq_net::Error> {
 		let hang_track = broadcast.create_track(moq_net::Track::new(hang::Catalog::DEFAULT_NAME))?;
 		let hangz_track = broadcast.create_track(hang::Catalog::compressed_track())?;
+		let audio_track = broadcast.create_track(hang::Catalog::audio_track())?;
+		let video_track = broadcast.create_track(hang::Catalog::video_track())?;
 		let msf_track = broadcast.create_track(moq_net::Track::new(moq_msf::DEFAULT_NAME))?;
 
 		// Disable deltas for now to stay byte-compatible with consumers that only read snapshots.
 		let mut json_config = moq_json::snapshot::ProducerConfig::default();
 		json_config.delta_ratio = 0;
 		let hang = moq_json::snapshot::Producer::new(hang_track, json_config.clone());
+		let hang_audio = moq_json::snapshot::Producer::new(audio_track, json_config.clone());
+		let hang_video = moq_json::snapshot::Producer::new(video_track, json_config.clone());
 
 		// The `.z` track carries the same catalog, DEFLATE-compressed. Deltas stay off for parity
 		// with the plaintext track; only the per-group compression differs.
@@ -92,14 +115,29 @@ impl<E: CatalogExt> Producer<E> {
 		Ok(Self {
 			hang,
 			hangz,
+			hang_audio,
+			hang_video,
 			msf_track,
 			current: Arc::new(Mutex::new(catalog)),
 			clock: crate::Clock::new(),
+			progress: Default::default(),
 			broadcast: broadcast.clone(),
 			timelines: Arc::new(Mutex::new(BTreeMap::new())),
+			indexes: Arc::new(Mutex::new(BTreeMap::new())),
+			index_capacity: Some(crate::timeline::DEFAULT_INDEX_CAPACITY),
 		})
 	}
 
+	/// Bound each rendition's seek [`index`](Self::index) to `capacity` groups, or keep every group
+	/// with `None`. Defaults to [`DEFAULT_INDEX_CAPACITY`](crate::timeline::DEFAULT_INDEX_CAPACITY),
+	/// a rolling window for live; a file-backed broadcast passes `None` to index its whole timeline.
+	///
+	/// Applies to renditions registered after this call.
+	pub fn with_index_capacity(mut self, capacity: Option<usize>) -> Self {
+		self.index_capacity = capacity;
+		self
+	}
+
 	/// Resolve a timestamp, synthesizing one from the broadcast's shared
 	/// [`Clock`](crate::Clock) when the caller has none.
 	///
@@ -118,6 +156,8 @@ impl<E: CatalogExt> Producer<E> {
 			catalog: self.current.lock().unwrap(),
 			hang: &mut self.hang,
 			hangz: &mut self.hangz,
+			hang_audio: &mut self.hang_audio,
+			hang_video: &mut self.hang_video,
 			msf_track: &mut self.msf_track,
 			updated: false,
 		}
@@ -154,7 +194,17 @@ impl<E: CatalogExt> Producer<E> {
 		container: C,
 	) -> crate::container::Producer<C> {
 		let recorder = self.timeline_recorder(track.name());
-		crate::container::Producer::new(track, container).with_recorder(recorder)
+		crate::container::Producer::new(track, container)
+			.with_recorder(recorder)
+			.with_progress(self.progress.clone())
+	}
+
+	/// How far the broadcast's media has been written: the timestamp of the newest frame on any
+	/// rendition, fed by every [`media_producer`](Self::media_producer) and the fMP4 importer.
+	///
+	/// Lets a caller pace its input against the media, e.g. to replay a file at 1x.
+	pub fn progress(&self) -> crate::container::Progress {
+		self.progress.clone()
 	}
 
 	/// The catalog [`Timeline`](hang::catalog::Timeline) section for media rendition `name`, to
@@ -173,13 +223,28 @@ impl<E: CatalogExt> Producer<E> {
 		self.with_timeline(name, |timeline| timeline.recorder())
 	}
 
+	/// The in-memory seek [`Index`](crate::timeline::Index) of media rendition `name`, mapping a
+	/// presentation time to the keyframe group covering it.
+	///
+	/// Filled as the rendition's groups open, by its [`media_producer`](Self::media_producer) or the
+	/// fMP4 importer. Returns the same shared index on every call, empty until the first group.
+	pub fn index(&self, name: &str) -> crate::timeline::Index {
+		let mut indexes = self.indexes.lock().unwrap();
+		indexes
+			.entry(name.to_string())
+			.or_insert_with(|| crate::timeline::Index::new(self.index_capacity))
+			.clone()
+	}
+
 	/// Run `f` against the timeline producer for rendition `name`, memoized by name (creating its
 	/// track on first use). Panics only if the broadcast can't mint the track (a duplicate name),
 	/// which the `<name>.timeline.z` convention avoids.
 	fn with_timeline<R>(&self, name: &str, f: impl FnOnce(&mut crate::timeline::Producer) -> R) -> R {
 		let mut timelines = self.timelines.lock().unwrap();
 		let timeline = timelines.entry(name.to_string()).or_insert_with(|| {
-			crate::timeline::Producer::new(&mut self.broadcast.clone(), name).expect("failed to create timeline track")
+			crate::timeline::Producer::new(&mut self.broadcast.clone(), name)
+				.expect("failed to create timeline track")
+				.with_index(self.index(name))
 		});
 		f(timeline)
 	}
@@ -193,6 +258,8 @@ impl<E: CatalogExt> Producer<E> {
 	pub fn finish(&mut self) -> crate::Result<()> {
 		self.hang.finish()?;
 		self.hangz.finish()?;
+		self.hang_audio.finish()?;
+		self.hang_video.finish()?;
 		self.msf_track.finish()?;
 		for timeline in self.timelines.lock().unwrap().values_mut() {
 			timeline.finish()?;
@@ -206,11 +273,14 @@ impl<E: CatalogExt> Producer<E> {
 /// Obtained via [`Producer::lock`]. Derefs to the [`Catalog<E>`](super::hang::Catalog), so `video`/`audio`
 /// and (through the catalog's own deref) the extension sections are editable directly.
 ///
-/// On drop, the hang, compressed-hang, and MSF catalog tracks are updated if the catalog was mutated.
+/// On drop, the hang, compressed-hang, and MSF catalog tracks are updated if the catalog was mutated,
+/// and each per-section track if its section was.
 pub struct Guard<'a, E: CatalogExt = ()> {
 	catalog: MutexGuard<'a, Catalog<E>>,
 	hang: &'a mut moq_json::snapshot::Producer<Catalog<E>>,
 	hangz: &'a mut moq_json::snapshot::Producer<Catalog<E>>,
+	hang_audio: &'a mut moq_json::snapshot::Producer<hang::Catalog>,
+	hang_video: &'a mut moq_json::snapshot::Producer<hang::Catalog>,
 	msf_track: &'a mut moq_net::TrackProducer,
 	updated: bool,
 }
@@ -258,14 +328,31 @@ impl<E: CatalogExt> Drop for Guard<'_, E> {
 			return;
 		}
 
+		// Stamp the schema version we write, whatever the initial catalog carried.
+		self.catalog.version = hang::Catalog::VERSION;
+
 		// Publish the hang catalog (one snapshot per group while deltas are disabled), plus its
 		// DEFLATE-compressed `.z` sibling carrying the identical catalog.
 		let catalog: &Catalog<E> = &self.catalog;
 		let _ = self.hang.update(catalog);
 		let _ = self.hangz.update(catalog);
 
+		// Split the media sections onto their own tracks. An unchanged section diffs to nothing,
+		// so an audio-only consumer isn't woken by a video rendition coming or going.
+		let media = self.catalog.media();
+		let _ = self.hang_audio.update(&hang::Catalog {
+			version: media.version,
+			audio: media.audio.clone(),
+			..Default::default()
+		});
+		let _ = self.hang_video.update(&hang::Catalog {
+			version: media.version,
+			video: media.video.clone(),
+			..Default::default()
+		});
+
 		// Publish the MSF catalog, derived from the base media sections.
-		let msf = to_msf(&self.catalog.media());
+		let msf = to_msf(&media);
 		if let Ok(mut group) = self.msf_track.append_group() {
 			let _ = group.write_frame(msf.to_string().expect("invalid MSF catalog"));
 			let _ = group.finish();
@@ -301,6 +388,7 @@ fn to_msf(catalog: &hang::Catalog) -> moq_msf::Catalog {
 	for (name, config) in &catalog.video.renditions {
 		let packaging = match &config.container {
 			hang::catalog::Container::Cmaf { .. } => moq_msf::Packaging::Cmaf,
+			hang::catalog::Container::Loc => moq_msf::Packaging::Loc,
 			_ => moq_msf::Packaging::Legacy,
 		};
 
@@ -323,7 +411,7 @@ fn to_msf(catalog: &hang::Catalog) -> moq_msf::Catalog {
 		track.bitrate = config.bitrate;
 		track.init_data = init_data;
 		track.render_group = Some(1);
-		track.alt_group = if has_multiple_video { Some(1) } else { None };
+		track.alt_group = config.alt_group.or(has_multiple_video.then_some(1));
 		track.max_grp_sap_starting_type = sap_type;
 		track.max_obj_sap_starting_type = sap_type;
 		track.jitter = config.jitter.map(std::time::Duration::from);
@@ -334,6 +422,7 @@ fn to_msf(catalog: &hang::Catalog) -> moq_msf::Catalog {
 	for (name, config) in &catalog.audio.renditions {
 		let packaging = match &config.container {
 			hang::catalog::Container::Cmaf { .. } => moq_msf::Packaging::Cmaf,
+			hang::catalog::Container::Loc => moq_msf::Packaging::Loc,
 			_ => moq_msf::Packaging::Legacy,
 		};
 
@@ -354,7 +443,7 @@ fn to_msf(catalog: &hang::Catalog) -> moq_msf::Catalog {
 		track.bitrate = config.bitrate;
 		track.init_data = init_data;
 		track.render_group = Some(1);
-		track.alt_group = if has_multiple_audio { Some(1) } else { None };
+		track.alt_group = config.alt_group.or(has_multiple_audio.then_some(1));
 		track.max_grp_sap_starting_type = Some(1);
 		track.max_obj_sap_starting_type = Some(1);
 		track.jitter = config.jitter.map(std::time::Duration::from);
@@ -408,6 +497,50 @@ mod test {
 		assert_eq!(got_compressed, expected);
 	}
 
+	#[test]
+	fn publishes_sections_independently() {
+		let mut broadcast = moq_net::Broadcast::new().produce();
+		let mut catalog = Producer::new(&mut broadcast).unwrap();
+
+		let consumer = broadcast.consume();
+		let mut audio = Consumer::<()>::new(consumer.subscribe_track(&hang::Catalog::audio_track()).unwrap());
+		let mut video = Consumer::<()>::new(consumer.subscribe_track(&hang::Catalog::video_track()).unwrap());
+
+		catalog
+			.lock()
+			.audio
+			.renditions
+			.insert("audio0".to_string(), AudioConfig::new(AudioCodec::Opus, 48_000, 2));
+
+		let waiter = kio::Waiter::noop();
+		let Poll::Ready(Ok(Some(got))) = audio.poll_next(&waiter) else {
+			panic!("expected the audio section");
+		};
+		assert!(got.audio.renditions.contains_key("audio0"));
+		assert!(got.video.renditions.is_empty());
+		let Poll::Ready(Ok(Some(got))) = video.poll_next(&waiter) else {
+			panic!("expected the (empty) video section");
+		};
+		assert!(got.video.renditions.is_empty());
+		assert!(got.audio.renditions.is_empty());
+
+		// A new video rendition reaches the video track only.
+		let mut config = VideoConfig::new(H264 {
+			profile: 0x42,
+			constraints: 0,
+			level: 0x1e,
+			inline: false,
+		});
+		config.container = Container::Legacy;
+		catalog.lock().video.renditions.insert("video0".to_string(), config);
+
+		let Poll::Ready(Ok(Some(got))) = video.poll_next(&waiter) else {
+			panic!("expected the video section");
+		};
+		assert!(got.video.renditions.contains_key("video0"));
+		assert!(audio.poll_next(&waiter).is_pending());
+	}
+
 	#[test]
 	fn convert_simple() {
 		let mut video_config = VideoConfig::new(H264 {
@@ -433,6 +566,7 @@ mod test {
 		audio_renditions.insert("audio0".to_string(), audio_config);
 
 		let catalog = hang::Catalog {
+			version: hang::Catalog::VERSION,
 			video: Video {
 				renditions: video_renditions,
 				display: None,
@@ -577,6 +711,7 @@ mod test {
 		audio_renditions.insert("audio0".to_string(), audio_config);
 
 		let catalog = hang::Catalog {
+			version: hang::Catalog::VERSION,
 			video: Video {
 				renditions: video_renditions,
 				display: None,
@@ -669,4 +804,25 @@ mod test {
 		assert_eq!(video.max_grp_sap_starting_type, None);
 		assert_eq!(video.max_obj_sap_starting_type, None);
 	}
+
+	#[test]
+	fn convert_loc() {
+		let mut audio_config = AudioConfig::new(AudioCodec::Opus, 48_000, 2);
+		audio_config.container = Container::Loc;
+
+		let mut audio_renditions = BTreeMap::new();
+		audio_renditions.insert("audio0".to_string(), audio_config);
+
+		let catalog = hang::Catalog {
+			audio: Audio {
+				renditions: audio_renditions,
+			},
+			..Default::default()
+		};
+
+		// LOC frames carry their timestamp in a property block, so advertising them
+		// as Legacy would have an IETF subscriber misparse every frame.
+		let msf = to_msf(&catalog);
+		assert_eq!(msf.tracks[0].packaging, moq_msf::Packaging::Loc);
+	}
 }
@@ -11,8 +11,8 @@
 
 use std::task::Poll;
 
-use super::Select;
 use super::hang::{Catalog, CatalogExt};
+use super::{Changes, Select};
 
 /// A stream of catalog snapshots.
 ///
@@ -44,4 +44,13 @@ pub trait Stream: Send + 'static {
 	{
 		Select::new(self, selection)
 	}
+
+	/// Wrap this stream in [`Changes`], yielding the renditions added, removed or
+	/// reconfigured between snapshots instead of the snapshots themselves.
+	fn changes(self) -> Changes<Self>
+	where
+		Self: Sized,
+	{
+		Changes::new(self)
+	}
 }
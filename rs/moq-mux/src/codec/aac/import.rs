@@ -10,6 +10,10 @@ use crate::container::Frame;
 /// forward each frame without waiting for a group boundary. The codec's packet loss
 /// concealment handles drops. Build it with [`new`](Self::new), passing the track producer
 /// and the [`catalog::Producer`](crate::catalog::Producer) it publishes its rendition into.
+///
+/// Packets are never coalesced: a legacy frame is exactly one raw access unit, with no sizes
+/// a decoder could split a concatenation by. Batching samples per frame is what CMAF
+/// fragments are for, so import fMP4 instead when per-frame overhead matters.
 pub struct Import<E: CatalogExt = ()> {
 	track: crate::container::Producer<crate::catalog::hang::Container>,
 	rendition: crate::catalog::AudioTrack<E>,
@@ -24,7 +28,7 @@ impl<E: CatalogExt> Import<E> {
 	) -> crate::Result<Self> {
 		let mut audio_config = hang::catalog::AudioConfig::new(
 			hang::catalog::AAC {
-				profile: config.profile,
+				profile: config.object_type(),
 			},
 			config.sample_rate,
 			config.channel_count,
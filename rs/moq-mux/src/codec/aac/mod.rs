@@ -33,12 +33,36 @@ pub type Result<T> = std::result::Result<T, Error>;
 
 /// Typed AAC configuration mirroring the relevant fields of an
 /// AudioSpecificConfig.
+///
+/// `sample_rate` and `channel_count` are what the decoder outputs, so with an
+/// [`Extension`] they're the SBR-doubled rate and the PS-upmixed stereo, not the
+/// core AAC stream's.
 pub struct Config {
+	/// The core audioObjectType, e.g. 2 for AAC-LC, also under SBR/PS.
 	pub profile: u8,
 	pub sample_rate: u32,
 	pub channel_count: u32,
+	/// The SBR/PS extension (HE-AAC / HE-AACv2), if signaled.
+	pub extension: Option<Extension>,
+}
+
+/// An SBR or PS extension layered on the core AAC stream.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Extension {
+	/// Spectral band replication: HE-AAC (`mp4a.40.5`).
+	Sbr,
+	/// SBR plus parametric stereo: HE-AACv2 (`mp4a.40.29`), a mono core decoded to stereo.
+	SbrPs,
 }
 
+// audioObjectTypes of the SBR and PS extensions (ISO 14496-3 Table 1.1).
+const OBJECT_TYPE_SBR: u8 = 5;
+const OBJECT_TYPE_PS: u8 = 29;
+
+// syncExtensionTypes of backward-compatible SBR and PS signaling (ISO 14496-3 §1.6.5).
+const SYNC_EXTENSION_SBR: u32 = 0x2b7;
+const SYNC_EXTENSION_PS: u32 = 0x548;
+
 impl Config {
 	/// Parse an AudioSpecificConfig buffer.
 	///
@@ -46,7 +70,11 @@ impl Config {
 	/// (object_type == 31), and explicit sample rates (freq_index == 15). The
 	/// fields are bit-packed and not byte-aligned, so a bit reader is required:
 	/// with an explicit 24-bit rate the channelConfiguration lands mid-byte after
-	/// it. Any SBR/PS extension bits after the core fields are consumed.
+	/// it.
+	///
+	/// SBR/PS is detected in both forms: explicit (an audioObjectType of 5 or 29
+	/// wrapping the core type) and backward-compatible (a sync extension after an
+	/// AAC-LC config). Any further extension bits are consumed.
 	pub fn parse<T: Buf>(buf: &mut T) -> Result<Self> {
 		if buf.remaining() < 2 {
 			return Err(Error::ConfigTooShort);
@@ -54,27 +82,42 @@ impl Config {
 
 		let mut reader = BitReader::new(buf);
 
-		// audioObjectType: 5 bits, escaped to 6 more when it reads 31.
-		let mut object_type = reader.read(5, Error::ConfigTooShort)? as u8;
-		if object_type == 31 {
-			object_type = 32 + reader.read(6, Error::ExtendedConfigTooShort)? as u8;
-		}
-
-		// samplingFrequencyIndex: 4 bits; index 15 means an explicit 24-bit rate follows.
-		let freq_index = reader.read(4, Error::IncompleteConfig)? as u8;
-		let sample_rate = if freq_index == 15 {
-			reader.read(24, Error::ExplicitSampleRateTooShort)?
-		} else {
-			*SAMPLE_RATES
-				.get(freq_index as usize)
-				.ok_or(Error::UnsupportedSampleRateIndex(freq_index))?
-		};
+		let mut object_type = read_object_type(&mut reader, Error::ConfigTooShort)?;
+		let mut sample_rate = read_sample_rate(&mut reader)?;
 
 		// channelConfiguration: 4 bits, immediately after the (possibly explicit) rate.
 		let channel_config = reader.read(4, Error::IncompleteConfig)? as u8;
-		let channel_count = channel_count_from_config(channel_config);
+		let mut channel_count = channel_count_from_config(channel_config);
+
+		// Explicit signaling: the extension type comes first, then the output rate and
+		// the core type.
+		let mut extension = None;
+		if object_type == OBJECT_TYPE_SBR || object_type == OBJECT_TYPE_PS {
+			extension = Some(match object_type {
+				OBJECT_TYPE_PS => Extension::SbrPs,
+				_ => Extension::Sbr,
+			});
+			sample_rate = read_sample_rate(&mut reader)?;
+			object_type = read_object_type(&mut reader, Error::IncompleteConfig)?;
+		}
 
-		// AudioSpecificConfig can carry variable-length extensions (SBR, PS, etc.).
+		// Backward-compatible signaling: an AAC-LC config followed by a sync extension.
+		// Old decoders stop before it and play the core stream; a short or mismatched
+		// tail just means there's none. A PCE (channel config 0) isn't parsed, so the
+		// tail can't be located behind one.
+		if extension.is_none() && object_type == 2 && channel_config != 0 {
+			let implicit = skip_ga_specific_config(&mut reader).and_then(|()| read_implicit_extension(&mut reader));
+			if let Some((ext, rate)) = implicit {
+				extension = Some(ext);
+				sample_rate = rate;
+			}
+		}
+
+		if extension == Some(Extension::SbrPs) {
+			channel_count = 2;
+		}
+
+		// AudioSpecificConfig can carry further variable-length extensions.
 		// We've extracted the essential fields; drain the rest so the buffer is advanced.
 		if buf.remaining() > 0 {
 			buf.advance(buf.remaining());
@@ -84,54 +127,121 @@ impl Config {
 			profile: object_type,
 			sample_rate,
 			channel_count,
+			extension,
 		})
 	}
 
+	/// The audioObjectType advertised in the codec string (`mp4a.40.N`): 5 for
+	/// HE-AAC, 29 for HE-AACv2, otherwise the core [`profile`](Self::profile).
+	pub fn object_type(&self) -> u8 {
+		match self.extension {
+			None => self.profile,
+			Some(Extension::Sbr) => OBJECT_TYPE_SBR,
+			Some(Extension::SbrPs) => OBJECT_TYPE_PS,
+		}
+	}
+
 	/// Encode this configuration as an AudioSpecificConfig (ISO 14496-3 §1.6.2.1).
 	///
 	/// Standard sample rates produce 2 bytes; non-standard rates fall back to
-	/// the 5-byte form with an explicit 24-bit frequency.
+	/// the 5-byte form with an explicit 24-bit frequency. An [`Extension`] is
+	/// signaled explicitly, with a core stream at half the rate (and mono, for PS).
 	pub fn encode(&self) -> Bytes {
-		// audioObjectType is a 5-bit field; mask to prevent shift overflow.
-		let profile = self.profile & 0x1F;
-
-		let freq_index: u8 = match self.sample_rate {
-			96000 => 0,
-			88200 => 1,
-			64000 => 2,
-			48000 => 3,
-			44100 => 4,
-			32000 => 5,
-			24000 => 6,
-			22050 => 7,
-			16000 => 8,
-			12000 => 9,
-			11025 => 10,
-			8000 => 11,
-			7350 => 12,
-			_ => 0xF, // explicit 24-bit frequency follows
-		};
+		let mut writer = BitWriter::default();
+
+		match self.extension {
+			None => {
+				// audioObjectType is a 5-bit field; mask to prevent shift overflow.
+				writer.write(5, (self.profile & 0x1F) as u32);
+				write_sample_rate(&mut writer, self.sample_rate);
+				writer.write(4, channel_config_from_count(self.channel_count) as u32);
+			}
+			Some(extension) => {
+				let channel_count = match extension {
+					Extension::Sbr => self.channel_count,
+					Extension::SbrPs => 1,
+				};
+				writer.write(5, self.object_type() as u32);
+				write_sample_rate(&mut writer, self.sample_rate / 2);
+				writer.write(4, channel_config_from_count(channel_count) as u32);
+				write_sample_rate(&mut writer, self.sample_rate);
+				writer.write(5, (self.profile & 0x1F) as u32);
+				// GASpecificConfig: frameLengthFlag, dependsOnCoreCoder, extensionFlag.
+				writer.write(3, 0);
+			}
+		}
+
+		writer.finish()
+	}
+}
 
-		let channel_config = channel_config_from_count(self.channel_count) as u64;
-
-		if freq_index != 0xF {
-			// 5 + 4 + 4 = 13 bits → 2 bytes (3 bits padding)
-			let b0 = (profile << 3) | (freq_index >> 1);
-			let b1 = ((freq_index & 1) << 7) | ((channel_config as u8 & 0x0F) << 3);
-			Bytes::from(vec![b0, b1])
-		} else {
-			// 5 + 4 + 24 + 4 = 37 bits → 5 bytes (3 bits padding)
-			let mut bits: u64 = 0;
-			bits |= (profile as u64) << 35;
-			bits |= 0xF_u64 << 31;
-			bits |= (self.sample_rate as u64) << 7;
-			bits |= (channel_config & 0xF) << 3;
-			let all = bits.to_be_bytes();
-			Bytes::copy_from_slice(&all[3..8])
+// audioObjectType: 5 bits, escaped to 6 more when it reads 31.
+fn read_object_type<T: Buf>(reader: &mut BitReader<'_, T>, short: Error) -> Result<u8> {
+	let object_type = reader.read(5, short)? as u8;
+	if object_type == 31 {
+		return Ok(32 + reader.read(6, Error::ExtendedConfigTooShort)? as u8);
+	}
+	Ok(object_type)
+}
+
+// samplingFrequencyIndex: 4 bits; index 15 means an explicit 24-bit rate follows.
+fn read_sample_rate<T: Buf>(reader: &mut BitReader<'_, T>) -> Result<u32> {
+	let freq_index = reader.read(4, Error::IncompleteConfig)? as u8;
+	if freq_index == 15 {
+		return reader.read(24, Error::ExplicitSampleRateTooShort);
+	}
+	sample_rate(freq_index).ok_or(Error::UnsupportedSampleRateIndex(freq_index))
+}
+
+/// The rate of a standard samplingFrequencyIndex, or `None` for a reserved or explicit (15) one.
+pub(crate) fn sample_rate(freq_index: u8) -> Option<u32> {
+	SAMPLE_RATES.get(freq_index as usize).copied()
+}
+
+fn write_sample_rate(writer: &mut BitWriter, sample_rate: u32) {
+	match SAMPLE_RATES.iter().position(|rate| *rate == sample_rate) {
+		Some(freq_index) => writer.write(4, freq_index as u32),
+		None => {
+			writer.write(4, 0xF);
+			writer.write(24, sample_rate);
 		}
 	}
 }
 
+// GASpecificConfig for AAC-LC: frameLengthFlag, dependsOnCoreCoder (+ a 14-bit
+// delay), extensionFlag (+ extensionFlag3).
+fn skip_ga_specific_config<T: Buf>(reader: &mut BitReader<'_, T>) -> Option<()> {
+	let flags = reader.read(3, Error::IncompleteConfig).ok()?;
+	if flags & 0b010 != 0 {
+		reader.read(14, Error::IncompleteConfig).ok()?;
+	}
+	if flags & 0b001 != 0 {
+		reader.read(1, Error::IncompleteConfig).ok()?;
+	}
+	Some(())
+}
+
+// The sync extensions of backward-compatible SBR/PS signaling, returning the
+// extension and the output rate. `None` unless SBR is present.
+fn read_implicit_extension<T: Buf>(reader: &mut BitReader<'_, T>) -> Option<(Extension, u32)> {
+	if reader.read(11, Error::IncompleteConfig).ok()? != SYNC_EXTENSION_SBR {
+		return None;
+	}
+	if read_object_type(reader, Error::IncompleteConfig).ok()? != OBJECT_TYPE_SBR {
+		return None;
+	}
+	if reader.read(1, Error::IncompleteConfig).ok()? == 0 {
+		return None;
+	}
+	let sample_rate = read_sample_rate(reader).ok()?;
+
+	let ps = reader.read(11, Error::IncompleteConfig).ok() == Some(SYNC_EXTENSION_PS)
+		&& reader.read(1, Error::IncompleteConfig).ok() == Some(1);
+	let extension = if ps { Extension::SbrPs } else { Extension::Sbr };
+
+	Some((extension, sample_rate))
+}
+
 /// The 13 standard AAC sampling frequencies, indexed by samplingFrequencyIndex
 /// (ISO 14496-3 Table 1.18). Index 15 is the escape for an explicit 24-bit rate.
 const SAMPLE_RATES: [u32; 13] = [
@@ -176,6 +286,32 @@ impl<'a, T: Buf> BitReader<'a, T> {
 	}
 }
 
+/// MSB-first bit writer, the inverse of [`BitReader`]; the last byte is zero-padded.
+#[derive(Default)]
+struct BitWriter {
+	bytes: Vec<u8>,
+	bits_used: u8,
+}
+
+impl BitWriter {
+	/// Write the low `n` bits (n <= 32) of `value`, MSB-first.
+	fn write(&mut self, n: u8, value: u32) {
+		for i in (0..n).rev() {
+			if self.bits_used % 8 == 0 {
+				self.bytes.push(0);
+				self.bits_used = 0;
+			}
+			let bit = ((value >> i) & 1) as u8;
+			*self.bytes.last_mut().unwrap() |= bit << (7 - self.bits_used);
+			self.bits_used += 1;
+		}
+	}
+
+	fn finish(self) -> Bytes {
+		Bytes::from(self.bytes)
+	}
+}
+
 /// Map an AAC `channel_config` (ISO 14496-3 Table 1.19) to its real channel count.
 /// Configs 1..=6 happen to be identity (5.1 has config=6 and 6 channels). Config
 /// 7 is 7.1 = 8 channels. Config 0 means "described elsewhere" — we default to
@@ -233,6 +369,7 @@ mod tests {
 			profile: 2,
 			sample_rate: 44_056, // not in the standard table
 			channel_count: 2,
+			extension: None,
 		};
 		let encoded = cfg.encode();
 		assert_eq!(encoded.len(), 5, "explicit-rate config is 5 bytes");
@@ -262,6 +399,7 @@ mod tests {
 			profile: 2,
 			sample_rate: 48000,
 			channel_count: 6,
+			extension: None,
 		};
 		let encoded = cfg.encode();
 		let parsed = Config::parse(&mut encoded.as_ref()).unwrap();
@@ -275,12 +413,56 @@ mod tests {
 			profile: 2,
 			sample_rate: 48000,
 			channel_count: 8,
+			extension: None,
 		};
 		let encoded = cfg.encode();
 		let parsed = Config::parse(&mut encoded.as_ref()).unwrap();
 		assert_eq!(parsed.channel_count, 8, "7.1 surround should round-trip as 8 channels");
 	}
 
+	#[test]
+	fn parses_explicit_sbr() {
+		// AOT 5 wrapping AAC-LC: a 24 kHz stereo core, 48 kHz out.
+		let buf = [0x2B, 0x11, 0x88, 0x00];
+		let cfg = Config::parse(&mut buf.as_slice()).unwrap();
+		assert_eq!(cfg.profile, 2);
+		assert_eq!(cfg.extension, Some(Extension::Sbr));
+		assert_eq!(cfg.object_type(), 5);
+		assert_eq!(cfg.sample_rate, 48_000);
+		assert_eq!(cfg.channel_count, 2);
+
+		// Encoding signals it the same way.
+		assert_eq!(cfg.encode().as_ref(), &buf);
+	}
+
+	#[test]
+	fn parses_explicit_ps() {
+		// AOT 29: a 24 kHz mono core, decoded to 48 kHz stereo.
+		let buf = [0xEB, 0x09, 0x88, 0x00];
+		let cfg = Config::parse(&mut buf.as_slice()).unwrap();
+		assert_eq!(cfg.extension, Some(Extension::SbrPs));
+		assert_eq!(cfg.object_type(), 29);
+		assert_eq!(cfg.sample_rate, 48_000);
+		assert_eq!(cfg.channel_count, 2);
+		assert_eq!(cfg.encode().as_ref(), &buf);
+	}
+
+	#[test]
+	fn parses_backward_compatible_sbr_and_ps() {
+		// AAC-LC at 24 kHz stereo, then sync extension 0x2b7 with SBR to 48 kHz.
+		let buf = [0x13, 0x10, 0x56, 0xE5, 0x98];
+		let cfg = Config::parse(&mut buf.as_slice()).unwrap();
+		assert_eq!(cfg.profile, 2);
+		assert_eq!(cfg.extension, Some(Extension::Sbr));
+		assert_eq!(cfg.sample_rate, 48_000);
+
+		// ... plus sync extension 0x548 with PS.
+		let buf = [0x13, 0x10, 0x56, 0xE5, 0x9D, 0x48, 0x80];
+		let cfg = Config::parse(&mut buf.as_slice()).unwrap();
+		assert_eq!(cfg.extension, Some(Extension::SbrPs));
+		assert_eq!(cfg.channel_count, 2);
+	}
+
 	#[test]
 	fn channel_config_zero_falls_back_to_stereo() {
 		// Config 0 means "described in PCE" which we don't implement.
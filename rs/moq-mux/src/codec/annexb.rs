@@ -94,6 +94,10 @@ pub(crate) fn push_distinct(set: &mut Vec<Bytes>, nal: &Bytes) -> bool {
 ///
 /// `seen` is this AU's inline NALs (already appended to `chunks`); `retained` is
 /// the cross-GOP set re-injected on bare keyframes.
+///
+/// The re-injected NALs are copied, but they're a few dozen bytes going into a
+/// buffer the splitter assembles the access unit in anyway, and they land before
+/// the slice, so the slice data is never copied a second time to make room.
 pub(crate) fn reconcile_keyframe_params(chunks: &mut BytesMut, retained: &mut Vec<Bytes>, seen: &mut Vec<Bytes>) {
 	if seen.is_empty() {
 		for nal in retained.iter() {
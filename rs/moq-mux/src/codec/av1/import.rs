@@ -132,6 +132,7 @@ impl<E: CatalogExt> Import<E> {
 		});
 		config.coded_width = Some(seq_header.max_frame_width as u32);
 		config.coded_height = Some(seq_header.max_frame_height as u32);
+		config.color_space = super::color_space(seq_header);
 		config.container = hang::catalog::Container::Legacy;
 		self.apply_config(config);
 		Ok(())
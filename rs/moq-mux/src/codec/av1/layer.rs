@@ -0,0 +1,104 @@
+//! AV1 scalability layers.
+//!
+//! A scalable (SVC) AV1 stream tags each OBU with a temporal and spatial layer in
+//! its extension header. Reading them per temporal unit lets a relay or player drop
+//! enhancement layers under congestion without decoding anything.
+
+/// The scalability layer of one AV1 temporal unit.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct Layer {
+	/// The temporal layer. Every OBU in a temporal unit shares it; 0 is the base layer.
+	pub temporal_id: u8,
+
+	/// The highest spatial layer present. A temporal unit carries one frame per
+	/// spatial layer, so dropping layers above N means dropping those frames' OBUs.
+	pub spatial_id: u8,
+}
+
+/// Read the scalability layer of a temporal unit: the OBUs of one frame, as carried
+/// in a CMAF sample, a LOC payload or a [`Split`](super::Split) frame.
+///
+/// Returns `None` when no OBU has an extension header, which is the case for every
+/// non-scalable stream (implicitly base layer), or when the OBUs are malformed.
+pub fn layer(data: &[u8]) -> Option<Layer> {
+	let mut buf = data;
+	let mut found: Option<Layer> = None;
+
+	while let Some((&header, rest)) = buf.split_first() {
+		buf = rest;
+
+		let has_extension = header & 0x04 != 0;
+		let has_size = header & 0x02 != 0;
+
+		if has_extension {
+			let (&extension, rest) = buf.split_first()?;
+			buf = rest;
+
+			let temporal_id = extension >> 5;
+			let spatial_id = (extension >> 3) & 0x03;
+			let layer = found.get_or_insert(Layer {
+				temporal_id,
+				spatial_id,
+			});
+			layer.spatial_id = layer.spatial_id.max(spatial_id);
+		}
+
+		// Without a size field, the OBU runs to the end of the temporal unit.
+		if !has_size {
+			break;
+		}
+
+		let size = usize::try_from(super::leb128(&mut buf)?).ok()?;
+		buf = buf.get(size..)?;
+	}
+
+	found
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	// An OBU with obu_has_size_field set and, if given, an extension header.
+	fn obu(kind: u8, extension: Option<(u8, u8)>, payload: &[u8]) -> Vec<u8> {
+		let mut obu = vec![(kind << 3) | 0x02];
+		if let Some((temporal_id, spatial_id)) = extension {
+			obu[0] |= 0x04;
+			obu.push((temporal_id << 5) | (spatial_id << 3));
+		}
+		obu.push(payload.len() as u8);
+		obu.extend_from_slice(payload);
+		obu
+	}
+
+	const OBU_TEMPORAL_DELIMITER: u8 = 2;
+	const OBU_FRAME: u8 = 6;
+
+	#[test]
+	fn non_scalable_has_no_layer() {
+		let mut tu = obu(OBU_TEMPORAL_DELIMITER, None, &[]);
+		tu.extend(obu(OBU_FRAME, None, &[0xaa; 4]));
+		assert_eq!(layer(&tu), None);
+	}
+
+	#[test]
+	fn reads_temporal_and_highest_spatial_layer() {
+		let mut tu = obu(OBU_TEMPORAL_DELIMITER, None, &[]);
+		tu.extend(obu(OBU_FRAME, Some((2, 0)), &[0xaa; 4]));
+		tu.extend(obu(OBU_FRAME, Some((2, 1)), &[0xbb; 3]));
+
+		assert_eq!(
+			layer(&tu),
+			Some(Layer {
+				temporal_id: 2,
+				spatial_id: 1,
+			})
+		);
+	}
+
+	#[test]
+	fn truncated_obu_is_none() {
+		let tu = obu(OBU_FRAME, Some((1, 0)), &[0xaa; 4]);
+		assert_eq!(layer(&tu[..tu.len() - 1]), None);
+	}
+}
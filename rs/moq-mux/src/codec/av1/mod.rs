@@ -2,15 +2,24 @@
 //!
 //! Maps the AV1CodecConfigurationRecord (av1C) flag bits into the
 //! catalog's AV1 codec struct, and provides an [`Import`] that publishes
-//! raw AV1 bitstreams (OBU-framed) to a moq broadcast.
+//! raw AV1 bitstreams (OBU-framed) to a moq broadcast. [`layer`] reads the
+//! SVC layer of a temporal unit for selective dropping.
 
 mod import;
+mod layer;
 mod split;
 
 pub use import::*;
+pub use layer::*;
 pub use split::*;
 
+use bytes::Bytes;
 use hang::catalog::AV1;
+use scuffle_av1::seq::SequenceHeaderObu;
+use scuffle_av1::{ObuHeader, ObuType};
+
+use crate::codec::hdr::Hdr;
+use split::ObuIterator;
 
 /// AV1 parsing errors.
 #[derive(Debug, Clone, thiserror::Error)]
@@ -49,8 +58,9 @@ pub type Result<T> = std::result::Result<T, Error>;
 /// Used by the enhanced-RTMP / FLV importer, where the av1C arrives out of band
 /// in the sequence-header tag (leading `0x81` marker) and the coded samples are
 /// raw OBU temporal units, so the record passes straight through as the catalog
-/// `description`. Resolution and color live in the inline sequence header, not
-/// the av1C, so `coded_width`/`coded_height` are left unset here.
+/// `description`. Resolution lives in the inline sequence header, not the av1C,
+/// so `coded_width`/`coded_height` are left unset here; the HDR metadata is read
+/// from `configOBUs` when the encoder put it there.
 pub(crate) fn config_from_av1c(av1c: &[u8]) -> Result<hang::catalog::VideoConfig> {
 	// av1C: byte 0 = marker(1)|version(7) = 0x81, byte 1 = seq_profile(3)|seq_level_idx_0(5),
 	// byte 2 = seq_tier_0|high_bitdepth|twelve_bit|monochrome|subsampling_x|subsampling_y|sample_position(2).
@@ -73,16 +83,18 @@ pub(crate) fn config_from_av1c(av1c: &[u8]) -> Result<hang::catalog::VideoConfig
 	});
 	config.description = Some(bytes::Bytes::copy_from_slice(av1c));
 	config.container = hang::catalog::Container::Legacy;
+	hdr_from_obus(&av1c[4..]).apply(&mut config);
 	Ok(config)
 }
 
 /// Map a parsed `mp4_atom::Av1c` (AV1CodecConfigurationRecord) to the
 /// hang catalog's AV1 codec struct.
 ///
-/// Fills in profile, level, bit depth, and chroma sampling info. Color/HDR
-/// fields default to unspecified.
+/// Fills in profile, level, bit depth, and chroma sampling info. The color
+/// fields come from the sequence header in `configOBUs` when present, and
+/// default to BT.709 otherwise.
 pub(crate) fn av1_from_av1c(av1c: &mp4_atom::Av1c) -> AV1 {
-	AV1 {
+	let mut av1 = AV1 {
 		profile: av1c.seq_profile,
 		level: av1c.seq_level_idx_0,
 		bitdepth: bitdepth(av1c.twelve_bit, av1c.high_bitdepth),
@@ -91,7 +103,98 @@ pub(crate) fn av1_from_av1c(av1c: &mp4_atom::Av1c) -> AV1 {
 		chroma_subsampling_y: av1c.chroma_subsampling_y,
 		chroma_sample_position: av1c.chroma_sample_position,
 		..Default::default()
+	};
+
+	if let Some(seq) = config_obus(&av1c.config_obus).find_map(|(header, payload)| sequence_header(header, &payload)) {
+		av1.color_primaries = seq.color_config.color_primaries;
+		av1.transfer_characteristics = seq.color_config.transfer_characteristics;
+		av1.matrix_coefficients = seq.color_config.matrix_coefficients;
+		av1.full_range = seq.color_config.full_color_range;
+	}
+
+	av1
+}
+
+/// Collect the HDR metadata from an av1C `configOBUs`: the color description from
+/// the sequence header and the mastering display / content light level from any
+/// metadata OBU.
+pub(crate) fn hdr_from_obus(obus: &[u8]) -> Hdr {
+	let mut hdr = Hdr::default();
+	for (header, payload) in config_obus(obus) {
+		if header.obu_type == ObuType::Metadata {
+			parse_metadata(&payload, &mut hdr);
+		} else if let Some(seq) = sequence_header(header, &payload) {
+			hdr.color_space = color_space(&seq);
+		}
+	}
+	hdr
+}
+
+/// The color description signalled in a sequence header, if any.
+pub(crate) fn color_space(seq: &SequenceHeaderObu) -> Option<hang::catalog::ColorSpace> {
+	crate::codec::hdr::color_space(
+		seq.color_config.color_primaries,
+		seq.color_config.transfer_characteristics,
+		seq.color_config.matrix_coefficients,
+		seq.color_config.full_color_range,
+	)
+}
+
+/// Iterate the OBUs in a `configOBUs` blob as (header, payload) pairs, stopping at the
+/// first one that fails to parse.
+fn config_obus(obus: &[u8]) -> impl Iterator<Item = (ObuHeader, Bytes)> {
+	let mut buf = Bytes::copy_from_slice(obus);
+	let obus: Vec<Bytes> = ObuIterator::new(&mut buf).map_while(|obu| obu.ok()).collect();
+
+	obus.into_iter().map_while(|obu| {
+		let mut reader = &obu[..];
+		let header = ObuHeader::parse(&mut reader).ok()?;
+		let payload = obu.slice(obu.len() - reader.len()..);
+		Some((header, payload))
+	})
+}
+
+fn sequence_header(header: ObuHeader, payload: &[u8]) -> Option<SequenceHeaderObu> {
+	if header.obu_type != ObuType::SequenceHeader {
+		return None;
 	}
+	SequenceHeaderObu::parse(header, &mut &payload[..]).ok()
+}
+
+/// `metadata_type` for the content light level (AV1 §6.7.3).
+const METADATA_TYPE_HDR_CLL: u64 = 1;
+/// `metadata_type` for the mastering display color volume (AV1 §6.7.4).
+const METADATA_TYPE_HDR_MDCV: u64 = 2;
+
+/// Parse an HDR metadata OBU payload into `hdr`, ignoring the other metadata types.
+fn parse_metadata(payload: &[u8], hdr: &mut Hdr) {
+	let mut buf = payload;
+	let Some(kind) = leb128(&mut buf) else {
+		return;
+	};
+
+	match kind {
+		METADATA_TYPE_HDR_CLL => {
+			hdr.content_light_level = crate::codec::hdr::content_light_level(buf).or(hdr.content_light_level)
+		}
+		METADATA_TYPE_HDR_MDCV => {
+			hdr.mastering_display = crate::codec::hdr::mastering_display_av1(buf).or(hdr.mastering_display)
+		}
+		_ => {}
+	}
+}
+
+fn leb128(buf: &mut &[u8]) -> Option<u64> {
+	let mut value = 0u64;
+	for i in 0..8 {
+		let (&byte, rest) = buf.split_first()?;
+		*buf = rest;
+		value |= ((byte & 0x7f) as u64) << (i * 7);
+		if byte & 0x80 == 0 {
+			return Some(value);
+		}
+	}
+	None
 }
 
 /// Build an `mp4_atom::Av1c` (AV1CodecConfigurationRecord) from the hang
@@ -137,7 +240,7 @@ pub(crate) fn bitdepth_flags(bitdepth: u8) -> (bool, bool) {
 
 #[cfg(test)]
 mod tests {
-	use super::{av1_from_av1c, av1c_from_av1, bitdepth, bitdepth_flags};
+	use super::{av1_from_av1c, av1c_from_av1, bitdepth, bitdepth_flags, hdr_from_obus};
 	use hang::catalog::AV1;
 
 	#[test]
@@ -195,4 +298,28 @@ mod tests {
 		assert_eq!(back.chroma_subsampling_y, av1.chroma_subsampling_y);
 		assert_eq!(back.chroma_sample_position, av1.chroma_sample_position);
 	}
+
+	#[test]
+	fn hdr_from_metadata_obus() {
+		// OBU_METADATA (type 5) with obu_has_size_field set, for each HDR metadata type.
+		let mut obus = vec![0x2a, 6, 1, 0x03, 0xe8, 0x01, 0x90, 0x80];
+		let mut mdcv = vec![2u8];
+		for v in [0.708f64, 0.292, 0.170, 0.797, 0.131, 0.046, 0.3127, 0.3290] {
+			mdcv.extend_from_slice(&((v * 65536.0).round() as u16).to_be_bytes());
+		}
+		mdcv.extend_from_slice(&(1000u32 << 8).to_be_bytes());
+		mdcv.extend_from_slice(&(1u32 << 12).to_be_bytes());
+		mdcv.push(0x80);
+		obus.extend_from_slice(&[0x2a, mdcv.len() as u8]);
+		obus.extend_from_slice(&mdcv);
+
+		let hdr = hdr_from_obus(&obus);
+		let cll = hdr.content_light_level.unwrap();
+		assert_eq!((cll.max_cll, cll.max_fall), (1000, 400));
+		let md = hdr.mastering_display.unwrap();
+		assert_eq!(md.max_luminance, 1000.0);
+		assert!((md.green[1] - 0.797).abs() < 1e-4);
+		// No sequence header, so no color description.
+		assert_eq!(hdr.color_space, None);
+	}
 }
@@ -279,14 +279,14 @@ mod tests {
 		let mut track = broadcast.create_track(moq_net::Track::new("video.m4s")).unwrap();
 
 		// Group 0 (keyframe-starting group): one IDR frame.
-		let mut g0 = track.create_group(moq_net::Group { sequence: 0 }).unwrap();
+		let mut g0 = track.create_group(moq_net::Group::new(0)).unwrap();
 		write_length_prefixed(&mut g0, 0, &[idr]);
 		g0.finish().unwrap();
 
 		// Group 1 (next group): one P-slice. Consumer marks the first frame
 		// of every group as keyframe by protocol invariant, so the exporter
 		// MUST treat both group-starts as keyframes and inject SPS+PPS twice.
-		let mut g1 = track.create_group(moq_net::Group { sequence: 1 }).unwrap();
+		let mut g1 = track.create_group(moq_net::Group::new(1)).unwrap();
 		write_length_prefixed(&mut g1, 33_000, &[p_slice]);
 		g1.finish().unwrap();
 		track.finish().unwrap();
@@ -136,6 +136,7 @@ impl<E: CatalogExt> Import<E> {
 		config.framerate = vui_data.framerate;
 		config.display_ratio_width = vui_data.display_ratio_width;
 		config.display_ratio_height = vui_data.display_ratio_height;
+		config.color_space = sps.rbsp.vui_parameters.as_ref().and_then(super::color_space);
 		config.container = hang::catalog::Container::Legacy;
 
 		self.last_sps = Some(sps_nal.clone());
@@ -17,6 +17,8 @@ pub use split::*;
 use bytes::{Buf, BufMut, Bytes, BytesMut};
 use scuffle_h265::{NALUnitType, SpsNALUnit};
 
+use crate::codec::hdr::Hdr;
+
 /// H.265 parsing and transform errors.
 #[derive(Debug, Clone, thiserror::Error)]
 #[non_exhaustive]
@@ -84,6 +86,8 @@ pub struct Hvcc {
 	pub sps: Vec<Bytes>,
 	/// PPS NAL units carried out-of-band in the record.
 	pub pps: Vec<Bytes>,
+	/// Prefix SEI NAL units carried out-of-band in the record, e.g. the HDR metadata.
+	pub sei: Vec<Bytes>,
 }
 
 impl Hvcc {
@@ -99,6 +103,7 @@ impl Hvcc {
 		let mut vps = Vec::new();
 		let mut sps = Vec::new();
 		let mut pps = Vec::new();
+		let mut sei = Vec::new();
 		let mut pos: usize = 23;
 
 		for _ in 0..num_arrays {
@@ -127,6 +132,7 @@ impl Hvcc {
 					NALUnitType::VpsNut => vps.push(bytes),
 					NALUnitType::SpsNut => sps.push(bytes),
 					NALUnitType::PpsNut => pps.push(bytes),
+					NALUnitType::PrefixSeiNut => sei.push(bytes),
 					_ => {}
 				}
 			}
@@ -137,6 +143,7 @@ impl Hvcc {
 			vps,
 			sps,
 			pps,
+			sei,
 		})
 	}
 }
@@ -168,9 +175,96 @@ pub(crate) fn config_from_hvcc(hvcc: &[u8]) -> Result<hang::catalog::VideoConfig
 	config.coded_height = Some(sps.rbsp.cropped_height() as u32);
 	config.description = Some(Bytes::copy_from_slice(hvcc));
 	config.container = hang::catalog::Container::Legacy;
+	hdr(&sps, &params.sei).apply(&mut config);
 	Ok(config)
 }
 
+/// Collect the HDR metadata for a track: the color description from the SPS VUI
+/// and the mastering display / content light level from any prefix SEI.
+pub(crate) fn hdr(sps: &SpsNALUnit, sei: &[Bytes]) -> Hdr {
+	let mut hdr = Hdr {
+		color_space: sps.rbsp.vui_parameters.as_ref().and_then(color_space),
+		..Default::default()
+	};
+	for nal in sei {
+		parse_sei(nal, &mut hdr);
+	}
+	hdr
+}
+
+/// The color description signalled in the VUI, if any.
+pub(crate) fn color_space(vui: &scuffle_h265::VuiParameters) -> Option<hang::catalog::ColorSpace> {
+	let signal = &vui.video_signal_type;
+	crate::codec::hdr::color_space(
+		signal.colour_primaries,
+		signal.transfer_characteristics,
+		signal.matrix_coeffs,
+		signal.video_full_range_flag,
+	)
+}
+
+/// SEI payloadType for the mastering display colour volume (ITU H.265 §D.2.28).
+const SEI_MASTERING_DISPLAY: usize = 137;
+/// SEI payloadType for the content light level information (ITU H.265 §D.2.35).
+const SEI_CONTENT_LIGHT_LEVEL: usize = 144;
+
+/// Parse the HDR messages out of a prefix SEI NAL unit into `hdr`, ignoring the rest.
+fn parse_sei(nal: &[u8], hdr: &mut Hdr) {
+	// Skip the two-byte NAL header.
+	let rbsp = unescape(nal.get(2..).unwrap_or_default());
+	let mut buf = &rbsp[..];
+
+	// Stop at the rbsp_trailing_bits.
+	while !matches!(buf, [] | [0x80]) {
+		let (Some(kind), Some(size)) = (sei_value(&mut buf), sei_value(&mut buf)) else {
+			return;
+		};
+		let Some(payload) = buf.get(..size) else {
+			return;
+		};
+
+		match kind {
+			SEI_MASTERING_DISPLAY => {
+				hdr.mastering_display = crate::codec::hdr::mastering_display(payload).or(hdr.mastering_display)
+			}
+			SEI_CONTENT_LIGHT_LEVEL => {
+				hdr.content_light_level = crate::codec::hdr::content_light_level(payload).or(hdr.content_light_level)
+			}
+			_ => {}
+		}
+
+		buf = &buf[size..];
+	}
+}
+
+/// Read an SEI payloadType or payloadSize: a run of 0xFF bytes summed with the final byte.
+fn sei_value(buf: &mut &[u8]) -> Option<usize> {
+	let mut value = 0;
+	loop {
+		let (&byte, rest) = buf.split_first()?;
+		*buf = rest;
+		value += byte as usize;
+		if byte != 0xff {
+			return Some(value);
+		}
+	}
+}
+
+/// Strip the emulation prevention bytes (the 0x03 in 0x000003) from a NAL payload.
+fn unescape(nal: &[u8]) -> Vec<u8> {
+	let mut out = Vec::with_capacity(nal.len());
+	let mut zeros = 0;
+	for &byte in nal {
+		if zeros >= 2 && byte == 0x03 {
+			zeros = 0;
+			continue;
+		}
+		zeros = if byte == 0 { zeros + 1 } else { 0 };
+		out.push(byte);
+	}
+	out
+}
+
 /// Annex-B → length-prefixed transmuxer; the H.265 analogue of
 /// [`crate::codec::h264::Avc1`].
 ///
@@ -452,4 +546,47 @@ mod tests {
 		assert_eq!(params[1].as_ref(), sps);
 		assert_eq!(params[2].as_ref(), pps);
 	}
+
+	#[test]
+	fn parses_hdr10_sei() {
+		// Prefix SEI (type 39) with a mastering display message, then a content light
+		// level message, as x265 emits them. The minimum luminance (0x00000032) needs an
+		// emulation prevention byte.
+		let mut nal = vec![0x4e, 0x01, 137, 24];
+		for v in [8500u16, 39850, 6550, 2300, 35400, 14600, 15635, 16450] {
+			nal.extend_from_slice(&v.to_be_bytes());
+		}
+		nal.extend_from_slice(&10_000_000u32.to_be_bytes());
+		nal.extend_from_slice(&[0x00, 0x00, 0x03, 0x00, 0x32]);
+		nal.extend_from_slice(&[144, 4, 0x03, 0xe8, 0x01, 0x90]);
+		nal.push(0x80);
+
+		let mut hdr = Hdr::default();
+		parse_sei(&nal, &mut hdr);
+
+		let cll = hdr.content_light_level.unwrap();
+		assert_eq!((cll.max_cll, cll.max_fall), (1000, 400));
+		let md = hdr.mastering_display.unwrap();
+		assert!((md.red[0] - 0.708).abs() < 1e-9);
+		assert!((md.max_luminance - 1000.0).abs() < 1e-9);
+		assert!((md.min_luminance - 0.005).abs() < 1e-9);
+	}
+
+	#[test]
+	fn hvcc_parse_keeps_prefix_sei() {
+		let sei = &[0x4e, 0x01, 144, 4, 0x03, 0xe8, 0x01, 0x90, 0x80][..]; // NAL type 39
+
+		let mut hvcc = BytesMut::new();
+		hvcc.extend_from_slice(&[0u8; 21]);
+		hvcc.put_u8(0xfc | 0x03);
+		hvcc.put_u8(1); // numOfArrays
+		hvcc.put_u8(0x80 | 39);
+		hvcc.put_u16(1);
+		hvcc.put_u16(sei.len() as u16);
+		hvcc.put_slice(sei);
+
+		let parsed = Hvcc::parse(&hvcc).unwrap();
+		assert_eq!(parsed.sei.len(), 1);
+		assert_eq!(parsed.sei[0].as_ref(), sei);
+	}
 }
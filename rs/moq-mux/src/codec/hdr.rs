@@ -0,0 +1,152 @@
+//! HDR metadata shared by the video codecs.
+//!
+//! Maps the color description and the mastering display / content light level
+//! payloads onto the catalog's [`ColorSpace`], [`MasteringDisplay`] and
+//! [`ContentLightLevel`]. The payload layouts are the ones shared by the H.265
+//! SEI messages and the ISO BMFF `mdcv`/`clli` boxes, plus the AV1 metadata OBUs.
+
+use hang::catalog::{ColorSpace, ContentLightLevel, MasteringDisplay, VideoConfig};
+
+/// The H.273 code point for "unspecified", used by every color field.
+const UNSPECIFIED: u8 = 2;
+
+/// The HDR metadata found for a track, applied to its catalog config.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub(crate) struct Hdr {
+	pub color_space: Option<ColorSpace>,
+	pub mastering_display: Option<MasteringDisplay>,
+	pub content_light_level: Option<ContentLightLevel>,
+}
+
+impl Hdr {
+	/// Copy whatever was found into `config`, keeping the existing values otherwise.
+	pub fn apply(self, config: &mut VideoConfig) {
+		config.color_space = self.color_space.or(config.color_space);
+		config.mastering_display = self.mastering_display.or(config.mastering_display);
+		config.content_light_level = self.content_light_level.or(config.content_light_level);
+	}
+}
+
+/// A color space from CICP code points, or `None` when nothing is specified.
+pub(crate) fn color_space(primaries: u8, transfer: u8, matrix: u8, full_range: bool) -> Option<ColorSpace> {
+	if primaries == UNSPECIFIED && transfer == UNSPECIFIED && matrix == UNSPECIFIED {
+		return None;
+	}
+
+	Some(ColorSpace {
+		primaries,
+		transfer,
+		matrix,
+		full_range,
+	})
+}
+
+/// Parse a SMPTE ST 2086 payload, as carried by the H.265 mastering display colour
+/// volume SEI and the ISO BMFF `mdcv` box.
+///
+/// The primaries are ordered green, blue, red in units of 0.00002, and the luminance
+/// is in units of 0.0001 cd/m².
+pub(crate) fn mastering_display(buf: &[u8]) -> Option<MasteringDisplay> {
+	let chromaticity = |i| Some(read_u16(buf, i)? as f64 * 0.00002);
+	let luminance = |i| Some(read_u32(buf, i)? as f64 * 0.0001);
+
+	Some(MasteringDisplay {
+		green: [chromaticity(0)?, chromaticity(2)?],
+		blue: [chromaticity(4)?, chromaticity(6)?],
+		red: [chromaticity(8)?, chromaticity(10)?],
+		white_point: [chromaticity(12)?, chromaticity(14)?],
+		max_luminance: luminance(16)?,
+		min_luminance: luminance(20)?,
+	})
+}
+
+/// Parse an AV1 `METADATA_TYPE_HDR_MDCV` payload.
+///
+/// Unlike ST 2086, the primaries are ordered red, green, blue as 0.16 fixed point,
+/// the maximum luminance is 24.8 and the minimum luminance 18.14 fixed point.
+pub(crate) fn mastering_display_av1(buf: &[u8]) -> Option<MasteringDisplay> {
+	let chromaticity = |i| Some(read_u16(buf, i)? as f64 / 65536.0);
+	let fixed = |i, frac| Some(read_u32(buf, i)? as f64 / 2f64.powi(frac));
+
+	Some(MasteringDisplay {
+		red: [chromaticity(0)?, chromaticity(2)?],
+		green: [chromaticity(4)?, chromaticity(6)?],
+		blue: [chromaticity(8)?, chromaticity(10)?],
+		white_point: [chromaticity(12)?, chromaticity(14)?],
+		max_luminance: fixed(16, 8)?,
+		min_luminance: fixed(20, 14)?,
+	})
+}
+
+/// Parse a content light level payload: MaxCLL then MaxFALL, both in cd/m².
+///
+/// The H.265 SEI, the ISO BMFF `clli` box and the AV1 metadata OBU share this layout.
+pub(crate) fn content_light_level(buf: &[u8]) -> Option<ContentLightLevel> {
+	Some(ContentLightLevel {
+		max_cll: read_u16(buf, 0)?,
+		max_fall: read_u16(buf, 2)?,
+	})
+}
+
+fn read_u16(buf: &[u8], offset: usize) -> Option<u16> {
+	Some(u16::from_be_bytes(buf.get(offset..offset + 2)?.try_into().ok()?))
+}
+
+fn read_u32(buf: &[u8], offset: usize) -> Option<u32> {
+	Some(u32::from_be_bytes(buf.get(offset..offset + 4)?.try_into().ok()?))
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn parses_st2086_bt2020() {
+		// BT.2020 primaries, D65 white, 1000 / 0.0050 cd/m², as written by x265.
+		let mut buf = Vec::new();
+		for v in [8500u16, 39850, 6550, 2300, 35400, 14600, 15635, 16450] {
+			buf.extend_from_slice(&v.to_be_bytes());
+		}
+		buf.extend_from_slice(&10_000_000u32.to_be_bytes());
+		buf.extend_from_slice(&50u32.to_be_bytes());
+
+		let md = mastering_display(&buf).unwrap();
+		assert!((md.red[0] - 0.708).abs() < 1e-9);
+		assert!((md.green[1] - 0.797).abs() < 1e-9);
+		assert!((md.white_point[0] - 0.3127).abs() < 1e-9);
+		assert!((md.max_luminance - 1000.0).abs() < 1e-9);
+		assert!((md.min_luminance - 0.005).abs() < 1e-9);
+
+		assert_eq!(mastering_display(&buf[..23]), None);
+	}
+
+	#[test]
+	fn parses_av1_mdcv() {
+		let mut buf = Vec::new();
+		for v in [0.708f64, 0.292, 0.170, 0.797, 0.131, 0.046, 0.3127, 0.3290] {
+			buf.extend_from_slice(&((v * 65536.0).round() as u16).to_be_bytes());
+		}
+		buf.extend_from_slice(&(1000u32 << 8).to_be_bytes());
+		buf.extend_from_slice(&(1u32 << 12).to_be_bytes()); // 0.25 cd/m²
+
+		let md = mastering_display_av1(&buf).unwrap();
+		assert!((md.red[0] - 0.708).abs() < 1e-4);
+		assert!((md.blue[1] - 0.046).abs() < 1e-4);
+		assert_eq!(md.max_luminance, 1000.0);
+		assert_eq!(md.min_luminance, 0.25);
+	}
+
+	#[test]
+	fn parses_content_light_level() {
+		let cll = content_light_level(&[0x03, 0xe8, 0x01, 0x90]).unwrap();
+		assert_eq!(cll.max_cll, 1000);
+		assert_eq!(cll.max_fall, 400);
+		assert_eq!(content_light_level(&[0x03]), None);
+	}
+
+	#[test]
+	fn unspecified_color_space_is_none() {
+		assert_eq!(color_space(2, 2, 2, false), None);
+		assert_eq!(color_space(9, 16, 9, false).map(|c| c.transfer), Some(16));
+	}
+}
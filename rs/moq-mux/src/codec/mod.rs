@@ -14,6 +14,7 @@ pub(crate) mod eac3;
 pub mod flac;
 pub mod h264;
 pub mod h265;
+pub(crate) mod hdr;
 pub(crate) mod legacy;
 pub(crate) mod mp2;
 pub mod mp3;
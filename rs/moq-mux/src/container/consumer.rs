@@ -723,7 +723,7 @@ mod tests {
 
 	/// Write a finished group with explicit sequence and timestamps (Container::Legacy format).
 	fn write_group(track: &mut moq_net::TrackProducer, sequence: u64, timestamps: &[Timestamp]) {
-		let mut group = track.create_group(moq_net::Group { sequence }).unwrap();
+		let mut group = track.create_group(moq_net::Group::new(sequence)).unwrap();
 		for &timestamp in timestamps {
 			let frame = Frame {
 				timestamp,
@@ -817,7 +817,7 @@ mod tests {
 		let mut consumer = Consumer::new(consumer_track, Container::Legacy).with_latency(Duration::from_millis(100));
 
 		// Group 0: 5 frames, NOT finished (blocks consumer)
-		let mut group0 = track.create_group(moq_net::Group { sequence: 0 }).unwrap();
+		let mut group0 = track.create_group(moq_net::Group::new(0)).unwrap();
 		for f in 0..5u64 {
 			Container::Legacy
 				.write(
@@ -860,7 +860,7 @@ mod tests {
 
 		// Group 0 at ts 0 keeps timestamps monotonic with sequence (groups 1-9 follow at
 		// g*50 ms), so the test exercises latency skipping and not rewind detection.
-		let mut group0 = track.create_group(moq_net::Group { sequence: 0 }).unwrap();
+		let mut group0 = track.create_group(moq_net::Group::new(0)).unwrap();
 		Container::Legacy
 			.write(
 				&mut group0,
@@ -897,7 +897,7 @@ mod tests {
 		let consumer_track = track.consume();
 		let mut consumer = Consumer::new(consumer_track, Container::Legacy).with_latency(Duration::from_millis(100));
 
-		let mut group0 = track.create_group(moq_net::Group { sequence: 0 }).unwrap();
+		let mut group0 = track.create_group(moq_net::Group::new(0)).unwrap();
 		Container::Legacy
 			.write(
 				&mut group0,
@@ -1103,7 +1103,7 @@ mod tests {
 		let consumer_track = track.consume();
 		let mut consumer = Consumer::new(consumer_track, Container::Legacy).with_latency(Duration::from_millis(500));
 
-		let mut group = track.create_group(moq_net::Group { sequence: 0 }).unwrap();
+		let mut group = track.create_group(moq_net::Group::new(0)).unwrap();
 		let media = |timestamp| Frame {
 			timestamp,
 			payload: Bytes::from_static(&[0xDE, 0xAD]),
@@ -1132,7 +1132,7 @@ mod tests {
 		let consumer_track = track.consume();
 		let mut consumer = Consumer::new(consumer_track, Container::Legacy).with_latency(Duration::from_millis(500));
 
-		let mut group = track.create_group(moq_net::Group { sequence: 0 }).unwrap();
+		let mut group = track.create_group(moq_net::Group::new(0)).unwrap();
 		Container::Legacy
 			.write(
 				&mut group,
@@ -1165,7 +1165,7 @@ mod tests {
 		let consumer_track = track.consume();
 		let mut consumer = Consumer::new(consumer_track, Container::Legacy).with_latency(Duration::from_millis(500));
 
-		let mut group0 = track.create_group(moq_net::Group { sequence: 0 }).unwrap();
+		let mut group0 = track.create_group(moq_net::Group::new(0)).unwrap();
 		Container::Legacy
 			.write(
 				&mut group0,
@@ -1343,7 +1343,7 @@ mod tests {
 		let mut consumer = Consumer::new(consumer_track, Container::Legacy).with_latency(Duration::from_millis(100));
 
 		// Group 0: a frame the consumer reads, positioning it there.
-		let mut group0 = track.create_group(moq_net::Group { sequence: 0 }).unwrap();
+		let mut group0 = track.create_group(moq_net::Group::new(0)).unwrap();
 		Container::Legacy
 			.write(
 				&mut group0,
@@ -1453,7 +1453,7 @@ mod tests {
 		let mut consumer = Consumer::new(consumer_track, FailingDecode);
 
 		// A decodable frame first (so startup selects the group), then a malformed one.
-		let mut group = track.create_group(moq_net::Group { sequence: 0 }).unwrap();
+		let mut group = track.create_group(moq_net::Group::new(0)).unwrap();
 		group.write_frame(Bytes::from(0u64.to_le_bytes().to_vec())).unwrap();
 		group.write_frame(Bytes::from_static(b"FAIL")).unwrap();
 		group.finish().unwrap();
@@ -1501,7 +1501,7 @@ mod tests {
 		let mut consumer = Consumer::new(consumer_track, Container::Legacy).with_latency(Duration::from_millis(500));
 
 		let payload_bytes = vec![0x01, 0x02, 0x03, 0x04, 0x05];
-		let mut group = track.create_group(moq_net::Group { sequence: 0 }).unwrap();
+		let mut group = track.create_group(moq_net::Group::new(0)).unwrap();
 		Container::Legacy
 			.write(
 				&mut group,
@@ -1538,7 +1538,7 @@ mod tests {
 		let consumer_track = track.consume();
 		let mut consumer = Consumer::new(consumer_track, Container::Legacy).with_latency(Duration::from_secs(10));
 
-		let mut group0 = track.create_group(moq_net::Group { sequence: 0 }).unwrap();
+		let mut group0 = track.create_group(moq_net::Group::new(0)).unwrap();
 		Container::Legacy
 			.write(
 				&mut group0,
@@ -1620,7 +1620,7 @@ mod tests {
 		// to avoid the latency skip and test B-frame timestamp tracking.
 		let mut consumer = Consumer::new(consumer_track, Container::Legacy).with_latency(Duration::from_millis(110));
 
-		let mut group0 = track.create_group(moq_net::Group { sequence: 0 }).unwrap();
+		let mut group0 = track.create_group(moq_net::Group::new(0)).unwrap();
 		for &timestamp in &[ts(0), ts(66_000), ts(33_000)] {
 			Container::Legacy
 				.write(
@@ -1673,7 +1673,7 @@ mod tests {
 		write_group(&mut track, 3, &[ts(0)]);
 		write_group(&mut track, 5, &[ts(150_000)]);
 
-		let mut group7 = track.create_group(moq_net::Group { sequence: 7 }).unwrap();
+		let mut group7 = track.create_group(moq_net::Group::new(7)).unwrap();
 		Container::Legacy
 			.write(
 				&mut group7,
@@ -1723,7 +1723,7 @@ mod tests {
 		let consumer_track = track.consume();
 		let mut consumer = Consumer::new(consumer_track, Container::Legacy).with_latency(Duration::from_millis(500));
 
-		let _group5 = track.create_group(moq_net::Group { sequence: 5 }).unwrap();
+		let _group5 = track.create_group(moq_net::Group::new(5)).unwrap();
 		write_group(&mut track, 7, &[ts(210_000)]);
 		track.finish().unwrap();
 
@@ -1760,7 +1760,7 @@ mod tests {
 		let consumer_track = track.consume();
 		let mut consumer = Consumer::new(consumer_track, Container::Legacy).with_latency(Duration::from_millis(50));
 
-		let mut group0 = track.create_group(moq_net::Group { sequence: 0 }).unwrap();
+		let mut group0 = track.create_group(moq_net::Group::new(0)).unwrap();
 		Container::Legacy
 			.write(
 				&mut group0,
@@ -1796,7 +1796,7 @@ mod tests {
 		let consumer_track = track.consume();
 		let mut consumer = Consumer::new(consumer_track, Container::Legacy).with_latency(Duration::from_millis(100));
 
-		let mut group0 = track.create_group(moq_net::Group { sequence: 0 }).unwrap();
+		let mut group0 = track.create_group(moq_net::Group::new(0)).unwrap();
 		Container::Legacy
 			.write(
 				&mut group0,
@@ -1835,7 +1835,7 @@ mod tests {
 		let mut consumer = Consumer::new(consumer_track, Container::Legacy).with_latency(Duration::from_millis(100));
 
 		// Group 0: stalled at ts=0, NOT finished
-		let mut group0 = track.create_group(moq_net::Group { sequence: 0 }).unwrap();
+		let mut group0 = track.create_group(moq_net::Group::new(0)).unwrap();
 		Container::Legacy
 			.write(
 				&mut group0,
@@ -1888,7 +1888,7 @@ mod tests {
 		let consumer_track = track.consume();
 		let mut consumer = Consumer::new(consumer_track, Container::Legacy).with_latency(Duration::from_millis(500));
 
-		let mut group0 = track.create_group(moq_net::Group { sequence: 0 }).unwrap();
+		let mut group0 = track.create_group(moq_net::Group::new(0)).unwrap();
 		group0.abort(moq_net::Error::Cancel).unwrap();
 
 		write_group(&mut track, 1, &[ts(30_000)]);
@@ -1934,7 +1934,7 @@ mod tests {
 		let consumer_track = track.consume();
 		let mut consumer = Consumer::new(consumer_track, Container::Legacy).with_latency(Duration::from_millis(500));
 
-		let mut group0 = track.create_group(moq_net::Group { sequence: 0 }).unwrap();
+		let mut group0 = track.create_group(moq_net::Group::new(0)).unwrap();
 		group0.finish().unwrap();
 
 		write_group(&mut track, 1, &[ts(30_000)]);
@@ -1955,7 +1955,7 @@ mod tests {
 		let mut consumer = Consumer::new(consumer_track, Container::Legacy).with_latency(Duration::from_millis(500));
 
 		// Write frames using Container::Legacy encoding
-		let mut group = track.create_group(moq_net::Group { sequence: 0 }).unwrap();
+		let mut group = track.create_group(moq_net::Group::new(0)).unwrap();
 		for i in 0..3u64 {
 			let frame = Frame {
 				timestamp: ts(i * 33_333),
@@ -1998,11 +1998,11 @@ mod tests {
 		let mut consumer = Consumer::new(consumer_track, DurationWire).with_latency(Duration::from_secs(10));
 
 		// Group 0: one frame at ts=0 lasting 33ms, never finished.
-		let mut group0 = track.create_group(moq_net::Group { sequence: 0 }).unwrap();
+		let mut group0 = track.create_group(moq_net::Group::new(0)).unwrap();
 		write_duration_frame(&mut group0, ts(0), ts(33_000));
 
 		// Group 1: finished, starts exactly where group 0's frame ends.
-		let mut group1 = track.create_group(moq_net::Group { sequence: 1 }).unwrap();
+		let mut group1 = track.create_group(moq_net::Group::new(1)).unwrap();
 		write_duration_frame(&mut group1, ts(33_000), ts(33_000));
 		group1.finish().unwrap();
 
@@ -2038,11 +2038,11 @@ mod tests {
 		let mut consumer = Consumer::new(consumer_track, DurationWire).with_latency(Duration::from_secs(10));
 
 		// Group 0: frame at ts=0 lasting only 10ms, far short of group 1 at 33ms.
-		let mut group0 = track.create_group(moq_net::Group { sequence: 0 }).unwrap();
+		let mut group0 = track.create_group(moq_net::Group::new(0)).unwrap();
 		write_duration_frame(&mut group0, ts(0), ts(10_000));
 
 		// Group 1: finished at 33ms.
-		let mut group1 = track.create_group(moq_net::Group { sequence: 1 }).unwrap();
+		let mut group1 = track.create_group(moq_net::Group::new(1)).unwrap();
 		write_duration_frame(&mut group1, ts(33_000), ts(33_000));
 		group1.finish().unwrap();
 		track.finish().unwrap();
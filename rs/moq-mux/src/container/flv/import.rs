@@ -679,7 +679,13 @@ fn config_from_avcc(avcc_bytes: &[u8]) -> anyhow::Result<VideoConfig> {
 fn config_from_asc(asc_bytes: &[u8]) -> anyhow::Result<AudioConfig> {
 	let mut cursor = asc_bytes;
 	let cfg = crate::codec::aac::Config::parse(&mut cursor)?;
-	let mut config = AudioConfig::new(AAC { profile: cfg.profile }, cfg.sample_rate, cfg.channel_count);
+	let mut config = AudioConfig::new(
+		AAC {
+			profile: cfg.object_type(),
+		},
+		cfg.sample_rate,
+		cfg.channel_count,
+	);
 	config.description = Some(Bytes::copy_from_slice(asc_bytes));
 	config.container = Container::Legacy;
 	Ok(config)
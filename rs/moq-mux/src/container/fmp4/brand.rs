@@ -0,0 +1,53 @@
+//! File type brands (`ftyp` / `styp`).
+//!
+//! The brands at the head of a file or segment say which specification it follows.
+//! The importer reads them into a [`Profile`] and can refuse brands whose layout it
+//! doesn't handle, before failing later on an unexpected box.
+
+use mp4_atom::FourCC;
+
+/// The fMP4 flavor declared by a file's brands, from [`Import::profile`](super::Import::profile).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[non_exhaustive]
+pub enum Profile {
+	/// Plain fragmented ISO BMFF, with no CMAF brand.
+	Fmp4,
+	/// CMAF (`cmfc`, `cmf2`, or a CMAF header, segment or fragment brand).
+	Cmaf,
+	/// CMAF carrying chunks (`cmfl`): fragments split for low-latency delivery.
+	CmafLowLatency,
+}
+
+impl Profile {
+	/// Detect the profile from a major brand and its compatible brands.
+	pub fn detect(major: FourCC, compatible: &[FourCC]) -> Self {
+		let brands = || std::iter::once(&major).chain(compatible);
+
+		if brands().any(|brand| *brand == FourCC::new(b"cmfl")) {
+			Self::CmafLowLatency
+		} else if brands().any(|brand| CMAF.iter().any(|cmaf| *brand == FourCC::new(cmaf))) {
+			Self::Cmaf
+		} else {
+			Self::Fmp4
+		}
+	}
+}
+
+// The CMAF structural brands, plus the header, segment and fragment brands.
+const CMAF: [&[u8; 4]; 5] = [b"cmfc", b"cmf2", b"cmfh", b"cmfs", b"cmff"];
+
+#[cfg(test)]
+mod test {
+	use super::*;
+
+	#[test]
+	fn detect() {
+		assert_eq!(Profile::detect(b"isom".into(), &[b"iso6".into()]), Profile::Fmp4);
+		assert_eq!(Profile::detect(b"cmfc".into(), &[]), Profile::Cmaf);
+		assert_eq!(Profile::detect(b"msdh".into(), &[b"cmfs".into()]), Profile::Cmaf);
+		assert_eq!(
+			Profile::detect(b"cmf2".into(), &[b"cmfl".into()]),
+			Profile::CmafLowLatency
+		);
+	}
+}
@@ -0,0 +1,102 @@
+//! fMP4 catalog extension (the `fmp4` section).
+//!
+//! The `fmp4` section describes tracks of an imported file that aren't audio or
+//! video, so they can still flow through as opaque CMAF fragments. Today that's
+//! timed metadata (`meta` handler): ID3 or event message tracks used for ad
+//! insertion and other in-band signaling. Event messages sent as top-level `emsg`
+//! boxes land on their own JSON track instead, named here too. Still images, a VOD
+//! poster or a thumbnail strip, are listed separately so a player can find them
+//! without parsing every metadata track.
+
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Serialize};
+
+use crate::catalog::hang::CatalogExt;
+
+/// The `fmp4` catalog section.
+///
+/// Omitted from the catalog when empty, so a broadcast without metadata tracks stays
+/// byte-identical to one without the extension.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Default)]
+#[serde(rename_all = "camelCase")]
+#[non_exhaustive]
+pub struct Fmp4 {
+	/// Timed metadata tracks, keyed by MoQ track name.
+	#[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+	pub metadata: BTreeMap<String, Metadata>,
+
+	/// The track carrying top-level `emsg` boxes, a [`moq_json::stream`] of
+	/// [`Event`](super::Event) records (uncompressed). Present once the first event arrives.
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub events: Option<String>,
+
+	/// Still image tracks, keyed by MoQ track name.
+	#[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+	pub images: BTreeMap<String, Image>,
+}
+
+impl Fmp4 {
+	/// True when the section carries nothing, so it's omitted from the catalog.
+	pub fn is_empty(&self) -> bool {
+		self.metadata.is_empty() && self.events.is_none() && self.images.is_empty()
+	}
+}
+
+/// A timed metadata track, carried as one moof+mdat fragment per frame.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct Metadata {
+	/// The sample entry four-character code (e.g. `mett`, `metx`, `urim`, `evte`),
+	/// telling a consumer how to interpret the samples.
+	pub codec: String,
+
+	/// The single-track init segment, as for a CMAF media track.
+	pub container: hang::catalog::Container,
+}
+
+/// A still image track: a `pict` track, or a video track of `jpeg` or `png ` samples.
+///
+/// Each frame is one moof+mdat fragment holding a single sample, a complete image, and
+/// starts its own group. A poster is a track with a single frame; a thumbnail track has
+/// one frame per image, timed at the media time it depicts, so a player seeking to a
+/// time shows the image of the group covering it.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct Image {
+	/// The sample entry four-character code, e.g. `jpeg` or `png `.
+	pub codec: String,
+
+	/// The single-track init segment, as for a CMAF media track.
+	pub container: hang::catalog::Container,
+}
+
+/// The application catalog extension carrying the `fmp4` section. Empty by
+/// default, so the section is omitted until a metadata track is imported.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Default)]
+pub struct Ext {
+	#[serde(default, skip_serializing_if = "Fmp4::is_empty")]
+	pub fmp4: Fmp4,
+}
+
+impl CatalogExt for Ext {}
+
+/// Typed `&mut` access to the `fmp4` section of a catalog whose extension is
+/// [`Ext`], or `None` for any other extension, in which case the importer skips
+/// metadata tracks.
+pub(crate) fn fmp4_mut<E: CatalogExt>(catalog: &mut crate::catalog::hang::Catalog<E>) -> Option<&mut Fmp4> {
+	(&mut catalog.ext as &mut dyn std::any::Any)
+		.downcast_mut::<Ext>()
+		.map(|ext| &mut ext.fmp4)
+}
+
+#[cfg(test)]
+mod test {
+	use super::*;
+
+	#[test]
+	fn empty_section_omitted() {
+		let ext = Ext::default();
+		assert_eq!(serde_json::to_string(&ext).unwrap(), "{}");
+	}
+}
@@ -0,0 +1,109 @@
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use hang::catalog::{AudioConfig, VideoConfig};
+use mp4_atom::{Atom, FourCC, Trak};
+
+use crate::Result;
+
+type VideoHandler = Arc<dyn Fn(&Trak) -> Result<VideoConfig> + Send + Sync>;
+type AudioHandler = Arc<dyn Fn(&Trak) -> Result<AudioConfig> + Send + Sync>;
+
+/// Caller-provided codec handlers for CMAF import, keyed by sample entry fourcc.
+///
+/// [`Import`](super::Import) consults these before its built-in codecs, so a handler
+/// can add a codec mp4-atom doesn't know (it decodes as `Codec::Unknown`) or replace
+/// the built-in mapping for a known one. The handler gets the whole `trak` and builds
+/// the catalog config; the importer fills in the CMAF `container` afterwards.
+#[derive(Clone, Default)]
+pub struct Codecs {
+	video: HashMap<FourCC, VideoHandler>,
+	audio: HashMap<FourCC, AudioHandler>,
+}
+
+impl Codecs {
+	/// Create an empty registry; every sample entry falls back to the built-in codecs.
+	pub fn new() -> Self {
+		Self::default()
+	}
+
+	/// Map a video sample entry fourcc to a [`VideoConfig`] builder.
+	pub fn with_video<F>(mut self, fourcc: impl Into<FourCC>, handler: F) -> Self
+	where
+		F: Fn(&Trak) -> Result<VideoConfig> + Send + Sync + 'static,
+	{
+		self.video.insert(fourcc.into(), Arc::new(handler));
+		self
+	}
+
+	/// Map an audio sample entry fourcc to an [`AudioConfig`] builder.
+	pub fn with_audio<F>(mut self, fourcc: impl Into<FourCC>, handler: F) -> Self
+	where
+		F: Fn(&Trak) -> Result<AudioConfig> + Send + Sync + 'static,
+	{
+		self.audio.insert(fourcc.into(), Arc::new(handler));
+		self
+	}
+
+	/// Run the video handler registered for `codec`, if any.
+	pub(super) fn video(&self, codec: &mp4_atom::Codec, trak: &Trak) -> Option<Result<VideoConfig>> {
+		if self.video.is_empty() {
+			return None;
+		}
+		let handler = self.video.get(&fourcc(codec)?)?;
+		Some(handler(trak))
+	}
+
+	/// Run the audio handler registered for `codec`, if any.
+	pub(super) fn audio(&self, codec: &mp4_atom::Codec, trak: &Trak) -> Option<Result<AudioConfig>> {
+		if self.audio.is_empty() {
+			return None;
+		}
+		let handler = self.audio.get(&fourcc(codec)?)?;
+		Some(handler(trak))
+	}
+}
+
+impl std::fmt::Debug for Codecs {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		f.debug_struct("Codecs")
+			.field("video", &self.video.keys().collect::<Vec<_>>())
+			.field("audio", &self.audio.keys().collect::<Vec<_>>())
+			.finish()
+	}
+}
+
+/// The sample entry fourcc of `codec`, or `None` for a variant this version doesn't know.
+pub(super) fn fourcc(codec: &mp4_atom::Codec) -> Option<FourCC> {
+	use mp4_atom::Codec;
+
+	Some(match codec {
+		Codec::Unknown(fourcc) => *fourcc,
+		Codec::Avc1(_) => mp4_atom::Avc1::KIND,
+		Codec::Hev1(_) => mp4_atom::Hev1::KIND,
+		Codec::Hvc1(_) => mp4_atom::Hvc1::KIND,
+		Codec::Vp08(_) => mp4_atom::Vp08::KIND,
+		Codec::Vp09(_) => mp4_atom::Vp09::KIND,
+		Codec::Av01(_) => mp4_atom::Av01::KIND,
+		Codec::Mp4a(_) => mp4_atom::Mp4a::KIND,
+		Codec::Tx3g(_) => mp4_atom::Tx3g::KIND,
+		Codec::Opus(_) => mp4_atom::Opus::KIND,
+		Codec::Uncv(_) => mp4_atom::Uncv::KIND,
+		Codec::Flac(_) => mp4_atom::Flac::KIND,
+		Codec::Ac3(_) => mp4_atom::Ac3::KIND,
+		Codec::Eac3(_) => mp4_atom::Eac3::KIND,
+		Codec::Ipcm(_) => mp4_atom::Ipcm::KIND,
+		Codec::Fpcm(_) => mp4_atom::Fpcm::KIND,
+		Codec::Sowt(_) => mp4_atom::Sowt::KIND,
+		Codec::Twos(_) => mp4_atom::Twos::KIND,
+		Codec::Lpcm(_) => mp4_atom::Lpcm::KIND,
+		Codec::In24(_) => mp4_atom::In24::KIND,
+		Codec::In32(_) => mp4_atom::In32::KIND,
+		Codec::Fl32(_) => mp4_atom::Fl32::KIND,
+		Codec::Fl64(_) => mp4_atom::Fl64::KIND,
+		Codec::S16l(_) => mp4_atom::S16l::KIND,
+		Codec::Wvtt(_) => mp4_atom::Wvtt::KIND,
+		Codec::Samr(_) => mp4_atom::Samr::KIND,
+		_ => return None,
+	})
+}
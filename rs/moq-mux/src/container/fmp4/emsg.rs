@@ -0,0 +1,228 @@
+//! DASH event message (`emsg`) boxes.
+//!
+//! An `emsg` box sits between fragments and carries an out-of-band event: an SCTE-35
+//! splice for ad insertion, an ID3 tag, a manifest refresh, ... The importer decodes
+//! each one into an [`Event`] on the media timeline and publishes it on the broadcast's
+//! event track, advertised in the [`fmp4`](super::catalog) catalog section.
+
+use bytes::Bytes;
+use serde::{Deserialize, Serialize};
+use serde_with::base64::Base64;
+use serde_with::serde_as;
+
+use super::Error;
+use crate::container::Timestamp;
+
+/// A timed event decoded from an `emsg` box, one JSON record on the event track.
+#[serde_as]
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct Event {
+	/// Identifies the message scheme, e.g. `urn:scte:scte35:2013:bin` for binary SCTE-35.
+	pub scheme_id_uri: String,
+
+	/// A scheme-specific value, often a sub-type of the scheme.
+	pub value: String,
+
+	/// When the event takes effect, on the same timeline as the media frames (microseconds).
+	pub timestamp: Timestamp,
+
+	/// How long the event lasts, or `None` if unknown.
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub duration: Option<Timestamp>,
+
+	/// Identifies the event within its scheme; repeats of the same event share it.
+	pub id: u32,
+
+	/// The scheme-specific payload, base64-encoded in JSON.
+	#[serde_as(as = "Base64")]
+	pub message_data: Bytes,
+}
+
+/// An `emsg` box before its timing is resolved against the media timeline.
+#[derive(Debug, Clone, PartialEq)]
+pub(super) struct Emsg {
+	pub scheme_id_uri: String,
+	pub value: String,
+	pub timescale: u32,
+	pub time: EmsgTime,
+	// 0xFFFFFFFF means unknown.
+	pub event_duration: u32,
+	pub id: u32,
+	pub message_data: Bytes,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(super) enum EmsgTime {
+	/// Version 0: relative to the earliest presentation time of the next fragment.
+	Delta(u32),
+	/// Version 1: an absolute presentation time.
+	Absolute(u64),
+}
+
+impl Emsg {
+	/// Decode a whole `emsg` box, header included.
+	pub fn decode(data: &[u8]) -> Result<Self, Error> {
+		let mut buf = data;
+
+		let size = take_u32(&mut buf)?;
+		if buf.get(..4) != Some(b"emsg") {
+			return Err(Error::InvalidEmsg);
+		}
+		buf = &buf[4..];
+		if size == 1 {
+			// A 64-bit largesize; the box runs to the end of `data` either way.
+			take(&mut buf, 8)?;
+		}
+
+		let version = take(&mut buf, 4)?[0];
+		match version {
+			0 => {
+				let scheme_id_uri = take_str(&mut buf)?;
+				let value = take_str(&mut buf)?;
+				let timescale = take_u32(&mut buf)?;
+				let delta = take_u32(&mut buf)?;
+				let event_duration = take_u32(&mut buf)?;
+				let id = take_u32(&mut buf)?;
+
+				Ok(Self {
+					scheme_id_uri,
+					value,
+					timescale,
+					time: EmsgTime::Delta(delta),
+					event_duration,
+					id,
+					message_data: Bytes::copy_from_slice(buf),
+				})
+			}
+			1 => {
+				let timescale = take_u32(&mut buf)?;
+				let time = u64::from_be_bytes(take(&mut buf, 8)?.try_into().unwrap());
+				let event_duration = take_u32(&mut buf)?;
+				let id = take_u32(&mut buf)?;
+				let scheme_id_uri = take_str(&mut buf)?;
+				let value = take_str(&mut buf)?;
+
+				Ok(Self {
+					scheme_id_uri,
+					value,
+					timescale,
+					time: EmsgTime::Absolute(time),
+					event_duration,
+					id,
+					message_data: Bytes::copy_from_slice(buf),
+				})
+			}
+			_ => Err(Error::InvalidEmsg),
+		}
+	}
+
+	/// Resolve the event's timing, given the earliest presentation time of the
+	/// fragment it precedes (only consulted by version 0).
+	pub fn event(self, earliest: Timestamp) -> Result<Event, Error> {
+		if self.timescale == 0 {
+			return Err(Error::InvalidEmsg);
+		}
+		let timescale = self.timescale as u64;
+
+		let timestamp = match self.time {
+			EmsgTime::Delta(delta) => earliest.checked_add(Timestamp::from_scale(delta as u64, timescale)?)?,
+			EmsgTime::Absolute(time) => Timestamp::from_scale(time, timescale)?,
+		};
+		let duration = match self.event_duration {
+			u32::MAX => None,
+			duration => Some(Timestamp::from_scale(duration as u64, timescale)?),
+		};
+
+		Ok(Event {
+			scheme_id_uri: self.scheme_id_uri,
+			value: self.value,
+			timestamp,
+			duration,
+			id: self.id,
+			message_data: self.message_data,
+		})
+	}
+}
+
+fn take<'a>(buf: &mut &'a [u8], size: usize) -> Result<&'a [u8], Error> {
+	if buf.len() < size {
+		return Err(Error::InvalidEmsg);
+	}
+	let (head, tail) = buf.split_at(size);
+	*buf = tail;
+	Ok(head)
+}
+
+fn take_u32(buf: &mut &[u8]) -> Result<u32, Error> {
+	Ok(u32::from_be_bytes(take(buf, 4)?.try_into().unwrap()))
+}
+
+// A null-terminated UTF-8 string.
+fn take_str(buf: &mut &[u8]) -> Result<String, Error> {
+	let end = buf.iter().position(|&b| b == 0).ok_or(Error::InvalidEmsg)?;
+	let s = std::str::from_utf8(&buf[..end])
+		.map_err(|_| Error::InvalidEmsg)?
+		.to_string();
+	*buf = &buf[end + 1..];
+	Ok(s)
+}
+
+#[cfg(test)]
+pub(super) mod test {
+	use super::*;
+
+	/// Encode an `emsg` box of the given version.
+	pub fn emsg(version: u8, time: u64, duration: u32, data: &[u8]) -> Vec<u8> {
+		let mut body = vec![version, 0, 0, 0];
+		let strings = b"urn:scte:scte35:2013:bin\0\0";
+		match version {
+			0 => {
+				body.extend_from_slice(strings);
+				body.extend_from_slice(&90_000u32.to_be_bytes());
+				body.extend_from_slice(&(time as u32).to_be_bytes());
+				body.extend_from_slice(&duration.to_be_bytes());
+				body.extend_from_slice(&7u32.to_be_bytes());
+			}
+			_ => {
+				body.extend_from_slice(&90_000u32.to_be_bytes());
+				body.extend_from_slice(&time.to_be_bytes());
+				body.extend_from_slice(&duration.to_be_bytes());
+				body.extend_from_slice(&7u32.to_be_bytes());
+				body.extend_from_slice(strings);
+			}
+		}
+		body.extend_from_slice(data);
+
+		let mut out = ((body.len() + 8) as u32).to_be_bytes().to_vec();
+		out.extend_from_slice(b"emsg");
+		out.extend(body);
+		out
+	}
+
+	#[test]
+	fn decode_v1() {
+		let emsg = Emsg::decode(&emsg(1, 180_000, 90_000, b"cue")).unwrap();
+		let event = emsg.event(Timestamp::ZERO).unwrap();
+		assert_eq!(event.scheme_id_uri, "urn:scte:scte35:2013:bin");
+		assert_eq!(event.value, "");
+		assert_eq!(event.timestamp, Timestamp::from_secs_unchecked(2));
+		assert_eq!(event.duration, Some(Timestamp::from_secs_unchecked(1)));
+		assert_eq!(event.id, 7);
+		assert_eq!(event.message_data, Bytes::from_static(b"cue"));
+	}
+
+	#[test]
+	fn decode_v0_is_relative() {
+		let emsg = Emsg::decode(&emsg(0, 45_000, u32::MAX, b"")).unwrap();
+		let event = emsg.event(Timestamp::from_secs_unchecked(10)).unwrap();
+		assert_eq!(event.timestamp, Timestamp::from_millis_unchecked(10_500));
+		assert_eq!(event.duration, None);
+	}
+
+	#[test]
+	fn truncated_is_invalid() {
+		let data = emsg(1, 0, 0, b"");
+		assert!(Emsg::decode(&data[..20]).is_err());
+	}
+}
@@ -134,6 +134,7 @@ async fn legacy_aac_source_to_cmaf_export_synthesizes_esds() {
 		profile: 2,
 		sample_rate: 44100,
 		channel_count: 2,
+		extension: None,
 	}
 	.encode();
 	let mut config = AudioConfig::new(AAC { profile: 2 }, 44100, 2);
@@ -4,13 +4,33 @@ use mp4_atom::{Any, Atom, DecodeMaybe, Encode, Mdat, Moof, Moov, Trak};
 use std::collections::{HashMap, HashSet};
 
 use super::Error;
+use super::emsg::{Emsg, EmsgTime};
 use crate::Result;
 use crate::container::Timestamp;
 
+/// Where [`Import`] starts a new MoQ group, set with [`Import::with_grouping`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[non_exhaustive]
+pub enum Grouping {
+	/// Start a group at each fragment containing a keyframe, so every group is independently
+	/// decodable and a subscriber can join or seek at any group.
+	#[default]
+	Keyframe,
+
+	/// Start a group at every fragment, for the lowest latency and per-fragment priority.
+	///
+	/// A group without a keyframe depends on the ones before it, so a subscriber must
+	/// start at a keyframe group; only those are recorded in the seek index. The first
+	/// group still has to start with a keyframe, as with [`Grouping::Keyframe`].
+	Fragment,
+}
+
 /// Converts fMP4/CMAF files into MoQ broadcast streams using CMAF passthrough.
 ///
 /// This struct processes fragmented MP4 (fMP4) files and transports complete
 /// moof+mdat fragments directly as MoQ frames, preserving the CMAF container format.
+/// For low-latency CMAF, a partially received mdat is forwarded sample by sample as
+/// the bytes arrive, each batch as its own moof+mdat frame.
 ///
 /// ## Supported Codecs
 ///
@@ -25,6 +45,19 @@ use crate::container::Timestamp;
 /// - AAC (MP4A)
 /// - Opus
 /// - FLAC
+///
+/// Other sample entries can be handled by registering them with [`Import::with_codecs`].
+///
+/// **Timed metadata** (`meta` handler, e.g. ID3 or event messages) is passed through as
+/// opaque fragments and described in the [`fmp4`](super::catalog) catalog section, when
+/// the catalog carries it; otherwise it's skipped. Top-level `emsg` boxes are decoded into
+/// [`Event`](super::Event)s on a separate event track, advertised in the same section.
+/// **Still images** (`pict` handler, or `jpeg`/`png ` video samples) are passed through the
+/// same way, as a poster or thumbnail track listed under [`images`](super::catalog::Fmp4::images).
+///
+/// Each audio and video group open is recorded in the rendition's seek
+/// [`Index`](crate::timeline::Index), read back through
+/// [`catalog::Producer::index`](crate::catalog::Producer::index).
 pub struct Import<E: crate::catalog::hang::CatalogExt = ()> {
 	/// The broadcast being produced
 	broadcast: moq_net::BroadcastProducer,
@@ -35,6 +68,36 @@ pub struct Import<E: crate::catalog::hang::CatalogExt = ()> {
 	// Which track roles to publish. `None` imports every supported track.
 	select: Option<crate::select::Broadcast>,
 
+	// Caller-registered codec handlers, consulted before the built-in ones.
+	codecs: super::Codecs,
+
+	// Brands refused in an ftyp/styp, set by `with_rejected_brands`.
+	rejected_brands: Vec<mp4_atom::FourCC>,
+
+	// The strongest profile declared by any ftyp/styp so far.
+	profile: Option<super::Profile>,
+
+	// Delivery priority of the video and audio tracks, set by `with_video_priority` and
+	// `with_audio_priority`.
+	video_priority: u8,
+	audio_priority: u8,
+
+	// Where groups start, set by `with_grouping`.
+	grouping: Grouping,
+
+	// How long an audio group spans when there's no video, set by `with_audio_group_duration`.
+	audio_group_duration: Option<std::time::Duration>,
+
+	// How much recent history each track keeps for late subscribers, set by `with_cache_*`.
+	cache_age: Option<std::time::Duration>,
+	cache_groups: Option<usize>,
+
+	// A delay added to each track's timestamps by track ID, set by `set_track_offset`.
+	offsets: HashMap<u32, std::time::Duration>,
+
+	// Catalog alternate groups by track ID, set by `set_track_alt_group`.
+	alt_groups: HashMap<u32, u32>,
+
 	// A lookup to tracks in the broadcast
 	tracks: HashMap<u32, Fmp4Track>,
 
@@ -45,19 +108,43 @@ pub struct Import<E: crate::catalog::hang::CatalogExt = ()> {
 	// The moov atom at the start of the file.
 	moov: Option<Moov>,
 
-	// The latest moof header
+	// The latest moof header, paired with the next mdat and cleared once it's extracted.
 	moof: Option<Moof>,
 	moof_size: usize,
+	// Where the moof starts in the input, to resolve an absolute tfhd base_data_offset.
+	moof_position: u64,
+	// Each traf's tfhd default-base-is-moof flag, in order; mp4_atom doesn't decode it.
+	moof_base_is_moof: Vec<bool>,
+
+	// Bytes of input parsed so far, before `buffer`.
+	consumed: u64,
+
+	// Samples per track already emitted from a partially buffered mdat, cleared with the moof.
+	emitted: HashMap<u32, usize>,
+
+	// The event track for `emsg` boxes, created on the first one.
+	events: Option<moq_json::stream::Producer<super::Event>>,
+
+	// Version 0 `emsg` boxes, timed relative to the next moof and held until it arrives.
+	pending_events: Vec<Emsg>,
+
+	// The newest timestamp written by this importer alone, unlike the catalog's.
+	progress: crate::container::Progress,
 
 	// Bytes carried across calls: a partial atom at the tail of one `decode` waits
 	// here for the rest to arrive on the next call.
 	buffer: BytesMut,
 }
 
+// Sample entries of a `vide` track that carry still images rather than video.
+const IMAGE_CODECS: &[&str] = &["jpeg", "png "];
+
 #[derive(PartialEq, Debug)]
 enum TrackKind {
 	Video,
 	Audio,
+	Metadata,
+	Image,
 }
 
 struct Fmp4Track {
@@ -77,6 +164,15 @@ struct Fmp4Track {
 
 	// Sequence to use for the next group, set by `Import::seek`.
 	pending_sequence: Option<u64>,
+
+	// The timestamp of the current group's first fragment.
+	group_start: Option<Timestamp>,
+
+	// The decode time the previous fragment ended at, to detect a discontinuity.
+	next_dts: Option<u64>,
+
+	// The rendition's seek index, fed each group open. `None` for metadata tracks.
+	index: Option<crate::timeline::Index>,
 }
 
 impl<E: crate::catalog::hang::CatalogExt> Import<E> {
@@ -87,11 +183,29 @@ impl<E: crate::catalog::hang::CatalogExt> Import<E> {
 		Self {
 			catalog,
 			select: None,
+			codecs: super::Codecs::default(),
+			rejected_brands: Vec::new(),
+			profile: None,
+			video_priority: 0,
+			audio_priority: 0,
+			grouping: Grouping::default(),
+			audio_group_duration: None,
+			cache_age: None,
+			cache_groups: None,
+			offsets: HashMap::default(),
+			alt_groups: HashMap::default(),
 			tracks: HashMap::default(),
 			skipped: HashSet::default(),
 			moov: None,
 			moof: None,
 			moof_size: 0,
+			moof_position: 0,
+			moof_base_is_moof: Vec::new(),
+			consumed: 0,
+			emitted: HashMap::default(),
+			events: None,
+			pending_events: Vec::new(),
+			progress: Default::default(),
 			broadcast,
 			buffer: BytesMut::new(),
 		}
@@ -110,12 +224,123 @@ impl<E: crate::catalog::hang::CatalogExt> Import<E> {
 		self
 	}
 
+	/// Register extra codec handlers, consulted before the built-in codecs.
+	///
+	/// [`Error::UnknownCodec`] / [`Error::UnsupportedCodec`] are only returned when
+	/// neither a handler nor a built-in codec matches the sample entry.
+	pub fn with_codecs(mut self, codecs: super::Codecs) -> Self {
+		self.codecs = codecs;
+		self
+	}
+
+	/// Refuse input whose `ftyp` or `styp` lists any of `brands`, as the major brand or a
+	/// compatible one, with [`Error::UnsupportedBrand`].
+	///
+	/// Nothing is refused by default. Useful to fail fast on a layout the importer doesn't
+	/// parse, e.g. `sisx` DASH segments whose subsegment index (`ssix`) it ignores.
+	pub fn with_rejected_brands(mut self, brands: impl IntoIterator<Item = mp4_atom::FourCC>) -> Self {
+		self.rejected_brands = brands.into_iter().collect();
+		self
+	}
+
+	/// Create video tracks with the given delivery priority, `0` by default.
+	///
+	/// Higher values preempt lower ones when bandwidth is constrained, so e.g. an audio
+	/// priority above the video one keeps the audio flowing while video stalls.
+	pub fn with_video_priority(mut self, priority: u8) -> Self {
+		self.video_priority = priority;
+		self
+	}
+
+	/// Create audio tracks with the given delivery priority, `0` by default.
+	///
+	/// See [`with_video_priority`](Self::with_video_priority).
+	pub fn with_audio_priority(mut self, priority: u8) -> Self {
+		self.audio_priority = priority;
+		self
+	}
+
+	/// Choose where groups start: at keyframes (the default) or at every fragment.
+	///
+	/// See [`Grouping`]. With [`Grouping::Fragment`], [`Self::with_audio_group_duration`]
+	/// has no effect.
+	pub fn with_grouping(mut self, grouping: Grouping) -> Self {
+		self.grouping = grouping;
+		self
+	}
+
+	/// Group audio-only input into groups of at least `duration`, rather than one per fragment.
+	///
+	/// Every audio fragment is independently decodable, so by default each one opens a new
+	/// group: the finest seek granularity, but a group per 20ms fragment adds up for a
+	/// low-latency source. With a duration, fragments join the open group until it spans
+	/// that long. Only applies when no video track is imported (none in the file, or none
+	/// selected); alongside video, audio keeps a group per fragment.
+	pub fn with_audio_group_duration(mut self, duration: std::time::Duration) -> Self {
+		self.audio_group_duration = Some(duration);
+		self
+	}
+
+	/// Keep each track's groups cached for `age`, so a late subscriber starts with that much
+	/// recent history instead of waiting for the next group.
+	///
+	/// See [`moq_net::TrackProducer::set_cache_age`]; the default is 5 seconds.
+	pub fn with_cache_age(mut self, age: std::time::Duration) -> Self {
+		self.cache_age = Some(age);
+		self
+	}
+
+	/// Cache at most `groups` groups per track, bounding the memory [`Self::with_cache_age`] uses.
+	///
+	/// See [`moq_net::TrackProducer::set_cache_groups`].
+	pub fn with_cache_groups(mut self, groups: usize) -> Self {
+		self.cache_groups = Some(groups);
+		self
+	}
+
+	/// Delay every timestamp of the track with ID `track_id` by `offset`.
+	///
+	/// Corrects a known, constant A/V offset, e.g. from an edit list or encoder delay that
+	/// this importer doesn't otherwise apply: the fragment's decode time is rewritten, so
+	/// consumers see the aligned timeline. To move a track earlier, delay the others.
+	/// Takes effect from the next fragment.
+	pub fn set_track_offset(&mut self, track_id: u32, offset: std::time::Duration) {
+		self.offsets.insert(track_id, offset);
+	}
+
+	/// Put the track with ID `track_id` in catalog alternate group `group`.
+	///
+	/// Renditions sharing a group are the same content at different qualities (an ABR
+	/// ladder), which a player switches between. By default the group is the track's
+	/// `tkhd` alternate group, if non-zero. Must be set before the `moov` is decoded.
+	pub fn set_track_alt_group(&mut self, track_id: u32, group: u32) {
+		self.alt_groups.insert(track_id, group);
+	}
+
+	/// How far this importer has written: the timestamp of its newest frame.
+	///
+	/// Unlike [`catalog::Producer::progress`](crate::catalog::Producer::progress), which covers
+	/// every importer sharing the catalog, this only follows the input fed to this one.
+	pub fn progress(&self) -> crate::container::Progress {
+		self.progress.clone()
+	}
+
+	/// The profile declared by the input's brands, or `None` before the first `ftyp`/`styp`.
+	///
+	/// When segments declare brands of their own, the strongest profile seen wins, so a CMAF
+	/// file stays [`Cmaf`](super::Profile::Cmaf) across plain `msdh` segments.
+	pub fn profile(&self) -> Option<super::Profile> {
+		self.profile
+	}
+
 	/// Whether `kind` is selected for import (every role when unset).
 	fn selects(&self, kind: &TrackKind) -> bool {
 		match (&self.select, kind) {
 			(None, _) => true,
 			(Some(select), TrackKind::Video) => select.has_video(),
 			(Some(select), TrackKind::Audio) => select.has_audio(),
+			// Not a role `select` can pick, so a selection leaves it out.
+			(Some(_), TrackKind::Metadata | TrackKind::Image) => false,
 		}
 	}
 
@@ -125,11 +350,22 @@ impl<E: crate::catalog::hang::CatalogExt> Import<E> {
 		self.drain()
 	}
 
+	/// Whether the input so far ends partway through an atom or a moof/mdat pair.
+	///
+	/// A partial atom is buffered, not an error: a reader tailing a file that is still
+	/// being written can wait for more bytes and call [`decode`](Self::decode) again.
+	/// Only at a true end of stream does it mean the last fragment was truncated.
+	pub fn is_partial(&self) -> bool {
+		!self.buffer.is_empty() || self.moof.is_some()
+	}
+
 	/// Parse every whole top-level atom buffered so far, leaving any trailing
 	/// partial atom for the next call.
+	///
+	/// A trailing partial mdat still has its complete samples emitted early.
 	fn drain(&mut self) -> Result<()> {
-		// Parse complete atoms first, recording each one's byte range, then process
-		// them. Collecting up front keeps `self.buffer` un-borrowed while the handlers
+		// Parse complete atoms first, recording each one's size, then process them.
+		// Collecting up front keeps `self.buffer` un-borrowed while the handlers
 		// (`init`/`extract`) take `&mut self`.
 		let mut parsed = Vec::new();
 		let mut position = 0;
@@ -144,38 +380,50 @@ impl<E: crate::catalog::hang::CatalogExt> Import<E> {
 		}
 
 		if position == 0 {
-			return Ok(());
+			return self.extract_partial();
 		}
 
-		// Detach the fully-parsed prefix as a cheap ref-counted buffer so each mdat's
-		// raw bytes can be sliced out without copying or borrowing `self`.
-		let consumed = self.buffer.split_to(position).freeze();
+		let raw = self.buffer.split_to(position).freeze();
+		let raw_position = self.consumed;
+		self.consumed += position as u64;
 
 		for (atom, start, size) in parsed {
 			match atom {
-				Any::Ftyp(_) | Any::Styp(_) => {}
+				Any::Ftyp(ftyp) => self.brands(ftyp.major_brand, &ftyp.compatible_brands)?,
+				Any::Styp(styp) => self.brands(styp.major_brand, &styp.compatible_brands)?,
 				Any::Moov(moov) => {
 					self.init(moov)?;
 				}
 				Any::Moof(moof) => {
+					// Strict moof→mdat pairing: a moof without its own mdat has no samples we
+					// can locate, so the newer moof replaces it rather than failing the stream.
 					if self.moof.is_some() {
-						return Err(Error::DuplicateMoof.into());
+						tracing::warn!("moof without an mdat, dropping it");
 					}
+					self.flush_events(&moof)?;
 					self.moof.replace(moof);
 					self.moof_size = size;
+					self.moof_position = raw_position + start as u64;
+					self.moof_base_is_moof = default_base_is_moof(&raw[start..start + size]);
+					self.emitted.clear();
 				}
 				Any::Mdat(mdat) => {
-					let raw = consumed.slice(start..start + size);
-					self.extract(mdat, &raw)?;
+					let header_size = size - mdat.data.len();
+					self.extract(&mdat.data, header_size, true)?;
+				}
+				_ if &raw[start + 4..start + 8] == b"emsg" => {
+					self.emsg(&raw[start..start + size])?;
 				}
 				_ => {
 					// Skip unknown atoms (e.g., sidx, which is optional and used for segment indexing)
-					// These are safe to ignore and don't affect playback
+					// These are safe to ignore and don't affect playback. A sidx maps time to input
+					// bytes, but the input is pushed to us, not read at an offset, and the seek index
+					// maps time to group sequences that only exist once each fragment is published.
 				}
 			}
 		}
 
-		Ok(())
+		self.extract_partial()
 	}
 
 	fn init(&mut self, moov: Moov) -> Result<()> {
@@ -189,8 +437,13 @@ impl<E: crate::catalog::hang::CatalogExt> Import<E> {
 			let suffix = ".m4s";
 
 			let kind = match handler.as_ref() {
+				b"vide" if Self::sample_entry(trak).is_ok_and(|codec| IMAGE_CODECS.contains(&codec.as_str())) => {
+					TrackKind::Image
+				}
 				b"vide" => TrackKind::Video,
+				b"pict" => TrackKind::Image,
 				b"soun" => TrackKind::Audio,
+				b"meta" => TrackKind::Metadata,
 				b"sbtl" => return Err(Error::UnsupportedSubtitle.into()),
 				handler => {
 					let mut buf = [0u8; 4];
@@ -206,23 +459,73 @@ impl<E: crate::catalog::hang::CatalogExt> Import<E> {
 				continue;
 			}
 
+			// Metadata and images can only be described by a catalog that carries the `fmp4` section.
+			if matches!(kind, TrackKind::Metadata | TrackKind::Image)
+				&& super::catalog::fmp4_mut(&mut catalog).is_none()
+			{
+				tracing::debug!(track_id, ?kind, "track without `fmp4` catalog support; skipping");
+				self.skipped.insert(track_id);
+				continue;
+			}
+
 			// Declare the track at the fMP4's native timescale. Frame timestamps are
 			// emitted at this same scale (see below), so they satisfy the track's
 			// timescale invariant and ride the wire for the relay, redundant with the
 			// timing already inside each CMAF fragment.
-			let track = self.broadcast.unique_track(suffix)?;
+			let priority = match kind {
+				TrackKind::Video => self.video_priority,
+				TrackKind::Audio => self.audio_priority,
+				TrackKind::Metadata | TrackKind::Image => 0,
+			};
+			let track = self.broadcast.unique_track_with_priority(suffix, priority)?;
+			if let Some(age) = self.cache_age {
+				track.set_cache_age(age);
+			}
+			if let Some(groups) = self.cache_groups {
+				track.set_cache_groups(groups);
+			}
+			let duration = Self::duration(trak, &moov);
+			let alt_group = match self.alt_groups.get(&track_id) {
+				Some(group) => Some(*group),
+				None => Some(trak.tkhd.alternate_group as u32).filter(|group| *group != 0),
+			};
 
 			match kind {
 				TrackKind::Video => {
-					let config = self.init_video(trak, &moov)?;
+					let mut config = self.init_video(trak, &moov)?;
+					config.duration = duration;
+					config.alt_group = alt_group;
 					catalog.video.renditions.insert(track.name().to_string(), config);
 				}
 				TrackKind::Audio => {
-					let config = self.init_audio(trak, &moov)?;
+					let mut config = self.init_audio(trak, &moov)?;
+					config.duration = duration;
+					config.alt_group = alt_group;
 					catalog.audio.renditions.insert(track.name().to_string(), config);
 				}
+				TrackKind::Metadata => {
+					let metadata = super::catalog::Metadata {
+						codec: Self::sample_entry(trak)?,
+						container: self.container(trak, &moov)?,
+					};
+					if let Some(fmp4) = super::catalog::fmp4_mut(&mut catalog) {
+						fmp4.metadata.insert(track.name().to_string(), metadata);
+					}
+				}
+				TrackKind::Image => {
+					let image = super::catalog::Image {
+						codec: Self::sample_entry(trak)?,
+						container: self.container(trak, &moov)?,
+					};
+					if let Some(fmp4) = super::catalog::fmp4_mut(&mut catalog) {
+						fmp4.images.insert(track.name().to_string(), image);
+					}
+				}
 			}
 
+			// Index media group opens so a seek can find the keyframe group covering a time.
+			let index = matches!(kind, TrackKind::Video | TrackKind::Audio).then(|| self.catalog.index(track.name()));
+
 			self.tracks.insert(
 				track_id,
 				Fmp4Track {
@@ -233,6 +536,9 @@ impl<E: crate::catalog::hang::CatalogExt> Import<E> {
 					last_timestamp: None,
 					min_duration: None,
 					pending_sequence: None,
+					group_start: None,
+					next_dts: None,
+					index,
 				},
 			);
 		}
@@ -244,6 +550,54 @@ impl<E: crate::catalog::hang::CatalogExt> Import<E> {
 		Ok(())
 	}
 
+	/// The total duration of a track, or `None` for live content.
+	///
+	/// A progressive file records it in `mdhd` (track timescale), while a fragmented one
+	/// usually leaves that at zero and may record the whole presentation in `mehd` or
+	/// `mvhd` (movie timescale) instead. Zero and all-ones both mean unknown.
+	fn duration(trak: &Trak, moov: &Moov) -> Option<moq_net::Time> {
+		let known = |duration: u64, timescale: u32| {
+			if duration == 0 || duration == u32::MAX as u64 || duration == u64::MAX || timescale == 0 {
+				return None;
+			}
+			moq_net::Time::from_scale(duration, timescale as u64).ok()
+		};
+
+		let mdhd = &trak.mdia.mdhd;
+		let mehd = moov.mvex.as_ref().and_then(|mvex| mvex.mehd.as_ref());
+
+		known(mdhd.duration, mdhd.timescale)
+			.or_else(|| known(mehd?.fragment_duration, moov.mvhd.timescale))
+			.or_else(|| known(moov.mvhd.duration, moov.mvhd.timescale))
+	}
+
+	/// The four-character code of a track's single sample entry.
+	fn sample_entry(trak: &Trak) -> Result<String> {
+		let stsd = &trak.mdia.minf.stbl.stsd;
+		let codec = match stsd.codecs.len() {
+			0 => return Err(Error::MissingCodec.into()),
+			1 => &stsd.codecs[0],
+			_ => return Err(Error::MultipleCodecs.into()),
+		};
+
+		let fourcc = super::codecs::fourcc(codec).ok_or(Error::MissingCodec)?;
+		Ok(fourcc.to_string())
+	}
+
+	// Check an ftyp/styp against the rejected brands and fold it into the profile.
+	fn brands(&mut self, major: mp4_atom::FourCC, compatible: &[mp4_atom::FourCC]) -> Result<()> {
+		if let Some(brand) = std::iter::once(&major)
+			.chain(compatible)
+			.find(|brand| self.rejected_brands.contains(brand))
+		{
+			return Err(Error::UnsupportedBrand(*brand).into());
+		}
+
+		let profile = super::Profile::detect(major, compatible);
+		self.profile = self.profile.max(Some(profile));
+		Ok(())
+	}
+
 	fn container(&self, trak: &Trak, moov: &Moov) -> Result<Container> {
 		// Build a single-track init segment (ftyp+moov) for this track.
 		{
@@ -300,6 +654,12 @@ impl<E: crate::catalog::hang::CatalogExt> Import<E> {
 			_ => return Err(Error::MultipleCodecs.into()),
 		};
 
+		if let Some(config) = self.codecs.video(codec, trak) {
+			let mut config = config?;
+			config.container = container;
+			return Ok(config);
+		}
+
 		let config = match codec {
 			mp4_atom::Codec::Avc1(avc1) => {
 				let avcc = &avc1.avcc;
@@ -319,8 +679,12 @@ impl<E: crate::catalog::hang::CatalogExt> Import<E> {
 				config.container = container;
 				config
 			}
-			mp4_atom::Codec::Hev1(hev1) => self.init_h265(true, &hev1.hvcc, &hev1.visual, container)?,
-			mp4_atom::Codec::Hvc1(hvc1) => self.init_h265(false, &hvc1.hvcc, &hvc1.visual, container)?,
+			mp4_atom::Codec::Hev1(hev1) => {
+				self.init_h265(true, &hev1.hvcc, &hev1.visual, hev1.colr.as_ref(), container)?
+			}
+			mp4_atom::Codec::Hvc1(hvc1) => {
+				self.init_h265(false, &hvc1.hvcc, &hvc1.visual, hvc1.colr.as_ref(), container)?
+			}
 			mp4_atom::Codec::Vp08(vp08) => {
 				let mut config = VideoConfig::new(VideoCodec::VP8);
 				config.coded_width = Some(vp08.visual.width as _);
@@ -344,6 +708,12 @@ impl<E: crate::catalog::hang::CatalogExt> Import<E> {
 				});
 				config.coded_width = Some(vp09.visual.width as _);
 				config.coded_height = Some(vp09.visual.height as _);
+				config.color_space = crate::codec::hdr::color_space(
+					vpcc.color_primaries,
+					vpcc.transfer_characteristics,
+					vpcc.matrix_coefficients,
+					vpcc.video_full_range_flag,
+				);
 				config.container = container;
 				config
 			}
@@ -352,6 +722,7 @@ impl<E: crate::catalog::hang::CatalogExt> Import<E> {
 				config.coded_width = Some(av01.visual.width as _);
 				config.coded_height = Some(av01.visual.height as _);
 				config.container = container;
+				crate::codec::av1::hdr_from_obus(&av01.av1c.config_obus).apply(&mut config);
 				config
 			}
 			mp4_atom::Codec::Unknown(unknown) => return Err(Error::UnknownCodec(*unknown).into()),
@@ -366,6 +737,7 @@ impl<E: crate::catalog::hang::CatalogExt> Import<E> {
 		in_band: bool,
 		hvcc: &mp4_atom::Hvcc,
 		visual: &mp4_atom::Visual,
+		colr: Option<&mp4_atom::Colr>,
 		container: Container,
 	) -> Result<VideoConfig> {
 		let mut description = BytesMut::new();
@@ -380,10 +752,26 @@ impl<E: crate::catalog::hang::CatalogExt> Import<E> {
 			level_idc: hvcc.general_level_idc,
 			constraint_flags: hvcc.general_constraint_indicator_flags,
 		});
-		config.description = Some(description.freeze());
 		config.coded_width = Some(visual.width as _);
 		config.coded_height = Some(visual.height as _);
 		config.container = container;
+
+		// The color description comes from the SPS VUI and the mastering display / content
+		// light level from the prefix SEI in the hvcC. mp4-atom doesn't expose the
+		// `mdcv`/`clli` boxes, but encoders write the same payloads into the SEI.
+		if let Ok(params) = crate::codec::h265::Hvcc::parse(&description)
+			&& let Some(sps) = params.sps.first()
+			&& let Ok(sps) = scuffle_h265::SpsNALUnit::parse(&mut &sps[..])
+		{
+			crate::codec::h265::hdr(&sps, &params.sei).apply(&mut config);
+		}
+
+		// A `colr` box overrides the bitstream, like it does for the decoder.
+		if let Some(color_space) = colr.and_then(colr_color_space) {
+			config.color_space = Some(color_space);
+		}
+
+		config.description = Some(description.freeze());
 		Ok(config)
 	}
 
@@ -397,6 +785,12 @@ impl<E: crate::catalog::hang::CatalogExt> Import<E> {
 			_ => return Err(Error::MultipleCodecs.into()),
 		};
 
+		if let Some(config) = self.codecs.audio(codec, trak) {
+			let mut config = config?;
+			config.container = container;
+			return Ok(config);
+		}
+
 		let config = match codec {
 			mp4_atom::Codec::Mp4a(mp4a) => {
 				let desc = &mp4a.esds.es_desc.dec_config;
@@ -407,20 +801,45 @@ impl<E: crate::catalog::hang::CatalogExt> Import<E> {
 				}
 
 				let bitrate = desc.avg_bitrate.max(desc.max_bitrate);
-				let profile = desc.dec_specific.profile;
-				let sample_rate = mp4a.audio.sample_rate.integer() as u32;
-				let channel_count = mp4a.audio.channel_count as u32;
+				let mut aac = crate::codec::aac::Config {
+					profile: desc.dec_specific.profile,
+					sample_rate: mp4a.audio.sample_rate.integer() as u32,
+					channel_count: mp4a.audio.channel_count as u32,
+					extension: None,
+				};
+
+				// mp4_atom keeps only the leading fields of the AudioSpecificConfig. Explicit
+				// HE-AAC signaling puts the extension type there (5 for SBR, 29 for PS) around
+				// an AAC-LC core at half the output rate, which is the rate players must be
+				// configured with. Backward-compatible signaling hides behind plain AAC-LC and
+				// isn't visible here; the decoder still finds it in the stream.
+				aac.extension = match aac.profile {
+					5 => Some(crate::codec::aac::Extension::Sbr),
+					29 => Some(crate::codec::aac::Extension::SbrPs),
+					_ => None,
+				};
+				if let Some(extension) = aac.extension {
+					aac.profile = 2;
+					if let Some(core) = crate::codec::aac::sample_rate(desc.dec_specific.freq_index) {
+						aac.sample_rate = core * 2;
+					}
+					if extension == crate::codec::aac::Extension::SbrPs {
+						aac.channel_count = 2;
+					}
+				}
 
 				// Build the AudioSpecificConfig (ISO 14496-3 §1.6.2.1)
 				// This is what GStreamer/WebCodecs need as codec_data.
-				let description = crate::codec::aac::Config {
-					profile,
+				let description = aac.encode();
+
+				let (sample_rate, channel_count) = (aac.sample_rate, aac.channel_count);
+				let mut config = AudioConfig::new(
+					AAC {
+						profile: aac.object_type(),
+					},
 					sample_rate,
 					channel_count,
-				}
-				.encode();
-
-				let mut config = AudioConfig::new(AAC { profile }, sample_rate, channel_count);
+				);
 				config.bitrate = Some(bitrate.into());
 				config.description = Some(description);
 				config.container = container;
@@ -482,16 +901,165 @@ impl<E: crate::catalog::hang::CatalogExt> Import<E> {
 		Ok(config)
 	}
 
-	// Extract all frames out of an mdat atom using CMAF passthrough.
-	fn extract(&mut self, mdat: Mdat, mdat_raw: &[u8]) -> Result<()> {
+	// Handle a top-level `emsg` box. A malformed one is logged and skipped, like any
+	// other atom we can't use.
+	fn emsg(&mut self, data: &[u8]) -> Result<()> {
+		let emsg = match Emsg::decode(data) {
+			Ok(emsg) => emsg,
+			Err(err) => {
+				tracing::warn!(%err, "skipping emsg");
+				return Ok(());
+			}
+		};
+
+		match emsg.time {
+			EmsgTime::Absolute(_) => self.publish_event(emsg, Timestamp::ZERO),
+			// Relative to the fragment that follows, so wait for its moof.
+			EmsgTime::Delta(_) => {
+				self.pending_events.push(emsg);
+				Ok(())
+			}
+		}
+	}
+
+	// Publish the version 0 events waiting on `moof`, relative to its earliest decode time.
+	fn flush_events(&mut self, moof: &Moof) -> Result<()> {
+		if self.pending_events.is_empty() {
+			return Ok(());
+		}
+
+		let earliest = self.earliest(moof);
+		for emsg in std::mem::take(&mut self.pending_events) {
+			match earliest {
+				Some(earliest) => self.publish_event(emsg, earliest)?,
+				None => tracing::warn!(id = emsg.id, "skipping emsg: next fragment has no decode time"),
+			}
+		}
+
+		Ok(())
+	}
+
+	// The earliest decode time across a moof's trafs, on the media timeline after track offsets.
+	fn earliest(&self, moof: &Moof) -> Option<Timestamp> {
+		let moov = self.moov.as_ref()?;
+		moof.traf
+			.iter()
+			.filter_map(|traf| {
+				let tfdt = traf.tfdt.as_ref()?;
+				let track_id = traf.tfhd.track_id;
+				let trak = moov.trak.iter().find(|trak| trak.tkhd.track_id == track_id)?;
+				let timescale = trak.mdia.mdhd.timescale as u64;
+				let dts = shifted(self.offsets.get(&track_id), tfdt.base_media_decode_time, timescale).ok()?;
+				Timestamp::from_scale(dts, timescale).ok()
+			})
+			.min()
+	}
+
+	// Write an event to the event track, creating it (and advertising it) on first use.
+	fn publish_event(&mut self, emsg: Emsg, earliest: Timestamp) -> Result<()> {
+		let event = match emsg.event(earliest) {
+			Ok(event) => event,
+			Err(err) => {
+				tracing::warn!(%err, "skipping emsg");
+				return Ok(());
+			}
+		};
+
+		if self.events.is_none() {
+			// Events aren't a role `select` can pick, and need the `fmp4` section to be found.
+			if !self.selects(&TrackKind::Metadata) {
+				return Ok(());
+			}
+
+			let mut catalog = self.catalog.lock();
+			let Some(fmp4) = super::catalog::fmp4_mut(&mut catalog) else {
+				tracing::debug!("emsg without `fmp4` catalog support; skipping");
+				return Ok(());
+			};
+
+			let track = self.broadcast.unique_track(".events")?;
+			fmp4.events = Some(track.name().to_string());
+			self.events = Some(moq_json::stream::Producer::new(track, Default::default()));
+		}
+
+		if let Some(events) = &mut self.events {
+			events.append(&event)?;
+		}
+
+		Ok(())
+	}
+
+	// Emit the samples of a partially buffered mdat that have fully arrived.
+	//
+	// Low-latency CMAF (LL-DASH/LL-HLS) delivers a fragment in chunks, so the mdat
+	// can be split across `decode` calls. Rather than stalling until the whole atom
+	// is buffered, each newly complete sample is forwarded as soon as its bytes are in.
+	fn extract_partial(&mut self) -> Result<()> {
+		if self.moof.is_none() {
+			return Ok(());
+		}
+
+		let Some(header_size) = mdat_header_size(&self.buffer) else {
+			return Ok(());
+		};
+
+		// Detach the buffer so `extract` can take `&mut self`; it's restored untouched.
+		let buffer = std::mem::take(&mut self.buffer);
+		let result = self.extract(&buffer[header_size..], header_size, false);
+		self.buffer = buffer;
+		result
+	}
+
+	// Extract frames out of an mdat atom using CMAF passthrough.
+	//
+	// `data` is the mdat payload, which is only a prefix when `complete` is false.
+	// Samples already emitted from an earlier prefix are skipped, and the rest are
+	// written as a single moof+mdat fragment per track.
+	fn extract(&mut self, data: &[u8], header_size: usize, complete: bool) -> Result<()> {
 		let moov = self.moov.as_ref().ok_or(Error::NoMoov)?;
-		let moof = self.moof.take().ok_or(Error::NoMoof)?;
+		// A complete mdat consumes the moof; a prefix leaves it for the rest of the data.
+		let moof = match complete {
+			true => self.moof.take(),
+			false => self.moof.clone(),
+		}
+		.ok_or(Error::NoMoof)?;
 		let moof_size = self.moof_size;
-		let header_size = mdat_raw.len() - mdat.data.len();
+
+		// The audio group cadence, which only applies without video and at keyframe grouping.
+		let grouping = self.grouping;
+		let audio_group_duration = self
+			.audio_group_duration
+			.filter(|_| grouping == Grouping::Keyframe)
+			.filter(|_| self.tracks.values().all(|track| track.kind != TrackKind::Video));
+
+		// Where the previous traf's data ended, relative to the moof, for a traf addressed from it.
+		let mut previous_end = 0;
 
 		// Loop over all of the traf boxes in the moof.
-		for traf in &moof.traf {
+		for (index, traf) in moof.traf.iter().enumerate() {
 			let track_id = traf.tfhd.track_id;
+			let trex = moov
+				.mvex
+				.as_ref()
+				.and_then(|mvex| mvex.trex.iter().find(|trex| trex.track_id == track_id));
+			let default_sample_size = traf
+				.tfhd
+				.default_sample_size
+				.unwrap_or(trex.map(|trex| trex.default_sample_size).unwrap_or_default());
+
+			// The base data offset, relative to the moof (ISO 14496-12 §8.8.7): an explicit one is
+			// absolute in the input; otherwise it's the moof for the first traf or when flagged
+			// default-base-is-moof, and the end of the previous traf's data for the rest.
+			let base = match traf.tfhd.base_data_offset {
+				Some(absolute) => absolute
+					.checked_sub(self.moof_position)
+					.and_then(|base| usize::try_from(base).ok())
+					.ok_or(Error::InvalidDataOffset)?,
+				None if index == 0 || self.moof_base_is_moof.get(index).copied().unwrap_or_default() => 0,
+				None => previous_end,
+			};
+			let (starts, end) = trun_starts(traf, base, moof_size + header_size, default_sample_size)?;
+			previous_end = end;
 			let track = match self.tracks.get_mut(&track_id) {
 				Some(track) => track,
 				// A fragment for a track `select` dropped: ignore it.
@@ -505,27 +1073,37 @@ impl<E: crate::catalog::hang::CatalogExt> Import<E> {
 				.iter()
 				.find(|trak| trak.tkhd.track_id == track_id)
 				.ok_or(Error::UnknownTrack(track_id))?;
-			let trex = moov
-				.mvex
-				.as_ref()
-				.and_then(|mvex| mvex.trex.iter().find(|trex| trex.track_id == track_id));
 
 			// The moov contains some defaults
 			let default_sample_duration = trex.map(|trex| trex.default_sample_duration).unwrap_or_default();
-			let default_sample_size = trex.map(|trex| trex.default_sample_size).unwrap_or_default();
 			let default_sample_flags = trex.map(|trex| trex.default_sample_flags).unwrap_or_default();
 
 			let tfdt = traf.tfdt.as_ref().ok_or(Error::MissingTfdt)?;
-			let mut dts = tfdt.base_media_decode_time;
 			let timescale = trak.mdia.mdhd.timescale as u64;
 
-			let mut offset = traf.tfhd.base_data_offset.unwrap_or_default() as usize;
-			let mut track_data_start: Option<usize> = None;
+			let mut dts = shifted(self.offsets.get(&track_id), tfdt.base_media_decode_time, timescale)?;
 
 			if traf.trun.is_empty() {
 				return Err(Error::MissingTrun.into());
 			}
 
+			// The samples emitted from an earlier prefix of this mdat.
+			let emitted = self.emitted.get(&track_id).copied().unwrap_or_default();
+
+			// A fragment starting before the previous one ended is a discontinuity (e.g. a
+			// spliced or restarted source): forget the old timing rather than measure across it.
+			let discontinuity = emitted == 0 && track.next_dts.is_some_and(|next| dts < next);
+			if discontinuity {
+				tracing::warn!(track = track.track.name(), dts, expected = ?track.next_dts, "decode time went backwards");
+				track.last_timestamp = None;
+				track.group_start = None;
+			}
+
+			// The data and decode time of the samples emitted this time. Copied sample by sample:
+			// another traf's runs may sit between this one's in a shared mdat.
+			let mut track_data = Vec::new();
+			let mut base_dts = None;
+
 			// Keep track of the minimum and maximum timestamp for this track to compute the jitter.
 			let mut min_timestamp = None;
 			let mut max_timestamp = None;
@@ -533,85 +1111,75 @@ impl<E: crate::catalog::hang::CatalogExt> Import<E> {
 			let total_samples: usize = traf.trun.iter().map(|t| t.entries.len()).sum();
 			let mut sample_index = 0usize;
 
-			for trun in &traf.trun {
+			'samples: for (trun, start) in traf.trun.iter().zip(starts) {
 				let tfhd = &traf.tfhd;
-
-				if let Some(data_offset) = trun.data_offset {
-					let base_offset = tfhd.base_data_offset.unwrap_or_default() as usize;
-					let data_offset: usize = data_offset.try_into().map_err(|_| Error::InvalidDataOffset)?;
-
-					let relative_offset = data_offset
-						.checked_sub(moof_size)
-						.and_then(|v| v.checked_sub(header_size))
-						.ok_or(Error::InvalidDataOffset)?;
-
-					offset = base_offset
-						.checked_add(relative_offset)
-						.ok_or(Error::InvalidDataOffset)?;
-				}
-
-				// Capture the actual start offset for this traf before consuming samples
-				if track_data_start.is_none() {
-					track_data_start = Some(offset);
-				}
+				let mut offset = start;
 
 				for entry in &trun.entries {
 					let flags = entry
 						.flags
 						.unwrap_or(tfhd.default_sample_flags.unwrap_or(default_sample_flags));
-					let duration = entry
-						.duration
-						.or(tfhd.default_sample_duration)
+					// An explicit zero is a real duration, but a zero default means there's none.
+					let duration = entry.duration.or(tfhd
+						.default_sample_duration
 						.or(Some(default_sample_duration))
-						.filter(|duration| *duration != 0);
-					let size = entry
-						.size
-						.unwrap_or(tfhd.default_sample_size.unwrap_or(default_sample_size)) as usize;
+						.filter(|duration| *duration != 0));
+					let size = entry.size.unwrap_or(default_sample_size) as usize;
 
 					if duration.is_none() && sample_index + 1 != total_samples {
 						return Err(Error::MissingSampleDuration.into());
 					}
 
-					// Checked: a negative composition offset must not wrap into a huge u64 PTS.
-					let pts = dts
-						.checked_add_signed(entry.cts.unwrap_or_default() as i64)
-						.ok_or(Error::PtsOverflow)?;
-					// Preserve the fmp4 track's native timescale so a passthrough re-emit
-					// doesn't go through a lossy microsecond detour.
-					let timestamp = Timestamp::from_scale(pts, timescale)?;
-
 					let sample_end = offset.checked_add(size).ok_or(Error::InvalidDataOffset)?;
-					if sample_end > mdat.data.len() {
-						return Err(Error::InvalidDataOffset.into());
+					if sample_end > data.len() {
+						if complete {
+							return Err(Error::InvalidDataOffset.into());
+						}
+						// The rest of this track's samples haven't arrived yet.
+						break 'samples;
 					}
 
-					let keyframe = match track.kind {
-						TrackKind::Video => {
-							let keyframe = (flags >> 24) & 0x3 == 0x2;
-							let non_sync = (flags >> 16) & 0x1 == 0x1;
-							keyframe && !non_sync
+					if sample_index >= emitted {
+						// Checked: a negative composition offset must not wrap into a huge u64 PTS.
+						let pts = dts
+							.checked_add_signed(entry.cts.unwrap_or_default() as i64)
+							.ok_or(Error::PtsOverflow)?;
+						// Preserve the fmp4 track's native timescale so a passthrough re-emit
+						// doesn't go through a lossy microsecond detour.
+						let timestamp = Timestamp::from_scale(pts, timescale)?;
+
+						let keyframe = match track.kind {
+							TrackKind::Video => {
+								let keyframe = (flags >> 24) & 0x3 == 0x2;
+								let non_sync = (flags >> 16) & 0x1 == 0x1;
+								keyframe && !non_sync
+							}
+							// Every image stands alone, so each starts its own group.
+							TrackKind::Audio | TrackKind::Metadata | TrackKind::Image => true,
+						};
+
+						contains_keyframe |= keyframe;
+
+						if max_timestamp.is_none_or(|max| timestamp >= max) {
+							max_timestamp = Some(timestamp);
+						}
+						if min_timestamp.is_none_or(|min| timestamp <= min) {
+							min_timestamp = Some(timestamp);
 						}
-						TrackKind::Audio => true,
-					};
 
-					contains_keyframe |= keyframe;
+						if let Some(last_timestamp) = track.last_timestamp
+							&& let Ok(duration) = timestamp.checked_sub(last_timestamp)
+							&& track.min_duration.is_none_or(|min| duration < min)
+						{
+							track.min_duration = Some(duration);
+						}
 
-					if max_timestamp.is_none_or(|max| timestamp >= max) {
-						max_timestamp = Some(timestamp);
-					}
-					if min_timestamp.is_none_or(|min| timestamp <= min) {
-						min_timestamp = Some(timestamp);
-					}
+						track.last_timestamp = Some(timestamp);
 
-					if let Some(last_timestamp) = track.last_timestamp
-						&& let Ok(duration) = timestamp.checked_sub(last_timestamp)
-						&& track.min_duration.is_none_or(|min| duration < min)
-					{
-						track.min_duration = Some(duration);
+						track_data.extend_from_slice(&data[offset..sample_end]);
+						base_dts.get_or_insert(dts);
 					}
 
-					track.last_timestamp = Some(timestamp);
-
 					if let Some(duration) = duration {
 						dts = dts.checked_add(duration as u64).ok_or(Error::PtsOverflow)?;
 					}
@@ -619,31 +1187,35 @@ impl<E: crate::catalog::hang::CatalogExt> Import<E> {
 					sample_index += 1;
 				}
 			}
+			track.next_dts = Some(dts);
+
+			// Nothing new has arrived for this track.
+			let Some(base_dts) = base_dts else {
+				continue;
+			};
+			let ready = sample_index;
+			self.emitted.insert(track_id, ready);
+
+			// Build a per-track moof containing only this traf, trimmed to the samples
+			// emitted now, and a per-track mdat with only those samples.
+			let mut traf = traf.clone();
+			let mut first = 0;
+			for trun in &mut traf.trun {
+				let last = first + trun.entries.len();
+				let range = emitted.clamp(first, last) - first..ready.clamp(first, last) - first;
+				trun.entries = trun.entries[range].to_vec();
+				first = last;
+			}
+			traf.trun.retain(|trun| !trun.entries.is_empty());
+			if let Some(tfdt) = &mut traf.tfdt {
+				tfdt.base_media_decode_time = base_dts;
+			}
 
-			// Build a per-track moof containing only this traf, and a per-track mdat
-			// with only the samples belonging to this track.
 			let single_traf_moof = Moof {
 				mfhd: moof.mfhd.clone(),
-				traf: vec![traf.clone()],
+				traf: vec![traf],
 			};
 
-			// Compute the data range within the original mdat for this traf's samples.
-			let track_data_start = track_data_start.unwrap_or(0);
-			let track_data_end = offset; // offset was advanced past all samples above
-
-			// The per-track sample range must be in bounds of the original mdat.
-			// If not, the parsed sample sizes/offsets disagree with the actual data
-			// and we cannot safely emit a passthrough fragment with rewritten offsets.
-			if !(track_data_start <= track_data_end && track_data_end <= mdat.data.len()) {
-				return Err(Error::SampleRangeOutOfBounds {
-					start: track_data_start,
-					end: track_data_end,
-					len: mdat.data.len(),
-				}
-				.into());
-			}
-			let track_mdat_data = &mdat.data[track_data_start..track_data_end];
-
 			let mut adjusted_moof = single_traf_moof;
 
 			// Apply structural (flag-presence) changes BEFORE measuring the encoded
@@ -659,11 +1231,6 @@ impl<E: crate::catalog::hang::CatalogExt> Import<E> {
 				for trun_mut in &mut traf_mut.trun {
 					// Reserve the data_offset field; the real value is filled in below.
 					trun_mut.data_offset = Some(0);
-					for entry in &mut trun_mut.entries {
-						if entry.duration == Some(0) {
-							entry.duration = None;
-						}
-					}
 				}
 			}
 
@@ -696,39 +1263,64 @@ impl<E: crate::catalog::hang::CatalogExt> Import<E> {
 			moof_buf.clear();
 			adjusted_moof.encode(&mut moof_buf)?;
 
-			let per_track_mdat = Mdat {
-				data: track_mdat_data.to_vec(),
-			};
+			let per_track_mdat = Mdat { data: track_data };
 			per_track_mdat.encode(&mut moof_buf)?;
 
 			let fragment_bytes = Bytes::from(moof_buf);
 
+			// Carry the fragment's earliest presentation time as the frame timestamp,
+			// in the track's native timescale. The relay reads it off the wire; the
+			// consumer still drives playback from the fragment's internal timing.
+			let timestamp = min_timestamp.ok_or(Error::MissingTrun)?;
+
+			// An audio-only fragment joins the open group until the group spans the cadence.
+			let join = track.kind == TrackKind::Audio
+				&& track.group.is_some()
+				&& track.pending_sequence.is_none()
+				&& audio_group_duration
+					.zip(track.group_start)
+					.is_some_and(|(duration, start)| {
+						timestamp
+							.checked_sub(start)
+							.is_ok_and(|elapsed| std::time::Duration::from(elapsed) < duration)
+					});
+
+			let new_group = match grouping {
+				Grouping::Keyframe => contains_keyframe && !join,
+				// Until a keyframe opens the first group, there's nothing for a delta to depend on.
+				Grouping::Fragment => contains_keyframe || track.group.is_some(),
+			};
+
 			// Write the per-track fragment as a single MoQ frame (passthrough).
-			let mut g = if contains_keyframe {
+			let mut g = if new_group {
 				if let Some(mut prev) = track.group.take() {
 					prev.finish()?;
 				}
-				match track.pending_sequence.take() {
-					Some(sequence) => track.track.create_group(moq_net::Group { sequence })?,
+				let g = match track.pending_sequence.take() {
+					Some(sequence) => track.track.create_group(moq_net::Group::new(sequence))?,
 					None => track.track.append_group()?,
+				};
+				// Only a keyframe group is a place to seek to.
+				if contains_keyframe && let Some(index) = &track.index {
+					index.insert(g.sequence, timestamp);
 				}
+				track.group_start = Some(timestamp);
+				g
 			} else {
 				track.group.take().ok_or(Error::NoKeyframe)?
 			};
 
-			// Carry the fragment's earliest presentation time as the frame timestamp,
-			// in the track's native timescale. The relay reads it off the wire; the
-			// consumer still drives playback from the fragment's internal timing.
-			let timestamp = min_timestamp.ok_or(Error::MissingTrun)?;
-			let _ = timestamp;
-			let mut frame = g.create_frame(moq_net::Frame {
-				size: fragment_bytes.len() as u64,
-			})?;
+			let mut frame = g.create_frame(moq_net::Frame::new(fragment_bytes.len() as u64))?;
 			frame.write(fragment_bytes)?;
 			frame.finish()?;
 
 			track.group = Some(g);
 
+			if let Some(max) = max_timestamp {
+				self.catalog.progress().advance(max);
+				self.progress.advance(max);
+			}
+
 			if let (Some(min), Some(max), Some(min_duration)) = (min_timestamp, max_timestamp, track.min_duration) {
 				let jitter = max - min + min_duration;
 
@@ -754,24 +1346,38 @@ impl<E: crate::catalog::hang::CatalogExt> Import<E> {
 								.ok_or_else(|| Error::MissingAudioTrack(track.track.name().to_string()))?;
 							config.jitter = moq_net::Time::from_scale(jitter.as_micros() as u64, 1_000_000).ok();
 						}
+						TrackKind::Metadata | TrackKind::Image => {}
 					}
 				}
 			}
 		}
 
+		if complete {
+			self.emitted.clear();
+		}
+
 		Ok(())
 	}
 }
 
 impl<E: crate::catalog::hang::CatalogExt> Import<E> {
 	/// Finish all tracks, flushing current groups.
+	///
+	/// A truncated final fragment (see [`is_partial`](Self::is_partial)) is dropped.
 	pub fn finish(&mut self) -> Result<()> {
+		if self.is_partial() {
+			tracing::warn!(buffered = self.buffer.len(), "dropping a truncated final fragment");
+		}
+
 		for track in self.tracks.values_mut() {
 			if let Some(mut g) = track.group.take() {
 				g.finish()?;
 			}
 			track.track.finish()?;
 		}
+		if let Some(events) = &mut self.events {
+			events.finish()?;
+		}
 		Ok(())
 	}
 
@@ -802,7 +1408,123 @@ impl<E: crate::catalog::hang::CatalogExt> Drop for Import<E> {
 				TrackKind::Audio => {
 					catalog.audio.renditions.remove(track.track.name());
 				}
+				TrackKind::Metadata => {
+					if let Some(fmp4) = super::catalog::fmp4_mut(&mut catalog) {
+						fmp4.metadata.remove(track.track.name());
+					}
+				}
+				TrackKind::Image => {
+					if let Some(fmp4) = super::catalog::fmp4_mut(&mut catalog) {
+						fmp4.images.remove(track.track.name());
+					}
+				}
 			}
 		}
+
+		if self.events.is_some()
+			&& let Some(fmp4) = super::catalog::fmp4_mut(&mut catalog)
+		{
+			fmp4.events = None;
+		}
+	}
+}
+
+// Shift a decode time by a track's configured offset, if any, in the track's timescale.
+fn shifted(offset: Option<&std::time::Duration>, dts: u64, timescale: u64) -> Result<u64> {
+	let shift = match offset {
+		Some(offset) => {
+			u64::try_from(offset.as_nanos() * timescale as u128 / 1_000_000_000).map_err(|_| Error::PtsOverflow)?
+		}
+		None => 0,
+	};
+	Ok(dts.checked_add(shift).ok_or(Error::PtsOverflow)?)
+}
+
+/// Where each of a traf's truns starts in the mdat payload, and where its data ends relative to
+/// the moof, given the traf's `base` offset (relative to the moof) and the size of the moof plus
+/// the mdat header, `payload`.
+///
+/// A trun's data_offset counts from the base; a trun without one follows the previous trun's
+/// data (ISO 14496-12 §8.8.8). A first trun without one, addressed from the moof itself, has
+/// nowhere valid to start but the mdat payload, so it's taken to start there.
+fn trun_starts(traf: &mp4_atom::Traf, base: usize, payload: usize, default_size: u32) -> Result<(Vec<usize>, usize)> {
+	let mut starts = Vec::with_capacity(traf.trun.len());
+	let mut end = base.max(payload);
+
+	for trun in &traf.trun {
+		let start = match trun.data_offset {
+			Some(data_offset) => base
+				.checked_add_signed(data_offset as isize)
+				.ok_or(Error::InvalidDataOffset)?,
+			None => end,
+		};
+		let size: usize = trun
+			.entries
+			.iter()
+			.map(|entry| entry.size.unwrap_or(default_size) as usize)
+			.sum();
+		end = start.checked_add(size).ok_or(Error::InvalidDataOffset)?;
+
+		starts.push(start.checked_sub(payload).ok_or(Error::InvalidDataOffset)?);
+	}
+
+	Ok((starts, end))
+}
+
+/// The default-base-is-moof flag (0x020000) of each traf's tfhd in a raw `moof` box, in order.
+fn default_base_is_moof(moof: &[u8]) -> Vec<bool> {
+	boxes(moof.get(8..).unwrap_or_default())
+		.filter(|(kind, _)| *kind == b"traf")
+		.map(|(_, traf)| {
+			boxes(traf)
+				.find(|(kind, _)| *kind == b"tfhd")
+				.and_then(|(_, tfhd)| tfhd.get(1))
+				.is_some_and(|flags| flags & 0x02 != 0)
+		})
+		.collect()
+}
+
+/// The (type, body) of each box in `data`, stopping at a malformed or 64-bit sized one.
+fn boxes(mut data: &[u8]) -> impl Iterator<Item = (&[u8], &[u8])> {
+	std::iter::from_fn(move || {
+		let size = u32::from_be_bytes(data.get(..4)?.try_into().unwrap()) as usize;
+		if size < 8 || size > data.len() {
+			return None;
+		}
+		let (item, rest) = data.split_at(size);
+		data = rest;
+		Some((&item[4..8], &item[8..]))
+	})
+}
+
+/// The color description from a `colr` box, if it carries CICP code points (`nclx`)
+/// rather than an ICC profile.
+fn colr_color_space(colr: &mp4_atom::Colr) -> Option<hang::catalog::ColorSpace> {
+	match colr {
+		mp4_atom::Colr::Nclx {
+			colour_primaries,
+			transfer_characteristics,
+			matrix_coefficients,
+			full_range_flag,
+		} => crate::codec::hdr::color_space(
+			*colour_primaries as u8,
+			*transfer_characteristics as u8,
+			*matrix_coefficients as u8,
+			*full_range_flag,
+		),
+		_ => None,
+	}
+}
+
+/// The header size of the mdat atom at the start of `buf`, or `None` if `buf` starts with another atom.
+fn mdat_header_size(buf: &[u8]) -> Option<usize> {
+	if buf.get(4..8)? != b"mdat" {
+		return None;
+	}
+
+	// A size of 1 means a 64-bit largesize follows the type.
+	match buf[..4] {
+		[0, 0, 0, 1] => (buf.len() >= 16).then_some(16),
+		_ => Some(8),
 	}
 }
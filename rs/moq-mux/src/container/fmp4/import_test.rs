@@ -14,10 +14,7 @@ fn drain_group_sequences(consumer: &mut moq_net::TrackConsumer) -> Vec<u64> {
 }
 
 fn run_fmp4(data: &[u8]) -> crate::catalog::hang::Catalog {
-	let mut broadcast = moq_net::Broadcast::new().produce();
-	let catalog = crate::catalog::Producer::new(&mut broadcast).unwrap();
-
-	let mut fmp4 = crate::container::fmp4::Import::new(broadcast, catalog.clone());
+	let (mut fmp4, catalog, _) = import();
 
 	let buf = bytes::BytesMut::from(data);
 	// Ignore errors from incomplete/malformed trailing fragments in test files.
@@ -27,10 +24,8 @@ fn run_fmp4(data: &[u8]) -> crate::catalog::hang::Catalog {
 }
 
 fn run_fmp4_select(data: &[u8], select: crate::select::Broadcast) -> crate::catalog::hang::Catalog {
-	let mut broadcast = moq_net::Broadcast::new().produce();
-	let catalog = crate::catalog::Producer::new(&mut broadcast).unwrap();
-
-	let mut fmp4 = crate::container::fmp4::Import::new(broadcast, catalog.clone()).with_select(select);
+	let (fmp4, catalog, _) = import();
+	let mut fmp4 = fmp4.with_select(select);
 
 	// A dropped track's moof fragments must be skipped, not raise `UnknownTrack`.
 	// (The test files end on a malformed fragment, so other decode errors are expected
@@ -53,6 +48,89 @@ fn decode_init(init: &[u8]) -> (mp4_atom::Ftyp, mp4_atom::Moov) {
 	(ftyp, moov)
 }
 
+/// The trak with the given handler (`vide`, `soun`, ...).
+fn find_trak<'a>(moov: &'a mp4_atom::Moov, handler: &[u8; 4]) -> &'a mp4_atom::Trak {
+	moov.trak
+		.iter()
+		.find(|trak| trak.mdia.hdlr.handler.as_ref() == handler)
+		.expect("no trak with that handler")
+}
+
+/// An importer on a fresh broadcast, with its media-only catalog and a consumer for the broadcast.
+fn import() -> (
+	crate::container::fmp4::Import,
+	crate::catalog::Producer,
+	moq_net::BroadcastConsumer,
+) {
+	import_with(Default::default())
+}
+
+/// Like [`import`], with the given (possibly extended) initial catalog.
+fn import_with<E: crate::catalog::hang::CatalogExt>(
+	catalog: crate::catalog::hang::Catalog<E>,
+) -> (
+	crate::container::fmp4::Import<E>,
+	crate::catalog::Producer<E>,
+	moq_net::BroadcastConsumer,
+) {
+	let mut broadcast = moq_net::Broadcast::new().produce();
+	let consumer = broadcast.consume();
+	let catalog = crate::catalog::Producer::with_catalog(&mut broadcast, catalog).unwrap();
+	let fmp4 = crate::container::fmp4::Import::new(broadcast, catalog.clone());
+	(fmp4, catalog, consumer)
+}
+
+/// Subscribe to the first video rendition in the catalog.
+fn subscribe_video<E: crate::catalog::hang::CatalogExt>(
+	catalog: &crate::catalog::Producer<E>,
+	consumer: &moq_net::BroadcastConsumer,
+) -> moq_net::TrackConsumer {
+	let name = catalog
+		.snapshot()
+		.video
+		.renditions
+		.keys()
+		.next()
+		.expect("video track")
+		.clone();
+	consumer
+		.subscribe_track(&moq_net::Track::new(name.as_str()))
+		.expect("video track should exist")
+}
+
+/// A fragment's single traf and the payload its one trun points at.
+fn single_run(raw: &[u8], mdat: &[u8]) -> (mp4_atom::Mfhd, mp4_atom::Traf, Vec<u8>) {
+	let moof = mp4_atom::Moof::decode(&mut std::io::Cursor::new(raw)).unwrap();
+	let traf = moof.traf[0].clone();
+	assert_eq!(traf.trun.len(), 1);
+
+	// The data_offset counts from the moof, so past the moof it lands inside the mdat box.
+	let payload = mdat[traf.trun[0].data_offset.unwrap() as usize - raw.len()..].to_vec();
+	(moof.mfhd, traf, payload)
+}
+
+#[test]
+fn progress_follows_the_samples() {
+	let (init, fragments) = split_fragments(include_bytes!("test_data/bbb.mp4"));
+	let (mut fmp4, catalog, _) = import();
+	let progress = catalog.progress();
+
+	fmp4.decode(&init).unwrap();
+	assert_eq!(progress.newest(), None);
+
+	// Fragments move the newest timestamp forward, never back, even when one track trails another.
+	let mut seen = Vec::new();
+	for (moof, mdat) in fragments {
+		fmp4.decode(&[moof, mdat].concat()).unwrap();
+		seen.push(progress.newest().expect("no sample written"));
+	}
+	assert!(seen.is_sorted());
+	assert!(seen.first() < seen.last());
+
+	// The importer's own progress matches, as it's the catalog's only writer.
+	assert_eq!(fmp4.progress().newest(), progress.newest());
+}
+
 #[test]
 fn test_bbb_catalog() {
 	let data = include_bytes!("test_data/bbb.mp4");
@@ -75,13 +153,296 @@ fn test_bbb_catalog() {
 }
 
 #[test]
-fn dropping_import_retires_catalog_renditions() {
+fn seek_index_covers_the_file() {
 	let data = include_bytes!("test_data/bbb.mp4");
 	let mut broadcast = moq_net::Broadcast::new().produce();
-	let catalog = crate::catalog::Producer::new(&mut broadcast).unwrap();
+	let catalog = crate::catalog::Producer::new(&mut broadcast)
+		.unwrap()
+		.with_index_capacity(None);
+	let mut fmp4 = crate::container::fmp4::Import::new(broadcast, catalog.clone());
+
+	// bbb.mp4 ends by repeating its first keyframe at decode time zero; move it after the
+	// delta frame at 1000 so the file opens a second keyframe group later on.
+	let (init, mut fragments) = split_fragments(data);
+	let (moof, _) = fragments.last_mut().unwrap();
+	let mut parsed = mp4_atom::Moof::decode(&mut std::io::Cursor::new(&moof[..])).unwrap();
+	parsed.traf[0].tfdt.as_mut().unwrap().base_media_decode_time = 2000;
+	*moof = encode_moof(parsed, moof.len());
+
+	fmp4.decode(&init[..]).unwrap();
+	for (moof, mdat) in &fragments {
+		fmp4.decode(&[&moof[..], &mdat[..]].concat()[..]).unwrap();
+	}
+
+	let name = catalog.snapshot().video.renditions.keys().next().unwrap().clone();
+	let index = catalog.index(&name);
+	let (first, last) = (index.first().unwrap(), index.last().unwrap());
+	assert!(first.pts < last.pts);
+
+	// Every time maps to the last keyframe group opening at or before it.
+	assert_eq!(index.group_at(first.pts), Some(first.clone()));
+	assert_eq!(index.group_at(last.pts), Some(last.clone()));
+	let mid = (first.pts.as_micros() + last.pts.as_micros()) / 2;
+	let mid = index
+		.group_at(crate::container::Timestamp::from_micros(mid as u64).unwrap())
+		.unwrap();
+	assert!(first.group <= mid.group && mid.group <= last.group);
+}
+
+#[test]
+fn test_vod_duration() {
+	let data = include_bytes!("test_data/bbb.mp4");
+
+	// bbb.mp4 is fragmented without an overall duration, like a live stream.
+	let catalog = run_fmp4(data);
+	assert!(catalog.video.renditions.values().all(|v| v.duration.is_none()));
+	assert!(catalog.audio.renditions.values().all(|a| a.duration.is_none()));
+
+	let (init, _) = split_fragments(data);
+	let (ftyp, mut moov) = decode_init(&init);
+
+	// A progressive file records the duration per track, in the track timescale.
+	for trak in &mut moov.trak {
+		trak.mdia.mdhd.duration = 10 * trak.mdia.mdhd.timescale as u64;
+	}
+	// A fragmented file records it once, in the movie timescale.
+	moov.trak[1].mdia.mdhd.duration = 0;
+	moov.mvex.as_mut().unwrap().mehd = Some(mp4_atom::Mehd {
+		fragment_duration: 12 * moov.mvhd.timescale as u64,
+	});
+
+	let mut data = Vec::new();
+	ftyp.encode(&mut data).unwrap();
+	moov.encode(&mut data).unwrap();
+
+	let catalog = run_fmp4(&data);
+	let video = catalog.video.renditions.values().next().unwrap();
+	let audio = catalog.audio.renditions.values().next().unwrap();
+	let durations = [video.duration, audio.duration].map(|d| d.unwrap().as_secs());
+	assert!(durations.contains(&10));
+	assert!(durations.contains(&12));
+}
+
+#[test]
+fn brands_set_profile() {
+	use crate::container::fmp4::Profile;
+
+	let data = include_bytes!("test_data/bbb.mp4");
+	let (init, _) = split_fragments(data);
+	let (mut ftyp, moov) = decode_init(&init);
+	ftyp.compatible_brands.push(b"cmfc".into());
+
+	let mut init = Vec::new();
+	ftyp.encode(&mut init).unwrap();
+	moov.encode(&mut init).unwrap();
+
+	let (mut fmp4, _, _) = import();
+	assert_eq!(fmp4.profile(), None);
+	fmp4.decode(&init).unwrap();
+	assert_eq!(fmp4.profile(), Some(Profile::Cmaf));
+
+	// A plain segment brand doesn't downgrade it.
+	let styp = mp4_atom::Styp {
+		major_brand: b"msdh".into(),
+		minor_version: 0,
+		compatible_brands: vec![b"msdh".into()],
+	};
+	let mut segment = Vec::new();
+	styp.encode(&mut segment).unwrap();
+	fmp4.decode(&segment).unwrap();
+	assert_eq!(fmp4.profile(), Some(Profile::Cmaf));
+
+	// A rejected brand fails up front, before any track is published.
+	let (fmp4, catalog, _) = import();
+	let mut fmp4 = fmp4.with_rejected_brands([mp4_atom::FourCC::new(b"cmfc")]);
+	let err = fmp4.decode(&init).unwrap_err();
+	assert!(
+		matches!(err, crate::Error::Cmaf(super::Error::UnsupportedBrand(brand)) if brand == b"cmfc".into()),
+		"got {err:?}"
+	);
+	assert!(catalog.snapshot().video.renditions.is_empty());
+}
+
+#[test]
+fn metadata_track_passes_through() {
+	use crate::container::fmp4::catalog::Ext;
+
+	let data = include_bytes!("test_data/bbb.mp4");
+	let (init, fragments) = split_fragments(data);
+	let (ftyp, mut moov) = decode_init(&init);
+
+	// Relabel the audio track as timed metadata.
+	let trak = moov
+		.trak
+		.iter_mut()
+		.find(|trak| trak.mdia.hdlr.handler.as_ref() == b"soun")
+		.unwrap();
+	trak.mdia.hdlr.handler = b"meta".into();
+	let track_id = trak.tkhd.track_id;
+
+	let mut init = Vec::new();
+	ftyp.encode(&mut init).unwrap();
+	moov.encode(&mut init).unwrap();
+
+	// A media-only catalog can't describe it, so it's skipped rather than failing the import.
+	let (mut fmp4, catalog, _) = import();
+	fmp4.decode(&init).unwrap();
+	for (moof, mdat) in &fragments {
+		fmp4.decode(moof).unwrap();
+		fmp4.decode(mdat).unwrap();
+	}
+	let snapshot = catalog.snapshot();
+	assert_eq!(snapshot.video.renditions.len(), 1);
+	assert!(snapshot.audio.renditions.is_empty());
+
+	// With the `fmp4` section, it's published as an opaque CMAF track.
+	let (mut fmp4, catalog, consumer) = import_with(crate::catalog::hang::Catalog::<Ext>::default());
+	fmp4.decode(&init).unwrap();
+	for (moof, mdat) in &fragments {
+		fmp4.decode(moof).unwrap();
+		fmp4.decode(mdat).unwrap();
+	}
+
+	let snapshot = catalog.snapshot();
+	let (name, metadata) = snapshot.fmp4.metadata.iter().next().unwrap();
+	assert_eq!(metadata.codec, "mp4a");
+	let Container::Cmaf { init, .. } = &metadata.container else {
+		panic!("expected a CMAF container");
+	};
+	let (_, single) = decode_init(init);
+	assert_eq!(single.trak[0].tkhd.track_id, track_id);
+
+	let mut track = consumer.subscribe_track(&moq_net::Track::new(name.as_str())).unwrap();
+	assert!(!drain_group_sequences(&mut track).is_empty());
+
+	drop(fmp4);
+	assert!(catalog.snapshot().fmp4.metadata.is_empty());
+}
+
+#[test]
+fn image_track_passes_through() {
+	use crate::container::fmp4::catalog::Ext;
+
+	let data = include_bytes!("test_data/bbb.mp4");
+	let (init, fragments) = split_fragments(data);
+	let (ftyp, mut moov) = decode_init(&init);
+
+	// Relabel the audio track as a still image track.
+	let trak = moov
+		.trak
+		.iter_mut()
+		.find(|trak| trak.mdia.hdlr.handler.as_ref() == b"soun")
+		.unwrap();
+	trak.mdia.hdlr.handler = b"pict".into();
+
+	let mut init = Vec::new();
+	ftyp.encode(&mut init).unwrap();
+	moov.encode(&mut init).unwrap();
+
+	let (mut fmp4, catalog, consumer) = import_with(crate::catalog::hang::Catalog::<Ext>::default());
+	fmp4.decode(&init).unwrap();
+	for (moof, mdat) in &fragments {
+		fmp4.decode(moof).unwrap();
+		fmp4.decode(mdat).unwrap();
+	}
+
+	let snapshot = catalog.snapshot();
+	assert!(snapshot.audio.renditions.is_empty());
+	assert!(snapshot.fmp4.metadata.is_empty());
+	let (name, image) = snapshot.fmp4.images.iter().next().unwrap();
+	assert_eq!(image.codec, "mp4a");
+
+	let mut track = consumer.subscribe_track(&moq_net::Track::new(name.as_str())).unwrap();
+	assert!(!drain_group_sequences(&mut track).is_empty());
+
+	drop(fmp4);
+	assert!(catalog.snapshot().fmp4.images.is_empty());
+}
+
+#[test]
+fn emsg_events_published() {
+	emsg_events_case(std::time::Duration::ZERO);
+}
+
+/// A version 0 event follows the track offsets applied to the fragment after it.
+#[test]
+fn emsg_events_follow_track_offset() {
+	emsg_events_case(std::time::Duration::from_secs(1));
+}
+
+// Publish a version 1 and a version 0 event with every track delayed by `offset`.
+fn emsg_events_case(offset: std::time::Duration) {
+	use crate::container::Timestamp;
+	use crate::container::fmp4::Event;
+	use crate::container::fmp4::catalog::Ext;
+
+	let data = include_bytes!("test_data/bbb.mp4");
+	let (init, fragments) = split_fragments(data);
+	let (_, moov) = decode_init(&init);
+	let offset = Timestamp::try_from(offset).unwrap();
+
+	// A version 0 event is timed from the earliest decode time of the fragment after it.
+	let moof = mp4_atom::Moof::decode(&mut std::io::Cursor::new(&fragments[1].0)).unwrap();
+	let earliest = moof
+		.traf
+		.iter()
+		.map(|traf| {
+			let trak = moov
+				.trak
+				.iter()
+				.find(|trak| trak.tkhd.track_id == traf.tfhd.track_id)
+				.unwrap();
+			let tfdt = traf.tfdt.as_ref().unwrap().base_media_decode_time;
+			Timestamp::from_scale(tfdt, trak.mdia.mdhd.timescale as u64).unwrap()
+		})
+		.min()
+		.unwrap();
+
+	let (mut fmp4, catalog, consumer) = import_with(crate::catalog::hang::Catalog::<Ext>::default());
+	for trak in &moov.trak {
+		fmp4.set_track_offset(trak.tkhd.track_id, offset.into());
+	}
+	fmp4.decode(&init).unwrap();
+	fmp4.decode(&super::emsg::test::emsg(1, 90_000, 45_000, b"first"))
+		.unwrap();
+	for (i, (moof, mdat)) in fragments.iter().enumerate() {
+		if i == 1 {
+			fmp4.decode(&super::emsg::test::emsg(0, 9_000, u32::MAX, b"second"))
+				.unwrap();
+		}
+		fmp4.decode(moof).unwrap();
+		fmp4.decode(mdat).unwrap();
+	}
+
+	let name = catalog.snapshot().fmp4.events.clone().expect("event track advertised");
+	let track = consumer.subscribe_track(&moq_net::Track::new(name.as_str())).unwrap();
+	let mut events = moq_json::stream::Consumer::<Event>::new(track, Default::default());
+	let mut next = || events.next().now_or_never().unwrap().unwrap().unwrap();
+
+	let first = next();
+	assert_eq!(first.timestamp, Timestamp::from_secs_unchecked(1));
+	assert_eq!(first.duration, Some(Timestamp::from_millis_unchecked(500)));
+	assert_eq!(first.message_data.as_ref(), b"first");
+
+	let second = next();
+	assert_eq!(
+		second.timestamp,
+		earliest + offset + Timestamp::from_millis_unchecked(100)
+	);
+	assert_eq!(second.duration, None);
+	assert_eq!(second.message_data.as_ref(), b"second");
+
+	drop(fmp4);
+	assert!(catalog.snapshot().fmp4.events.is_none());
+}
+
+#[test]
+fn dropping_import_retires_catalog_renditions() {
+	let data = include_bytes!("test_data/bbb.mp4");
+	let (fmp4, catalog, _) = import();
 
 	{
-		let mut fmp4 = crate::container::fmp4::Import::new(broadcast, catalog.clone());
+		let mut fmp4 = fmp4;
 		let mut cursor = std::io::Cursor::new(data);
 		mp4_atom::Ftyp::decode(&mut cursor).unwrap();
 		mp4_atom::Moov::decode(&mut cursor).unwrap();
@@ -96,6 +457,108 @@ fn dropping_import_retires_catalog_renditions() {
 	assert!(snapshot.audio.renditions.is_empty());
 }
 
+#[test]
+fn track_priorities() {
+	let data = include_bytes!("test_data/bbb.mp4");
+	let (fmp4, catalog, consumer) = import();
+	let mut fmp4 = fmp4.with_video_priority(2).with_audio_priority(3);
+	let (init, _) = split_fragments(data);
+	fmp4.decode(&init[..]).unwrap();
+
+	let snapshot = catalog.snapshot();
+	let priority = |name: &String| {
+		consumer
+			.subscribe_track(&moq_net::Track::new(name.as_str()))
+			.unwrap()
+			.priority
+	};
+	let video = snapshot.video.renditions.keys().next().unwrap();
+	let audio = snapshot.audio.renditions.keys().next().unwrap();
+	assert_eq!(priority(video), 2);
+	assert_eq!(priority(audio), 3);
+}
+
+#[test]
+fn cache_groups() {
+	let data = include_bytes!("test_data/bbb.mp4");
+	let (fmp4, catalog, consumer) = import();
+	let mut fmp4 = fmp4.with_cache_groups(1);
+	let (init, fragments) = split_fragments(data);
+	fmp4.decode(&init[..]).unwrap();
+	for (moof, mdat) in fragments {
+		fmp4.decode(&[moof, mdat].concat()[..]).unwrap();
+	}
+
+	// A subscriber joining now only finds the newest audio group.
+	let name = catalog.snapshot().audio.renditions.keys().next().unwrap().clone();
+	let mut track = consumer.subscribe_track(&moq_net::Track::new(name.as_str())).unwrap();
+	assert_eq!(drain_group_sequences(&mut track).len(), 1);
+}
+
+/// The number of audio groups published for `data`, with an optional audio group duration.
+fn audio_groups(data: &[u8], select: crate::select::Broadcast, duration: Option<std::time::Duration>) -> usize {
+	let (fmp4, catalog, consumer) = import();
+	let mut fmp4 = fmp4.with_select(select);
+	if let Some(duration) = duration {
+		fmp4 = fmp4.with_audio_group_duration(duration);
+	}
+
+	let (init, fragments) = split_fragments(data);
+	fmp4.decode(&init[..]).unwrap();
+	let name = catalog.snapshot().audio.renditions.keys().next().unwrap().clone();
+	let mut track = consumer.subscribe_track(&moq_net::Track::new(name.as_str())).unwrap();
+
+	for (moof, mdat) in fragments {
+		fmp4.decode(&[moof, mdat].concat()[..]).unwrap();
+	}
+
+	drain_group_sequences(&mut track).len()
+}
+
+#[test]
+fn audio_only_group_duration() {
+	use crate::select::{Audio, Broadcast, Video};
+
+	let data = include_bytes!("test_data/bbb.mp4");
+	let audio_only = || Broadcast::default().audio(Audio::default());
+	let both = || Broadcast::default().video(Video::default()).audio(Audio::default());
+	let hour = Some(std::time::Duration::from_secs(3600));
+
+	// One group per fragment by default.
+	let fragments = audio_groups(data, audio_only(), None);
+	assert!(fragments > 1);
+
+	// Audio-only, the fragments share one group until it spans the duration.
+	assert_eq!(audio_groups(data, audio_only(), hour), 1);
+
+	// Alongside video, the duration doesn't apply.
+	assert_eq!(audio_groups(data, both(), hour), fragments);
+}
+
+#[test]
+fn decode_time_regression_starts_a_group() {
+	use crate::select::{Audio, Broadcast};
+
+	let data = include_bytes!("test_data/bbb.mp4");
+	let (init, fragments) = split_fragments(data);
+
+	let (fmp4, catalog, consumer) = import();
+	let mut fmp4 = fmp4
+		.with_select(Broadcast::default().audio(Audio::default()))
+		.with_audio_group_duration(std::time::Duration::from_secs(3600));
+
+	fmp4.decode(&init[..]).unwrap();
+	let name = catalog.snapshot().audio.renditions.keys().next().unwrap().clone();
+	let mut track = consumer.subscribe_track(&moq_net::Track::new(name.as_str())).unwrap();
+
+	// The whole file shares one audio group, then replaying the first audio fragment jumps back in time.
+	for (moof, mdat) in fragments.iter().chain(fragments.get(1)) {
+		fmp4.decode(&[&moof[..], &mdat[..]].concat()[..]).unwrap();
+	}
+
+	assert_eq!(drain_group_sequences(&mut track).len(), 2);
+}
+
 #[test]
 fn select_video_only() {
 	use crate::select::{Broadcast, Video};
@@ -220,10 +683,7 @@ fn test_vp9_catalog() {
 async fn test_seek_sets_initial_sequence() {
 	use mp4_atom::{Any, DecodeMaybe};
 
-	let mut broadcast = moq_net::Broadcast::new().produce();
-	let broadcast_consumer = broadcast.consume();
-	let catalog = crate::catalog::Producer::new(&mut broadcast).unwrap();
-	let mut fmp4 = crate::container::fmp4::Import::new(broadcast, catalog.clone());
+	let (mut fmp4, catalog, broadcast_consumer) = import();
 
 	let data = include_bytes!("test_data/bbb.mp4");
 
@@ -253,11 +713,7 @@ async fn test_seek_sets_initial_sequence() {
 	// Decode init so the tracks exist, then seek, then decode the fragments.
 	fmp4.decode(&init_buf).unwrap();
 
-	let snap = catalog.snapshot();
-	let video_name = snap.video.renditions.keys().next().expect("video track").clone();
-	let mut video_track = broadcast_consumer
-		.subscribe_track(&moq_net::Track::new(video_name.as_str()))
-		.expect("video track should exist");
+	let mut video_track = subscribe_video(&catalog, &broadcast_consumer);
 
 	fmp4.seek(100).unwrap();
 	// Trailing partial fragments may error; ignore.
@@ -272,6 +728,603 @@ async fn test_seek_sets_initial_sequence() {
 	}
 }
 
+/// The raw bytes of one moof and the mdat that follows it.
+type Fragment = (Vec<u8>, Vec<u8>);
+
+/// Split a fragmented file into its init atoms and the raw bytes of each complete
+/// moof and mdat, dropping styp/sidx so fragments can be re-laid out back to back.
+fn split_fragments(data: &[u8]) -> (Vec<u8>, Vec<Fragment>) {
+	use mp4_atom::{Any, DecodeMaybe};
+
+	let mut init = Vec::new();
+	let mut fragments = Vec::new();
+	let mut moof = None;
+	let mut cursor = std::io::Cursor::new(data);
+	let mut position = 0;
+	while let Some(atom) = Any::decode_maybe(&mut cursor).unwrap_or(None) {
+		let end = cursor.position() as usize;
+		let bytes = data[position..end].to_vec();
+		match atom {
+			Any::Ftyp(_) | Any::Moov(_) => init.extend_from_slice(&bytes),
+			Any::Moof(_) => moof = Some(bytes),
+			Any::Mdat(_) => fragments.extend(moof.take().map(|moof| (moof, bytes))),
+			_ => {}
+		}
+		position = end;
+	}
+	(init, fragments)
+}
+
+/// Merge fragments of one track into a single fragment whose one run holds all their samples.
+///
+/// Every fragment of bbb.mp4 carries a single sample (video in 0 and 3, audio in 1 and 2), so
+/// tests that need several samples in one moof build it here. The run starts at the first
+/// fragment's decode time and each sample spells out its own size, duration and flags.
+fn merge_fragments(fragments: &[&Fragment]) -> Fragment {
+	let mut merged: Option<mp4_atom::Moof> = None;
+	let mut entries = Vec::new();
+	let mut payload = Vec::new();
+	for (raw, mdat) in fragments {
+		let moof = mp4_atom::Moof::decode(&mut std::io::Cursor::new(&raw[..])).unwrap();
+		let traf = &moof.traf[0];
+		assert_eq!(traf.trun.len(), 1);
+		let trun = &traf.trun[0];
+
+		// The data_offset counts from the moof, so past the moof it lands inside the mdat box.
+		let mut offset = trun.data_offset.unwrap() as usize - raw.len();
+		for entry in &trun.entries {
+			let size = entry.size.or(traf.tfhd.default_sample_size).unwrap();
+			entries.push(mp4_atom::TrunEntry {
+				duration: entry.duration.or(traf.tfhd.default_sample_duration),
+				size: Some(size),
+				flags: entry.flags.or(traf.tfhd.default_sample_flags),
+				cts: entry.cts,
+			});
+			payload.extend_from_slice(&mdat[offset..offset + size as usize]);
+			offset += size as usize;
+		}
+		merged.get_or_insert(moof);
+	}
+
+	let mut moof = merged.expect("at least one fragment");
+	moof.traf[0].trun = vec![mp4_atom::Trun {
+		data_offset: Some(0),
+		entries,
+	}];
+	moof.traf[0].trun[0].data_offset = Some(moof_len(&moof) as i32 + 8);
+
+	let mut raw = Vec::new();
+	moof.encode(&mut raw).unwrap();
+	let mut mdat = Vec::new();
+	mp4_atom::Mdat { data: payload }.encode(&mut mdat).unwrap();
+	(raw, mdat)
+}
+
+#[test]
+fn grouping_per_fragment() {
+	let data = include_bytes!("test_data/bbb.mp4");
+	let (init, fragments) = split_fragments(data);
+	let (_, moov) = decode_init(&init);
+	let video = find_trak(&moov, b"vide").tkhd.track_id;
+	let count = fragments
+		.iter()
+		.filter(|(moof, _)| {
+			let moof = mp4_atom::Moof::decode(&mut std::io::Cursor::new(moof)).unwrap();
+			moof.traf.iter().any(|traf| traf.tfhd.track_id == video)
+		})
+		.count();
+	let fragments: Vec<u8> = fragments
+		.into_iter()
+		.flat_map(|(moof, mdat)| [moof, mdat].concat())
+		.collect();
+
+	let (fmp4, catalog, consumer) = import();
+	let mut fmp4 = fmp4.with_grouping(crate::container::fmp4::Grouping::Fragment);
+
+	fmp4.decode(&init[..]).unwrap();
+	let mut track = subscribe_video(&catalog, &consumer);
+	fmp4.decode(&fragments[..]).unwrap();
+
+	// Every video fragment is in a group of its own.
+	let groups = drain_group_sequences(&mut track);
+	assert_eq!(groups.len(), count);
+
+	// By default, only keyframe fragments open a group, and bbb.mp4 has delta fragments.
+	assert!(decode_video_groups(&init, &fragments).len() < count);
+}
+
+/// Even per-fragment grouping won't open the first group on a fragment without a keyframe.
+#[test]
+fn grouping_per_fragment_starts_at_a_keyframe() {
+	let (init, fragments) = split_fragments(include_bytes!("test_data/bbb.mp4"));
+	// Fragment 3 is video without a keyframe.
+	let (moof, mdat) = &fragments[3];
+
+	let (fmp4, catalog, consumer) = import();
+	let mut fmp4 = fmp4.with_grouping(crate::container::fmp4::Grouping::Fragment);
+	fmp4.decode(&init[..]).unwrap();
+	let mut track = subscribe_video(&catalog, &consumer);
+
+	let err = fmp4.decode(&[&moof[..], &mdat[..]].concat()[..]).unwrap_err();
+	assert!(
+		matches!(err, crate::Error::Cmaf(super::Error::NoKeyframe)),
+		"got {err:?}"
+	);
+	assert!(drain_group_sequences(&mut track).is_empty());
+}
+
+/// Decode `data` and return the group sequences published on the video track.
+fn decode_video_groups(init: &[u8], data: &[u8]) -> Vec<u64> {
+	let (mut fmp4, catalog, broadcast_consumer) = import();
+
+	fmp4.decode(init).unwrap();
+	let mut video_track = subscribe_video(&catalog, &broadcast_consumer);
+
+	fmp4.decode(data).unwrap();
+	fmp4.finish().unwrap();
+
+	drain_group_sequences(&mut video_track)
+}
+
+/// The passthrough import keeps each trun sample-duration, so a CMAF consumer
+/// decodes a duration for every sample, at the track's native timescale.
+#[tokio::test]
+async fn test_passthrough_keeps_sample_durations() {
+	let data = include_bytes!("test_data/bbb.mp4");
+	let (init, fragments) = split_fragments(data);
+	let (moof, mdat) = fragments.first().expect("at least one fragment");
+
+	let (mut fmp4, catalog, broadcast_consumer) = import();
+
+	fmp4.decode(&init[..]).unwrap();
+	let mut video_track = subscribe_video(&catalog, &broadcast_consumer);
+
+	fmp4.decode(&[&moof[..], &mdat[..]].concat()[..]).unwrap();
+	fmp4.finish().unwrap();
+
+	let mut group = video_track.recv_group().await.unwrap().expect("a video group");
+	let fragment = group.read_frame().await.unwrap().expect("a fragment");
+	let frames = super::decode(fragment, 24000).unwrap();
+
+	assert!(!frames.is_empty());
+	for frame in &frames {
+		let duration = frame.duration.expect("every sample carries a duration");
+		assert!(!duration.is_zero());
+	}
+}
+
+/// A passthrough fragment keeps every trun entry (flags, duration, composition offset and
+/// size) of the source, so an fMP4 remux reproduces the original B-frame timing. That
+/// includes a sample with an explicit zero duration.
+#[tokio::test]
+async fn test_passthrough_keeps_trun_entries() {
+	let data = include_bytes!("test_data/bbb.mp4");
+	let (init, fragments) = split_fragments(data);
+	let (moof, mdat) = merge_fragments(&[&fragments[0], &fragments[3]]);
+	let mut source = mp4_atom::Moof::decode(&mut std::io::Cursor::new(&moof)).unwrap();
+	source.traf[0].trun[0].entries[0].duration = Some(0);
+	let moof = encode_moof(source.clone(), moof.len());
+
+	let (mut fmp4, catalog, broadcast_consumer) = import();
+
+	fmp4.decode(&init[..]).unwrap();
+	let mut video_track = subscribe_video(&catalog, &broadcast_consumer);
+
+	fmp4.decode(&[&moof[..], &mdat[..]].concat()[..]).unwrap();
+	fmp4.finish().unwrap();
+
+	let mut group = video_track.recv_group().await.unwrap().expect("a video group");
+	let fragment = group.read_frame().await.unwrap().expect("a fragment");
+	let published = mp4_atom::Moof::decode(&mut std::io::Cursor::new(&fragment[..])).unwrap();
+
+	let entries = |moof: &mp4_atom::Moof| -> Vec<mp4_atom::TrunEntry> {
+		moof.traf
+			.iter()
+			.flat_map(|traf| traf.trun.iter())
+			.flat_map(|trun| trun.entries.clone())
+			.collect()
+	};
+	assert!(!entries(&source).is_empty());
+	assert_eq!(entries(&published), entries(&source));
+	assert_eq!(
+		published.traf[0].tfdt.as_ref().unwrap().base_media_decode_time,
+		source.traf[0].tfdt.as_ref().unwrap().base_media_decode_time
+	);
+
+	// Every sample survives, the zero-duration one included.
+	let frames = super::decode(fragment, 24000).unwrap();
+	assert_eq!(frames.len(), entries(&source).len());
+	assert_eq!(frames[0].duration, Some(crate::container::Timestamp::ZERO));
+}
+
+/// The samples (timestamp, payload) published on the video track for `init` followed by `data`.
+async fn video_samples(init: &[u8], data: &[u8]) -> Vec<(crate::container::Timestamp, bytes::Bytes)> {
+	track_samples(init, data, b"vide").await
+}
+
+/// The (timestamp, payload) of every sample published on the track with the given handler.
+async fn track_samples(
+	init: &[u8],
+	data: &[u8],
+	handler: &[u8; 4],
+) -> Vec<(crate::container::Timestamp, bytes::Bytes)> {
+	let (_, moov) = decode_init(init);
+	let timescale = find_trak(&moov, handler).mdia.mdhd.timescale as u64;
+
+	let (mut fmp4, catalog, broadcast_consumer) = import();
+
+	fmp4.decode(init).unwrap();
+	let snapshot = catalog.snapshot();
+	let name = match handler {
+		b"vide" => snapshot.video.renditions.keys().next(),
+		_ => snapshot.audio.renditions.keys().next(),
+	}
+	.unwrap()
+	.clone();
+	let mut track = broadcast_consumer
+		.subscribe_track(&moq_net::Track::new(name.as_str()))
+		.unwrap();
+
+	fmp4.decode(data).unwrap();
+	fmp4.finish().unwrap();
+
+	let mut samples = Vec::new();
+	while let Some(mut group) = track.recv_group().now_or_never().and_then(|r| r.ok().flatten()) {
+		while let Some(fragment) = group.read_frame().await.unwrap() {
+			let frames = super::decode(fragment, timescale).unwrap();
+			samples.extend(frames.into_iter().map(|frame| (frame.timestamp, frame.payload)));
+		}
+	}
+	samples
+}
+
+#[test]
+fn track_alt_group() {
+	let data = include_bytes!("test_data/bbb.mp4");
+	let (init, _) = split_fragments(data);
+	let (_, moov) = decode_init(&init);
+	let video = find_trak(&moov, b"vide").tkhd.track_id;
+
+	// bbb.mp4's video tkhd declares no alternate group; its audio tkhd puts it in group 1.
+	let catalog = run_fmp4(data);
+	assert_eq!(catalog.video.renditions.values().next().unwrap().alt_group, None);
+	assert_eq!(catalog.audio.renditions.values().next().unwrap().alt_group, Some(1));
+
+	let (mut fmp4, catalog, _) = import();
+	fmp4.set_track_alt_group(video, 3);
+	fmp4.decode(&init[..]).unwrap();
+
+	let snapshot = catalog.snapshot();
+	assert_eq!(snapshot.video.renditions.values().next().unwrap().alt_group, Some(3));
+	assert_eq!(snapshot.audio.renditions.values().next().unwrap().alt_group, Some(1));
+}
+
+/// A track offset delays every published timestamp of that track, and only that track.
+#[tokio::test]
+async fn track_offset_shifts_timestamps() {
+	let data = include_bytes!("test_data/bbb.mp4");
+	let (init, fragments) = split_fragments(data);
+	let fragments: Vec<u8> = fragments
+		.into_iter()
+		.flat_map(|(moof, mdat)| [moof, mdat].concat())
+		.collect();
+	let baseline = video_samples(&init, &fragments).await;
+
+	let (_, moov) = decode_init(&init);
+	let video = find_trak(&moov, b"vide").tkhd.track_id;
+
+	let (mut fmp4, catalog, broadcast_consumer) = import();
+	fmp4.set_track_offset(video, std::time::Duration::from_secs(1));
+
+	fmp4.decode(&init[..]).unwrap();
+	let mut video_track = subscribe_video(&catalog, &broadcast_consumer);
+	fmp4.decode(&fragments[..]).unwrap();
+	fmp4.finish().unwrap();
+
+	let mut shifted = Vec::new();
+	while let Some(mut group) = video_track.recv_group().now_or_never().and_then(|r| r.ok().flatten()) {
+		while let Some(fragment) = group.read_frame().await.unwrap() {
+			shifted.extend(super::decode(fragment, 24000).unwrap());
+		}
+	}
+
+	assert!(!baseline.is_empty());
+	assert_eq!(shifted.len(), baseline.len());
+	let second = crate::container::Timestamp::from_secs_unchecked(1);
+	for (frame, (timestamp, payload)) in shifted.iter().zip(&baseline) {
+		assert_eq!(frame.timestamp, timestamp.checked_add(second).unwrap());
+		assert_eq!(&frame.payload, payload);
+	}
+}
+
+/// Encode `moof`, shifting every trun data_offset by however much the encoding grew or shrank
+/// from `original` bytes, so the offsets still land in the mdat that follows.
+fn encode_moof(mut moof: mp4_atom::Moof, original: usize) -> Vec<u8> {
+	let mut buf = Vec::new();
+	moof.encode(&mut buf).unwrap();
+	let delta = buf.len() as i32 - original as i32;
+
+	for trun in moof.traf.iter_mut().flat_map(|traf| traf.trun.iter_mut()) {
+		if let Some(data_offset) = &mut trun.data_offset {
+			*data_offset += delta;
+		}
+	}
+
+	buf.clear();
+	moof.encode(&mut buf).unwrap();
+	assert_eq!(buf.len() as i32 - original as i32, delta);
+	buf
+}
+
+/// Truns without a data_offset follow the previous trun's data, and an explicit tfhd
+/// base_data_offset is an absolute position in the input.
+#[tokio::test]
+async fn test_multi_trun_offsets() {
+	let data = include_bytes!("test_data/bbb.mp4");
+	let (init, fragments) = split_fragments(data);
+	let (moof, mdat) = merge_fragments(&[&fragments[0], &fragments[3], &fragments[3]]);
+	let expected = video_samples(&init, &[&moof[..], &mdat[..]].concat()).await;
+	assert!(expected.len() > 2);
+
+	let parsed = mp4_atom::Moof::decode(&mut std::io::Cursor::new(&moof[..])).unwrap();
+
+	// Split each traf's first trun in two; the second half carries no data_offset.
+	let mut split = parsed.clone();
+	for traf in &mut split.traf {
+		let trun = traf.trun[0].clone();
+		let (head, tail) = trun.entries.split_at(trun.entries.len() / 2);
+		let halves = [
+			mp4_atom::Trun {
+				entries: head.to_vec(),
+				..trun.clone()
+			},
+			mp4_atom::Trun {
+				data_offset: None,
+				entries: tail.to_vec(),
+			},
+		];
+		traf.trun.splice(0..1, halves);
+	}
+	let split = encode_moof(split, moof.len());
+	assert_eq!(video_samples(&init, &[&split[..], &mdat[..]].concat()).await, expected);
+
+	// Address every traf from the moof's absolute position instead.
+	let mut absolute = parsed;
+	for traf in &mut absolute.traf {
+		traf.tfhd.base_data_offset = Some(init.len() as u64);
+	}
+	let absolute = encode_moof(absolute, moof.len());
+	assert_eq!(
+		video_samples(&init, &[&absolute[..], &mdat[..]].concat()).await,
+		expected
+	);
+}
+
+/// One moof whose video and audio trafs share a single mdat, their runs alternating
+/// (V A V A) as low-latency packagers lay them out: each track gets exactly its own samples.
+#[tokio::test]
+async fn test_interleaved_mdat_offsets() {
+	let data = include_bytes!("test_data/bbb.mp4");
+	let (init, fragments) = split_fragments(data);
+	let (video_moof, video_mdat) = merge_fragments(&[&fragments[0], &fragments[3], &fragments[3]]);
+	let (audio_moof, audio_mdat) = merge_fragments(&[&fragments[1], &fragments[2], &fragments[2]]);
+
+	let expected_video = video_samples(&init, &[&video_moof[..], &video_mdat[..]].concat()).await;
+	let expected_audio = track_samples(&init, &[&audio_moof[..], &audio_mdat[..]].concat(), b"soun").await;
+	assert!(expected_video.len() > 2 && expected_audio.len() > 2);
+
+	// Split a fragment's single trun in two, returning the trafs and each half's payload.
+	let halves = |raw: &[u8], mdat: &[u8]| {
+		let (mfhd, mut traf, payload) = single_run(raw, mdat);
+		let trun = traf.trun.remove(0);
+		let (head, tail) = trun.entries.split_at(trun.entries.len() / 2);
+		let default_size = traf.tfhd.default_sample_size;
+		let size: usize = head
+			.iter()
+			.map(|entry| entry.size.or(default_size).unwrap() as usize)
+			.sum();
+
+		traf.trun = [head, tail]
+			.map(|entries| mp4_atom::Trun {
+				entries: entries.to_vec(),
+				..trun.clone()
+			})
+			.to_vec();
+		(mfhd, traf, payload[..size].to_vec(), payload[size..].to_vec())
+	};
+	let (mfhd, mut video, video_head, video_tail) = halves(&video_moof[..], &video_mdat[..]);
+	let (_, mut audio, audio_head, audio_tail) = halves(&audio_moof[..], &audio_mdat[..]);
+
+	// Address both trafs from the moof, so each run's offset is independent of the other traf.
+	video.tfhd.base_data_offset = Some(init.len() as u64);
+	audio.tfhd.base_data_offset = Some(init.len() as u64);
+	let mut moof = mp4_atom::Moof {
+		mfhd,
+		traf: vec![video, audio],
+	};
+
+	// The runs in mdat order, as (traf, trun, payload); every trun already has a data_offset,
+	// so the moof's size is final and the offsets can be filled in place.
+	let runs = [
+		(0, 0, &video_head),
+		(1, 0, &audio_head),
+		(0, 1, &video_tail),
+		(1, 1, &audio_tail),
+	];
+	let mut position = moof_len(&moof) + 8;
+	for (traf, trun, payload) in runs {
+		moof.traf[traf].trun[trun].data_offset = Some(position as i32);
+		position += payload.len();
+	}
+
+	let mut interleaved = Vec::new();
+	moof.encode(&mut interleaved).unwrap();
+	mp4_atom::Mdat {
+		data: runs
+			.iter()
+			.flat_map(|(_, _, payload)| payload.iter().copied())
+			.collect(),
+	}
+	.encode(&mut interleaved)
+	.unwrap();
+
+	assert_eq!(video_samples(&init, &interleaved).await, expected_video);
+	assert_eq!(track_samples(&init, &interleaved, b"soun").await, expected_audio);
+}
+
+/// A traf flagged default-base-is-moof counts its data_offset from the moof, even when an
+/// earlier traf's data comes first; without the flag it would follow on from that data.
+#[tokio::test]
+async fn test_default_base_is_moof() {
+	let data = include_bytes!("test_data/bbb.mp4");
+	let (init, fragments) = split_fragments(data);
+	let (video_moof, video_mdat) = &fragments[0];
+	let (audio_moof, audio_mdat) = &fragments[1];
+
+	let expected_video = video_samples(&init, &[&video_moof[..], &video_mdat[..]].concat()).await;
+	let expected_audio = track_samples(&init, &[&audio_moof[..], &audio_mdat[..]].concat(), b"soun").await;
+	assert!(!expected_video.is_empty() && !expected_audio.is_empty());
+
+	let (mfhd, video, video_payload) = single_run(video_moof, video_mdat);
+	let (_, audio, audio_payload) = single_run(audio_moof, audio_mdat);
+
+	let mut moof = mp4_atom::Moof {
+		mfhd,
+		traf: vec![video, audio],
+	};
+	for traf in &mut moof.traf {
+		traf.tfhd.base_data_offset = None;
+	}
+
+	// Both data_offsets count from the moof; the video run comes first in the mdat.
+	let header = moof_len(&moof) + 8;
+	moof.traf[0].trun[0].data_offset = Some(header as i32);
+	moof.traf[1].trun[0].data_offset = Some((header + video_payload.len()) as i32);
+
+	let mut interleaved = Vec::new();
+	moof.encode(&mut interleaved).unwrap();
+
+	// mp4_atom can't set the flag, so patch it into the audio tfhd's flags.
+	let tfhd = interleaved
+		.windows(4)
+		.enumerate()
+		.filter(|(_, kind)| *kind == b"tfhd")
+		.map(|(position, _)| position)
+		.nth(1)
+		.unwrap();
+	interleaved[tfhd + 5] |= 0x02;
+
+	mp4_atom::Mdat {
+		data: [video_payload, audio_payload].concat(),
+	}
+	.encode(&mut interleaved)
+	.unwrap();
+
+	assert_eq!(video_samples(&init, &interleaved).await, expected_video);
+	assert_eq!(track_samples(&init, &interleaved, b"soun").await, expected_audio);
+}
+
+fn moof_len(moof: &mp4_atom::Moof) -> usize {
+	let mut buf = Vec::new();
+	moof.encode(&mut buf).unwrap();
+	buf.len()
+}
+
+/// A low-latency chunk that splits the mdat emits the samples that have already arrived,
+/// and the rest follow once the mdat completes, without duplicates.
+#[tokio::test]
+async fn test_partial_mdat_emits_early() {
+	let data = include_bytes!("test_data/bbb.mp4");
+	let (init, fragments) = split_fragments(data);
+	// Two video samples in one fragment, so a split mdat can deliver the first on its own.
+	let (moof, mdat) = merge_fragments(&[&fragments[0], &fragments[3]]);
+
+	let (_, moov) = decode_init(&init);
+	let video_id = find_trak(&moov, b"vide").tkhd.track_id;
+	let parsed = mp4_atom::Moof::decode(&mut std::io::Cursor::new(&moof[..])).unwrap();
+	let expected: usize = parsed
+		.traf
+		.iter()
+		.filter(|traf| traf.tfhd.track_id == video_id)
+		.flat_map(|traf| &traf.trun)
+		.map(|trun| trun.entries.len())
+		.sum();
+
+	let (mut fmp4, catalog, broadcast_consumer) = import();
+
+	fmp4.decode(&init[..]).unwrap();
+	let mut video_track = subscribe_video(&catalog, &broadcast_consumer);
+
+	// Everything but the last byte of the mdat.
+	let split = mdat.len() - 1;
+	fmp4.decode(&[&moof[..], &mdat[..split]].concat()[..]).unwrap();
+
+	let mut group = video_track.recv_group().await.unwrap().expect("a video group");
+	let first = group.read_frame().await.unwrap().expect("an early fragment");
+	let mut samples = super::decode(first, 24000).unwrap().len();
+	assert!(samples > 0);
+
+	fmp4.decode(&mdat[split..]).unwrap();
+	fmp4.finish().unwrap();
+
+	while let Some(fragment) = group.read_frame().await.unwrap() {
+		samples += super::decode(fragment, 24000).unwrap().len();
+	}
+	assert_eq!(samples, expected);
+}
+
+/// moof/mdat/moof/mdat with no styp between fragments: each mdat consumes its moof.
+#[tokio::test]
+async fn test_back_to_back_fragments() {
+	let (init, fragments) = split_fragments(include_bytes!("test_data/bbb.mp4"));
+	assert!(fragments.len() >= 2, "expected at least two fragments");
+
+	let mut data = Vec::new();
+	for (moof, mdat) in &fragments[..2] {
+		data.extend_from_slice(moof);
+		data.extend_from_slice(mdat);
+	}
+
+	assert!(!decode_video_groups(&init, &data).is_empty());
+}
+
+/// A file that is still being written ends mid-fragment; the tail is buffered, not an error.
+#[test]
+fn test_partial_fragment_resumes() {
+	let (init, fragments) = split_fragments(include_bytes!("test_data/bbb.mp4"));
+	let (moof, mdat) = &fragments[0];
+
+	let (mut fmp4, _, _) = import();
+
+	fmp4.decode(&init).unwrap();
+	assert!(!fmp4.is_partial());
+
+	// Cut inside the moof header, then after the moof, then inside the mdat.
+	fmp4.decode(&moof[..4]).unwrap();
+	assert!(fmp4.is_partial());
+	fmp4.decode(&moof[4..]).unwrap();
+	assert!(fmp4.is_partial());
+	fmp4.decode(&mdat[..mdat.len() / 2]).unwrap();
+	assert!(fmp4.is_partial());
+
+	fmp4.decode(&mdat[mdat.len() / 2..]).unwrap();
+	assert!(!fmp4.is_partial());
+}
+
+/// A moof followed by another moof instead of its mdat is dropped as an orphan.
+#[tokio::test]
+async fn test_moof_without_mdat() {
+	let (init, fragments) = split_fragments(include_bytes!("test_data/bbb.mp4"));
+	assert!(fragments.len() >= 2, "expected at least two fragments");
+
+	let mut data = Vec::new();
+	data.extend_from_slice(&fragments[1].0);
+	data.extend_from_slice(&fragments[0].0);
+	data.extend_from_slice(&fragments[0].1);
+
+	assert!(!decode_video_groups(&init, &data).is_empty());
+}
+
 /// E2E test: publish via the fMP4 importer, subscribe to the MSF catalog track,
 /// and verify the resulting `hang::Catalog` matches what the hang catalog would
 /// have produced.
@@ -281,12 +1334,7 @@ async fn test_seek_sets_initial_sequence() {
 /// exercises the full unified pipeline (hang -> MSF JSON on the wire -> hang).
 #[tokio::test]
 async fn test_msf_catalog_roundtrip() {
-	let mut broadcast = moq_net::Broadcast::new().produce();
-	// Take the consumer before adding tracks; track() is called after the
-	// MSF catalog track has been created by `catalog::Producer::new`.
-	let consumer = broadcast.consume();
-	let catalog = crate::catalog::Producer::new(&mut broadcast).unwrap();
-	let mut fmp4 = crate::container::fmp4::Import::new(broadcast, catalog);
+	let (mut fmp4, _, consumer) = import();
 
 	let data = include_bytes!("test_data/bbb.mp4");
 	let buf = bytes::BytesMut::from(&data[..]);
@@ -434,3 +1482,24 @@ fn test_flac_catalog() {
 	let desc = a.description.as_ref().expect("flac description");
 	assert_eq!(&desc[..4], b"fLaC");
 }
+
+#[test]
+fn test_registered_codec_overrides_builtin() {
+	let codecs = crate::container::fmp4::Codecs::new().with_video(b"vp09", |trak| {
+		let mut config = hang::catalog::VideoConfig::new(hang::catalog::VideoCodec::Unknown("custom".into()));
+		if let mp4_atom::Codec::Vp09(vp09) = &trak.mdia.minf.stbl.stsd.codecs[0] {
+			config.coded_width = Some(vp09.visual.width as _);
+		}
+		Ok(config)
+	});
+	let (fmp4, catalog, _) = import();
+	let mut fmp4 = fmp4.with_codecs(codecs);
+	fmp4.decode(include_bytes!("test_data/vp9.mp4")).unwrap();
+
+	let catalog = catalog.snapshot();
+	let video = catalog.video.renditions.values().next().unwrap();
+	assert_eq!(video.codec.to_string(), "custom");
+	assert!(video.coded_width.is_some());
+	// The importer still supplies the CMAF init segment.
+	assert!(matches!(video.container, Container::Cmaf { .. }));
+}
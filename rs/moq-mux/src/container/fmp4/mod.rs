@@ -6,9 +6,18 @@
 //! container; [`Import`] parses external fMP4 streams and [`Export`]
 //! produces them.
 
+mod brand;
+mod codecs;
+mod emsg;
 mod export;
 mod import;
 
+/// The `fmp4` catalog section: timed metadata and still image tracks passed through as opaque fragments.
+pub mod catalog;
+
+pub use brand::Profile;
+pub use codecs::*;
+pub use emsg::Event;
 pub use export::*;
 pub use import::*;
 
@@ -91,9 +100,6 @@ pub enum Error {
 	#[error("unsupported codec: MPEG2")]
 	UnsupportedMpeg2,
 
-	#[error("duplicate moof")]
-	DuplicateMoof,
-
 	#[error("missing trun")]
 	MissingTrun,
 
@@ -132,6 +138,12 @@ pub enum Error {
 
 	#[error("multi-sample fragment has a non-final sample with no duration; DTS is unrecoverable")]
 	MissingSampleDuration,
+
+	#[error("malformed emsg box")]
+	InvalidEmsg,
+
+	#[error("unsupported brand: {0:?}")]
+	UnsupportedBrand(mp4_atom::FourCC),
 }
 
 impl From<mp4_atom::Error> for Error {
@@ -266,7 +278,8 @@ pub(crate) fn decode(data: Bytes, timescale: u64) -> Result<Vec<Frame>> {
 
 			// Carry the sample-duration through at the track's scale when present, so
 			// the jitter buffer can use it and an exporter can write it back.
-			let sample_duration = entry.duration.or(default_duration).filter(|d| *d != 0);
+			// An explicit zero is kept, but a zero default means the sample has none.
+			let sample_duration = entry.duration.or(default_duration.filter(|d| *d != 0));
 
 			// The last sample needs no duration (nothing follows it to time), but any
 			// earlier sample without one makes the rest of the fragment's DTS ambiguous.
@@ -309,9 +322,7 @@ pub(crate) fn encode(
 	let bytes = encode_fragment(track_id, timescale, sequence_number, frames)?;
 	// The fragment may carry several samples; the net frame's timestamp is the
 	// fragment's earliest presentation time so a relay can order it.
-	let mut writer = group.create_frame(moq_net::Frame {
-		size: bytes.len() as u64,
-	})?;
+	let mut writer = group.create_frame(moq_net::Frame::new(bytes.len() as u64))?;
 	writer.write(bytes)?;
 	writer.finish()?;
 
@@ -30,9 +30,7 @@ impl Container for Wire {
 
 			// Carry the timestamp on the net frame too (converted to the track's
 			// timescale), so a relay sees it without parsing the LOC payload.
-			let mut chunked = group.create_frame(moq_net::Frame {
-				size: data.len() as u64,
-			})?;
+			let mut chunked = group.create_frame(moq_net::Frame::new(data.len() as u64))?;
 			chunked.write(data)?;
 			chunked.finish()?;
 		}
@@ -520,7 +520,9 @@ fn build_audio_config(
 			let cfg = crate::codec::aac::Config::parse(&mut cursor)?;
 
 			let mut config = AudioConfig::new(
-				AAC { profile: cfg.profile },
+				AAC {
+					profile: cfg.object_type(),
+				},
 				if cfg.sample_rate > 0 {
 					cfg.sample_rate
 				} else {
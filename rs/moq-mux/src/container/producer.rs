@@ -1,3 +1,5 @@
+use std::sync::{Arc, Mutex};
+
 use super::{Container, Frame, Timestamp};
 
 /// A producer for media tracks that manages group boundaries.
@@ -42,6 +44,9 @@ pub struct Producer<C: Container> {
 	/// Records each group open (sequence + keyframe timestamp) into this rendition's
 	/// timeline track, when the producer was built with one.
 	recorder: Option<crate::timeline::Recorder>,
+
+	/// Advanced past each written frame's timestamp, when the producer was built by the catalog.
+	progress: Option<Progress>,
 }
 
 impl<C: Container> Producer<C> {
@@ -59,6 +64,7 @@ impl<C: Container> Producer<C> {
 			latency: std::time::Duration::ZERO,
 			pending_sequence: None,
 			recorder: None,
+			progress: None,
 		}
 	}
 
@@ -84,6 +90,12 @@ impl<C: Container> Producer<C> {
 		self
 	}
 
+	/// Advance `progress` past each frame written, see [`catalog::Producer::progress`](crate::catalog::Producer::progress).
+	pub(crate) fn with_progress(mut self, progress: Progress) -> Self {
+		self.progress = Some(progress);
+		self
+	}
+
 	/// The underlying moq-lite track producer. Read-only; mutating it directly
 	/// would sidestep group/keyframe invariants.
 	pub fn track(&self) -> &moq_net::TrackProducer {
@@ -110,7 +122,7 @@ impl<C: Container> Producer<C> {
 				return Err(super::MissingKeyframe.into());
 			}
 			let group = match self.pending_sequence.take() {
-				Some(sequence) => self.inner.create_group(moq_net::Group { sequence })?,
+				Some(sequence) => self.inner.create_group(moq_net::Group::new(sequence))?,
 				None => self.inner.append_group()?,
 			};
 
@@ -129,6 +141,10 @@ impl<C: Container> Producer<C> {
 			self.group = Some(group);
 		}
 
+		if let Some(progress) = &self.progress {
+			progress.advance(frame.timestamp);
+		}
+
 		// Buffer or write the frame.
 		if self.latency.is_zero() {
 			let group = self.group.as_mut().unwrap();
@@ -257,6 +273,30 @@ impl<C: Container> std::ops::Deref for Producer<C> {
 	}
 }
 
+/// The presentation time of the newest frame written to any track of a broadcast.
+///
+/// A cheap handle: clones share the same value, so a reader can query it while the importers
+/// keep writing. Timestamps only move forward; an earlier frame (a B-frame, or a track lagging
+/// another) leaves it alone.
+#[derive(Clone, Debug, Default)]
+pub struct Progress {
+	newest: Arc<Mutex<Option<Timestamp>>>,
+}
+
+impl Progress {
+	pub(crate) fn advance(&self, timestamp: Timestamp) {
+		let mut newest = self.newest.lock().unwrap();
+		if newest.is_none_or(|newest| newest < timestamp) {
+			*newest = Some(timestamp);
+		}
+	}
+
+	/// The newest timestamp written, or `None` before the first frame.
+	pub fn newest(&self) -> Option<Timestamp> {
+		*self.newest.lock().unwrap()
+	}
+}
+
 #[cfg(test)]
 mod tests {
 	use bytes::Bytes;
@@ -1103,6 +1103,8 @@ impl<E: CatalogExt> AacStream<E> {
 						profile: header.object_type,
 						sample_rate: header.sample_rate,
 						channel_count: header.channel_count,
+						// ADTS can't signal SBR/PS; a decoder detects it in the stream.
+						extension: None,
 					};
 					// Synthesize the AudioSpecificConfig from the first ADTS header so
 					// downstream consumers that need out-of-band config (fMP4/MKV export,
@@ -607,6 +607,7 @@ mod tests {
 			profile: 2,
 			sample_rate: 44_100,
 			channel_count: 2,
+			extension: None,
 		};
 		let init = config.encode();
 		let request = broadcast.create_track(moq_net::Track::new("audio")).unwrap();
@@ -30,7 +30,15 @@
 //! keyframes are already a granularity or more apart, so every group is indexed; short audio groups
 //! are thinned out. A consumer that lands between two records extrapolates the group number
 //! (sequences are contiguous) or fetches to fill the gap.
-
+//!
+//! Alongside the track, the publisher keeps an unthrottled in-memory [`Index`] of the same group
+//! opens, so a local seek can map a time to its keyframe group exactly. It is rolling by default
+//! ([`DEFAULT_INDEX_CAPACITY`] groups, for live); a file-backed broadcast lifts the bound to index
+//! its whole timeline (see
+//! [`catalog::Producer::with_index_capacity`](crate::catalog::Producer::with_index_capacity)).
+
+use std::collections::VecDeque;
+use std::sync::{Arc, Mutex};
 use std::task::Poll;
 use std::time::SystemTime;
 
@@ -43,6 +51,10 @@ use crate::container::Timestamp;
 /// media time.
 pub const DEFAULT_GRANULARITY: Timestamp = Timestamp::from_secs_unchecked(1);
 
+/// The default bound of an [`Index`]: the most recent 3600 groups, an hour of video at one
+/// keyframe per second.
+pub const DEFAULT_INDEX_CAPACITY: usize = 3600;
+
 /// Owns one media track's timeline: its catalog [`section`](Self::section) and wall anchor, and the
 /// `Recorder` its group opens are recorded through.
 ///
@@ -55,6 +67,8 @@ pub struct Producer<E: RecordExt = ()> {
 	granularity: Timestamp,
 	// The wall-clock time of pts 0, in timescale units since the moq epoch, advertised in section().
 	wall: Option<u64>,
+	// Every group open, unthrottled, for local seeks.
+	index: Index,
 }
 
 impl<E: RecordExt> Producer<E> {
@@ -74,6 +88,7 @@ impl<E: RecordExt> Producer<E> {
 			timescale: Timeline::default_timescale(),
 			granularity: DEFAULT_GRANULARITY,
 			wall: None,
+			index: Index::new(Some(DEFAULT_INDEX_CAPACITY)),
 		})
 	}
 
@@ -84,6 +99,18 @@ impl<E: RecordExt> Producer<E> {
 		self
 	}
 
+	/// Share `index` as this timeline's in-memory [`Index`], so recorders minted after this call
+	/// feed it. The catalog hands over the rendition's index, which may already be in use.
+	pub(crate) fn with_index(mut self, index: Index) -> Self {
+		self.index = index;
+		self
+	}
+
+	/// The in-memory [`Index`] of every group recorded through this timeline.
+	pub fn index(&self) -> Index {
+		self.index.clone()
+	}
+
 	/// The catalog section advertising this timeline, to attach to the rendition's config.
 	pub fn section(&self) -> Timeline {
 		let mut section = Timeline::new(&self.track);
@@ -122,6 +149,7 @@ impl<E: RecordExt> Producer<E> {
 			timescale: self.timescale,
 			granularity: self.granularity,
 			last: None,
+			index: self.index.clone(),
 		}
 	}
 
@@ -147,12 +175,17 @@ pub(crate) struct Recorder<E: RecordExt = ()> {
 	granularity: Timestamp,
 	// The pts of the last recorded group; the throttle floor. Owned, since a recorder is 1:1.
 	last: Option<Timestamp>,
+	index: Index,
 }
 
 impl<E: RecordExt> Recorder<E> {
 	/// Record that group `sequence` opened at presentation time `pts`, unless it falls within the
 	/// granularity of the last recorded group (skipped, so a consumer extrapolates or fetches).
+	///
+	/// The in-memory [`Index`] takes every group regardless.
 	pub(crate) fn record(&mut self, sequence: u64, pts: Timestamp) -> Result<(), moq_net::Error> {
+		self.index.insert(sequence, pts);
+
 		if let Some(last) = self.last
 			&& pts.as_micros() < last.as_micros() + self.granularity.as_micros()
 		{
@@ -187,6 +220,81 @@ pub struct Entry<E: RecordExt = ()> {
 	pub ext: E,
 }
 
+/// An in-memory map from presentation time to the keyframe group that covers it.
+///
+/// Fed by the publisher as each group opens, with no throttle, so unlike the timeline track it
+/// answers [`group_at`](Self::group_at) exactly. A cheap handle: clones share the same index, so a
+/// seek can query it while the importer keeps appending.
+///
+/// Bounded to a number of groups, dropping the oldest first (a rolling window for live), or
+/// unbounded to cover a whole file. Group starts are kept in increasing time order: a group that
+/// opens at or before an indexed one (a timestamp discontinuity) replaces every later entry.
+#[derive(Debug, Clone)]
+pub struct Index {
+	inner: Arc<Mutex<IndexState>>,
+}
+
+#[derive(Debug)]
+struct IndexState {
+	entries: VecDeque<Entry>,
+	capacity: Option<usize>,
+}
+
+impl Index {
+	/// An empty index holding at most `capacity` groups, or every group if `None`.
+	pub fn new(capacity: Option<usize>) -> Self {
+		Self {
+			inner: Arc::new(Mutex::new(IndexState {
+				entries: VecDeque::new(),
+				capacity,
+			})),
+		}
+	}
+
+	pub(crate) fn insert(&self, group: u64, pts: Timestamp) {
+		let mut state = self.inner.lock().unwrap();
+
+		let keep = state.entries.partition_point(|entry| entry.pts < pts);
+		state.entries.truncate(keep);
+		state.entries.push_back(Entry { group, pts, ext: () });
+
+		if let Some(capacity) = state.capacity {
+			while state.entries.len() > capacity {
+				state.entries.pop_front();
+			}
+		}
+	}
+
+	/// The keyframe group to start from to present `pts`: the last one opening at or before it.
+	///
+	/// `None` if `pts` precedes every indexed group, e.g. it has already rolled out of a live index.
+	pub fn group_at(&self, pts: Timestamp) -> Option<Entry> {
+		let state = self.inner.lock().unwrap();
+		let index = state.entries.partition_point(|entry| entry.pts <= pts);
+		index.checked_sub(1).map(|index| state.entries[index].clone())
+	}
+
+	/// The oldest indexed group.
+	pub fn first(&self) -> Option<Entry> {
+		self.inner.lock().unwrap().entries.front().cloned()
+	}
+
+	/// The newest indexed group, the live edge.
+	pub fn last(&self) -> Option<Entry> {
+		self.inner.lock().unwrap().entries.back().cloned()
+	}
+
+	/// The number of indexed groups.
+	pub fn len(&self) -> usize {
+		self.inner.lock().unwrap().entries.len()
+	}
+
+	/// True if no group has been indexed (or every one rolled out).
+	pub fn is_empty(&self) -> bool {
+		self.len() == 0
+	}
+}
+
 /// Reads a media track's timeline, yielding decoded [`Entry`]s in publish order.
 ///
 /// Generic over the record extension `E` (see [`RecordExt`]).
@@ -345,4 +453,47 @@ mod test {
 		assert_eq!(entries, vec![entry(3, 7)]);
 		assert_eq!(entries[0].pts, Timestamp::from_micros(7_000).unwrap());
 	}
+
+	#[test]
+	fn index_is_unthrottled() {
+		let mut broadcast = moq_net::Broadcast::new().produce();
+		let timeline = Producer::<()>::new(&mut broadcast, "audio0").unwrap();
+		let index = timeline.index();
+		let mut recorder = timeline.recorder();
+
+		// The track keeps one record per second, the index every group.
+		for (seq, ms) in [(0u64, 0u64), (1, 300), (2, 600)] {
+			recorder.record(seq, Timestamp::from_millis(ms).unwrap()).unwrap();
+		}
+		assert_eq!(index.len(), 3);
+		assert_eq!(
+			index.group_at(Timestamp::from_millis(450).unwrap()),
+			Some(entry(1, 300))
+		);
+		assert_eq!(
+			index.group_at(Timestamp::from_millis(600).unwrap()),
+			Some(entry(2, 600))
+		);
+	}
+
+	#[test]
+	fn index_rolls_and_restarts() {
+		let index = Index::new(Some(2));
+		for (seq, ms) in [(0u64, 0u64), (1, 2_000), (2, 4_000)] {
+			index.insert(seq, Timestamp::from_millis(ms).unwrap());
+		}
+
+		// Group 0 rolled out, so a time before group 1 has nothing to start from.
+		assert_eq!(index.first(), Some(entry(1, 2_000)));
+		assert_eq!(index.group_at(Timestamp::from_millis(1_000).unwrap()), None);
+		assert_eq!(
+			index.group_at(Timestamp::from_millis(9_000).unwrap()),
+			Some(entry(2, 4_000))
+		);
+
+		// A discontinuity back in time replaces the entries it overlaps.
+		index.insert(3, Timestamp::from_millis(3_000).unwrap());
+		assert_eq!(index.len(), 2);
+		assert_eq!(index.last(), Some(entry(3, 3_000)));
+	}
 }
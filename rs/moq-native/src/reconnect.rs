@@ -95,6 +95,13 @@ struct State {
 /// The extra toggle a plain session doesn't have is the connection lifecycle: [`connected`](Self::connected)
 /// reads it synchronously and [`status`](Self::status) waits for the next change. [`closed`](Self::closed)
 /// waits for the loop to stop. Dropping the handle aborts the background task.
+///
+/// Each new session publishes from a fresh cursor over the publish origin, so every local broadcast
+/// still active is announced again, e.g. to a relay that restarted.
+///
+/// Remote broadcasts are announced again into the same consume origin after each reconnect, but
+/// existing track subscriptions die with the session; read through [`moq_net::ResumeTrack`] to
+/// resubscribe automatically.
 pub struct Reconnect {
 	abort: tokio::task::AbortHandle,
 	state: kio::Consumer<State>,
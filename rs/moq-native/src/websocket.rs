@@ -1,3 +1,9 @@
+//! WebSocket fallback for networks that block QUIC.
+//!
+//! The session is carried by [qmux], which frames uni and bidi streams over a single
+//! WebSocket connection and implements the `web_transport_trait` session and stream
+//! traits, so `moq_net` runs over it unchanged. The client races it against QUIC.
+
 use qmux::tokio_tungstenite;
 use qmux::tokio_tungstenite::tungstenite::{self, client::IntoClientRequest, http};
 use std::collections::HashSet;
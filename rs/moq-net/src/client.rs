@@ -1,10 +1,31 @@
 use crate::{
 	ALPN_14, ALPN_15, ALPN_16, ALPN_17, ALPN_18, ALPN_19, ALPN_LITE, ALPN_LITE_03, ALPN_LITE_04, ALPN_LITE_05_WIP,
-	Error, NEGOTIATED, OriginConsumer, OriginProducer, Session, StatsHandle, Version, Versions,
+	Error, NEGOTIATED, OriginConsumer, OriginProducer, RateLimit, Session, StatsHandle, Version, Versions,
 	coding::{self, Decode, Encode, Stream},
-	ietf, lite, setup,
+	ietf,
+	limit::DEFAULT_MAX_STREAMS,
+	lite, setup,
+	window::DEFAULT_GROUP_WINDOW,
 };
 
+/// Which directions a client session is used for.
+///
+/// Narrows what [`Client::with_origin`] (or the publish/consume pair) wires up, so an
+/// ingest client that happens to hold a full origin still only publishes. The IETF
+/// drafts we speak have no ROLE setup parameter any more, so the role shows up on the
+/// wire as the requests the session makes: a publisher never sends SUBSCRIBE_NAMESPACE
+/// and a subscriber never announces.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum SessionRole {
+	/// Only publish broadcasts, never subscribe.
+	Publisher,
+	/// Only subscribe to broadcasts, never publish.
+	Subscriber,
+	/// Publish and subscribe.
+	#[default]
+	Both,
+}
+
 /// A MoQ client session builder.
 #[derive(Default, Clone)]
 pub struct Client {
@@ -13,6 +34,14 @@ pub struct Client {
 	stats: StatsHandle,
 	versions: Versions,
 	path: Option<String>,
+	role: SessionRole,
+	// Extra IETF SETUP parameters, applied over the defaults.
+	parameters: ietf::Parameters,
+	rate_limit: RateLimit,
+	max_streams: Option<usize>,
+	group_window: Option<u64>,
+	max_latency: Option<std::time::Duration>,
+	wire_log: bool,
 }
 
 impl Client {
@@ -51,6 +80,96 @@ impl Client {
 		self
 	}
 
+	/// Restrict the session to publishing or subscribing.
+	pub fn with_role(mut self, role: SessionRole) -> Self {
+		self.role = role;
+		self
+	}
+
+	/// Add a varint IETF SETUP parameter, overriding the default with the same `id`.
+	///
+	/// Ignored by moq-lite. Draft-14 through 16 reserve even ids for varints.
+	pub fn with_setup_varint(mut self, id: u64, value: u64) -> Self {
+		self.parameters.set_varint(id, value);
+		self
+	}
+
+	/// Add a bytes IETF SETUP parameter, overriding the default with the same `id`.
+	///
+	/// Ignored by moq-lite. Draft-14 through 16 reserve odd ids for byte strings.
+	pub fn with_setup_bytes(mut self, id: u64, value: impl Into<Vec<u8>>) -> Self {
+		self.parameters.set_bytes(id.into(), value.into());
+		self
+	}
+
+	/// Cap the bytes per second written for each track this session publishes.
+	///
+	/// Each subscription gets its own token bucket, refilled at the rate `limit` picks
+	/// for the track's priority and holding up to one second of budget, so one
+	/// high-bitrate track can't take more than its share however the transport
+	/// prioritizes streams. Uncapped by default.
+	pub fn with_rate_limit(mut self, limit: RateLimit) -> Self {
+		self.rate_limit = limit;
+		self
+	}
+
+	/// Cap the incoming group streams processed at once.
+	///
+	/// Further streams are left unaccepted until one finishes, so QUIC flow control
+	/// pushes back on the peer. Setup and control streams don't count. Defaults to 4096,
+	/// well above what multi-track playback needs; a `max` of zero removes the cap.
+	pub fn with_max_streams(mut self, max: usize) -> Self {
+		self.max_streams = Some(max);
+		self
+	}
+
+	/// Drop incoming groups that fall `size` or more behind the newest group of their track.
+	///
+	/// A player that can't keep up would otherwise reassemble every group it was sent,
+	/// accumulating latency. With a window, stale groups are aborted with [`Error::Old`]
+	/// so the player stays near the live edge. The default of `2` keeps the latest two
+	/// groups, like the publisher; a `size` of zero keeps every group.
+	pub fn with_group_window(mut self, size: u64) -> Self {
+		self.group_window = Some(size);
+		self
+	}
+
+	/// Ask publishers to drop a group once it is `latency` older than the newest group of its track.
+	///
+	/// Sent in every moq-lite SUBSCRIBE. The newest group is never dropped, however long it
+	/// runs, and zero, what older subscribers send, drops nothing. Defaults to ten seconds. Ignored by IETF
+	/// sessions and by moq-lite-01/02, which have no such field.
+	pub fn with_max_latency(mut self, latency: std::time::Duration) -> Self {
+		self.max_latency = Some(latency);
+		self
+	}
+
+	/// Log every IETF control message sent or received after SETUP as hex at TRACE level.
+	///
+	/// Covers the control stream on draft-14 to 16 and each request stream on draft-17+.
+	/// For diagnosing interop failures, where one malformed varint breaks the control stream.
+	/// Off by default, as it formats every message. Ignored by moq-lite.
+	pub fn with_wire_log(mut self, enabled: bool) -> Self {
+		self.wire_log = enabled;
+		self
+	}
+
+	// The origin to publish from, unless the role is subscribe-only.
+	fn publish(&self) -> Option<OriginConsumer> {
+		match self.role {
+			SessionRole::Subscriber => None,
+			SessionRole::Publisher | SessionRole::Both => self.publish.clone(),
+		}
+	}
+
+	// The origin to consume into, unless the role is publish-only.
+	fn consume(&self) -> Option<OriginProducer> {
+		match self.role {
+			SessionRole::Publisher => None,
+			SessionRole::Subscriber | SessionRole::Both => self.consume.clone(),
+		}
+	}
+
 	/// Set the request path to advertise in the SETUP (moq-lite-05).
 	///
 	/// Required on transports that carry no request URI (native QUIC, qmux over
@@ -70,9 +189,39 @@ impl Client {
 		self
 	}
 
+	fn ietf_config(&self, version: ietf::Version) -> ietf::StartConfig {
+		ietf::StartConfig {
+			version,
+			client: true,
+			request_id_max: None,
+			parameters: self.parameters.clone(),
+			publish: self.publish(),
+			subscribe: self.consume(),
+			stats: self.stats.clone(),
+			rate_limit: self.rate_limit.clone(),
+			max_streams: self.max_streams.unwrap_or(DEFAULT_MAX_STREAMS),
+			group_window: Some(self.group_window.unwrap_or(DEFAULT_GROUP_WINDOW)).filter(|&size| size > 0),
+			wire_log: self.wire_log,
+		}
+	}
+
+	fn lite_config(&self, version: lite::Version, setup: lite::Setup) -> lite::StartConfig {
+		lite::StartConfig {
+			version,
+			publish: self.publish(),
+			subscribe: self.consume(),
+			stats: self.stats.clone(),
+			rate_limit: self.rate_limit.clone(),
+			max_streams: self.max_streams.unwrap_or(DEFAULT_MAX_STREAMS),
+			group_window: Some(self.group_window.unwrap_or(DEFAULT_GROUP_WINDOW)).filter(|&size| size > 0),
+			max_latency: self.max_latency.unwrap_or(lite::DEFAULT_MAX_LATENCY),
+			setup,
+		}
+	}
+
 	/// Perform the MoQ handshake as a client negotiating the version.
 	pub async fn connect<S: web_transport_trait::Session>(&self, session: S) -> Result<Session, Error> {
-		if self.publish.is_none() && self.consume.is_none() {
+		if self.publish().is_none() && self.consume().is_none() {
 			tracing::warn!("not publishing or consuming anything");
 		}
 
@@ -86,19 +235,10 @@ impl Client {
 					.ok_or(Error::Version)?;
 
 				// Draft-17+: SETUP is exchanged in the background by the session.
-				ietf::start(
-					session.clone(),
-					None,
-					None,
-					true,
-					self.publish.clone(),
-					self.consume.clone(),
-					self.stats.clone(),
-					ietf::Version::Draft19,
-				)?;
+				let started = ietf::start(session.clone(), None, self.ietf_config(ietf::Version::Draft19))?;
 
 				tracing::debug!(version = ?v, "connected");
-				return Ok(Session::new(session, v, None));
+				return Ok(Session::new(session, v, None).with_ietf(Some(started)));
 			}
 			Some(ALPN_18) => {
 				let v = self
@@ -107,19 +247,10 @@ impl Client {
 					.ok_or(Error::Version)?;
 
 				// Draft-17+: SETUP is exchanged in the background by the session.
-				ietf::start(
-					session.clone(),
-					None,
-					None,
-					true,
-					self.publish.clone(),
-					self.consume.clone(),
-					self.stats.clone(),
-					ietf::Version::Draft18,
-				)?;
+				let started = ietf::start(session.clone(), None, self.ietf_config(ietf::Version::Draft18))?;
 
 				tracing::debug!(version = ?v, "connected");
-				return Ok(Session::new(session, v, None));
+				return Ok(Session::new(session, v, None).with_ietf(Some(started)));
 			}
 			Some(ALPN_17) => {
 				let v = self
@@ -128,19 +259,10 @@ impl Client {
 					.ok_or(Error::Version)?;
 
 				// Draft-17+: SETUP is exchanged in the background by the session.
-				ietf::start(
-					session.clone(),
-					None,
-					None,
-					true,
-					self.publish.clone(),
-					self.consume.clone(),
-					self.stats.clone(),
-					ietf::Version::Draft17,
-				)?;
+				let started = ietf::start(session.clone(), None, self.ietf_config(ietf::Version::Draft17))?;
 
 				tracing::debug!(version = ?v, "connected");
-				return Ok(Session::new(session, v, None));
+				return Ok(Session::new(session, v, None).with_ietf(Some(started)));
 			}
 			Some(ALPN_16) => {
 				let v = self
@@ -171,15 +293,7 @@ impl Client {
 				let setup = lite::Setup {
 					path: self.path.clone(),
 				};
-				let recv_bw = lite::start(
-					session.clone(),
-					None,
-					self.publish.clone(),
-					self.consume.clone(),
-					self.stats.clone(),
-					lite::Version::Lite05Wip,
-					setup,
-				)?;
+				let recv_bw = lite::start(session.clone(), None, self.lite_config(lite::Version::Lite05Wip, setup))?;
 
 				return Ok(Session::new(session, lite::Version::Lite05Wip.into(), recv_bw));
 			}
@@ -191,11 +305,7 @@ impl Client {
 				let recv_bw = lite::start(
 					session.clone(),
 					None,
-					self.publish.clone(),
-					self.consume.clone(),
-					self.stats.clone(),
-					lite::Version::Lite04,
-					lite::Setup::default(),
+					self.lite_config(lite::Version::Lite04, lite::Setup::default()),
 				)?;
 
 				return Ok(Session::new(session, lite::Version::Lite04.into(), recv_bw));
@@ -209,11 +319,7 @@ impl Client {
 				let recv_bw = lite::start(
 					session.clone(),
 					None,
-					self.publish.clone(),
-					self.consume.clone(),
-					self.stats.clone(),
-					lite::Version::Lite03,
-					lite::Setup::default(),
+					self.lite_config(lite::Version::Lite03, lite::Setup::default()),
 				)?;
 
 				return Ok(Session::new(session, lite::Version::Lite03.into(), recv_bw));
@@ -231,8 +337,9 @@ impl Client {
 		let ietf_encoding = ietf::Version::try_from(encoding).map_err(|_| Error::Version)?;
 
 		let mut parameters = ietf::Parameters::default();
-		parameters.set_varint(ietf::ParameterVarInt::MaxRequestId, u32::MAX as u64);
-		parameters.set_bytes(ietf::ParameterBytes::Implementation, b"moq-lite-rs".to_vec());
+		parameters.set_max_request_id(ietf::RequestId(ietf::REQUEST_ID_WINDOW));
+		parameters.set_implementation("moq-lite-rs");
+		parameters.extend(&self.parameters);
 		let parameters = parameters.encode_bytes(ietf_encoding)?;
 
 		let client = setup::Client {
@@ -250,43 +357,36 @@ impl Client {
 			.copied()
 			.ok_or(Error::Version)?;
 
-		let recv_bw = match version {
+		let (recv_bw, started) = match version {
 			Version::Lite(v) => {
 				let stream = stream.with_version(v);
 				// This path only negotiates lite-01/02, which have no Setup stream.
-				lite::start(
+				let recv_bw = lite::start(
 					session.clone(),
 					Some(stream),
-					self.publish.clone(),
-					self.consume.clone(),
-					self.stats.clone(),
-					v,
-					lite::Setup::default(),
-				)?
+					self.lite_config(v, lite::Setup::default()),
+				)?;
+				(recv_bw, None)
 			}
 			Version::Ietf(v) => {
 				// Decode the parameters to get the initial request ID.
 				let parameters = ietf::Parameters::decode(&mut server.parameters, v)?;
-				let request_id_max = parameters
-					.get_varint(ietf::ParameterVarInt::MaxRequestId)
-					.map(ietf::RequestId);
+				let request_id_max = parameters.max_request_id();
+				tracing::debug!(implementation = ?parameters.implementation(), "server setup");
 
 				let stream = stream.with_version(v);
-				ietf::start(
-					session.clone(),
-					Some(stream),
+				// The SETUP parameters were already sent with CLIENT_SETUP.
+				let config = ietf::StartConfig {
 					request_id_max,
-					true,
-					self.publish.clone(),
-					self.consume.clone(),
-					self.stats.clone(),
-					v,
-				)?;
-				None
+					parameters: ietf::Parameters::default(),
+					..self.ietf_config(v)
+				};
+				let started = ietf::start(session.clone(), Some(stream), config)?;
+				(None, Some(started))
 			}
 		};
 
-		Ok(Session::new(session, version, recv_bw))
+		Ok(Session::new(session, version, recv_bw).with_ietf(started))
 	}
 }
 
@@ -494,7 +594,8 @@ mod tests {
 			.into(),
 		);
 
-		let _session = client.connect(fake.clone()).await.unwrap();
+		let session = client.connect(fake.clone()).await.unwrap();
+		assert_eq!(session.version(), Version::Lite(lite::Version::Lite01));
 
 		// Verify the client setup was encoded using Draft14 framing (ALPN_LITE fallback path).
 		let mut setup_bytes = Bytes::from(fake.control_writes());
@@ -524,4 +625,52 @@ mod tests {
 	async fn no_alpn_falls_back_to_draft14_and_switches_version_post_setup() {
 		run_alpn_lite_fallback_case(None).await;
 	}
+
+	#[tokio::test(start_paused = true)]
+	async fn setup_parameters_override_defaults() {
+		let fake = FakeSession::new(None, mock_server_setup(Version::Lite(lite::Version::Lite01)));
+		let client = Client::new()
+			.with_role(SessionRole::Publisher)
+			.with_setup_bytes(3, b"token".to_vec())
+			.with_setup_bytes(7, "ingest");
+
+		let _session = client.connect(fake.clone()).await.unwrap();
+
+		let mut setup_bytes = Bytes::from(fake.control_writes());
+		let setup = setup::Client::decode(&mut setup_bytes, Version::Ietf(ietf::Version::Draft14)).unwrap();
+		let mut parameters = setup.parameters;
+		let parameters = ietf::Parameters::decode(&mut parameters, ietf::Version::Draft14).unwrap();
+
+		assert_eq!(parameters.authorization_token(), Some(&b"token"[..]));
+		assert_eq!(parameters.implementation(), Some("ingest"));
+		assert_eq!(
+			parameters.max_request_id(),
+			Some(ietf::RequestId(ietf::REQUEST_ID_WINDOW))
+		);
+	}
+
+	#[test]
+	fn group_window_defaults_to_two() {
+		let config = |client: Client| {
+			client
+				.lite_config(lite::Version::Lite03, lite::Setup::default())
+				.group_window
+		};
+
+		assert_eq!(config(Client::new()), Some(2));
+		assert_eq!(config(Client::new().with_group_window(5)), Some(5));
+		assert_eq!(config(Client::new().with_group_window(0)), None);
+	}
+
+	#[test]
+	fn role_narrows_origins() {
+		let origin = crate::Origin::random().produce();
+		let client = Client::new().with_origin(origin);
+
+		let publisher = client.clone().with_role(SessionRole::Publisher);
+		assert!(publisher.publish().is_some() && publisher.consume().is_none());
+
+		let subscriber = client.with_role(SessionRole::Subscriber);
+		assert!(subscriber.publish().is_none() && subscriber.consume().is_some());
+	}
 }
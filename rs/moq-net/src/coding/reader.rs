@@ -2,13 +2,15 @@ use std::{cmp, fmt::Debug, io};
 
 use bytes::{Buf, BufMut, Bytes, BytesMut};
 
-use crate::{Error, coding::*};
+use crate::{Error, coding::*, ietf};
 
 /// A reader for decoding messages from a stream.
 pub struct Reader<S: web_transport_trait::RecvStream, V> {
 	stream: S,
 	buffer: BytesMut,
 	version: V,
+	// Logs everything read, see [Self::with_wire_log].
+	wire_log: Option<Box<ietf::WireLog>>,
 }
 
 impl<S: web_transport_trait::RecvStream, V> Reader<S, V> {
@@ -17,9 +19,20 @@ impl<S: web_transport_trait::RecvStream, V> Reader<S, V> {
 			stream,
 			buffer: Default::default(),
 			version,
+			wire_log: None,
 		}
 	}
 
+	/// Log each IETF control message read from the stream, see [ietf::WireLog].
+	///
+	/// Every read then goes through the internal buffer, so nothing skips the log.
+	pub(crate) fn with_wire_log(mut self, mut wire_log: ietf::WireLog) -> Self {
+		// Anything already buffered hasn't been consumed yet.
+		wire_log.feed(&self.buffer);
+		self.wire_log = Some(Box::new(wire_log));
+		self
+	}
+
 	/// Decode the next message from the stream.
 	pub async fn decode<T: Decode<V> + Debug>(&mut self) -> Result<T, Error>
 	where
@@ -81,10 +94,18 @@ impl<S: web_transport_trait::RecvStream, V> Reader<S, V> {
 	///
 	/// Returns the number of bytes written, or `None` if the stream is closed
 	/// (and the internal buffer was empty).
+	///
+	/// This is how frame payloads are received: a [crate::FrameProducer] is a `BufMut` over
+	/// its preallocated buffer, so each read lands in place. Collecting `Bytes` chunks
+	/// instead would only defer the copy to the consumer, which reads a frame contiguously.
 	pub async fn read_buf<B: BufMut + web_transport_trait::MaybeSend>(
 		&mut self,
 		dst: &mut B,
 	) -> Result<Option<usize>, Error> {
+		if self.wire_log.is_some() && self.buffer.is_empty() && !self.read_more().await? {
+			return Ok(None);
+		}
+
 		if !self.buffer.is_empty() && dst.has_remaining_mut() {
 			let n = cmp::min(self.buffer.len(), dst.remaining_mut());
 			let chunk = self.buffer.split_to(n);
@@ -96,6 +117,14 @@ impl<S: web_transport_trait::RecvStream, V> Reader<S, V> {
 
 	/// Read exactly the given number of bytes from the stream.
 	pub async fn read_exact(&mut self, size: usize) -> Result<Bytes, Error> {
+		if self.wire_log.is_some() {
+			while self.buffer.len() < size {
+				if !self.read_more().await? {
+					return Err(DecodeError::Short.into());
+				}
+			}
+		}
+
 		// An optimization to avoid a copy if we have enough data in the buffer
 		if self.buffer.len() >= size {
 			return Ok(self.buffer.split_to(size).freeze());
@@ -119,25 +148,6 @@ impl<S: web_transport_trait::RecvStream, V> Reader<S, V> {
 		Ok(buf.into_inner().freeze())
 	}
 
-	/// Skip the given number of bytes from the stream.
-	pub async fn skip(&mut self, mut size: usize) -> Result<(), Error> {
-		let buffered = self.buffer.len().min(size);
-		self.buffer.advance(buffered);
-		size -= buffered;
-
-		while size > 0 {
-			let chunk = self
-				.stream
-				.read_chunk(size)
-				.await
-				.map_err(Error::from_transport)?
-				.ok_or(DecodeError::Short)?;
-			size -= chunk.len();
-		}
-
-		Ok(())
-	}
-
 	/// Wait until the stream is closed, erroring if there are any additional bytes.
 	pub async fn closed(&mut self) -> Result<(), Error> {
 		if self.has_more().await? {
@@ -158,8 +168,14 @@ impl<S: web_transport_trait::RecvStream, V> Reader<S, V> {
 
 	/// Try to read more data from the stream. Returns true if data was read, false if stream closed.
 	async fn read_more(&mut self) -> Result<bool, Error> {
+		let start = self.buffer.len();
 		match self.stream.read_buf(&mut self.buffer).await {
-			Ok(Some(_)) => Ok(true),
+			Ok(Some(_)) => {
+				if let Some(wire_log) = &mut self.wire_log {
+					wire_log.feed(&self.buffer[start..]);
+				}
+				Ok(true)
+			}
 			Ok(None) => Ok(false),
 			Err(e) => Err(Error::from_transport(e)),
 		}
@@ -176,6 +192,7 @@ impl<S: web_transport_trait::RecvStream, V> Reader<S, V> {
 			stream: self.stream,
 			buffer: self.buffer,
 			version,
+			wire_log: self.wire_log,
 		}
 	}
 }
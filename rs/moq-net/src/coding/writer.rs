@@ -7,6 +7,10 @@ pub struct Writer<S: web_transport_trait::SendStream, V> {
 	stream: Option<S>,
 	buffer: bytes::BytesMut,
 	version: V,
+	// The error passed to [Self::abort], if the stream was reset.
+	reset: Option<Error>,
+	// Logs everything written, see [Self::with_wire_log].
+	wire_log: Option<Box<ietf::WireLog>>,
 }
 
 impl<S: web_transport_trait::SendStream, V> Writer<S, V> {
@@ -16,9 +20,17 @@ impl<S: web_transport_trait::SendStream, V> Writer<S, V> {
 			stream: Some(stream),
 			buffer: Default::default(),
 			version,
+			reset: None,
+			wire_log: None,
 		}
 	}
 
+	/// Log each IETF control message written to the stream, see [ietf::WireLog].
+	pub(crate) fn with_wire_log(mut self, wire_log: ietf::WireLog) -> Self {
+		self.wire_log = Some(Box::new(wire_log));
+		self
+	}
+
 	/// Encode the given message to the stream.
 	pub async fn encode<T: Encode<V> + Debug>(&mut self, msg: &T) -> Result<(), Error>
 	where
@@ -27,6 +39,10 @@ impl<S: web_transport_trait::SendStream, V> Writer<S, V> {
 		self.buffer.clear();
 		msg.encode(&mut self.buffer, self.version.clone())?;
 
+		if let Some(wire_log) = &mut self.wire_log {
+			wire_log.feed(&self.buffer);
+		}
+
 		while !self.buffer.is_empty() {
 			self.stream
 				.as_mut()
@@ -53,6 +69,16 @@ impl<S: web_transport_trait::SendStream, V> Writer<S, V> {
 	///
 	/// NOTE: This can avoid performing a copy when using `Bytes`.
 	pub async fn write_all<Buf: bytes::Buf + Send>(&mut self, buf: &mut Buf) -> Result<(), Error> {
+		if let Some(wire_log) = &mut self.wire_log {
+			// Copy it out, so each byte is logged once however the transport splits the writes.
+			let mut bytes = buf.copy_to_bytes(buf.remaining());
+			wire_log.feed(&bytes);
+			while !bytes.is_empty() {
+				self.write(&mut bytes).await?;
+			}
+			return Ok(());
+		}
+
 		while buf.has_remaining() {
 			self.write(buf).await?;
 		}
@@ -60,13 +86,20 @@ impl<S: web_transport_trait::SendStream, V> Writer<S, V> {
 	}
 
 	/// Mark the stream as finished.
+	///
+	/// Returns the error passed to [Self::abort] if the stream was already reset,
+	/// instead of a generic transport error.
 	pub fn finish(&mut self) -> Result<(), Error> {
+		if let Some(err) = &self.reset {
+			return Err(err.clone());
+		}
 		self.stream.as_mut().unwrap().finish().map_err(Error::from_transport)
 	}
 
 	/// Abort the stream with the given error.
 	pub fn abort(&mut self, err: &Error) {
 		self.stream.as_mut().unwrap().reset(err.to_code());
+		self.reset.get_or_insert_with(|| err.clone());
 	}
 
 	/// Wait for the stream to be closed, or the [Self::finish] to be acknowledged by the peer.
@@ -92,6 +125,8 @@ impl<S: web_transport_trait::SendStream, V> Writer<S, V> {
 			stream: self.stream.take(),
 			buffer: std::mem::take(&mut self.buffer),
 			version,
+			reset: self.reset.take(),
+			wire_log: self.wire_log.take(),
 		}
 	}
 }
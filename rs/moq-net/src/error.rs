@@ -1,5 +1,12 @@
 use crate::coding;
 
+// Wire codes of the variants that carry a payload, which [Error::from_code] can't rebuild.
+const CODE_TRANSPORT: u32 = 4;
+const CODE_DECODE: u32 = 5;
+const CODE_BOUNDS_EXCEEDED: u32 = 11;
+const CODE_ENCODE: u32 = 18;
+const CODE_UNKNOWN_ALPN: u32 = 21;
+
 /// A list of possible errors that can occur during the session.
 #[derive(thiserror::Error, Debug, Clone)]
 #[non_exhaustive]
@@ -95,35 +102,44 @@ pub enum Error {
 	#[error("frame too large")]
 	FrameTooLarge,
 
-	/// A remote error received via a stream/session reset code.
+	/// A remote error received via a stream reset code.
+	///
+	/// The code is the peer's [Error::to_code], kept as is so a peer's error is never mistaken
+	/// for a local one. [Error::from_code] recovers the variant it came from, and
+	/// [Error::reason] classifies it the same way.
 	#[error("remote error: code={0}")]
 	Remote(u32),
 }
 
 impl Error {
 	/// An integer code that is sent over the wire.
+	///
+	/// Every variant has its own code, so a peer can tell them apart from a stream reset:
+	/// 0-30 are the variants below (7, 8, 22 and 23 unassigned, 28 and 29 reserved), 64 and
+	/// up are [Error::App] codes offset by 64, and [Error::Remote] passes a received code
+	/// through unchanged. See [Error::from_code] for the reverse.
 	pub fn to_code(&self) -> u32 {
 		match self {
 			Self::Cancel => 0,
 			Self::RequiredExtension => 1,
 			Self::Old => 2,
 			Self::Timeout => 3,
-			Self::Transport(_) => 4,
-			Self::Decode(_) => 5,
+			Self::Transport(_) => CODE_TRANSPORT,
+			Self::Decode(_) => CODE_DECODE,
 			Self::Unauthorized => 6,
 			Self::Version => 9,
 			Self::UnexpectedStream => 10,
-			Self::BoundsExceeded(_) => 11,
+			Self::BoundsExceeded(_) => CODE_BOUNDS_EXCEEDED,
 			Self::Duplicate => 12,
 			Self::NotFound => 13,
 			Self::WrongSize => 14,
 			Self::ProtocolViolation => 15,
 			Self::UnexpectedMessage => 16,
 			Self::Unsupported => 17,
-			Self::Encode(_) => 18,
+			Self::Encode(_) => CODE_ENCODE,
 			Self::TooManyParameters => 19,
 			Self::InvalidRole => 20,
-			Self::UnknownAlpn(_) => 21,
+			Self::UnknownAlpn(_) => CODE_UNKNOWN_ALPN,
 			Self::Dropped => 24,
 			Self::Closed => 25,
 			Self::CacheFull => 26,
@@ -136,7 +152,81 @@ impl Error {
 		}
 	}
 
-	/// Convert a transport error into an [Error], decoding stream reset codes.
+	/// The variant a wire code was produced from, the reverse of [Error::to_code].
+	///
+	/// Variants that carry a payload (e.g. [Error::Decode]) can't be rebuilt from their
+	/// code, so those and any unassigned code return [Error::Remote].
+	pub fn from_code(code: u32) -> Self {
+		match code {
+			0 => Self::Cancel,
+			1 => Self::RequiredExtension,
+			2 => Self::Old,
+			3 => Self::Timeout,
+			6 => Self::Unauthorized,
+			9 => Self::Version,
+			10 => Self::UnexpectedStream,
+			12 => Self::Duplicate,
+			13 => Self::NotFound,
+			14 => Self::WrongSize,
+			15 => Self::ProtocolViolation,
+			16 => Self::UnexpectedMessage,
+			17 => Self::Unsupported,
+			19 => Self::TooManyParameters,
+			20 => Self::InvalidRole,
+			24 => Self::Dropped,
+			25 => Self::Closed,
+			26 => Self::CacheFull,
+			27 => Self::FrameTooLarge,
+			30 => Self::Unroutable,
+			64.. => match u16::try_from(code - 64) {
+				Ok(app) => Self::App(app),
+				Err(_) => Self::Remote(code),
+			},
+			_ => Self::Remote(code),
+		}
+	}
+
+	/// Classify why a producer terminated, so consumers can decide whether to retry.
+	pub fn reason(&self) -> AbortReason {
+		match self {
+			Self::Cancel => AbortReason::Cancelled,
+			Self::Transport(_) | Self::Dropped | Self::Closed => AbortReason::Gone,
+			Self::Remote(code) => Self::remote_reason(*code),
+			Self::Old | Self::Timeout | Self::CacheFull => AbortReason::Lost,
+			Self::Unauthorized | Self::NotFound | Self::Unroutable => AbortReason::Rejected,
+			Self::App(code) => AbortReason::App(*code),
+			Self::Decode(_)
+			| Self::Encode(_)
+			| Self::Version
+			| Self::RequiredExtension
+			| Self::UnexpectedStream
+			| Self::BoundsExceeded(_)
+			| Self::Duplicate
+			| Self::WrongSize
+			| Self::ProtocolViolation
+			| Self::UnexpectedMessage
+			| Self::Unsupported
+			| Self::TooManyParameters
+			| Self::InvalidRole
+			| Self::UnknownAlpn(_)
+			| Self::FrameTooLarge => AbortReason::Protocol,
+		}
+	}
+
+	// Classify a reset code sent by the peer, mirroring the local variant it came from.
+	fn remote_reason(code: u32) -> AbortReason {
+		match Self::from_code(code) {
+			// The peer cancelled, so from here it went away rather than being cancelled by us.
+			Self::Cancel => AbortReason::Gone,
+			// Variants with a payload aren't rebuilt, so classify them by code.
+			Self::Remote(CODE_DECODE | CODE_BOUNDS_EXCEEDED | CODE_ENCODE | CODE_UNKNOWN_ALPN) => AbortReason::Protocol,
+			// A transport error or an unassigned code: the peer went away.
+			Self::Remote(_) => AbortReason::Gone,
+			err => err.reason(),
+		}
+	}
+
+	/// Convert a transport error into an [Error], keeping stream reset codes as [Error::Remote].
 	pub fn from_transport(err: impl web_transport_trait::Error) -> Self {
 		if let Some(code) = err.stream_error() {
 			return Self::Remote(code);
@@ -146,6 +236,24 @@ impl Error {
 	}
 }
 
+/// A coarse classification of an [Error], returned by [Error::reason].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum AbortReason {
+	/// Cancelled locally, usually because there are no more readers.
+	Cancelled,
+	/// The publisher or the connection went away. Retrying may succeed.
+	Gone,
+	/// The data was skipped or evicted to keep up with the live edge.
+	Lost,
+	/// The request was refused (unauthorized or not found). Retrying won't help.
+	Rejected,
+	/// The peer misbehaved or sent something we don't support.
+	Protocol,
+	/// Closed by the application with the given code.
+	App(u16),
+}
+
 impl web_transport_trait::Error for Error {
 	fn session_error(&self) -> Option<(u32, String)> {
 		None
@@ -153,3 +261,103 @@ impl web_transport_trait::Error for Error {
 }
 
 pub type Result<T> = std::result::Result<T, Error>;
+
+#[cfg(test)]
+mod test {
+	use super::*;
+
+	#[test]
+	fn code_roundtrip() {
+		let errors = [
+			Error::Cancel,
+			Error::RequiredExtension,
+			Error::Old,
+			Error::Timeout,
+			Error::Unauthorized,
+			Error::Version,
+			Error::UnexpectedStream,
+			Error::Duplicate,
+			Error::NotFound,
+			Error::WrongSize,
+			Error::ProtocolViolation,
+			Error::UnexpectedMessage,
+			Error::Unsupported,
+			Error::TooManyParameters,
+			Error::InvalidRole,
+			Error::Dropped,
+			Error::Closed,
+			Error::CacheFull,
+			Error::FrameTooLarge,
+			Error::Unroutable,
+			Error::App(0),
+			Error::App(u16::MAX),
+		];
+
+		let mut codes = std::collections::HashSet::new();
+		for err in &errors {
+			let code = err.to_code();
+			assert!(codes.insert(code), "{err} shares code {code}");
+
+			let decoded = Error::from_code(code);
+			assert_eq!(decoded.to_code(), code);
+			assert_eq!(decoded.to_string(), err.to_string());
+		}
+	}
+
+	#[test]
+	fn remote_reason_mirrors_local() {
+		let errors = [
+			Error::Transport("reset".into()),
+			Error::Decode(coding::DecodeError::Short),
+			Error::BoundsExceeded(coding::BoundsExceeded),
+			Error::Encode(coding::EncodeError::TooLarge),
+			Error::UnknownAlpn("moq".into()),
+			Error::Old,
+			Error::Unauthorized,
+			Error::Duplicate,
+			Error::Dropped,
+			Error::Unroutable,
+			Error::App(7),
+		];
+		for err in &errors {
+			assert_eq!(Error::Remote(err.to_code()).reason(), err.reason(), "{err}");
+		}
+
+		// A cancel from the peer means it went away.
+		assert_eq!(Error::Remote(Error::Cancel.to_code()).reason(), AbortReason::Gone);
+		assert_eq!(Error::Remote(7).reason(), AbortReason::Gone);
+	}
+
+	#[test]
+	fn from_transport_keeps_resets_remote() {
+		use crate::loopback::LoopbackError;
+
+		let err = Error::from_transport(LoopbackError::Reset(2));
+		assert!(matches!(err, Error::Remote(2)));
+		assert_eq!(err.reason(), AbortReason::Lost);
+
+		let err = Error::from_transport(LoopbackError::Reset(64 + 7));
+		assert!(matches!(err, Error::Remote(71)));
+		assert_eq!(err.reason(), AbortReason::App(7));
+
+		assert!(matches!(
+			Error::from_transport(LoopbackError::Reset(0)),
+			Error::Remote(0)
+		));
+		assert!(matches!(
+			Error::from_transport(LoopbackError::Reset(5)),
+			Error::Remote(5)
+		));
+		assert!(matches!(
+			Error::from_transport(LoopbackError::Stopped),
+			Error::Transport(_)
+		));
+	}
+
+	#[test]
+	fn unknown_code_is_remote() {
+		assert!(matches!(Error::from_code(5), Error::Remote(5)));
+		assert!(matches!(Error::from_code(7), Error::Remote(7)));
+		assert!(matches!(Error::from_code(64 + 65_536), Error::Remote(65_600)));
+	}
+}
@@ -1,6 +1,6 @@
 use std::{
 	collections::HashMap,
-	sync::{Arc, Mutex},
+	sync::{Arc, Mutex, OnceLock},
 };
 
 use bytes::{Buf, BufMut, Bytes, BytesMut};
@@ -21,36 +21,39 @@ pub struct VirtualRecvStream {
 	buffer: Bytes,
 	rx: mpsc::UnboundedReceiver<Bytes>,
 	closed: bool,
+	/// Set once the control stream is gone, failing reads instead of a clean FIN.
+	aborted: Arc<OnceLock<Error>>,
 }
 
 impl VirtualRecvStream {
-	fn new(initial: Bytes, rx: mpsc::UnboundedReceiver<Bytes>) -> Self {
+	fn new(initial: Bytes, rx: mpsc::UnboundedReceiver<Bytes>, aborted: Arc<OnceLock<Error>>) -> Self {
 		Self {
 			buffer: initial,
 			rx,
 			closed: false,
+			aborted,
 		}
 	}
 
 	/// Fill the buffer from the channel if empty. Returns false if the stream is closed.
-	async fn fill(&mut self) -> bool {
+	async fn fill(&mut self) -> Result<bool, Error> {
 		if !self.buffer.is_empty() {
-			return true;
+			return Ok(true);
 		}
 
-		if self.closed {
-			return false;
+		if !self.closed {
+			match self.rx.recv().await {
+				Some(data) => {
+					self.buffer = data;
+					return Ok(true);
+				}
+				None => self.closed = true,
+			}
 		}
 
-		match self.rx.recv().await {
-			Some(data) => {
-				self.buffer = data;
-				true
-			}
-			None => {
-				self.closed = true;
-				false
-			}
+		match self.aborted.get() {
+			Some(err) => Err(err.clone()),
+			None => Ok(false),
 		}
 	}
 }
@@ -59,7 +62,7 @@ impl web_transport_trait::RecvStream for VirtualRecvStream {
 	type Error = crate::Error;
 
 	async fn read(&mut self, dst: &mut [u8]) -> Result<Option<usize>, Self::Error> {
-		if !self.fill().await {
+		if !self.fill().await? {
 			return Ok(None);
 		}
 
@@ -73,7 +76,7 @@ impl web_transport_trait::RecvStream for VirtualRecvStream {
 		&mut self,
 		buf: &mut B,
 	) -> Result<Option<usize>, Self::Error> {
-		if !self.fill().await {
+		if !self.fill().await? {
 			return Ok(None);
 		}
 
@@ -83,7 +86,7 @@ impl web_transport_trait::RecvStream for VirtualRecvStream {
 	}
 
 	async fn read_chunk(&mut self, max: usize) -> Result<Option<Bytes>, Self::Error> {
-		if !self.fill().await {
+		if !self.fill().await? {
 			return Ok(None);
 		}
 
@@ -219,7 +222,9 @@ impl web_transport_trait::SendStream for VirtualSendStream {
 		if let Some(pending) = self.pending.take()
 			&& !pending.buf.is_empty()
 		{
-			let _ = self.control_tx.send(pending.buf.freeze());
+			self.control_tx
+				.send(pending.buf.freeze())
+				.map_err(|_| crate::Error::Closed)?;
 		}
 		Ok(())
 	}
@@ -338,6 +343,26 @@ struct Shared {
 
 	/// Namespace → request_id reverse lookup (for v14/v15 namespace-keyed messages).
 	namespaces: Mutex<HashMap<PathOwned, RequestId>>,
+
+	/// Why the control stream stopped, shared with every [VirtualRecvStream].
+	aborted: Arc<OnceLock<Error>>,
+}
+
+impl Shared {
+	/// End every virtual stream with `err`, as none of them can receive anything more.
+	fn abort(&self, err: Error) {
+		let _ = self.aborted.set(err);
+		self.streams.lock().unwrap().clear();
+	}
+}
+
+/// Aborts the virtual streams once [ControlStreamAdapter::run] returns or is dropped.
+struct AbortOnDrop<'a>(&'a Shared);
+
+impl Drop for AbortOnDrop<'_> {
+	fn drop(&mut self) {
+		self.0.abort(Error::Closed);
+	}
 }
 
 #[derive(Clone)]
@@ -359,6 +384,7 @@ impl<S: web_transport_trait::Session> ControlStreamAdapter<S> {
 				control_tx,
 				streams: Mutex::new(HashMap::new()),
 				namespaces: Mutex::new(HashMap::new()),
+				aborted: Default::default(),
 			}),
 			control,
 			version,
@@ -375,28 +401,54 @@ impl<S: web_transport_trait::Session> ControlStreamAdapter<S> {
 	/// Run the control stream read + write tasks.
 	/// This reads from the control stream and routes messages to virtual streams,
 	/// and also drains the write channel to the control stream writer.
+	///
+	/// Once `finish` is closed, the write side is finished while reading carries on.
 	pub async fn run(
 		&self,
 		reader: Reader<S::RecvStream, Version>,
 		writer: Writer<S::SendStream, Version>,
 		rx: mpsc::UnboundedReceiver<Bytes>,
+		finish: kio::Consumer<()>,
 	) -> Result<(), Error> {
-		tokio::select! {
-			res = self.run_read(reader) => res,
-			res = Self::run_write(writer, rx) => res,
+		// However the session ends, the requests multiplexed over it fail instead of hanging.
+		let _abort = AbortOnDrop(&self.shared);
+
+		// Either side may finish first; the other keeps running, so a half-close works both ways.
+		let res = tokio::try_join!(self.run_read(reader), Self::run_write(writer, rx, finish));
+		if let Err(err) = &res {
+			self.shared.abort(err.clone());
 		}
+		res.map(|_| ())
 	}
 
 	/// Writer task: drains the channel and writes to the control stream.
+	///
+	/// When `finish` is closed, writes whatever is already queued and finishes the stream.
 	async fn run_write(
 		mut writer: Writer<S::SendStream, Version>,
 		mut rx: mpsc::UnboundedReceiver<Bytes>,
+		finish: kio::Consumer<()>,
 	) -> Result<(), Error> {
+		loop {
+			let msg = tokio::select! {
+				msg = rx.recv() => msg,
+				_ = finish.closed() => break,
+			};
+			let Some(msg) = msg else { break };
+
+			let mut buf = coalesce(msg, &mut rx);
+			writer.write_all(&mut buf).await?;
+		}
+
+		// Stop accepting messages, so later writes fail with Closed, then drain the queued ones.
+		rx.close();
 		while let Some(msg) = rx.recv().await {
-			let mut buf = std::io::Cursor::new(msg);
+			let mut buf = coalesce(msg, &mut rx);
 			writer.write_all(&mut buf).await?;
 		}
-		Ok(())
+
+		tracing::debug!("finished the control stream");
+		writer.finish()
 	}
 
 	/// Dispatcher loop that reads control stream messages and routes them.
@@ -419,8 +471,9 @@ impl<S: web_transport_trait::Session> ControlStreamAdapter<S> {
 
 			match route {
 				Route::NewRequest(request_id) => {
+					self.control.peer_request_id(request_id);
 					let (follow_tx, follow_rx) = mpsc::unbounded_channel();
-					let recv = VirtualRecvStream::new(raw, follow_rx);
+					let recv = VirtualRecvStream::new(raw, follow_rx, self.shared.aborted.clone());
 					let send = VirtualSendStream::new(self.shared.control_tx.clone());
 					self.shared.streams.lock().unwrap().insert(request_id, follow_tx);
 					self.shared.incoming_tx.send((send, recv)).map_err(|_| Error::Closed)?;
@@ -443,6 +496,10 @@ impl<S: web_transport_trait::Session> ControlStreamAdapter<S> {
 				Route::MaxRequestId(max) => {
 					self.control.max_request_id(max);
 				}
+				Route::RequestsBlocked(max) => {
+					tracing::debug!(%max, "peer is blocked on request id");
+					self.control.peer_request_id(max);
+				}
 				Route::GoAway => {
 					return Err(Error::Unsupported);
 				}
@@ -505,6 +562,14 @@ impl<S: web_transport_trait::Session> ControlStreamAdapter<S> {
 				let id = decode_response_request_id(body, self.version)?;
 				Ok(Route::Response(id))
 			}
+			// 0x0E: TrackStatusOk in v14 (v15+ answers with RequestOk)
+			ietf::TrackStatusOk::ID => match self.version {
+				Version::Draft14 => {
+					let id = decode_request_id(body, self.version)?;
+					Ok(Route::Response(id))
+				}
+				_ => Err(Error::UnexpectedMessage),
+			},
 			// 0x05: SubscribeError in v14, RequestError in v15+
 			ietf::SubscribeError::ID => {
 				let id = decode_response_request_id(body, self.version)?;
@@ -572,7 +637,7 @@ impl<S: web_transport_trait::Session> ControlStreamAdapter<S> {
 
 			// Follow-up messages: route to existing stream
 			ietf::SubscribeUpdate::ID => {
-				let id = decode_request_id(body, self.version)?;
+				let id = decode_subscription_request_id(body, self.version)?;
 				Ok(Route::FollowUp(id))
 			}
 
@@ -626,7 +691,10 @@ impl<S: web_transport_trait::Session> ControlStreamAdapter<S> {
 				let id = decode_request_id(body, self.version)?;
 				Ok(Route::MaxRequestId(id))
 			}
-			ietf::RequestsBlocked::ID => Err(Error::UnexpectedMessage),
+			ietf::RequestsBlocked::ID => {
+				let id = decode_request_id(body, self.version)?;
+				Ok(Route::RequestsBlocked(id))
+			}
 
 			// Terminal
 			ietf::GoAway::ID => Ok(Route::GoAway),
@@ -685,7 +753,7 @@ impl<S: web_transport_trait::Session> web_transport_trait::Session for ControlSt
 
 	async fn open_bi(&self) -> Result<(Self::SendStream, Self::RecvStream), Self::Error> {
 		let (follow_tx, follow_rx) = mpsc::unbounded_channel();
-		let recv = VirtualRecvStream::new(Bytes::new(), follow_rx);
+		let recv = VirtualRecvStream::new(Bytes::new(), follow_rx, self.shared.aborted.clone());
 		let send = VirtualSendStream::with_registration(
 			self.shared.control_tx.clone(),
 			OutgoingRegistration {
@@ -743,6 +811,7 @@ enum Route {
 	FollowUp(RequestId),
 	CloseStream(RequestId),
 	MaxRequestId(RequestId),
+	RequestsBlocked(RequestId),
 	GoAway,
 }
 
@@ -762,6 +831,38 @@ fn decode_request_id(body: &Bytes, version: Version) -> Result<RequestId, Error>
 	Ok(request_id)
 }
 
+/// Decode the subscription a SUBSCRIBE_UPDATE refers to, which follows its own request_id.
+fn decode_subscription_request_id(body: &Bytes, version: Version) -> Result<RequestId, Error> {
+	let mut cursor = std::io::Cursor::new(body);
+	let _request_id = RequestId::decode(&mut cursor, version)?;
+	let subscription_request_id = RequestId::decode(&mut cursor, version)?;
+	Ok(subscription_request_id)
+}
+
+/// Stop draining queued messages once a coalesced write reaches this size.
+const MAX_COALESCE: usize = 64 * 1024;
+
+/// Append any messages already queued behind `first` so a burst goes out as one write.
+fn coalesce(first: Bytes, rx: &mut mpsc::UnboundedReceiver<Bytes>) -> Bytes {
+	let Ok(next) = rx.try_recv() else {
+		// Nothing else queued, so skip the copy.
+		return first;
+	};
+
+	let mut buf = BytesMut::with_capacity(first.len() + next.len());
+	buf.extend_from_slice(&first);
+	buf.extend_from_slice(&next);
+
+	while buf.len() < MAX_COALESCE {
+		match rx.try_recv() {
+			Ok(msg) => buf.extend_from_slice(&msg),
+			Err(_) => break,
+		}
+	}
+
+	buf.freeze()
+}
+
 /// Decode request_id for response messages that have Option<RequestId> in v14-16.
 fn decode_response_request_id(body: &Bytes, version: Version) -> Result<RequestId, Error> {
 	// In v14-16, response messages always have request_id present
@@ -806,6 +907,7 @@ mod tests {
 			control_tx,
 			streams: Mutex::new(HashMap::new()),
 			namespaces: Mutex::new(HashMap::new()),
+			aborted: Default::default(),
 		});
 		// We need a dummy inner session — but classify doesn't use it.
 		// Use a struct that satisfies the trait bound. We can't easily construct one,
@@ -860,6 +962,13 @@ mod tests {
 				let id = decode_response_request_id(body, version)?;
 				Ok(Route::Response(id))
 			}
+			ietf::TrackStatusOk::ID => match version {
+				Version::Draft14 => {
+					let id = decode_request_id(body, version)?;
+					Ok(Route::Response(id))
+				}
+				_ => Err(Error::UnexpectedMessage),
+			},
 			ietf::SubscribeError::ID => {
 				let id = decode_response_request_id(body, version)?;
 				Ok(Route::CloseStream(id))
@@ -916,7 +1025,7 @@ mod tests {
 				_ => Err(Error::UnexpectedMessage),
 			},
 			ietf::SubscribeUpdate::ID => {
-				let id = decode_request_id(body, version)?;
+				let id = decode_subscription_request_id(body, version)?;
 				Ok(Route::FollowUp(id))
 			}
 			ietf::Unsubscribe::ID => {
@@ -974,7 +1083,10 @@ mod tests {
 				let id = decode_request_id(body, version)?;
 				Ok(Route::MaxRequestId(id))
 			}
-			ietf::RequestsBlocked::ID => Err(Error::UnexpectedMessage),
+			ietf::RequestsBlocked::ID => {
+				let id = decode_request_id(body, version)?;
+				Ok(Route::RequestsBlocked(id))
+			}
 			ietf::GoAway::ID => Ok(Route::GoAway),
 			_ => Err(Error::UnexpectedMessage),
 		}
@@ -1008,6 +1120,30 @@ mod tests {
 		assert!(matches!(route, Route::Response(RequestId(42))));
 	}
 
+	#[test]
+	fn test_coalesce_queued_messages() {
+		let (tx, mut rx) = mpsc::unbounded_channel();
+
+		// A lone message is passed through untouched.
+		assert_eq!(coalesce(Bytes::from_static(b"a"), &mut rx), Bytes::from_static(b"a"));
+
+		tx.send(Bytes::from_static(b"b")).unwrap();
+		tx.send(Bytes::from_static(b"c")).unwrap();
+		assert_eq!(coalesce(Bytes::from_static(b"a"), &mut rx), Bytes::from_static(b"abc"));
+		assert!(rx.try_recv().is_err());
+	}
+
+	#[test]
+	fn test_classify_track_status_ok_v14_response() {
+		let body = make_body_with_request_id(9, Version::Draft14);
+		let route = classify_msg(Version::Draft14, ietf::TrackStatusOk::ID, &body).unwrap();
+		assert!(matches!(route, Route::Response(RequestId(9))));
+
+		// 0x0E is not a control stream response in v15+.
+		let body = make_body_with_request_id(9, Version::Draft15);
+		assert!(classify_msg(Version::Draft15, ietf::TrackStatusOk::ID, &body).is_err());
+	}
+
 	#[test]
 	fn test_classify_request_error_v15_closes_stream() {
 		let body = make_body_with_request_id(7, Version::Draft15);
@@ -1031,9 +1167,23 @@ mod tests {
 
 	#[test]
 	fn test_classify_subscribe_update_followup() {
-		let body = make_body_with_request_id(10, Version::Draft15);
-		let route = classify_msg(Version::Draft15, ietf::SubscribeUpdate::ID, &body).unwrap();
-		assert!(matches!(route, Route::FollowUp(RequestId(10))));
+		for version in [Version::Draft14, Version::Draft15] {
+			let mut body = BytesMut::new();
+			ietf::SubscribeUpdate {
+				request_id: RequestId(12),
+				subscription_request_id: Some(RequestId(10)),
+				start_location: ietf::Location::default(),
+				end_group: 0,
+				subscriber_priority: 0,
+				forward: true,
+			}
+			.encode_msg(&mut body, version)
+			.unwrap();
+
+			// Routed to the subscription it updates, not its own request id.
+			let route = classify_msg(version, ietf::SubscribeUpdate::ID, &body.freeze()).unwrap();
+			assert!(matches!(route, Route::FollowUp(RequestId(10))));
+		}
 	}
 
 	#[test]
@@ -1050,6 +1200,13 @@ mod tests {
 		assert!(matches!(route, Route::MaxRequestId(RequestId(100))));
 	}
 
+	#[test]
+	fn test_classify_requests_blocked() {
+		let body = make_body_with_request_id(100, Version::Draft14);
+		let route = classify_msg(Version::Draft14, ietf::RequestsBlocked::ID, &body).unwrap();
+		assert!(matches!(route, Route::RequestsBlocked(RequestId(100))));
+	}
+
 	#[test]
 	fn test_classify_subscribe_namespace_v14_new_request() {
 		let body = make_body_with_request_id(20, Version::Draft14);
@@ -1089,7 +1246,7 @@ mod tests {
 	async fn test_virtual_recv_stream_reads_initial_then_followup() {
 		let initial = Bytes::from_static(b"initial");
 		let (tx, rx) = mpsc::unbounded_channel();
-		let mut stream = VirtualRecvStream::new(initial, rx);
+		let mut stream = VirtualRecvStream::new(initial, rx, Default::default());
 
 		// Read initial data
 		let mut buf = [0u8; 32];
@@ -1111,7 +1268,7 @@ mod tests {
 	async fn test_virtual_recv_stream_partial_reads() {
 		let initial = Bytes::from_static(b"hello world");
 		let (_tx, rx) = mpsc::unbounded_channel();
-		let mut stream = VirtualRecvStream::new(initial, rx);
+		let mut stream = VirtualRecvStream::new(initial, rx, Default::default());
 
 		// Read small chunks
 		let mut buf = [0u8; 5];
@@ -1,30 +1,86 @@
 use std::sync::{Arc, Mutex};
 
-use tokio::sync::Notify;
+use bytes::{Bytes, BytesMut};
+use tokio::sync::{Notify, mpsc};
 
-use crate::{Error, ietf::RequestId};
+use crate::{
+	Error,
+	coding::{Decode, DecodeError, Encode, Stream},
+	ietf::{self, MaxRequestId, Message, RequestId, RequestsBlocked, Version},
+};
+
+/// How many request ids we grant the peer at a time: the MAX_REQUEST_ID advertised in SETUP, and
+/// how far past its latest request each MAX_REQUEST_ID update raises the ceiling.
+pub(crate) const REQUEST_ID_WINDOW: u64 = u32::MAX as u64;
 
 struct ControlState {
 	request_id_next: RequestId,
 	/// None means no flow control (draft17 removed MaxRequestId).
 	request_id_max: Option<RequestId>,
 	request_id_notify: Arc<Notify>,
+	/// The ceiling we last reported via REQUESTS_BLOCKED, so each one is only sent once.
+	request_id_blocked: Option<RequestId>,
+	/// The ceiling we last granted the peer via MAX_REQUEST_ID.
+	/// None when there's no flow control to grant (draft17+).
+	peer_request_id_max: Option<RequestId>,
 }
 
 #[derive(Clone)]
 pub(super) struct Control {
 	state: Arc<Mutex<ControlState>>,
+	/// Raw writes to the control stream, used to send REQUESTS_BLOCKED.
+	/// None when there's no control stream (draft17+).
+	control_tx: Option<mpsc::UnboundedSender<Bytes>>,
+	version: Version,
+	/// Log each control message on the wire as hex, see [Self::with_wire_log].
+	wire_log: bool,
 }
 
 impl Control {
-	pub fn new(request_id_max: Option<RequestId>, client: bool) -> Self {
+	pub fn new(
+		request_id_max: Option<RequestId>,
+		client: bool,
+		control_tx: Option<mpsc::UnboundedSender<Bytes>>,
+		version: Version,
+	) -> Self {
 		Self {
 			state: Arc::new(Mutex::new(ControlState {
 				request_id_next: if client { RequestId(0) } else { RequestId(1) },
 				request_id_max,
 				request_id_notify: Arc::new(Notify::new()),
+				request_id_blocked: None,
+				peer_request_id_max: control_tx.as_ref().map(|_| RequestId(REQUEST_ID_WINDOW)),
 			})),
+			control_tx,
+			version,
+			wire_log: false,
+		}
+	}
+
+	/// Log every control message sent or received as hex at TRACE level, with its decoded
+	/// form where we have it, for diagnosing interop failures. Off by default.
+	///
+	/// Draft-14 to 16 log the control stream itself, see [WireLog]. Draft-17+ has no control
+	/// stream, so each request stream is logged instead via [Self::log_stream].
+	pub fn with_wire_log(mut self, enabled: bool) -> Self {
+		self.wire_log = enabled;
+		self
+	}
+
+	/// Log the messages on a draft-17+ request stream, if the wire log is enabled.
+	///
+	/// A no-op for draft-14 to 16, whose request streams are multiplexed over the
+	/// control stream and already logged there.
+	pub fn log_stream<S: web_transport_trait::Session>(&self, stream: Stream<S, Version>) -> Stream<S, Version> {
+		if !self.wire_log || self.control_tx.is_some() {
+			return stream;
 		}
+
+		WireLog::attach(stream, self.version)
+	}
+
+	pub fn version(&self) -> Version {
+		self.version
 	}
 
 	pub fn max_request_id(&self, max: RequestId) {
@@ -33,7 +89,30 @@ impl Control {
 		state.request_id_notify.notify_waiters();
 	}
 
+	/// Note a request id used by the peer, raising its ceiling once it has used half the window.
+	///
+	/// Also called with the ceiling from the peer's REQUESTS_BLOCKED, which raises it right away.
+	/// Keeps a long-lived session from ever running out of request ids.
+	pub fn peer_request_id(&self, id: RequestId) {
+		let mut state = self.state.lock().unwrap();
+		let Some(max) = state.peer_request_id_max else {
+			return;
+		};
+		if id.0.saturating_add(REQUEST_ID_WINDOW / 2) < max.0 {
+			return;
+		}
+
+		let max = RequestId(id.0.saturating_add(REQUEST_ID_WINDOW));
+		state.peer_request_id_max = Some(max);
+		if let Err(err) = self.send(MaxRequestId { request_id: max }) {
+			// Our side of the control stream is finished, so the peer gets no more requests in.
+			tracing::debug!(%err, "not raising MAX_REQUEST_ID");
+		}
+	}
+
 	/// Allocate the next request_id, blocking until MAX_REQUEST_ID allows it.
+	///
+	/// Sends REQUESTS_BLOCKED the first time we hit each ceiling, prompting the peer to raise it.
 	pub async fn next_request_id(&self) -> Result<RequestId, Error> {
 		let timeout = web_async::time::sleep(std::time::Duration::from_secs(10));
 		tokio::pin!(timeout);
@@ -42,13 +121,14 @@ impl Control {
 			let notify = {
 				let mut state = self.state.lock().unwrap();
 
-				let allowed = match state.request_id_max {
-					None => true,
-					Some(max) => state.request_id_next < max,
+				let max = match state.request_id_max {
+					Some(max) if state.request_id_next >= max => max,
+					_ => return Ok(state.request_id_next.increment()),
 				};
 
-				if allowed {
-					return Ok(state.request_id_next.increment());
+				if state.request_id_blocked != Some(max) {
+					state.request_id_blocked = Some(max);
+					self.send(RequestsBlocked { request_id: max })?;
 				}
 
 				state.request_id_notify.clone().notified_owned()
@@ -63,4 +143,216 @@ impl Control {
 			}
 		}
 	}
+
+	/// Write a control message outside of any request, e.g. REQUESTS_BLOCKED or MAX_REQUEST_ID.
+	///
+	/// Fails with [`Error::Closed`] once the control stream is finished.
+	fn send<M: Message + std::fmt::Debug>(&self, msg: M) -> Result<(), Error> {
+		let Some(control_tx) = &self.control_tx else {
+			return Ok(());
+		};
+
+		tracing::debug!(message = ?msg, "sending control message");
+
+		let mut buf = BytesMut::new();
+		if let Err(err) = M::ID
+			.encode(&mut buf, self.version)
+			.and_then(|_| msg.encode(&mut buf, self.version))
+		{
+			tracing::warn!(%err, "failed to encode control message");
+			return Ok(());
+		}
+
+		control_tx.send(buf.freeze()).map_err(|_| Error::Closed)
+	}
+}
+
+/// Which way the bytes fed to a [WireLog] are going.
+#[derive(Clone, Copy, Debug)]
+pub(crate) enum WireDirection {
+	Sent,
+	Received,
+}
+
+/// Splits the bytes of a control or request stream back into messages, logging each one.
+///
+/// Fed from the transport side of a [crate::coding::Reader] or [crate::coding::Writer], so
+/// every message is logged exactly as it went over the wire, however it was encoded.
+pub(crate) struct WireLog {
+	version: Version,
+	direction: WireDirection,
+	// Bytes of a message that hasn't fully arrived yet.
+	buffer: BytesMut,
+}
+
+impl WireLog {
+	pub fn new(version: Version, direction: WireDirection) -> Self {
+		Self {
+			version,
+			direction,
+			buffer: BytesMut::new(),
+		}
+	}
+
+	/// Log every message sent and received on the stream.
+	pub fn attach<S: web_transport_trait::Session>(stream: Stream<S, Version>, version: Version) -> Stream<S, Version> {
+		Stream {
+			writer: stream.writer.with_wire_log(Self::new(version, WireDirection::Sent)),
+			reader: stream.reader.with_wire_log(Self::new(version, WireDirection::Received)),
+		}
+	}
+
+	/// Buffer the given bytes and log every message they complete.
+	pub fn feed(&mut self, bytes: &[u8]) {
+		self.buffer.extend_from_slice(bytes);
+
+		while let Some((type_id, raw, offset)) = self.next() {
+			let message = ietf::message::decode_control(type_id, &mut &raw[offset..], self.version);
+			let bytes = Hex(&raw);
+
+			match (self.direction, message) {
+				(WireDirection::Sent, Ok(message)) => tracing::trace!(?message, %bytes, "control message sent"),
+				(WireDirection::Received, Ok(message)) => {
+					tracing::trace!(?message, %bytes, "control message received")
+				}
+				(direction, Err(err)) => {
+					let type_id = format_args!("{type_id:#x}");
+					tracing::trace!(?direction, %type_id, %err, %bytes, "control message");
+				}
+			}
+		}
+	}
+
+	/// Split off the next complete `[type][size][body]` once it has fully arrived, along with
+	/// the offset of the size prefix, where [ietf::message::decode_control] starts.
+	fn next(&mut self) -> Option<(u64, Bytes, usize)> {
+		let mut cursor = &self.buffer[..];
+		let header = u64::decode(&mut cursor, self.version).and_then(|type_id| {
+			let offset = self.buffer.len() - cursor.len();
+			let size = u16::decode(&mut cursor, self.version)?;
+			Ok((type_id, offset, size as usize))
+		});
+
+		let (type_id, offset, size) = match header {
+			Ok(header) => header,
+			Err(DecodeError::Short) => return None,
+			Err(err) => {
+				// Not control message framing, so nothing after it can be split either.
+				tracing::trace!(direction = ?self.direction, %err, bytes = %Hex(&self.buffer), "unframed control bytes");
+				self.buffer.clear();
+				return None;
+			}
+		};
+
+		if cursor.len() < size {
+			return None;
+		}
+
+		let len = self.buffer.len() - cursor.len() + size;
+		Some((type_id, self.buffer.split_to(len).freeze(), offset))
+	}
+}
+
+/// Formats bytes as lowercase hex, for the wire log.
+pub(super) struct Hex<'a>(pub &'a [u8]);
+
+impl std::fmt::Display for Hex<'_> {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		for byte in self.0 {
+			write!(f, "{byte:02x}")?;
+		}
+		Ok(())
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::coding::Decode;
+
+	#[tokio::test(start_paused = true)]
+	async fn requests_blocked_sent_once_per_ceiling() {
+		let (tx, mut rx) = mpsc::unbounded_channel();
+		let control = Control::new(Some(RequestId(2)), true, Some(tx), Version::Draft14);
+
+		assert_eq!(control.next_request_id().await.unwrap(), RequestId(0));
+
+		let blocked = tokio::spawn({
+			let control = control.clone();
+			async move { control.next_request_id().await }
+		});
+
+		let mut raw = rx.recv().await.unwrap();
+		assert_eq!(u64::decode(&mut raw, Version::Draft14).unwrap(), RequestsBlocked::ID);
+		let msg = RequestsBlocked::decode(&mut raw, Version::Draft14).unwrap();
+		assert_eq!(msg.request_id, RequestId(2));
+
+		control.max_request_id(RequestId(4));
+		assert_eq!(blocked.await.unwrap().unwrap(), RequestId(2));
+		assert!(rx.try_recv().is_err());
+	}
+
+	#[test]
+	fn max_request_id_raised_at_half_window() {
+		let (tx, mut rx) = mpsc::unbounded_channel();
+		let control = Control::new(None, false, Some(tx), Version::Draft14);
+
+		// The first id within half a window of the initial ceiling.
+		let half = REQUEST_ID_WINDOW - REQUEST_ID_WINDOW / 2;
+		control.peer_request_id(RequestId(half - 1));
+		assert!(rx.try_recv().is_err());
+
+		let used = RequestId(half);
+		control.peer_request_id(used);
+		let mut raw = rx.try_recv().unwrap();
+		assert_eq!(u64::decode(&mut raw, Version::Draft14).unwrap(), MaxRequestId::ID);
+		let msg = MaxRequestId::decode(&mut raw, Version::Draft14).unwrap();
+		assert_eq!(msg.request_id, RequestId(used.0 + REQUEST_ID_WINDOW));
+
+		// Not again until half of the new window is used, unless the peer reports it's blocked.
+		control.peer_request_id(RequestId(msg.request_id.0 - REQUEST_ID_WINDOW / 2 - 1));
+		assert!(rx.try_recv().is_err());
+		control.peer_request_id(msg.request_id);
+		assert!(rx.try_recv().is_ok());
+	}
+
+	#[test]
+	fn no_max_request_id_without_control_stream() {
+		let control = Control::new(None, false, None, Version::Draft17);
+		control.peer_request_id(RequestId(REQUEST_ID_WINDOW));
+		assert!(control.state.lock().unwrap().peer_request_id_max.is_none());
+	}
+
+	#[test]
+	fn wire_log_splits_messages() {
+		let mut raw = BytesMut::new();
+		for request_id in [1, 2] {
+			MaxRequestId::ID.encode(&mut raw, Version::Draft14).unwrap();
+			MaxRequestId {
+				request_id: RequestId(request_id),
+			}
+			.encode(&mut raw, Version::Draft14)
+			.unwrap();
+		}
+		let first = raw.len() / 2;
+
+		// However the bytes arrive, a message is only split off once it's complete.
+		let mut log = WireLog::new(Version::Draft14, WireDirection::Received);
+		log.buffer.extend_from_slice(&raw[..first - 1]);
+		assert!(log.next().is_none());
+		log.buffer.extend_from_slice(&raw[first - 1..]);
+
+		let (type_id, message, offset) = log.next().unwrap();
+		assert_eq!(type_id, MaxRequestId::ID);
+		assert_eq!(message, raw[..first]);
+		assert_eq!(offset, 1);
+		assert_eq!(log.next().unwrap().1, raw[first..]);
+		assert!(log.next().is_none());
+	}
+
+	#[test]
+	fn hex() {
+		assert_eq!(Hex(&[0x15, 0x00, 0x0a, 0xff]).to_string(), "15000aff");
+		assert_eq!(Hex(&[]).to_string(), "");
+	}
 }
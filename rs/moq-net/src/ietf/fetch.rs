@@ -4,7 +4,7 @@ use crate::{
 	Path,
 	coding::{Decode, DecodeError, Encode, EncodeError},
 	ietf::{
-		GroupOrder, Location, Parameters, RequestId,
+		GroupOrder, Location, ObjectStatus, Parameters, RequestId,
 		namespace::{decode_namespace, encode_namespace},
 	},
 };
@@ -328,6 +328,105 @@ impl Decode<Version> for FetchHeader {
 	}
 }
 
+/// An object on a fetch stream, following the [FetchHeader].
+///
+/// Every field is written out in full: no Serialization Flags are used to elide them.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FetchObject {
+	pub group_id: u64,
+	pub subgroup_id: u64,
+	pub object_id: u64,
+	pub publisher_priority: u8,
+	/// An empty payload is sent as a normal object with no data.
+	pub payload: bytes::Bytes,
+}
+
+impl FetchObject {
+	// Serialization Flags (draft-15+), for the fields we always write.
+	const SUBGROUP_ID: u64 = 0x03;
+	const OBJECT_ID: u64 = 0x04;
+	const GROUP_ID: u64 = 0x08;
+	const PRIORITY: u64 = 0x10;
+	const EXTENSIONS: u64 = 0x20;
+	const FLAGS: u64 = Self::SUBGROUP_ID | Self::OBJECT_ID | Self::GROUP_ID | Self::PRIORITY;
+}
+
+impl Encode<Version> for FetchObject {
+	fn encode<W: bytes::BufMut>(&self, w: &mut W, version: Version) -> Result<(), EncodeError> {
+		if !matches!(version, Version::Draft14) {
+			Self::FLAGS.encode(w, version)?;
+		}
+		self.group_id.encode(w, version)?;
+		self.subgroup_id.encode(w, version)?;
+		self.object_id.encode(w, version)?;
+		self.publisher_priority.encode(w, version)?;
+		if matches!(version, Version::Draft14) {
+			// No extensions.
+			0u64.encode(w, version)?;
+		}
+		// An empty payload is followed by the object status instead.
+		self.payload.len().encode(w, version)?;
+		if self.payload.is_empty() {
+			ObjectStatus::Normal.encode(w, version)?;
+		} else {
+			if w.remaining_mut() < self.payload.len() {
+				return Err(EncodeError::Short);
+			}
+			w.put_slice(&self.payload);
+		}
+		Ok(())
+	}
+}
+
+impl Decode<Version> for FetchObject {
+	fn decode<B: bytes::Buf>(buf: &mut B, version: Version) -> Result<Self, DecodeError> {
+		let extensions = match version {
+			Version::Draft14 => true,
+			_ => {
+				let flags = u64::decode(buf, version)?;
+				// Elided fields depend on the previous object, which a lone decode doesn't have.
+				if flags & Self::FLAGS != Self::FLAGS || flags & !(Self::FLAGS | Self::EXTENSIONS) != 0 {
+					return Err(DecodeError::Unsupported);
+				}
+				flags & Self::EXTENSIONS != 0
+			}
+		};
+
+		let group_id = u64::decode(buf, version)?;
+		let subgroup_id = u64::decode(buf, version)?;
+		let object_id = u64::decode(buf, version)?;
+		let publisher_priority = u8::decode(buf, version)?;
+		if extensions {
+			let size = usize::decode(buf, version)?;
+			if buf.remaining() < size {
+				return Err(DecodeError::Short);
+			}
+			buf.advance(size);
+		}
+
+		let size = usize::decode(buf, version)?;
+		let payload = if size == 0 {
+			match ObjectStatus::decode(buf, version)? {
+				ObjectStatus::Normal => bytes::Bytes::new(),
+				_ => return Err(DecodeError::Unsupported),
+			}
+		} else {
+			if buf.remaining() < size {
+				return Err(DecodeError::Short);
+			}
+			buf.copy_to_bytes(size)
+		};
+
+		Ok(Self {
+			group_id,
+			subgroup_id,
+			object_id,
+			publisher_priority,
+			payload,
+		})
+	}
+}
+
 #[cfg(test)]
 mod tests {
 	use super::*;
@@ -533,4 +632,39 @@ mod tests {
 		assert!(!decoded.end_of_track);
 		assert_eq!(decoded.end_location, Location { group: 5, object: 3 });
 	}
+
+	#[test]
+	fn test_fetch_object_round_trip() {
+		for version in [Version::Draft14, Version::Draft16, Version::Draft17] {
+			for payload in [bytes::Bytes::from_static(b"hello"), bytes::Bytes::new()] {
+				let object = FetchObject {
+					group_id: 3,
+					subgroup_id: 0,
+					object_id: 1,
+					publisher_priority: 127,
+					payload,
+				};
+
+				let mut buf = BytesMut::new();
+				object.encode(&mut buf, version).unwrap();
+				let mut buf = buf.freeze();
+				assert_eq!(FetchObject::decode(&mut buf, version).unwrap(), object);
+				assert!(buf.is_empty());
+			}
+		}
+	}
+
+	#[test]
+	fn test_fetch_object_elided_fields() {
+		// Group ID omitted, to be taken from the previous object.
+		let mut buf = BytesMut::new();
+		(FetchObject::FLAGS & !FetchObject::GROUP_ID)
+			.encode(&mut buf, Version::Draft16)
+			.unwrap();
+		let mut buf = buf.freeze();
+		assert!(matches!(
+			FetchObject::decode(&mut buf, Version::Draft16),
+			Err(DecodeError::Unsupported)
+		));
+	}
 }
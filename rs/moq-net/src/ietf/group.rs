@@ -21,6 +21,15 @@ impl GroupOrder {
 			other => other,
 		}
 	}
+
+	/// Rank `sequence` for stream scheduling, higher going first: newest first unless
+	/// `Ascending` was requested.
+	pub(crate) fn rank(self, sequence: u64) -> u64 {
+		match self {
+			Self::Ascending => u64::MAX - sequence,
+			Self::Any | Self::Descending => sequence,
+		}
+	}
 }
 
 impl Encode<Version> for GroupOrder {
@@ -49,6 +58,34 @@ impl Param for GroupOrder {
 	}
 }
 
+/// The status of an object, sent instead of a payload when the object size is zero.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, TryFromPrimitive, IntoPrimitive)]
+#[repr(u64)]
+pub enum ObjectStatus {
+	/// A normal (empty) object.
+	Normal = 0x0,
+	/// The object does not exist; a gap in the group.
+	DoesNotExist = 0x1,
+	/// The whole group does not exist.
+	GroupDoesNotExist = 0x2,
+	/// No more objects follow in this group.
+	EndOfGroup = 0x3,
+	/// No more objects follow in this track.
+	EndOfTrack = 0x4,
+}
+
+impl Encode<Version> for ObjectStatus {
+	fn encode<W: bytes::BufMut>(&self, w: &mut W, version: Version) -> Result<(), EncodeError> {
+		u64::from(*self).encode(w, version)
+	}
+}
+
+impl Decode<Version> for ObjectStatus {
+	fn decode<R: bytes::Buf>(r: &mut R, version: Version) -> Result<Self, DecodeError> {
+		Self::try_from(u64::decode(r, version)?).map_err(|_| DecodeError::InvalidValue)
+	}
+}
+
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct GroupFlags {
 	// The group has extensions.
@@ -57,9 +94,9 @@ pub struct GroupFlags {
 	// There's an explicit subgroup on the wire.
 	pub has_subgroup: bool,
 
-	// Use the first object ID as the subgroup ID
-	// Since we don't support subgroups or object ID > 0, this is trivial to support.
-	// Not compatibile with has_subgroup
+	// Use the first object ID as the subgroup ID, instead of a subgroup field.
+	// We only support subgroup 0, so the subscriber requires the first object ID to be 0.
+	// Not compatible with has_subgroup.
 	pub has_subgroup_object: bool,
 
 	// There's an implicit end marker when the stream is closed.
@@ -235,6 +272,32 @@ impl Decode<Version> for GroupHeader {
 mod tests {
 	use super::*;
 
+	#[test]
+	fn test_object_status_round_trip() {
+		for status in [
+			ObjectStatus::Normal,
+			ObjectStatus::DoesNotExist,
+			ObjectStatus::GroupDoesNotExist,
+			ObjectStatus::EndOfGroup,
+			ObjectStatus::EndOfTrack,
+		] {
+			let mut buf = bytes::BytesMut::new();
+			status.encode(&mut buf, Version::Draft14).unwrap();
+			let mut buf = buf.freeze();
+			assert_eq!(ObjectStatus::decode(&mut buf, Version::Draft14).unwrap(), status);
+		}
+
+		let mut buf = bytes::Bytes::from_static(&[0x05]);
+		assert!(ObjectStatus::decode(&mut buf, Version::Draft14).is_err());
+	}
+
+	#[test]
+	fn test_group_order_rank() {
+		assert!(GroupOrder::Descending.rank(2) > GroupOrder::Descending.rank(1));
+		assert!(GroupOrder::Any.rank(2) > GroupOrder::Any.rank(1));
+		assert!(GroupOrder::Ascending.rank(1) > GroupOrder::Ascending.rank(2));
+	}
+
 	// Test table from draft-ietf-moq-transport-14 Section 10.4.2 Table 7
 	#[test]
 	fn test_group_flags_spec_table() {
@@ -365,6 +428,57 @@ mod tests {
 		assert!(GroupFlags::decode(0x36, Version::Draft14).is_err());
 	}
 
+	#[test]
+	fn test_group_flags_subgroup_object() {
+		let flags = GroupFlags {
+			has_subgroup_object: true,
+			..Default::default()
+		};
+
+		for version in [Version::Draft14, Version::Draft18] {
+			let id = flags.encode(version).unwrap();
+			assert_eq!(id & !GroupFlags::FIRST_OBJECT_BIT, 0x1A);
+			assert_eq!(GroupFlags::decode(id, version).unwrap(), flags);
+		}
+
+		// Can't be combined with an explicit subgroup.
+		let both = GroupFlags {
+			has_subgroup: true,
+			..flags.clone()
+		};
+		assert!(both.encode(Version::Draft14).is_err());
+	}
+
+	#[test]
+	fn test_group_header_subgroup_object() {
+		let header = GroupHeader {
+			track_alias: 1,
+			group_id: 2,
+			sub_group_id: 0,
+			publisher_priority: 3,
+			flags: GroupFlags {
+				has_subgroup_object: true,
+				..Default::default()
+			},
+		};
+
+		// No subgroup field on the wire: the first object ID carries it.
+		let mut buf = bytes::BytesMut::new();
+		header.encode(&mut buf, Version::Draft14).unwrap();
+		assert_eq!(&buf[..], &[0x1A, 1, 2, 3]);
+
+		let mut buf = buf.freeze();
+		assert_eq!(GroupHeader::decode(&mut buf, Version::Draft14).unwrap(), header);
+		assert!(buf.is_empty());
+
+		// A subgroup other than 0 can't be expressed without the object.
+		let header = GroupHeader {
+			sub_group_id: 4,
+			..header
+		};
+		assert!(header.encode(&mut bytes::BytesMut::new(), Version::Draft14).is_err());
+	}
+
 	/// Draft-18 introduces the FIRST_OBJECT bit (0x40) per spec §11.4.2.
 	/// moq-lite always sets it on emit and ignores it on decode (we already
 	/// require what the bit asserts).
@@ -425,6 +539,37 @@ mod tests {
 		assert!(flags.has_end);
 	}
 
+	#[test]
+	fn test_group_header_publisher_priority() {
+		for version in [Version::Draft14, Version::Draft15, Version::Draft17, Version::Draft18] {
+			let header = GroupHeader {
+				track_alias: 1,
+				group_id: 2,
+				sub_group_id: 0,
+				publisher_priority: 200,
+				flags: GroupFlags::default(),
+			};
+
+			let mut buf = bytes::BytesMut::new();
+			header.encode(&mut buf, version).unwrap();
+			let mut buf = buf.freeze();
+			assert_eq!(GroupHeader::decode(&mut buf, version).unwrap(), header);
+
+			// Without the field, the priority inherits the default.
+			let header = GroupHeader {
+				flags: GroupFlags {
+					has_priority: false,
+					..Default::default()
+				},
+				..header
+			};
+			let mut buf = bytes::BytesMut::new();
+			header.encode(&mut buf, version).unwrap();
+			let mut buf = buf.freeze();
+			assert_eq!(GroupHeader::decode(&mut buf, version).unwrap().publisher_priority, 128);
+		}
+	}
+
 	/// Regression: a publisher-emitted Draft18 GroupHeader byte must satisfy the
 	/// subscriber's uni-stream classifier mask `(byte & 0x90) == 0x10`. Otherwise
 	/// the uni stream is dropped as UnexpectedStream and the data plane stalls.
@@ -14,6 +14,7 @@ mod group;
 mod location;
 pub mod message;
 mod namespace;
+mod priority;
 mod properties;
 mod publish;
 mod publish_namespace;
@@ -27,6 +28,7 @@ mod track;
 mod version;
 
 use control::Control;
+pub(crate) use control::{REQUEST_ID_WINDOW, WireLog};
 pub use fetch::*;
 pub use goaway::*;
 pub use group::*;
@@ -96,4 +96,20 @@ mod tests {
 		let bytes = encode_ns("a/b/c");
 		assert_eq!(bytes[0], 0x03);
 	}
+
+	#[test]
+	fn too_many_parts_rejected() {
+		let deepest = vec!["a"; Path::MAX_PARTS].join("/");
+		assert_eq!(decode_ns(&encode_ns(&deepest)).as_str(), deepest);
+
+		// The count alone is rejected, before any part is read or allocated.
+		let mut bytes = BytesMut::new();
+		(Path::MAX_PARTS as u64 + 1)
+			.encode(&mut bytes, Version::Draft17)
+			.unwrap();
+		assert!(matches!(
+			decode_namespace(&mut bytes.freeze(), Version::Draft17),
+			Err(DecodeError::BoundsExceeded)
+		));
+	}
 }
@@ -6,7 +6,7 @@ use num_enum::{FromPrimitive, IntoPrimitive};
 use crate::coding::*;
 
 use super::Version;
-use super::{FilterType, Location};
+use super::{FilterType, Location, RequestId};
 
 const MAX_PARAMS: u64 = 64;
 /// Maximum byte value length in Key-Value-Pairs per spec Section 1.4.3.
@@ -24,6 +24,19 @@ pub enum ParameterVarInt {
 	Unknown(u64),
 }
 
+// ---- Message Parameters (used in draft-14 requests such as SUBSCRIBE) ----
+
+/// Varint message parameters. Their IDs overlap the setup parameters above; which one
+/// is meant depends on the message carrying it.
+#[derive(Debug, Copy, Clone, FromPrimitive, IntoPrimitive, Eq, Hash, PartialEq)]
+#[repr(u64)]
+pub enum MessageParameterVarInt {
+	/// How long an object stays useful to the subscriber, in milliseconds.
+	DeliveryTimeout = 2,
+	#[num_enum(catch_all)]
+	Unknown(u64),
+}
+
 #[derive(Debug, Copy, Clone, FromPrimitive, IntoPrimitive, Eq, Hash, PartialEq)]
 #[repr(u64)]
 pub enum ParameterBytes {
@@ -37,7 +50,8 @@ pub enum ParameterBytes {
 
 #[derive(Default, Debug, Clone)]
 pub struct Parameters {
-	vars: HashMap<ParameterVarInt, u64>,
+	// Keyed by the raw ID, shared by setup and message parameters.
+	vars: HashMap<u64, u64>,
 	bytes: HashMap<ParameterBytes, Vec<u8>>,
 }
 
@@ -73,7 +87,6 @@ impl Decode<Version> for Parameters {
 					};
 
 					if kind % 2 == 0 {
-						let kind = ParameterVarInt::from(kind);
 						match vars.entry(kind) {
 							hash_map::Entry::Occupied(_) => return Err(DecodeError::Duplicate),
 							hash_map::Entry::Vacant(entry) => entry.insert(u64::decode(&mut r, version)?),
@@ -110,8 +123,7 @@ impl Decode<Version> for Parameters {
 					i += 1;
 
 					if abs % 2 == 0 {
-						let kind = ParameterVarInt::from(abs);
-						match vars.entry(kind) {
+						match vars.entry(abs) {
 							hash_map::Entry::Occupied(_) => return Err(DecodeError::Duplicate),
 							hash_map::Entry::Vacant(entry) => entry.insert(u64::decode(&mut r, version)?),
 						};
@@ -146,7 +158,7 @@ impl Encode<Version> for Parameters {
 				count.encode(w, version)?;
 
 				for (kind, value) in self.vars.iter() {
-					u64::from(*kind).encode(w, version)?;
+					kind.encode(w, version)?;
 					value.encode(w, version)?;
 				}
 
@@ -172,7 +184,7 @@ impl Encode<Version> for Parameters {
 				}
 				let mut all: Vec<(u64, ParamRef)> = Vec::new();
 				for (k, v) in self.vars.iter() {
-					all.push((u64::from(*k), ParamRef::Var(v)));
+					all.push((*k, ParamRef::Var(v)));
 				}
 				for (k, v) in self.bytes.iter() {
 					all.push((u64::from(*k), ParamRef::Bytes(v)));
@@ -203,12 +215,12 @@ impl Encode<Version> for Parameters {
 }
 
 impl Parameters {
-	pub fn get_varint(&self, kind: ParameterVarInt) -> Option<u64> {
-		self.vars.get(&kind).copied()
+	pub fn get_varint(&self, kind: impl Into<u64>) -> Option<u64> {
+		self.vars.get(&kind.into()).copied()
 	}
 
-	pub fn set_varint(&mut self, kind: ParameterVarInt, value: u64) {
-		self.vars.insert(kind, value);
+	pub fn set_varint(&mut self, kind: impl Into<u64>, value: u64) {
+		self.vars.insert(kind.into(), value);
 	}
 
 	#[cfg(test)]
@@ -219,6 +231,51 @@ impl Parameters {
 	pub fn set_bytes(&mut self, kind: ParameterBytes, value: Vec<u8>) {
 		self.bytes.insert(kind, value);
 	}
+
+	/// Copy every parameter from `other`, overriding any already set.
+	pub fn extend(&mut self, other: &Parameters) {
+		self.vars.extend(other.vars.iter().map(|(k, v)| (*k, *v)));
+		self.bytes.extend(other.bytes.iter().map(|(k, v)| (*k, v.clone())));
+	}
+
+	/// The initial request ID limit granted by the peer (called MAX_SUBSCRIBE_ID before draft-11).
+	///
+	/// Only sent by draft-14/15/16; draft-17+ has no request flow control.
+	pub fn max_request_id(&self) -> Option<RequestId> {
+		self.get_varint(ParameterVarInt::MaxRequestId).map(RequestId)
+	}
+
+	pub fn set_max_request_id(&mut self, max: RequestId) {
+		self.set_varint(ParameterVarInt::MaxRequestId, max.0);
+	}
+
+	/// The DELIVERY_TIMEOUT message parameter, sent in milliseconds.
+	pub fn delivery_timeout(&self) -> Option<std::time::Duration> {
+		self.get_varint(MessageParameterVarInt::DeliveryTimeout)
+			.map(std::time::Duration::from_millis)
+	}
+
+	/// Ask the publisher to drop objects still unsent `timeout` after it received them.
+	///
+	/// Only carried by draft-14 requests; later drafts encode it as a typed request parameter.
+	pub fn set_delivery_timeout(&mut self, timeout: std::time::Duration) {
+		self.set_varint(MessageParameterVarInt::DeliveryTimeout, timeout.as_millis() as u64);
+	}
+
+	/// The raw authorization token, whose format is up to the application.
+	pub fn authorization_token(&self) -> Option<&[u8]> {
+		self.bytes.get(&ParameterBytes::AuthorizationToken).map(Vec::as_slice)
+	}
+
+	/// The peer's implementation name, or `None` if absent or not UTF-8.
+	pub fn implementation(&self) -> Option<&str> {
+		let bytes = self.bytes.get(&ParameterBytes::Implementation)?;
+		std::str::from_utf8(bytes).ok()
+	}
+
+	pub fn set_implementation(&mut self, name: &str) {
+		self.set_bytes(ParameterBytes::Implementation, name.as_bytes().to_vec());
+	}
 }
 
 // ---- Message Parameter Value Encoding ----
@@ -544,6 +601,28 @@ mod tests {
 		);
 	}
 
+	#[test]
+	fn test_parameters_typed_round_trip() {
+		let mut params = Parameters::default();
+		params.set_max_request_id(RequestId(100));
+		params.set_bytes(ParameterBytes::AuthorizationToken, b"secret".to_vec());
+		params.set_implementation("test-impl");
+		// Unknown parameters still go through the raw API.
+		params.set_varint(ParameterVarInt::Unknown(0x40), 7);
+
+		let mut buf = BytesMut::new();
+		params.encode(&mut buf, Version::Draft16).unwrap();
+
+		let mut bytes = buf.freeze();
+		let decoded = Parameters::decode(&mut bytes, Version::Draft16).unwrap();
+
+		assert_eq!(decoded.max_request_id(), Some(RequestId(100)));
+		assert_eq!(decoded.get_varint(ParameterVarInt::MaxRequestId), Some(100));
+		assert_eq!(decoded.authorization_token(), Some(b"secret".as_ref()));
+		assert_eq!(decoded.implementation(), Some("test-impl"));
+		assert_eq!(decoded.get_varint(ParameterVarInt::Unknown(0x40)), Some(7));
+	}
+
 	#[test]
 	fn test_parameters_v15_round_trip() {
 		let mut params = Parameters::default();
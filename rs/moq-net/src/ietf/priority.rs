@@ -0,0 +1,35 @@
+//! Mapping between the model's priorities and the wire's.
+//!
+//! The model sends higher priorities first, while IETF MoQ sends lower values first.
+
+/// The model priority of a request that doesn't carry one, the wire's default of 128.
+pub(crate) const DEFAULT: u8 = from_wire(128);
+
+/// Convert a model priority to a wire priority.
+pub(crate) const fn to_wire(priority: u8) -> u8 {
+	u8::MAX - priority
+}
+
+/// Convert a wire priority to a model priority.
+pub(crate) const fn from_wire(priority: u8) -> u8 {
+	u8::MAX - priority
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn more_important_is_lower_on_the_wire() {
+		assert_eq!(to_wire(u8::MAX), 0);
+		assert_eq!(to_wire(0), u8::MAX);
+		assert!(to_wire(200) < to_wire(100));
+	}
+
+	#[test]
+	fn round_trip() {
+		for priority in [0, 1, 127, 128, 254, 255] {
+			assert_eq!(from_wire(to_wire(priority)), priority);
+		}
+	}
+}
@@ -8,9 +8,9 @@
 /// have no count and are read until the end of the message payload.
 ///
 /// For now we parse and validate the structure but discard the values.
-use bytes::Buf;
+use bytes::{Buf, Bytes, BytesMut};
 
-use crate::coding::{Decode, DecodeError};
+use crate::coding::{Decode, DecodeError, Encode, EncodeError};
 
 use super::Version;
 
@@ -32,40 +32,94 @@ pub fn skip<R: Buf>(r: &mut R, version: Version) -> Result<(), DecodeError> {
 		_ => {}
 	}
 
-	let mut prev_type: u64 = 0;
-	let mut i: u64 = 0;
+	let mut pairs = Pairs::default();
+	while pairs.next(r, version)?.is_some() {}
 
-	while r.has_remaining() {
-		if i >= MAX_PROPERTIES {
+	Ok(())
+}
+
+/// Object extension carrying the capture timestamp, in microseconds since the Unix epoch.
+pub const CAPTURE_TIMESTAMP: u64 = 0x02;
+
+/// Parse an object's extension headers and return the capture timestamp, if present.
+///
+/// Object extensions use the same Key-Value-Pair encoding as Track Properties,
+/// with absolute types before draft-16. Unknown extensions are skipped.
+pub fn capture_timestamp<R: Buf>(r: &mut R, version: Version) -> Result<Option<u64>, DecodeError> {
+	let mut pairs = Pairs::default();
+	let mut timestamp = None;
+
+	while let Some((kind, value)) = pairs.next(r, version)? {
+		if kind == CAPTURE_TIMESTAMP {
+			timestamp = value;
+		}
+	}
+
+	Ok(timestamp)
+}
+
+/// Encode an object's extension headers carrying just the capture timestamp.
+///
+/// The result is length-prefixed, ready to follow the object ID.
+pub fn encode_capture_timestamp(timestamp: crate::Time, version: Version) -> Result<Bytes, EncodeError> {
+	let micros = u64::try_from(timestamp.as_micros()).map_err(|_| EncodeError::TooLarge)?;
+
+	// A lone pair's type is absolute, with or without delta encoding.
+	let mut buf = BytesMut::new();
+	CAPTURE_TIMESTAMP.encode(&mut buf, version)?;
+	micros.encode(&mut buf, version)?;
+
+	let mut out = BytesMut::new();
+	buf.freeze().encode(&mut out, version)?;
+	Ok(out.freeze())
+}
+
+/// Walks a Key-Value-Pair sequence until the buffer is empty.
+#[derive(Default)]
+struct Pairs {
+	prev_type: u64,
+	count: u64,
+}
+
+impl Pairs {
+	/// Decode the next pair, returning its absolute type and, for even types, its varint value.
+	fn next<R: Buf>(&mut self, r: &mut R, version: Version) -> Result<Option<(u64, Option<u64>)>, DecodeError> {
+		if !r.has_remaining() {
+			return Ok(None);
+		}
+
+		if self.count >= MAX_PROPERTIES {
 			return Err(DecodeError::TooMany);
 		}
 
-		let delta = u64::decode(r, version)?;
-		let abs = if i == 0 {
-			delta
-		} else {
-			prev_type.checked_add(delta).ok_or(DecodeError::BoundsExceeded)?
+		// Types are delta-encoded since draft-16.
+		let kind = u64::decode(r, version)?;
+		let abs = match version {
+			Version::Draft14 | Version::Draft15 => kind,
+			_ if self.count == 0 => kind,
+			_ => self.prev_type.checked_add(kind).ok_or(DecodeError::BoundsExceeded)?,
 		};
-		prev_type = abs;
-		i += 1;
+		self.prev_type = abs;
+		self.count += 1;
 
 		if abs % 2 == 0 {
 			// Even type: single varint value
-			let _ = u64::decode(r, version)?;
-		} else {
-			// Odd type: length-prefixed bytes
-			let len = u64::decode(r, version)? as usize;
-			if len > MAX_KVP_VALUE_LEN {
-				return Err(DecodeError::BoundsExceeded);
-			}
-			if r.remaining() < len {
-				return Err(DecodeError::Short);
-			}
-			r.advance(len);
+			let value = u64::decode(r, version)?;
+			return Ok(Some((abs, Some(value))));
 		}
-	}
 
-	Ok(())
+		// Odd type: length-prefixed bytes
+		let len = u64::decode(r, version)? as usize;
+		if len > MAX_KVP_VALUE_LEN {
+			return Err(DecodeError::BoundsExceeded);
+		}
+		if r.remaining() < len {
+			return Err(DecodeError::Short);
+		}
+		r.advance(len);
+
+		Ok(Some((abs, None)))
+	}
 }
 
 #[cfg(test)]
@@ -121,4 +175,45 @@ mod tests {
 		skip(&mut bytes, Version::Draft17).unwrap();
 		assert!(!bytes.has_remaining());
 	}
+
+	#[test]
+	fn test_capture_timestamp() {
+		// Draft-14: absolute types, an unknown odd extension before the timestamp.
+		let mut buf = BytesMut::new();
+		0x05u64.encode(&mut buf, Version::Draft14).unwrap();
+		2u64.encode(&mut buf, Version::Draft14).unwrap();
+		buf.extend_from_slice(&[0xAA, 0xBB]);
+		0x02u64.encode(&mut buf, Version::Draft14).unwrap();
+		1_000_000u64.encode(&mut buf, Version::Draft14).unwrap();
+		let mut bytes = buf.freeze();
+		assert_eq!(
+			capture_timestamp(&mut bytes, Version::Draft14).unwrap(),
+			Some(1_000_000)
+		);
+
+		// Draft-16: delta-encoded types, timestamp first.
+		let mut buf = BytesMut::new();
+		0x02u64.encode(&mut buf, Version::Draft16).unwrap();
+		42u64.encode(&mut buf, Version::Draft16).unwrap();
+		0x02u64.encode(&mut buf, Version::Draft16).unwrap(); // delta → 0x04
+		7u64.encode(&mut buf, Version::Draft16).unwrap();
+		let mut bytes = buf.freeze();
+		assert_eq!(capture_timestamp(&mut bytes, Version::Draft16).unwrap(), Some(42));
+
+		// No timestamp extension.
+		let mut bytes = bytes::Bytes::new();
+		assert_eq!(capture_timestamp(&mut bytes, Version::Draft16).unwrap(), None);
+	}
+
+	#[test]
+	fn test_encode_capture_timestamp() {
+		for version in [Version::Draft14, Version::Draft16, Version::Draft17] {
+			let time = crate::Time::from_millis(1_500).unwrap();
+			let mut bytes = encode_capture_timestamp(time, version).unwrap();
+
+			let len = u64::decode(&mut bytes, version).unwrap();
+			assert_eq!(len as usize, bytes.remaining());
+			assert_eq!(capture_timestamp(&mut bytes, version).unwrap(), Some(1_500_000));
+		}
+	}
 }
@@ -17,6 +17,21 @@ pub struct PublishNamespace<'a> {
 	pub track_namespace: Path<'a>,
 }
 
+impl PublishNamespace<'_> {
+	/// Check that a PublishNamespace for `namespace` fits in a control message.
+	///
+	/// Sized with the largest possible request ID, so it holds whatever ID is allocated later.
+	/// Fails with [`EncodeError::BoundsExceeded`] past [`Path::MAX_PARTS`] parts, or
+	/// [`EncodeError::TooLarge`] past the 16-bit message size.
+	pub fn check(namespace: &Path, version: Version) -> Result<(), EncodeError> {
+		let msg = PublishNamespace {
+			request_id: RequestId(VarInt::MAX.into_inner()),
+			track_namespace: namespace.borrow(),
+		};
+		msg.encode(&mut Sizer::default(), version)
+	}
+}
+
 impl Message for PublishNamespace<'_> {
 	const ID: u64 = 0x06;
 
@@ -231,6 +246,47 @@ mod tests {
 		assert_eq!(decoded.track_namespace.as_str(), "test/broadcast");
 	}
 
+	#[test]
+	fn test_announce_size_boundary() {
+		let namespace = |len: usize| Path::from("x".repeat(len));
+		let body = |len: usize| {
+			let msg = PublishNamespace {
+				request_id: RequestId(VarInt::MAX.into_inner()),
+				track_namespace: namespace(len),
+			};
+			let mut sizer = Sizer::default();
+			msg.encode_msg(&mut sizer, Version::Draft17).unwrap();
+			sizer.size
+		};
+
+		// Past 16 KiB the part's length prefix stays 4 bytes, so the body grows byte for byte.
+		let overhead = body(20_000) - 20_000;
+		let max = u16::MAX as usize - overhead;
+
+		assert!(PublishNamespace::check(&namespace(max), Version::Draft17).is_ok());
+		assert!(matches!(
+			PublishNamespace::check(&namespace(max + 1), Version::Draft17),
+			Err(EncodeError::TooLarge)
+		));
+	}
+
+	#[test]
+	fn test_announce_too_many_parts() {
+		let deep = Path::from(vec!["a"; Path::MAX_PARTS + 1].join("/"));
+		assert!(matches!(
+			PublishNamespace::check(&deep, Version::Draft17),
+			Err(EncodeError::BoundsExceeded)
+		));
+
+		// A peer can't make us build a deeper one either.
+		let mut encoded = vec![0x00, 0x00, (Path::MAX_PARTS + 1) as u8];
+		encoded.extend(std::iter::repeat_n([0x01, b'a'], Path::MAX_PARTS + 1).flatten());
+		assert!(matches!(
+			decode_message::<PublishNamespace>(&encoded, Version::Draft17),
+			Err(DecodeError::BoundsExceeded)
+		));
+	}
+
 	#[test]
 	fn test_announce_error() {
 		let msg = PublishNamespaceError {
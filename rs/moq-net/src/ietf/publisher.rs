@@ -1,17 +1,73 @@
-use std::collections::HashMap;
+use std::{
+	collections::{HashMap, VecDeque},
+	sync::atomic::{AtomicBool, AtomicUsize, Ordering},
+	task::Poll,
+	time::Duration,
+};
 
 use futures::{FutureExt, StreamExt, stream::FuturesUnordered};
-use web_async::FuturesExt;
 use web_transport_trait::SendStream;
 
 use crate::{
-	AsPath, Error, Origin, OriginConsumer, StatsHandle, Track, TrackConsumer,
-	coding::{Stream, Writer},
+	AsPath, Error, Origin, OriginConsumer, RateLimit, StatsHandle, Track, TrackConsumer,
+	coding::{Reader, Stream, Writer},
 	ietf::{self, Control, FetchHeader, FetchType, FilterType, GroupOrder, Location, RequestId},
 	model::GroupConsumer,
+	opener::Opener,
+	pacer::Pacer,
 };
 
-use super::{Message, Version};
+use super::{Message, Version, priority, properties};
+
+use web_async::{Lock, time::Instant};
+
+/// A subscription to serve, as resolved from its SUBSCRIBE.
+struct TrackParams {
+	request_id: RequestId,
+	/// Decides which waiting group gets the next stream once the peer's stream limit is reached.
+	group_order: GroupOrder,
+	/// The subscriber's DELIVERY_TIMEOUT, if any.
+	delivery_timeout: Option<Duration>,
+	stats: std::sync::Arc<crate::PublisherTrack>,
+	/// Whether to open group streams, toggled by SUBSCRIBE_UPDATE.
+	forward: kio::Consumer<bool>,
+	/// Groups before it are skipped, and its group is served from the start object.
+	start: Option<Location>,
+	/// The last group to serve.
+	end: Option<u64>,
+	/// Where the first group served is published, the point a joining fetch catches up to.
+	join: kio::Producer<Option<u64>>,
+}
+
+/// A live subscription, as a joining fetch sees it.
+#[derive(Clone)]
+struct Joinable {
+	track: TrackConsumer,
+	/// Whether the subscription is opening group streams.
+	forward: kio::Consumer<bool>,
+	/// The first group served by the subscription, once there is one.
+	first: kio::Consumer<Option<u64>>,
+}
+
+/// A group to serve on its own uni stream.
+struct GroupParams<S: web_transport_trait::Session> {
+	session: S,
+	opener: Opener,
+	msg: ietf::GroupHeader,
+	/// The track priority.
+	priority: u8,
+	/// The group's rank within the track, see [GroupOrder::rank].
+	rank: u64,
+	group: GroupConsumer,
+	/// Objects before this one are skipped.
+	first_object: u64,
+	stats: std::sync::Arc<crate::PublisherTrack>,
+	/// The subscriber's DELIVERY_TIMEOUT, counted from when each object arrives.
+	delivery_timeout: Option<Duration>,
+	/// The track's egress cap, shared by all of its group streams.
+	pacer: Option<Pacer>,
+	version: Version,
+}
 
 #[derive(Clone)]
 pub(super) struct Publisher<S: web_transport_trait::Session> {
@@ -23,6 +79,13 @@ pub(super) struct Publisher<S: web_transport_trait::Session> {
 	/// subscription holds a guard so `broadcasts - broadcasts_closed` counts
 	/// the distinct sessions (viewers) watching each broadcast.
 	broadcasts: crate::SessionBroadcasts,
+	/// Egress caps for each subscribed track, picked by the track's priority.
+	rate_limit: RateLimit,
+	/// Opens group streams in priority order once the peer's stream limit is reached.
+	opener: Opener,
+	/// Each live subscription, keyed by request id, set once its track is resolved.
+	/// Joining fetches are resolved against it.
+	joins: Lock<HashMap<RequestId, kio::Consumer<Option<Joinable>>>>,
 	version: Version,
 }
 
@@ -32,6 +95,7 @@ impl<S: web_transport_trait::Session> Publisher<S> {
 		origin: Option<OriginConsumer>,
 		control: Control,
 		stats: StatsHandle,
+		rate_limit: RateLimit,
 		version: Version,
 	) -> Self {
 		let origin = origin.unwrap_or_else(|| Origin::random().produce().consume());
@@ -42,6 +106,9 @@ impl<S: web_transport_trait::Session> Publisher<S> {
 			control,
 			stats,
 			broadcasts,
+			rate_limit,
+			opener: Opener::default(),
+			joins: Default::default(),
 			version,
 		}
 	}
@@ -60,10 +127,18 @@ impl<S: web_transport_trait::Session> Publisher<S> {
 					return Err(Error::WrongSize);
 				}
 				tracing::debug!(message = ?msg, "received subscribe");
+
+				// Register before spawning, so a joining fetch sent right behind the
+				// SUBSCRIBE always finds it.
+				let request_id = msg.request_id;
+				let joinable = kio::Producer::default();
+				this.joins.lock().insert(request_id, joinable.consume());
+
 				web_async::spawn(async move {
-					if let Err(err) = this.run_subscribe_stream(stream, msg).await {
+					if let Err(err) = this.run_subscribe_stream(stream, msg, joinable).await {
 						tracing::debug!(%err, "subscribe stream error");
 					}
+					this.joins.lock().remove(&request_id);
 				});
 			}
 			ietf::Fetch::ID => {
@@ -102,7 +177,16 @@ impl<S: web_transport_trait::Session> Publisher<S> {
 				});
 			}
 			ietf::TrackStatus::ID => {
-				tracing::warn!("TrackStatus not supported");
+				let msg = ietf::TrackStatus::decode_msg(&mut data, this.version)?;
+				if !data.is_empty() {
+					return Err(Error::WrongSize);
+				}
+				tracing::debug!(message = ?msg, "received track status");
+				web_async::spawn(async move {
+					if let Err(err) = this.run_track_status_stream(stream, msg).await {
+						tracing::debug!(%err, "track status stream error");
+					}
+				});
 			}
 			_ => {
 				tracing::warn!(id, "unexpected bidi stream type for publisher");
@@ -112,22 +196,90 @@ impl<S: web_transport_trait::Session> Publisher<S> {
 		Ok(())
 	}
 
-	/// Handle a SUBSCRIBE on its bidi stream.
-	async fn run_subscribe_stream(self, mut stream: Stream<S, Version>, msg: ietf::Subscribe<'_>) -> Result<(), Error> {
-		match msg.filter_type {
-			FilterType::AbsoluteStart | FilterType::AbsoluteRange => {
-				tracing::warn!(?msg, "absolute subscribe not supported, ignoring");
+	/// Answer a TRACK_STATUS on its bidi stream, saying whether the track is live.
+	///
+	/// Only looks the track up: a probe must not start an upstream subscription through a relay.
+	async fn run_track_status_stream(
+		&self,
+		mut stream: Stream<S, Version>,
+		msg: ietf::TrackStatus<'_>,
+	) -> Result<(), Error> {
+		let found = match self.origin.request_broadcast(&msg.track_namespace).await {
+			Ok(broadcast) => broadcast.has_track(&msg.track_name),
+			Err(_) => false,
+		};
+
+		match self.version {
+			Version::Draft14 => {
+				// Draft-14 reports a missing track as a status code, not an error.
+				let status_code = if found {
+					ietf::TrackStatusCode::InProgress
+				} else {
+					ietf::TrackStatusCode::NotFound
+				};
+				stream.writer.encode(&ietf::TrackStatusOk::ID).await?;
+				stream
+					.writer
+					.encode(&ietf::TrackStatusOk {
+						request_id: msg.request_id,
+						status_code,
+						largest: None,
+					})
+					.await?;
 			}
-			FilterType::NextGroup => {
-				tracing::warn!(?msg, "next group subscribe not supported, ignoring");
+			_ if found => {
+				let request_id = match self.version {
+					Version::Draft15 | Version::Draft16 => Some(msg.request_id),
+					_ => None,
+				};
+				stream.writer.encode(&ietf::RequestOk::ID).await?;
+				stream.writer.encode(&ietf::RequestOk { request_id }).await?;
 			}
-			FilterType::LargestObject => {}
+			_ => {
+				self.write_subscribe_error(
+					&mut stream.writer,
+					msg.request_id,
+					ietf::ERROR_NOT_FOUND,
+					"track not found",
+				)
+				.await?;
+			}
+		}
+
+		stream.writer.finish()
+	}
+
+	/// Handle a SUBSCRIBE on its bidi stream.
+	async fn run_subscribe_stream(
+		&self,
+		mut stream: Stream<S, Version>,
+		msg: ietf::Subscribe<'_>,
+		joinable: kio::Producer<Option<Joinable>>,
+	) -> Result<(), Error> {
+		let (start, end) = match msg.filter_type {
+			FilterType::AbsoluteStart => (msg.start_location.clone(), None),
+			FilterType::AbsoluteRange => (msg.start_location.clone(), msg.end_group),
+			// NextGroup skips the group in progress; resolved once the track is known, below.
+			FilterType::NextGroup | FilterType::LargestObject => (None, None),
 		};
 
 		let request_id = msg.request_id;
 		let track_name = msg.track_name.clone();
 		let absolute = self.origin.absolute(&msg.track_namespace).to_owned();
 
+		if let (Some(start), Some(end)) = (&start, end)
+			&& end < start.group
+		{
+			self.write_subscribe_error(
+				&mut stream.writer,
+				request_id,
+				ietf::ERROR_BAD_REQUEST,
+				"end group before start",
+			)
+			.await?;
+			return Ok(());
+		}
+
 		tracing::info!(id = %request_id, broadcast = %absolute, track = %track_name, "subscribe started");
 
 		// Per-track subscription guard (bumps `subscriptions`). Taken before
@@ -140,29 +292,46 @@ impl<S: web_transport_trait::Session> Publisher<S> {
 		// Prefer an announced broadcast, but allow a dynamic origin to serve
 		// unannounced namespaces such as edge-local dashboard stats.
 		let Ok(broadcast) = self.origin.request_broadcast(&msg.track_namespace).await else {
-			self.write_subscribe_error(&mut stream.writer, request_id, 404, "Broadcast not found")
-				.await?;
+			self.write_subscribe_error(
+				&mut stream.writer,
+				request_id,
+				ietf::ERROR_NOT_FOUND,
+				"Broadcast not found",
+			)
+			.await?;
 			return Ok(());
 		};
 
 		let track = Track {
 			name: msg.track_name.to_string(),
-			priority: msg.subscriber_priority,
+			priority: priority::from_wire(msg.subscriber_priority),
 		};
 
-		let track = match broadcast.subscribe_track(&track) {
+		let mut track = match broadcast.subscribe_track(&track) {
 			Ok(track) => track,
 			Err(err) => {
-				self.write_subscribe_error(&mut stream.writer, request_id, 404, &err.to_string())
+				self.write_subscribe_error(&mut stream.writer, request_id, ietf::ERROR_NOT_FOUND, &err.to_string())
 					.await?;
 				return Ok(());
 			}
 		};
 
+		// LargestObject joins the latest group mid-way; NextGroup waits for the one after it.
+		// With no group yet, the first to arrive is the next one either way.
+		if matches!(msg.filter_type, FilterType::NextGroup)
+			&& let Some(latest) = track.latest()
+		{
+			track.start_at(latest + 1);
+		}
+
 		// Subscription is now active: count this session as a viewer of the
 		// broadcast. Dropping this guard (subscription end) releases it.
 		let _broadcast_sub = self.broadcasts.subscribe(&absolute);
 
+		let group_order = msg.group_order.any_to_descending();
+		// A zero timeout would expire every object before it's sent; treat it as none.
+		let delivery_timeout = msg.delivery_timeout.filter(|timeout| !timeout.is_zero());
+
 		// Send SubscribeOk on the stream
 		stream.writer.encode(&ietf::SubscribeOk::ID).await?;
 		stream
@@ -173,13 +342,42 @@ impl<S: web_transport_trait::Session> Publisher<S> {
 					_ => None,
 				},
 				track_alias: request_id.0,
+				// `Any` leaves the choice to us; run_track serves the order echoed here.
+				group_order,
 			})
 			.await?;
 
-		// Run the track, cancelling on reader close (Unsubscribe or stream close)
+		// Run the track, cancelling on reader close (Unsubscribe or stream close).
+		// SubscribeUpdates arriving on the stream toggle forwarding.
+		let forward_tx = kio::Producer::new(msg.forward);
+		let forward_rx = forward_tx.consume();
+		let join = kio::Producer::default();
+		if let Ok(mut joinable) = joinable.write() {
+			*joinable = Some(Joinable {
+				track: track.clone(),
+				forward: forward_rx.clone(),
+				first: join.consume(),
+			});
+		}
+
+		let params = TrackParams {
+			request_id,
+			group_order,
+			delivery_timeout,
+			stats: track_stats,
+			forward: forward_rx,
+			start,
+			end,
+			join,
+		};
 		let res = tokio::select! {
-			res = self.run_track(track, request_id, track_stats) => res,
-			_ = stream.reader.closed() => Ok(()),
+			res = self.run_track(track, params) => res,
+			res = Self::run_subscribe_updates(&mut stream.reader, forward_tx, self.version) => {
+				if let Err(err) = res {
+					tracing::debug!(%err, "subscribe stream closed");
+				}
+				Ok(())
+			}
 			_ = self.session.closed() => Ok(()),
 		};
 
@@ -207,6 +405,33 @@ impl<S: web_transport_trait::Session> Publisher<S> {
 		res
 	}
 
+	/// Read SubscribeUpdates until the subscribe stream closes, publishing each forward flag.
+	async fn run_subscribe_updates(
+		reader: &mut Reader<S::RecvStream, Version>,
+		forward: kio::Producer<bool>,
+		version: Version,
+	) -> Result<(), Error> {
+		while let Some(type_id) = reader.decode_maybe::<u64>().await? {
+			let size: u16 = reader.decode().await?;
+			let mut data = reader.read_exact(size as usize).await?;
+
+			match type_id {
+				ietf::SubscribeUpdate::ID => {
+					let msg = ietf::SubscribeUpdate::decode_msg(&mut data, version)?;
+					tracing::debug!(message = ?msg, "received subscribe update");
+					if let Ok(mut state) = forward.write()
+						&& *state != msg.forward
+					{
+						*state = msg.forward;
+					}
+				}
+				_ => return Err(Error::UnexpectedMessage),
+			}
+		}
+
+		Ok(())
+	}
+
 	/// Write a subscribe error on the bidi stream writer.
 	async fn write_subscribe_error(
 		&self,
@@ -253,70 +478,223 @@ impl<S: web_transport_trait::Session> Publisher<S> {
 	}
 
 	/// Serve a track using FuturesUnordered for unlimited concurrent groups.
-	async fn run_track(
-		&self,
-		mut track: TrackConsumer,
-		request_id: RequestId,
-		track_stats: std::sync::Arc<crate::PublisherTrack>,
-	) -> Result<(), Error> {
+	///
+	/// No group streams are opened while `forward` is false. With a `start` location, groups
+	/// before it are skipped and its group is served from the start object. With an `end`
+	/// group, the subscription ends once that group is served or a later one arrives. The first
+	/// group served is published on `join`, the point a joining fetch catches up to.
+	///
+	/// Groups are pulled as they arrive; `group_order` decides which waiting group gets the
+	/// next stream once the peer's stream limit is reached. A group is reset once one of its
+	/// objects is still unsent the subscriber's `delivery_timeout` after it arrived.
+	async fn run_track(&self, mut track: TrackConsumer, params: TrackParams) -> Result<(), Error> {
+		let TrackParams {
+			request_id,
+			group_order,
+			delivery_timeout,
+			stats: track_stats,
+			forward,
+			start,
+			end,
+			join,
+		} = params;
+
+		// Whether a served group already carried the End of Track marker.
+		let marked = AtomicBool::new(false);
+
 		let mut tasks = FuturesUnordered::new();
 
+		// One bucket per subscription, capped by the tier of the publisher's priority.
+		let pacer = self.rate_limit.rate(track.priority).map(Pacer::new);
+
+		// Whether the track itself ended, rather than the subscription's range.
+		let mut ended = false;
+
 		loop {
+			let forwarding = *forward.read();
 			let group = tokio::select! {
 				// Poll all active group futures; never matches but keeps them running.
 				true = async {
 					while tasks.next().await.is_some() {}
 					false
 				} => unreachable!(),
-				Some(group) = track.recv_group().transpose() => group,
+				// A SubscribeUpdate toggled forwarding; re-check before pulling the next group.
+				Some(()) = kio::wait(|waiter| forward.poll(waiter, |state| match **state != forwarding {
+					true => Poll::Ready(()),
+					false => Poll::Pending,
+				}).map(|res| res.ok())) => continue,
+				group = track.recv_group(), if forwarding => group?,
 				else => return Ok(()),
-			}?;
+			};
+
+			// The track ended: no more groups will ever arrive.
+			let Some(group) = group else {
+				ended = true;
+				break;
+			};
 
 			let sequence = group.sequence;
-			tracing::debug!(subscribe = %request_id, track = %track.name, sequence, "serving group");
+			if end.is_some_and(|end| sequence > end) {
+				tracing::debug!(subscribe = %request_id, track = %track.name, sequence, "past the end group");
+				break;
+			}
 
+			let first_object = match &start {
+				Some(start) if sequence < start.group => continue,
+				Some(start) if sequence == start.group => start.object,
+				_ => 0,
+			};
+			tracing::debug!(subscribe = %request_id, track = %track.name, sequence, first_object, "serving group");
+			if let Ok(mut join) = join.write()
+				&& join.is_none()
+			{
+				*join = Some(sequence);
+			}
+
+			// A group received from an upstream publisher keeps its priority across the relay.
+			let priority = group.priority.unwrap_or(track.priority);
 			let msg = ietf::GroupHeader {
 				track_alias: request_id.0,
 				group_id: sequence,
 				sub_group_id: 0,
-				publisher_priority: 0,
+				publisher_priority: priority::to_wire(priority),
 				flags: Default::default(),
 			};
 
 			tasks.push(
-				Self::run_group(
-					self.session.clone(),
+				Self::run_group(GroupParams {
+					session: self.session.clone(),
+					opener: self.opener.clone(),
 					msg,
-					track.priority,
+					priority,
+					rank: group_order.rank(sequence),
 					group,
-					track_stats.clone(),
-					self.version,
-				)
-				.map(|_| ()),
+					first_object,
+					stats: track_stats.clone(),
+					delivery_timeout,
+					pacer: pacer.clone(),
+					version: self.version,
+				})
+				.map(|res| {
+					if matches!(res, Ok(true)) {
+						marked.store(true, Ordering::Relaxed);
+					}
+				}),
 			);
+
+			// Don't wait for a group past the end that may never come.
+			if end == Some(sequence) {
+				break;
+			}
 		}
+
+		// Let the in-flight groups finish before PublishDone reports the end of the track.
+		while tasks.next().await.is_some() {}
+
+		// Also mark the end in-band, one past the last group, so it arrives with the data.
+		if ended && !marked.load(Ordering::Relaxed) {
+			let sequence = track.finished().await?;
+			let msg = ietf::GroupHeader {
+				track_alias: request_id.0,
+				group_id: sequence,
+				sub_group_id: 0,
+				publisher_priority: priority::to_wire(track.priority),
+				flags: Default::default(),
+			};
+			let rank = group_order.rank(sequence);
+			if let Err(err) = self.run_end_of_track(msg, track.priority, rank).await {
+				tracing::debug!(subscribe = %request_id, track = %track.name, %err, "failed to send end of track");
+			}
+		}
+
+		Ok(())
 	}
 
-	async fn run_group(
-		session: S,
-		msg: ietf::GroupHeader,
-		priority: u8,
-		mut group: GroupConsumer,
-		track_stats: std::sync::Arc<crate::PublisherTrack>,
-		version: Version,
-	) -> Result<(), Error> {
-		let mut stream = session.open_uni().await.map_err(Error::from_transport)?;
+	/// Send a lone End of Track object, as the first object of the group in `msg`.
+	async fn run_end_of_track(&self, msg: ietf::GroupHeader, priority: u8, rank: u64) -> Result<(), Error> {
+		let mut stream = self.opener.open(&self.session, (priority, rank)).await?;
+		stream.set_priority(priority);
+
+		let mut stream = Writer::new(stream, self.version);
+		stream.encode(&msg).await?;
+
+		// Object ID 0, no payload.
+		stream.encode(&0u64).await?;
+		stream.encode(&0u64).await?;
+		stream.encode(&ietf::ObjectStatus::EndOfTrack).await?;
+
+		stream.finish()?;
+		stream.closed().await?;
+
+		tracing::debug!(sequence = %msg.group_id, "sent end of track");
+
+		Ok(())
+	}
+
+	/// Serve a group on its own uni stream, skipping the objects before `first_object`.
+	///
+	/// A group is always sent as subgroup 0, so every object shares the stream and its
+	/// priority. Per-object priorities would need subgroups in the model: frames carry no
+	/// priority, and a group's frames are read in order from a single stream.
+	///
+	/// Returns true if the group ended with a [`Frame::end_of_track`](crate::Frame::end_of_track) marker.
+	async fn run_group(params: GroupParams<S>) -> Result<bool, Error> {
+		let GroupParams {
+			session,
+			opener,
+			msg,
+			priority,
+			rank,
+			mut group,
+			first_object,
+			stats: track_stats,
+			delivery_timeout,
+			pacer,
+			version,
+		} = params;
+
+		// Objects written (or skipped) so far; only the ones after them can still time out.
+		let written = AtomicUsize::new(0);
+		let arrivals = group.clone();
+		let expired = async {
+			match delivery_timeout {
+				Some(timeout) => delivery_expired(arrivals, &written, timeout).await,
+				None => std::future::pending().await,
+			}
+		};
+		tokio::pin!(expired);
+
+		let mut stream = tokio::select! {
+			stream = opener.open(&session, (priority, rank)) => stream?,
+			_ = &mut expired => {
+				tracing::debug!(sequence = %msg.group_id, "group timed out before opening stream");
+				return Err(Error::Old);
+			}
+		};
 		stream.set_priority(priority);
 
 		let mut stream = Writer::new(stream, version);
 
-		stream.encode(&msg).await?;
-		track_stats.group();
+		// The header waits for the first object, which decides whether objects carry extensions.
+		let sequence = msg.group_id;
+		let mut header = Some(msg);
+		let mut extensions = false;
+
+		// The first object written jumps over the skipped ones; the rest are consecutive.
+		let mut skip = first_object;
+		let mut id_delta = first_object;
+
+		let mut end_of_track = false;
 
 		loop {
 			let frame = tokio::select! {
 				biased;
 				_ = stream.closed() => return Err(Error::Cancel),
+				_ = &mut expired => {
+					tracing::debug!(%sequence, "group timed out");
+					stream.abort(&Error::Old);
+					return Err(Error::Old);
+				}
 				frame = group.next_frame() => frame,
 			};
 
@@ -325,40 +703,92 @@ impl<S: web_transport_trait::Session> Publisher<S> {
 				None => break,
 			};
 
-			// object id delta is always 0.
-			stream.encode(&0u64).await?;
+			if skip > 0 {
+				skip -= 1;
+				written.fetch_add(1, Ordering::Relaxed);
+				continue;
+			}
+
+			if let Some(mut msg) = header.take() {
+				msg.flags.has_extensions = frame.timestamp.is_some();
+				extensions = msg.flags.has_extensions;
+				stream.encode(&msg).await?;
+				track_stats.group();
+			}
 
-			// not using extensions.
-			if msg.flags.has_extensions {
-				stream.encode(&0u64).await?;
+			stream.encode(&id_delta).await?;
+			id_delta = 0;
+
+			// The capture timestamp is the only extension a frame carries.
+			if extensions {
+				match frame.timestamp {
+					Some(timestamp) => {
+						let mut buf = properties::encode_capture_timestamp(timestamp, version)?;
+						stream.write_all(&mut buf).await?;
+					}
+					None => stream.encode(&0u64).await?,
+				}
 			}
 
 			// Write the size of the frame.
 			stream.encode(&frame.size).await?;
 			track_stats.frame();
 
-			if frame.size == 0 {
+			if frame.is_end_of_track {
+				// Nothing follows the end of the track.
+				stream.encode(&ietf::ObjectStatus::EndOfTrack).await?;
+				end_of_track = true;
+				written.fetch_add(1, Ordering::Relaxed);
+				break;
+			} else if frame.size == 0 {
 				// Have to write the object status too.
-				stream.encode(&0u8).await?;
+				stream.encode(&ietf::ObjectStatus::Normal).await?;
 			} else {
 				// Stream each chunk of the frame.
 				loop {
 					let chunk = tokio::select! {
 						biased;
 						_ = stream.closed() => return Err(Error::Cancel),
+						_ = &mut expired => {
+							tracing::debug!(%sequence, "group timed out");
+							stream.abort(&Error::Old);
+							return Err(Error::Old);
+						}
 						chunk = frame.read_chunk() => chunk,
 					};
 
 					match chunk? {
 						Some(mut chunk) => {
 							let n = chunk.len() as u64;
-							stream.write_all(&mut chunk).await?;
+							let write = async {
+								if let Some(pacer) = &pacer {
+									pacer.acquire(n).await;
+								}
+								stream.write_all(&mut chunk).await
+							};
+							tokio::select! {
+								biased;
+								res = write => res?,
+								_ = &mut expired => {
+									tracing::debug!(%sequence, "group timed out");
+									stream.abort(&Error::Old);
+									return Err(Error::Old);
+								}
+							}
 							track_stats.bytes(n);
 						}
 						None => break,
 					}
 				}
 			}
+
+			written.fetch_add(1, Ordering::Relaxed);
+		}
+
+		// An empty group still needs its header.
+		if let Some(msg) = header.take() {
+			stream.encode(&msg).await?;
+			track_stats.group();
 		}
 
 		stream.finish()?;
@@ -366,41 +796,55 @@ impl<S: web_transport_trait::Session> Publisher<S> {
 		// Wait until everything is acknowledged by the peer so we can still cancel the stream.
 		stream.closed().await?;
 
-		tracing::debug!(sequence = %msg.group_id, "finished group");
+		tracing::debug!(%sequence, "finished group");
 
-		Ok(())
+		Ok(end_of_track)
 	}
 
 	/// Handle a FETCH on its bidi stream.
+	///
+	/// Only joining fetches whose range is empty are served: there's no cache of past
+	/// groups to deliver yet, so anything else is refused.
 	async fn run_fetch_stream(self, mut stream: Stream<S, Version>, msg: ietf::Fetch<'_>) -> Result<(), Error> {
-		let _subscribe_id = match msg.fetch_type {
+		let (subscriber_request_id, start) = match msg.fetch_type {
 			FetchType::Standalone { .. } => {
-				self.write_fetch_error(&mut stream.writer, msg.request_id, 500, "not supported")
-					.await?;
+				self.write_fetch_error(
+					&mut stream.writer,
+					msg.request_id,
+					ietf::ERROR_INTERNAL,
+					"not supported",
+				)
+				.await?;
 				return Ok(());
 			}
 			FetchType::RelativeJoining {
 				subscriber_request_id,
 				group_offset,
-			} => {
-				if group_offset != 0 {
-					self.write_fetch_error(&mut stream.writer, msg.request_id, 500, "not supported")
-						.await?;
-					return Ok(());
-				}
-				subscriber_request_id
-			}
-			FetchType::AbsoluteJoining { .. } => {
-				self.write_fetch_error(&mut stream.writer, msg.request_id, 500, "not supported")
-					.await?;
-				return Ok(());
-			}
+			} => (subscriber_request_id, Err(group_offset)),
+			FetchType::AbsoluteJoining {
+				subscriber_request_id,
+				group_id,
+			} => (subscriber_request_id, Ok(group_id)),
+		};
+
+		let Some((track, join)) = self.join_point(subscriber_request_id).await else {
+			self.write_fetch_error(
+				&mut stream.writer,
+				msg.request_id,
+				ietf::ERROR_NOT_FOUND,
+				"subscription not found",
+			)
+			.await?;
+			return Ok(());
 		};
 
+		// The fetch covers [start, join); the subscription delivers the rest.
+		let start = start.unwrap_or_else(|offset| join.saturating_sub(offset));
+		tracing::debug!(subscribe = %subscriber_request_id, start, join, "joining fetch");
+
 		// Send FetchOk/RequestOk
 		self.write_fetch_ok(&mut stream.writer, msg.request_id).await?;
 
-		// Create a uni stream with just a FetchHeader and FIN it
 		let uni = self.session.open_uni().await.map_err(Error::from_transport)?;
 		let mut writer = Writer::new(uni, self.version);
 		writer.encode(&FetchHeader::TYPE).await?;
@@ -409,12 +853,81 @@ impl<S: web_transport_trait::Session> Publisher<S> {
 				request_id: msg.request_id,
 			})
 			.await?;
+
+		for sequence in start..join {
+			// Only what's still cached; an evicted group can't be fetched.
+			let Some(Ok(Some(mut group))) = track.get_group(sequence).now_or_never() else {
+				continue;
+			};
+			let publisher_priority = priority::to_wire(group.priority.unwrap_or(track.priority));
+
+			let mut object_id = 0;
+			while let Some(mut frame) = group.next_frame().await? {
+				writer
+					.encode(&ietf::FetchObject {
+						group_id: sequence,
+						subgroup_id: 0,
+						object_id,
+						publisher_priority,
+						payload: frame.read_all().await?,
+					})
+					.await?;
+				object_id += 1;
+			}
+		}
+
 		writer.finish()?;
 		writer.closed().await?;
 
 		Ok(())
 	}
 
+	/// Resolve the live subscription `request_id` and the first group it serves.
+	///
+	/// Waits for that group while the subscription is forwarding. One that isn't forwarding
+	/// serves nothing, so it joins after the latest group instead of waiting.
+	/// Returns `None` if there's no such subscription, or it ends before resolving.
+	async fn join_point(&self, request_id: RequestId) -> Option<(TrackConsumer, u64)> {
+		let joinable = self.joins.lock().get(&request_id)?.clone();
+		let resolve = async move {
+			let Joinable { track, forward, first } = kio::wait(|waiter| {
+				joinable
+					.poll(waiter, |joinable| match &**joinable {
+						Some(joinable) => Poll::Ready(joinable.clone()),
+						None => Poll::Pending,
+					})
+					.map(|res| res.ok())
+			})
+			.await?;
+
+			loop {
+				if let Some(first) = *first.read() {
+					return Some((track, first));
+				}
+				if !*forward.read() {
+					let join = track.latest().map_or(0, |latest| latest + 1);
+					return Some((track, join));
+				}
+
+				tokio::select! {
+					res = kio::wait(|waiter| first.poll(waiter, |first| match first.is_some() {
+						true => Poll::Ready(()),
+						false => Poll::Pending,
+					}).map(|res| res.ok())) => res?,
+					res = kio::wait(|waiter| forward.poll(waiter, |state| match **state {
+						true => Poll::Pending,
+						false => Poll::Ready(()),
+					}).map(|res| res.ok())) => res?,
+				}
+			}
+		};
+
+		tokio::select! {
+			join = resolve => join,
+			_ = self.session.closed() => None,
+		}
+	}
+
 	async fn write_fetch_ok(
 		&self,
 		writer: &mut Writer<S::SendStream, Version>,
@@ -517,8 +1030,16 @@ impl<S: web_transport_trait::Session> Publisher<S> {
 				tracing::debug!(broadcast = %self.origin.absolute(&path), "announce");
 				let absolute = self.origin.absolute(&path).to_owned();
 
+				// A namespace too deep or too long for a control message would fail the encode
+				// below and tear down every announce on this session; reject just this one.
+				if let Err(err) = ietf::PublishNamespace::check(&suffix, self.version) {
+					tracing::warn!(broadcast = %absolute, %err, "namespace can't be announced, skipping");
+					continue;
+				}
+
 				let request_id = self.control.next_request_id().await?;
-				let mut stream = Stream::open(&self.session, self.version).await?;
+				let stream = Stream::open(&self.session, self.version).await?;
+				let mut stream = self.control.log_stream(stream);
 
 				let bs = self.stats.broadcast(&absolute);
 
@@ -719,3 +1240,274 @@ impl<S: web_transport_trait::Session> Publisher<S> {
 		}
 	}
 }
+
+/// Resolve once an object of `group` not yet written has been held longer than `timeout`.
+///
+/// DELIVERY_TIMEOUT counts from when each object is received, so this records when every
+/// object shows up in the group, even while the writer is still busy with an earlier one.
+async fn delivery_expired(group: GroupConsumer, written: &AtomicUsize, timeout: Duration) {
+	// When each object not yet written arrived, by index.
+	let mut arrivals: VecDeque<(usize, Instant)> = VecDeque::new();
+	let mut next = 0;
+	let mut ended = false;
+
+	loop {
+		let done = written.load(Ordering::Relaxed);
+		while arrivals.front().is_some_and(|(index, _)| *index < done) {
+			arrivals.pop_front();
+		}
+		let deadline = arrivals.front().map(|(_, arrived)| *arrived + timeout);
+
+		tokio::select! {
+			frame = group.get_frame(next), if !ended => match frame {
+				Ok(Some(_)) => {
+					arrivals.push_back((next, Instant::now()));
+					next += 1;
+				}
+				// No more objects; the ones already here can still time out.
+				Ok(None) | Err(_) => ended = true,
+			},
+			// The oldest object may have been written since; the loop checks again before expiring.
+			_ = web_async::time::sleep_until(deadline.unwrap_or_else(Instant::now)), if deadline.is_some() => {
+				let done = written.load(Ordering::Relaxed);
+				if arrivals.front().is_some_and(|(index, _)| *index >= done) {
+					return;
+				}
+			}
+			else => std::future::pending().await,
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::{Group, loopback};
+	use bytes::{Bytes, BytesMut};
+	use web_transport_trait::Session as _;
+
+	// A publisher on one end of a loopback pair, sent hand-written requests from the other.
+	struct Harness {
+		publisher: Publisher<loopback::Session>,
+		local: loopback::Session,
+		peer: loopback::Session,
+		_origin: crate::OriginProducer,
+		track: crate::TrackProducer,
+		_broadcast: crate::BroadcastProducer,
+	}
+
+	type Replies = (
+		Writer<loopback::SendStream, Version>,
+		Reader<loopback::RecvStream, Version>,
+	);
+
+	impl Harness {
+		// A "demo" broadcast whose "video" track already has groups 0 to 2.
+		fn new() -> Self {
+			let origin = Origin::random().produce();
+			let mut broadcast = origin.create_broadcast("demo").unwrap();
+			let mut track = broadcast.create_track(Track::new("video")).unwrap();
+			for payload in [&b"zero"[..], b"one", b"two"] {
+				track.write_frame(Bytes::from_static(payload)).unwrap();
+			}
+
+			let (local, peer) = loopback::pair(None);
+			let control = Control::new(None, false, None, Version::Draft14);
+			let publisher = Publisher::new(
+				local.clone(),
+				Some(origin.consume()),
+				control,
+				StatsHandle::default(),
+				RateLimit::default(),
+				Version::Draft14,
+			);
+
+			Self {
+				publisher,
+				local,
+				peer,
+				_origin: origin,
+				track,
+				_broadcast: broadcast,
+			}
+		}
+
+		// Send `msg` on its own bidi stream, returning the peer's end of it.
+		async fn request<M: Message>(&self, msg: &M) -> Replies {
+			let (send, recv) = self.peer.open_bi().await.unwrap();
+			let stream = Stream::accept(&self.local, Version::Draft14).await.unwrap();
+
+			let mut data = BytesMut::new();
+			msg.encode_msg(&mut data, Version::Draft14).unwrap();
+			self.publisher.handle_stream(M::ID, data.freeze(), stream).unwrap();
+
+			(Writer::new(send, Version::Draft14), Reader::new(recv, Version::Draft14))
+		}
+
+		async fn subscribe(&self, filter_type: FilterType, start_location: Option<Location>, forward: bool) -> Replies {
+			let mut replies = self
+				.request(&ietf::Subscribe {
+					request_id: RequestId(0),
+					track_namespace: "demo".into(),
+					track_name: "video".into(),
+					subscriber_priority: 128,
+					group_order: GroupOrder::Descending,
+					filter_type,
+					start_location,
+					end_group: None,
+					forward,
+					delivery_timeout: None,
+				})
+				.await;
+			assert_eq!(replies.1.decode::<u64>().await.unwrap(), ietf::SubscribeOk::ID);
+			replies.1.decode::<ietf::SubscribeOk>().await.unwrap();
+			replies
+		}
+
+		// Send an absolute joining fetch and return the objects on its stream.
+		async fn fetch(&self, group_id: u64) -> Vec<ietf::FetchObject> {
+			let mut replies = self
+				.request(&ietf::Fetch {
+					request_id: RequestId(2),
+					subscriber_priority: 128,
+					group_order: GroupOrder::Descending,
+					fetch_type: FetchType::AbsoluteJoining {
+						subscriber_request_id: RequestId(0),
+						group_id,
+					},
+				})
+				.await;
+			assert_eq!(replies.1.decode::<u64>().await.unwrap(), ietf::FetchOk::ID);
+			replies.1.decode::<ietf::FetchOk>().await.unwrap();
+
+			// Group streams from the subscription may arrive too; find the fetch stream.
+			loop {
+				let recv = self.peer.accept_uni().await.unwrap();
+				let mut reader = Reader::new(recv, Version::Draft14);
+				if reader.decode::<u64>().await.unwrap() != FetchHeader::TYPE {
+					continue;
+				}
+				assert_eq!(reader.decode::<FetchHeader>().await.unwrap().request_id, RequestId(2));
+
+				let mut objects = Vec::new();
+				while let Some(object) = reader.decode_maybe::<ietf::FetchObject>().await.unwrap() {
+					objects.push(object);
+				}
+				return objects;
+			}
+		}
+	}
+
+	#[tokio::test]
+	async fn joining_fetch_serves_groups_before_the_subscription() {
+		let harness = Harness::new();
+		let _subscribe = harness
+			.subscribe(FilterType::AbsoluteStart, Some(Location { group: 2, object: 0 }), true)
+			.await;
+
+		let objects = harness.fetch(0).await;
+		let groups: Vec<_> = objects.iter().map(|object| object.group_id).collect();
+		assert_eq!(groups, [0, 1]);
+		assert_eq!(objects[1].payload, Bytes::from_static(b"one"));
+		assert_eq!(objects[1].publisher_priority, priority::to_wire(harness.track.priority));
+	}
+
+	#[tokio::test(start_paused = true)]
+	async fn joining_fetch_without_forwarding_does_not_wait() {
+		let harness = Harness::new();
+		let _subscribe = harness.subscribe(FilterType::LargestObject, None, false).await;
+
+		// Nothing is forwarded, so the fetch catches up to the latest group rather than wait.
+		let objects = tokio::time::timeout(Duration::from_secs(1), harness.fetch(1))
+			.await
+			.expect("fetch waited for a group that is never forwarded");
+		let groups: Vec<_> = objects.iter().map(|object| object.group_id).collect();
+		assert_eq!(groups, [1, 2]);
+	}
+
+	// An AbsoluteRange SUBSCRIBE for groups `start` to `end` of the "video" track.
+	fn range(start: u64, end: u64) -> ietf::Subscribe<'static> {
+		ietf::Subscribe {
+			request_id: RequestId(0),
+			track_namespace: "demo".into(),
+			track_name: "video".into(),
+			subscriber_priority: 128,
+			group_order: GroupOrder::Descending,
+			filter_type: FilterType::AbsoluteRange,
+			start_location: Some(Location {
+				group: start,
+				object: 0,
+			}),
+			end_group: Some(end),
+			forward: true,
+			delivery_timeout: None,
+		}
+	}
+
+	#[tokio::test]
+	async fn absolute_range_ends_after_the_end_group() {
+		let mut harness = Harness::new();
+		let (_writer, mut reader) = harness.request(&range(3, 4)).await;
+		assert_eq!(reader.decode::<u64>().await.unwrap(), ietf::SubscribeOk::ID);
+		reader.decode::<ietf::SubscribeOk>().await.unwrap();
+
+		for payload in [&b"three"[..], b"four", b"five"] {
+			harness.track.write_frame(Bytes::from_static(payload)).unwrap();
+		}
+
+		// Only the groups in range are served, then the subscription is done.
+		let mut groups = Vec::new();
+		for _ in 0..2 {
+			let recv = harness.peer.accept_uni().await.unwrap();
+			let mut stream = Reader::new(recv, Version::Draft14);
+			groups.push(stream.decode::<ietf::GroupHeader>().await.unwrap().group_id);
+		}
+		groups.sort();
+		assert_eq!(groups, [3, 4]);
+
+		assert_eq!(reader.decode::<u64>().await.unwrap(), ietf::PublishDone::ID);
+		let done = reader.decode::<ietf::PublishDone>().await.unwrap();
+		assert_eq!(done.status_code, 200);
+
+		// Group 5 was written before the end, but never gets a stream.
+		let extra = tokio::time::timeout(Duration::from_millis(100), harness.peer.accept_uni()).await;
+		assert!(extra.is_err(), "a group past the end was served");
+	}
+
+	#[tokio::test]
+	async fn absolute_range_rejects_end_before_start() {
+		let harness = Harness::new();
+		let (_writer, mut reader) = harness.request(&range(4, 3)).await;
+		assert_eq!(reader.decode::<u64>().await.unwrap(), ietf::SubscribeError::ID);
+		let err = reader.decode::<ietf::SubscribeError>().await.unwrap();
+		assert_eq!(err.error_code, ietf::ERROR_BAD_REQUEST);
+	}
+
+	#[tokio::test(start_paused = true)]
+	async fn delivery_timeout_counts_per_object() {
+		let mut group = Group::new(0).produce();
+		let written = AtomicUsize::new(0);
+		let timeout = Duration::from_millis(100);
+
+		let start = Instant::now();
+		let expired = delivery_expired(group.consume(), &written, timeout);
+		tokio::pin!(expired);
+
+		// A long group whose objects are written as they arrive never times out.
+		for _ in 0..10 {
+			group.write_frame(bytes::Bytes::from_static(b"frame")).unwrap();
+			tokio::select! {
+				_ = &mut expired => panic!("expired while keeping up"),
+				_ = tokio::time::sleep(Duration::from_millis(50)) => {}
+			}
+			written.fetch_add(1, Ordering::Relaxed);
+		}
+
+		// An object left unwritten expires a timeout after it arrived, not after the group began.
+		let arrived = Instant::now();
+		group.write_frame(bytes::Bytes::from_static(b"stuck")).unwrap();
+		expired.await;
+		assert_eq!(arrived.duration_since(start), Duration::from_millis(500));
+		assert_eq!(arrived.elapsed(), timeout);
+	}
+}
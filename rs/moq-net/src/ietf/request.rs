@@ -113,6 +113,13 @@ impl Message for RequestOk {
 	}
 }
 
+/// The HTTP-style error codes we send in REQUEST_ERROR and the draft-14 per-request errors.
+pub const ERROR_BAD_REQUEST: u64 = 400;
+pub const ERROR_UNAUTHORIZED: u64 = 401;
+pub const ERROR_FORBIDDEN: u64 = 403;
+pub const ERROR_NOT_FOUND: u64 = 404;
+pub const ERROR_INTERNAL: u64 = 500;
+
 /// REQUEST_ERROR (0x05 in v15) - Generic error response for any request.
 /// Replaces SubscribeError, PublishError, PublishNamespaceError,
 /// SubscribeNamespaceError, FetchError in v15.
@@ -1,47 +1,116 @@
+use std::sync::Arc;
+
 use crate::{
-	Error, OriginConsumer, OriginProducer, StatsHandle,
+	Error, OriginConsumer, OriginProducer, Query, RateLimit, StatsHandle,
 	coding::{Encode, Reader, Stream, Writer},
 	ietf::{self, FetchHeader, RequestId},
+	limit::StreamLimit,
 	setup,
 };
 
-use super::{Control, Message, Publisher, Subscriber, Version, adapter::ControlStreamAdapter};
+use super::{
+	Control, Message, Publisher, Subscriber, SubscriberConfig, Version, WireLog, adapter::ControlStreamAdapter,
+};
+
+/// Options for [`start`], everything besides the transport and the SETUP stream.
+pub struct StartConfig {
+	pub version: Version,
+	/// Whether we initiated the session.
+	pub client: bool,
+	/// The initial request ID limit from the peer's SETUP (draft-14 to 16).
+	pub request_id_max: Option<RequestId>,
+	/// Extra SETUP parameters, sent by draft-17+ where SETUP is exchanged in the background.
+	pub parameters: ietf::Parameters,
+	pub publish: Option<OriginConsumer>,
+	pub subscribe: Option<OriginProducer>,
+	/// Tier-scoped stats handle. Use [`StatsHandle::default`] to opt out.
+	pub stats: StatsHandle,
+	/// Egress caps for each subscribed track, picked by the track's priority.
+	pub rate_limit: RateLimit,
+	/// The maximum number of incoming group streams processed concurrently, or zero for no limit.
+	pub max_streams: usize,
+	/// Drop incoming groups this far behind the newest one received, per track.
+	pub group_window: Option<u64>,
+	/// Log control messages on the wire as hex, see [Control::with_wire_log].
+	pub wire_log: bool,
+}
+
+/// A running IETF session, as held by [`crate::Session`].
+pub struct Started {
+	/// Closed by [`crate::Session::finish`], or dropped with the Session, to half-close the
+	/// draft-14 to 16 control stream.
+	pub finish: kio::Producer<()>,
+	/// Requests made outside of any subscription, such as TRACK_STATUS.
+	pub query: Arc<dyn Query>,
+}
 
-// Handshake dispatcher: each argument is an independent session parameter, so
-// bundling them into a config struct would just add indirection.
-#[allow(clippy::too_many_arguments)]
 pub fn start<S: web_transport_trait::Session>(
 	session: S,
 	setup: Option<Stream<S, Version>>,
-	request_id_max: Option<RequestId>,
-	client: bool,
-	publish: Option<OriginConsumer>,
-	subscribe: Option<OriginProducer>,
-	// Tier-scoped stats handle. Pass [`StatsHandle::default`] to opt out.
-	stats: StatsHandle,
-	version: Version,
-) -> Result<(), Error> {
-	web_async::spawn(async move {
-		let res = match version {
-			Version::Draft14 | Version::Draft15 | Version::Draft16 => {
-				let Some(setup) = setup else {
-					return session.close(Error::ProtocolViolation.to_code(), "setup stream required");
-				};
-				let (tx, rx) = tokio::sync::mpsc::unbounded_channel();
-				let control = Control::new(request_id_max, client);
-				let adapter = ControlStreamAdapter::new(session.clone(), tx, control.clone(), version);
-
-				let publisher = Publisher::new(adapter.clone(), publish, control.clone(), stats.clone(), version);
-				let subscriber = Subscriber::new(adapter.clone(), subscribe, control, stats, version);
-
-				let dispatch_session = adapter.clone();
-				let mut sub_ns = subscriber.clone();
-				let sub_ns_adapter = adapter.clone();
+	config: StartConfig,
+) -> Result<Started, Error> {
+	let StartConfig {
+		version,
+		client,
+		request_id_max,
+		parameters,
+		publish,
+		subscribe,
+		stats,
+		rate_limit,
+		max_streams,
+		group_window,
+		wire_log,
+	} = config;
+
+	let max_streams = StreamLimit::new(max_streams);
 
+	let finish = kio::Producer::default();
+	let finished = finish.consume();
+
+	let query: Arc<dyn Query> = match version {
+		Version::Draft14 | Version::Draft15 | Version::Draft16 => {
+			let Some(setup) = setup else {
+				session.close(Error::ProtocolViolation.to_code(), "setup stream required");
+				return Err(Error::ProtocolViolation);
+			};
+			// Every request is multiplexed over the control stream, so logging it covers them all.
+			let setup = match wire_log {
+				true => WireLog::attach(setup, version),
+				false => setup,
+			};
+			let (tx, rx) = tokio::sync::mpsc::unbounded_channel();
+			let control = Control::new(request_id_max, client, Some(tx.clone()), version).with_wire_log(wire_log);
+			let adapter = ControlStreamAdapter::new(session.clone(), tx, control.clone(), version);
+
+			let publisher = Publisher::new(
+				adapter.clone(),
+				publish,
+				control.clone(),
+				stats.clone(),
+				rate_limit,
+				version,
+			);
+			let subscriber = Subscriber::new(SubscriberConfig {
+				session: adapter.clone(),
+				origin: subscribe,
+				control: control.clone(),
+				stats,
+				max_streams,
+				group_window,
+				version,
+			});
+
+			let dispatch_session = adapter.clone();
+			let mut sub_ns = subscriber.clone();
+			let sub_ns_adapter = adapter.clone();
+			let run_subscriber = subscriber.clone();
+
+			let run = async move {
 				tokio::select! {
-					Err(err) = adapter.run(setup.reader, setup.writer, rx) => Err::<(), Error>(err),
-					Err(err) = run_unis(adapter.clone(), subscriber.clone(), version) => Err(err),
-					Err(err) = run_dispatch(dispatch_session, publisher.clone(), subscriber.clone(), version) => Err(err),
+					Err(err) = adapter.run(setup.reader, setup.writer, rx, finished) => Err::<(), Error>(err),
+					Err(err) = run_unis(adapter.clone(), run_subscriber.clone(), version) => Err(err),
+					Err(err) = run_dispatch(dispatch_session, publisher.clone(), run_subscriber, control.clone()) => Err(err),
 					Err(err) = publisher.run() => Err(err),
 					Err(err) = async {
 						if !sub_ns.has_origin() {
@@ -63,46 +132,82 @@ pub fn start<S: web_transport_trait::Session>(
 					Ok(())
 					} => Err(err),
 				}
-			}
-			_ => {
-				// Spawn SETUP sender (keeps stream alive for GOAWAY).
-				web_async::spawn({
-					let session = session.clone();
-					async move {
-						if let Err(err) = run_setup(session, version).await {
-							tracing::warn!(%err, "setup send error");
-						}
+			};
+
+			spawn_run(session, run);
+			Arc::new(subscriber)
+		}
+		_ => {
+			// Spawn SETUP sender (keeps stream alive for GOAWAY).
+			web_async::spawn({
+				let session = session.clone();
+				async move {
+					if let Err(err) = run_setup(session, version, parameters).await {
+						tracing::warn!(%err, "setup send error");
 					}
-				});
+				}
+			});
 
-				let control = Control::new(None, client);
-				let publisher = Publisher::new(session.clone(), publish, control.clone(), stats.clone(), version);
-				let subscriber = Subscriber::new(session.clone(), subscribe, control, stats, version);
+			let control = Control::new(None, client, None, version).with_wire_log(wire_log);
+			let publisher = Publisher::new(
+				session.clone(),
+				publish,
+				control.clone(),
+				stats.clone(),
+				rate_limit,
+				version,
+			);
+			let subscriber = Subscriber::new(SubscriberConfig {
+				session: session.clone(),
+				origin: subscribe,
+				control: control.clone(),
+				stats,
+				max_streams,
+				group_window,
+				version,
+			});
 
-				let sub_ns_session = session.clone();
-				let mut sub_ns = subscriber.clone();
+			let sub_ns_session = session.clone();
+			let mut sub_ns = subscriber.clone();
+			let run_subscriber = subscriber.clone();
+			let run_session = session.clone();
 
+			let run = async move {
 				tokio::select! {
-					Err(err) = run_unis(session.clone(), subscriber.clone(), version) => Err(err),
-					Err(err) = run_dispatch(session.clone(), publisher.clone(), subscriber.clone(), version) => Err(err),
+					Err(err) = run_unis(run_session.clone(), run_subscriber.clone(), version) => Err(err),
+					Err(err) = run_dispatch(run_session, publisher.clone(), run_subscriber, control.clone()) => Err(err),
 					Err(err) = publisher.run() => Err(err),
 					Err(err) = async {
 						if !sub_ns.has_origin() {
 							return Ok(());
 						}
 						let stream = Stream::open(&sub_ns_session, version).await?;
+						let stream = control.log_stream(stream);
 						if let Err(err) = sub_ns.run_subscribe_namespace(stream).await {
 							tracing::warn!(%err, "subscribe_namespace failed, continuing without");
 						}
 						Ok(())
 					} => Err(err),
 				}
-			}
-		};
+			};
+
+			spawn_run(session, run);
+			Arc::new(subscriber)
+		}
+	};
+
+	Ok(Started { finish, query })
+}
 
-		match res {
-			Err(Error::Transport(_)) => {
-				tracing::info!("session terminated");
+/// Drive the session in the background, closing the transport with the outcome.
+fn spawn_run<S: web_transport_trait::Session>(
+	session: S,
+	run: impl std::future::Future<Output = Result<(), Error>> + web_async::MaybeSend + 'static,
+) {
+	web_async::spawn(async move {
+		match run.await {
+			Err(Error::Transport(err)) => {
+				tracing::info!(%err, "session terminated");
 				session.close(1, "");
 			}
 			Err(err) => {
@@ -115,19 +220,22 @@ pub fn start<S: web_transport_trait::Session>(
 			}
 		}
 	});
-
-	Ok(())
 }
 
 /// Send our SETUP on a uni stream and keep it alive for potential GOAWAY.
-async fn run_setup<S: web_transport_trait::Session>(session: S, version: Version) -> Result<(), Error> {
+async fn run_setup<S: web_transport_trait::Session>(
+	session: S,
+	version: Version,
+	extra: ietf::Parameters,
+) -> Result<(), Error> {
 	let outer_version = crate::Version::Ietf(version);
 
 	let send = session.open_uni().await.map_err(Error::from_transport)?;
 	let mut writer: Writer<S::SendStream, crate::Version> = Writer::new(send, outer_version);
 
 	let mut parameters = ietf::Parameters::default();
-	parameters.set_bytes(ietf::ParameterBytes::Implementation, b"moq-lite-rs".to_vec());
+	parameters.set_implementation("moq-lite-rs");
+	parameters.extend(&extra);
 	let parameters = parameters.encode_bytes(version)?;
 
 	writer.encode(&setup::Setup { parameters }).await?;
@@ -151,6 +259,8 @@ async fn run_unis<S: web_transport_trait::Session>(
 	let outer_version = crate::Version::Ietf(version);
 
 	loop {
+		// Wait for a free slot before accepting, so QUIC flow control pushes back on the peer.
+		let permit = subscriber.acquire_stream().await;
 		let recv = session.accept_uni().await.map_err(Error::from_transport)?;
 		let mut reader: Reader<S::RecvStream, crate::Version> = Reader::new(recv, outer_version);
 		let kind: u64 = reader.decode_peek().await?;
@@ -159,6 +269,8 @@ async fn run_unis<S: web_transport_trait::Session>(
 		// We accept it in the background without blocking, since there are no
 		// extensions that require waiting on the SETUP before proceeding.
 		if kind == setup::SETUP_V17 {
+			// Not a group, so it doesn't count against the limit.
+			drop(permit);
 			web_async::spawn(async move {
 				// Decode and discard the unified SETUP message.
 				if let Err(err) = reader.decode::<setup::Setup>().await {
@@ -183,6 +295,7 @@ async fn run_unis<S: web_transport_trait::Session>(
 				tracing::debug!(%err, "uni stream error");
 				reader.abort(&err);
 			}
+			drop(permit);
 		});
 	}
 }
@@ -213,10 +326,11 @@ async fn run_dispatch<S: web_transport_trait::Session>(
 	session: S,
 	publisher: Publisher<S>,
 	mut subscriber: Subscriber<S>,
-	version: Version,
+	control: Control,
 ) -> Result<(), Error> {
 	loop {
-		let mut stream = Stream::accept(&session, version).await?;
+		let stream = Stream::accept(&session, control.version()).await?;
+		let mut stream = control.log_stream(stream);
 
 		let id: u64 = stream.reader.decode().await?;
 		let size: u16 = stream.reader.decode().await?;
@@ -38,6 +38,86 @@ impl Decode<Version> for FilterType {
 	}
 }
 
+/// A SUBSCRIBE filter: the filter type followed by the start location for the absolute filters,
+/// and the end group for AbsoluteRange.
+#[derive(Default, Clone, Debug, PartialEq, Eq)]
+struct SubscriptionFilter {
+	filter_type: FilterType,
+	start_location: Option<Location>,
+	end_group: Option<u64>,
+}
+
+impl Encode<Version> for SubscriptionFilter {
+	fn encode<W: bytes::BufMut>(&self, w: &mut W, version: Version) -> Result<(), EncodeError> {
+		match (self.filter_type, &self.start_location, self.end_group) {
+			(FilterType::AbsoluteRange, Some(start), Some(end)) => {
+				self.filter_type.encode(w, version)?;
+				start.encode(w, version)?;
+				end.encode(w, version)?;
+			}
+			// Without an end group, a range is open-ended.
+			(FilterType::AbsoluteStart | FilterType::AbsoluteRange, Some(start), _) => {
+				FilterType::AbsoluteStart.encode(w, version)?;
+				start.encode(w, version)?;
+			}
+			// Without a start location, fall back to the latest object.
+			(FilterType::AbsoluteStart | FilterType::AbsoluteRange, None, _) => {
+				FilterType::LargestObject.encode(w, version)?
+			}
+			_ => self.filter_type.encode(w, version)?,
+		}
+		Ok(())
+	}
+}
+
+impl Decode<Version> for SubscriptionFilter {
+	fn decode<R: bytes::Buf>(r: &mut R, version: Version) -> Result<Self, DecodeError> {
+		let filter_type = FilterType::decode(r, version)?;
+		let (start_location, end_group) = match filter_type {
+			FilterType::AbsoluteStart => (Some(Location::decode(r, version)?), None),
+			FilterType::AbsoluteRange => {
+				let start = Location::decode(r, version)?;
+				let end = u64::decode(r, version)?;
+				(Some(start), Some(end))
+			}
+			FilterType::NextGroup | FilterType::LargestObject => (None, None),
+		};
+
+		Ok(Self {
+			filter_type,
+			start_location,
+			end_group,
+		})
+	}
+}
+
+impl super::Param for SubscriptionFilter {
+	fn param_encode<W: bytes::BufMut>(&self, w: &mut W, version: Version) -> Result<(), EncodeError> {
+		// Same length-prefixed layout as the bare FilterType parameter.
+		let sv = match version {
+			Version::Draft14 | Version::Draft15 | Version::Draft16 => Version::Draft15,
+			_ => version,
+		};
+		let mut buf = Vec::new();
+		self.encode(&mut buf, sv)?;
+		buf.encode(w, version)?;
+		Ok(())
+	}
+
+	fn param_decode<R: bytes::Buf>(r: &mut R, version: Version) -> Result<Self, DecodeError> {
+		let sv = match version {
+			Version::Draft14 | Version::Draft15 | Version::Draft16 => Version::Draft15,
+			_ => version,
+		};
+		let mut buf = bytes::Bytes::from(Vec::<u8>::decode(r, version)?);
+		let filter = Self::decode(&mut buf, sv)?;
+		if bytes::Buf::has_remaining(&buf) {
+			return Err(DecodeError::TrailingBytes);
+		}
+		Ok(filter)
+	}
+}
+
 /// Subscribe message (0x03)
 /// Sent by the subscriber to request all future objects for the given track.
 #[derive(Clone, Debug)]
@@ -48,6 +128,14 @@ pub struct Subscribe<'a> {
 	pub subscriber_priority: u8,
 	pub group_order: GroupOrder,
 	pub filter_type: FilterType,
+	/// The first object to deliver for an AbsoluteStart or AbsoluteRange filter, possibly mid-group.
+	pub start_location: Option<Location>,
+	/// The last group to deliver for an AbsoluteRange filter, inclusive.
+	pub end_group: Option<u64>,
+	/// When false, the publisher holds off sending groups until a SubscribeUpdate sets it.
+	pub forward: bool,
+	/// DELIVERY_TIMEOUT: how long an object stays useful to the subscriber, in milliseconds on the wire.
+	pub delivery_timeout: Option<std::time::Duration>,
 }
 
 impl Message for Subscribe<'_> {
@@ -67,23 +155,9 @@ impl Message for Subscribe<'_> {
 				let group_order = GroupOrder::decode(r, version)?;
 
 				let forward = bool::decode(r, version)?;
-				if !forward {
-					return Err(DecodeError::Unsupported);
-				}
+				let filter = SubscriptionFilter::decode(r, version)?;
 
-				let filter_type = FilterType::decode(r, version)?;
-				match filter_type {
-					FilterType::AbsoluteStart => {
-						let _start = Location::decode(r, version)?;
-					}
-					FilterType::AbsoluteRange => {
-						let _start = Location::decode(r, version)?;
-						let _end_group = u64::decode(r, version)?;
-					}
-					FilterType::NextGroup | FilterType::LargestObject => {}
-				};
-
-				let _params = Parameters::decode(r, version)?;
+				let params = Parameters::decode(r, version)?;
 
 				Ok(Self {
 					request_id,
@@ -91,24 +165,27 @@ impl Message for Subscribe<'_> {
 					track_name,
 					subscriber_priority,
 					group_order,
-					filter_type,
+					filter_type: filter.filter_type,
+					start_location: filter.start_location,
+					end_group: filter.end_group,
+					forward,
+					delivery_timeout: params.delivery_timeout(),
 				})
 			}
 			_ => {
 				decode_params!(r, version,
+					0x02 => delivery_timeout: Option<u64>,
 					0x10 => forward: Option<bool>,
 					0x20 => subscriber_priority: Option<u8>,
-					0x21 => filter_type: Option<FilterType>,
+					0x21 => filter: Option<SubscriptionFilter>,
 					0x22 => group_order: Option<GroupOrder>,
 				);
 
-				if forward == Some(false) {
-					return Err(DecodeError::Unsupported);
-				}
-
+				let delivery_timeout = delivery_timeout.map(std::time::Duration::from_millis);
+				let forward = forward.unwrap_or(true);
 				let subscriber_priority = subscriber_priority.unwrap_or(128);
 				let group_order = group_order.unwrap_or(GroupOrder::Descending);
-				let filter_type = filter_type.unwrap_or(FilterType::LargestObject);
+				let filter = filter.unwrap_or_default();
 
 				Ok(Self {
 					request_id,
@@ -116,7 +193,11 @@ impl Message for Subscribe<'_> {
 					track_name,
 					subscriber_priority,
 					group_order,
-					filter_type,
+					filter_type: filter.filter_type,
+					start_location: filter.start_location,
+					end_group: filter.end_group,
+					forward,
+					delivery_timeout,
 				})
 			}
 		}
@@ -134,21 +215,22 @@ impl Message for Subscribe<'_> {
 			Version::Draft14 => {
 				self.subscriber_priority.encode(w, version)?;
 				self.group_order.encode(w, version)?;
-				true.encode(w, version)?; // forward
-
-				debug_assert!(
-					!matches!(self.filter_type, FilterType::AbsoluteStart | FilterType::AbsoluteRange),
-					"Absolute subscribe not supported"
-				);
+				self.forward.encode(w, version)?;
+				self.filter().encode(w, version)?;
 
-				self.filter_type.encode(w, version)?;
-				0u8.encode(w, version)?; // no parameters
+				let mut params = Parameters::default();
+				if let Some(timeout) = self.delivery_timeout {
+					params.set_delivery_timeout(timeout);
+				}
+				params.encode(w, version)?;
 			}
 			_ => {
+				let delivery_timeout = self.delivery_timeout.map(|timeout| timeout.as_millis() as u64);
 				encode_params!(w, version,
-					0x10 => true,
+					0x02 => delivery_timeout,
+					0x10 => self.forward,
 					0x20 => self.subscriber_priority,
-					0x21 => self.filter_type,
+					0x21 => self.filter(),
 					0x22 => self.group_order,
 				);
 			}
@@ -158,11 +240,25 @@ impl Message for Subscribe<'_> {
 	}
 }
 
+impl Subscribe<'_> {
+	fn filter(&self) -> SubscriptionFilter {
+		SubscriptionFilter {
+			filter_type: self.filter_type,
+			start_location: self.start_location.clone(),
+			end_group: self.end_group,
+		}
+	}
+}
+
 /// SubscribeOk message (0x04)
 #[derive(Clone, Debug)]
 pub struct SubscribeOk {
 	pub request_id: Option<RequestId>,
 	pub track_alias: u64,
+	/// The order the publisher will deliver groups in. `Any` is read as `Descending`.
+	///
+	/// Only our publisher acts on it; the subscriber accepts groups in any order.
+	pub group_order: GroupOrder,
 }
 
 impl Message for SubscribeOk {
@@ -181,13 +277,13 @@ impl Message for SubscribeOk {
 		match version {
 			Version::Draft14 => {
 				0u64.encode(w, version)?; // expires = 0
-				GroupOrder::Descending.encode(w, version)?;
+				self.group_order.encode(w, version)?;
 				false.encode(w, version)?; // no content
 				0u8.encode(w, version)?; // no parameters
 			}
 			_ => {
 				encode_params!(w, version,
-					0x22 => GroupOrder::Descending,
+					0x22 => self.group_order,
 				);
 			}
 		}
@@ -203,14 +299,15 @@ impl Message for SubscribeOk {
 		};
 		let track_alias = u64::decode(r, version)?;
 
-		match version {
+		let group_order = match version {
 			Version::Draft14 => {
 				let expires = u64::decode(r, version)?;
 				if expires != 0 {
 					return Err(DecodeError::Unsupported);
 				}
 
-				let _group_order = u8::decode(r, version)?;
+				// Lenient like the parameter form: an invalid value falls back to descending.
+				let group_order = GroupOrder::try_from(u8::decode(r, version)?).unwrap_or(GroupOrder::Descending);
 
 				if bool::decode(r, version)? {
 					let _group = u64::decode(r, version)?;
@@ -218,18 +315,21 @@ impl Message for SubscribeOk {
 				}
 
 				let _params = Parameters::decode(r, version)?;
+				group_order
 			}
 			_ => {
 				decode_params!(r, version,
-					0x22 => _group_order: Option<GroupOrder>,
+					0x22 => group_order: Option<GroupOrder>,
 				);
 				super::properties::skip(r, version)?;
+				group_order.unwrap_or(GroupOrder::Descending)
 			}
-		}
+		};
 
 		Ok(Self {
 			request_id,
 			track_alias,
+			group_order: group_order.any_to_descending(),
 		})
 	}
 }
@@ -438,6 +538,10 @@ mod tests {
 			subscriber_priority: 128,
 			group_order: GroupOrder::Descending,
 			filter_type: FilterType::LargestObject,
+			start_location: None,
+			end_group: None,
+			forward: true,
+			delivery_timeout: None,
 		};
 
 		let encoded = encode_message(&msg, Version::Draft14);
@@ -458,6 +562,10 @@ mod tests {
 			subscriber_priority: 128,
 			group_order: GroupOrder::Descending,
 			filter_type: FilterType::LargestObject,
+			start_location: None,
+			end_group: None,
+			forward: true,
+			delivery_timeout: None,
 		};
 
 		let encoded = encode_message(&msg, Version::Draft15);
@@ -478,6 +586,10 @@ mod tests {
 			subscriber_priority: 255,
 			group_order: GroupOrder::Descending,
 			filter_type: FilterType::LargestObject,
+			start_location: None,
+			end_group: None,
+			forward: true,
+			delivery_timeout: None,
 		};
 
 		let encoded = encode_message(&msg, Version::Draft14);
@@ -491,12 +603,29 @@ mod tests {
 		let msg = SubscribeOk {
 			request_id: Some(RequestId(42)),
 			track_alias: 42,
+			group_order: GroupOrder::Descending,
 		};
 
 		let encoded = encode_message(&msg, Version::Draft14);
 		let decoded: SubscribeOk = decode_message(&encoded, Version::Draft14).unwrap();
 
 		assert_eq!(decoded.request_id, Some(RequestId(42)));
+		assert_eq!(decoded.group_order, GroupOrder::Descending);
+	}
+
+	#[test]
+	fn test_subscribe_ok_group_order() {
+		for version in [Version::Draft14, Version::Draft16, Version::Draft17] {
+			let msg = SubscribeOk {
+				request_id: (version != Version::Draft17).then_some(RequestId(2)),
+				track_alias: 2,
+				group_order: GroupOrder::Ascending,
+			};
+
+			let encoded = encode_message(&msg, version);
+			let decoded: SubscribeOk = decode_message(&encoded, version).unwrap();
+			assert_eq!(decoded.group_order, GroupOrder::Ascending, "{version:?}");
+		}
 	}
 
 	#[test]
@@ -504,6 +633,7 @@ mod tests {
 		let msg = SubscribeOk {
 			request_id: Some(RequestId(42)),
 			track_alias: 42,
+			group_order: GroupOrder::Descending,
 		};
 
 		let encoded = encode_message(&msg, Version::Draft15);
@@ -626,6 +756,10 @@ mod tests {
 			subscriber_priority: 128,
 			group_order: GroupOrder::Descending,
 			filter_type: FilterType::LargestObject,
+			start_location: None,
+			end_group: None,
+			forward: true,
+			delivery_timeout: None,
 		};
 
 		let encoded = encode_message(&msg, Version::Draft17);
@@ -642,6 +776,7 @@ mod tests {
 		let msg = SubscribeOk {
 			request_id: None,
 			track_alias: 42,
+			group_order: GroupOrder::Descending,
 		};
 
 		let encoded = encode_message(&msg, Version::Draft17);
@@ -680,6 +815,10 @@ mod tests {
 			subscriber_priority: 128,
 			group_order: GroupOrder::Descending,
 			filter_type: FilterType::LargestObject,
+			start_location: None,
+			end_group: None,
+			forward: true,
+			delivery_timeout: None,
 		};
 
 		let encoded = encode_message(&msg, Version::Draft18);
@@ -696,6 +835,7 @@ mod tests {
 		let msg = SubscribeOk {
 			request_id: None,
 			track_alias: 42,
+			group_order: GroupOrder::Descending,
 		};
 
 		let encoded = encode_message(&msg, Version::Draft18);
@@ -746,4 +886,101 @@ mod tests {
 		let v18 = encode_message(&v18_msg, Version::Draft18);
 		assert_eq!(v17.len(), v18.len() + 1);
 	}
+
+	#[test]
+	fn test_subscribe_forward_false() {
+		for version in [Version::Draft14, Version::Draft15, Version::Draft17] {
+			let msg = Subscribe {
+				request_id: RequestId(1),
+				track_namespace: Path::new("test"),
+				track_name: "video".into(),
+				subscriber_priority: 128,
+				group_order: GroupOrder::Descending,
+				filter_type: FilterType::LargestObject,
+				start_location: None,
+				end_group: None,
+				forward: false,
+				delivery_timeout: None,
+			};
+
+			let encoded = encode_message(&msg, version);
+			let decoded: Subscribe = decode_message(&encoded, version).unwrap();
+			assert!(!decoded.forward);
+		}
+	}
+	#[test]
+	fn test_subscribe_absolute_start() {
+		for version in [Version::Draft14, Version::Draft15, Version::Draft16, Version::Draft17] {
+			let msg = Subscribe {
+				request_id: RequestId(1),
+				track_namespace: Path::new("test"),
+				track_name: "video".into(),
+				subscriber_priority: 128,
+				group_order: GroupOrder::Descending,
+				filter_type: FilterType::AbsoluteStart,
+				start_location: Some(Location { group: 5, object: 3 }),
+				end_group: None,
+				forward: true,
+				delivery_timeout: None,
+			};
+
+			let encoded = encode_message(&msg, version);
+			let decoded: Subscribe = decode_message(&encoded, version).unwrap();
+			assert_eq!(decoded.filter_type, FilterType::AbsoluteStart);
+			assert_eq!(decoded.start_location, Some(Location { group: 5, object: 3 }));
+		}
+	}
+
+	#[test]
+	fn test_subscribe_delivery_timeout() {
+		for version in [Version::Draft14, Version::Draft15, Version::Draft16, Version::Draft17] {
+			let msg = Subscribe {
+				request_id: RequestId(1),
+				track_namespace: Path::new("test"),
+				track_name: "video".into(),
+				subscriber_priority: 128,
+				group_order: GroupOrder::Descending,
+				filter_type: FilterType::LargestObject,
+				start_location: None,
+				end_group: None,
+				forward: true,
+				delivery_timeout: Some(std::time::Duration::from_millis(1500)),
+			};
+
+			let encoded = encode_message(&msg, version);
+			let decoded: Subscribe = decode_message(&encoded, version).unwrap();
+			assert_eq!(decoded.delivery_timeout, msg.delivery_timeout);
+			assert!(decoded.forward);
+		}
+	}
+
+	#[test]
+	fn test_subscribe_absolute_range() {
+		for version in [Version::Draft14, Version::Draft15, Version::Draft16, Version::Draft17] {
+			let msg = Subscribe {
+				request_id: RequestId(1),
+				track_namespace: Path::new("test"),
+				track_name: "video".into(),
+				subscriber_priority: 128,
+				group_order: GroupOrder::Descending,
+				filter_type: FilterType::AbsoluteRange,
+				start_location: Some(Location { group: 5, object: 0 }),
+				end_group: Some(9),
+				forward: true,
+				delivery_timeout: None,
+			};
+
+			let encoded = encode_message(&msg, version);
+			let decoded: Subscribe = decode_message(&encoded, version).unwrap();
+			assert_eq!(decoded.filter_type, FilterType::AbsoluteRange);
+			assert_eq!(decoded.start_location, Some(Location { group: 5, object: 0 }));
+			assert_eq!(decoded.end_group, Some(9));
+
+			// A range without an end is sent as an open-ended AbsoluteStart.
+			let msg = Subscribe { end_group: None, ..msg };
+			let decoded: Subscribe = decode_message(&encode_message(&msg, version), version).unwrap();
+			assert_eq!(decoded.filter_type, FilterType::AbsoluteStart);
+			assert_eq!(decoded.end_group, None);
+		}
+	}
 }
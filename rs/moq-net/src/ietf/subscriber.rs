@@ -7,18 +7,27 @@ use std::{
 
 use crate::{
 	Broadcast, BroadcastDynamic, Error, Frame, FrameProducer, Group, GroupProducer, MAX_FRAME_SIZE, OriginProducer,
-	Path, PathOwned, StatsHandle, SubscriberStats, SubscriberTrack, Track, TrackProducer,
+	Path, PathOwned, Query, StatsHandle, SubscriberStats, SubscriberTrack, Track, TrackProducer, TrackStatus,
 	coding::{Reader, Stream},
 	ietf::{self, Control, FilterType, GroupOrder, RequestId},
+	limit::StreamLimit,
 	model::BroadcastProducer,
+	window::GroupWindow,
 };
 
-use super::{Message, Version};
+use super::{Message, Version, priority};
 
+use tracing::Instrument;
 use web_async::Lock;
 
 const TRACK_ALIAS_TIMEOUT: Duration = Duration::from_secs(1);
 
+/// The most objects a single object ID delta may skip, each becoming an empty gap frame.
+const MAX_OBJECT_GAP: u64 = 1024;
+
+/// The session termination code for a track alias that's already routing another track.
+const DUPLICATE_TRACK_ALIAS: u32 = 0x5;
+
 type TrackAliases = kio::Producer<HashMap<u64, RequestId>>;
 
 fn insert_track_alias(aliases: &TrackAliases, alias: u64, request_id: RequestId) -> Result<(), Error> {
@@ -63,6 +72,10 @@ struct TrackState {
 	/// Subscriber-side track stats; counters bump as frames/bytes/groups arrive.
 	/// Dropping on subscription end records `subscriptions_closed`.
 	stats: Arc<SubscriberTrack>,
+	/// Span keyed by request id and track name; group and frame handling run inside it.
+	span: tracing::Span,
+	/// Drops groups that fall behind the newest one, if a group window is configured.
+	window: Option<GroupWindow>,
 }
 
 struct BroadcastState {
@@ -76,6 +89,23 @@ struct BroadcastState {
 	_stats: SubscriberStats,
 }
 
+pub(super) struct SubscriberConfig<S: web_transport_trait::Session> {
+	pub session: S,
+	/// The origin into which remote broadcasts are inserted.
+	pub origin: Option<OriginProducer>,
+	pub control: Control,
+	/// Stats aggregator for this session's ingress. Use [`StatsHandle::default`]
+	/// to opt out.
+	pub stats: StatsHandle,
+	/// Caps the group streams processed concurrently.
+	/// Further streams are left unaccepted until a slot frees up.
+	pub max_streams: StreamLimit,
+	/// Drop incoming groups this far behind the newest one of their track.
+	/// None keeps every group.
+	pub group_window: Option<u64>,
+	pub version: Version,
+}
+
 #[derive(Clone)]
 pub(super) struct Subscriber<S: web_transport_trait::Session> {
 	session: S,
@@ -93,6 +123,8 @@ pub(super) struct Subscriber<S: web_transport_trait::Session> {
 	// of colliding on an empty chain.
 	session_origin: crate::Origin,
 	state: Lock<State>,
+	max_streams: StreamLimit,
+	group_window: Option<u64>,
 	version: Version,
 }
 
@@ -116,27 +148,56 @@ async fn resolve_track_alias(aliases: kio::Consumer<HashMap<u64, RequestId>>, al
 	}
 }
 
+impl<S: web_transport_trait::Session> Query for Subscriber<S> {
+	fn track_status(
+		&self,
+		broadcast: PathOwned,
+		track: String,
+	) -> web_async::MaybeSendBoxFuture<'_, Result<TrackStatus, Error>> {
+		Box::pin(async move { Subscriber::track_status(self, broadcast, &track).await })
+	}
+
+	fn subscribe_armed(
+		&self,
+		broadcast: PathOwned,
+		track: TrackProducer,
+		forward: kio::Consumer<bool>,
+	) -> Result<(), Error> {
+		Subscriber::subscribe_armed(self, broadcast, track, forward)
+	}
+
+	fn subscribe_from(
+		&self,
+		broadcast: PathOwned,
+		track: TrackProducer,
+		(group, object): (u64, u64),
+	) -> Result<(), Error> {
+		Subscriber::subscribe_from(self, broadcast, track, ietf::Location { group, object })
+	}
+}
+
 impl<S: web_transport_trait::Session> Subscriber<S> {
-	pub fn new(
-		session: S,
-		origin: Option<OriginProducer>,
-		control: Control,
-		stats: StatsHandle,
-		version: Version,
-	) -> Self {
-		let broadcasts = stats.subscriber_broadcasts();
+	pub fn new(config: SubscriberConfig<S>) -> Self {
+		let broadcasts = config.stats.subscriber_broadcasts();
 		Self {
-			session,
-			origin,
-			control,
-			stats,
+			session: config.session,
+			origin: config.origin,
+			control: config.control,
+			stats: config.stats,
 			broadcasts,
 			session_origin: crate::Origin::random(),
 			state: Default::default(),
-			version,
+			max_streams: config.max_streams,
+			group_window: config.group_window,
+			version: config.version,
 		}
 	}
 
+	/// Wait for a free group stream slot, see [StreamLimit::acquire].
+	pub async fn acquire_stream(&self) -> Option<tokio::sync::OwnedSemaphorePermit> {
+		self.max_streams.acquire().await
+	}
+
 	pub fn has_origin(&self) -> bool {
 		self.origin.is_some()
 	}
@@ -334,7 +395,7 @@ impl<S: web_transport_trait::Session> Subscriber<S> {
 
 		if let Err(err) = self.start_publish(&msg) {
 			if matches!(err, Error::Duplicate) {
-				self.session.close(err.to_code(), err.to_string().as_ref());
+				self.session.close(DUPLICATE_TRACK_ALIAS, "duplicate track alias");
 				return Err(err);
 			}
 			self.write_publish_error(&mut stream, request_id, 400, &err.to_string())
@@ -342,7 +403,16 @@ impl<S: web_transport_trait::Session> Subscriber<S> {
 			return Ok(());
 		}
 
-		let res = self.write_publish_ok(&mut stream, &msg).await;
+		let mut res = self.write_publish_ok(&mut stream, &msg).await;
+
+		// A PUBLISH with forward=0 sends nothing until we ask. Draft-14's PUBLISH_OK carries
+		// forward=1, but later drafts' REQUEST_OK has no forward state, so follow it with an update.
+		// PUBLISH has no priority of its own to echo, so ask for the default.
+		if res.is_ok() && !msg.forward && self.version != Version::Draft14 {
+			res = self
+				.write_subscribe_update(&mut stream, request_id, priority::DEFAULT, true)
+				.await;
+		}
 
 		if res.is_ok() {
 			// PUBLISH is the peer feeding us a broadcast, so count this session as
@@ -457,7 +527,7 @@ impl<S: web_transport_trait::Session> Subscriber<S> {
 					.encode(&ietf::PublishOk {
 						request_id: Some(msg.request_id),
 						forward: true,
-						subscriber_priority: 0,
+						subscriber_priority: priority::to_wire(priority::DEFAULT),
 						group_order: GroupOrder::Descending,
 						filter_type: FilterType::LargestObject,
 					})
@@ -633,6 +703,7 @@ impl<S: web_transport_trait::Session> Subscriber<S> {
 			None => msg.track_namespace.to_owned(),
 		};
 		let track_stats = Arc::new(self.stats.broadcast(&abs).subscriber_track(&msg.track_name));
+		let span = tracing::info_span!("publish", id = %request_id, broadcast = %abs, track = %msg.track_name);
 
 		let mut state = self.state.lock();
 		match state.subscribes.entry(request_id) {
@@ -641,6 +712,8 @@ impl<S: web_transport_trait::Session> Subscriber<S> {
 					producer: track.clone(),
 					alias: Some(msg.track_alias),
 					stats: track_stats,
+					span,
+					window: self.group_window.map(GroupWindow::new),
 				});
 			}
 			Entry::Occupied(_) => return Err(Error::Duplicate),
@@ -674,17 +747,90 @@ impl<S: web_transport_trait::Session> Subscriber<S> {
 
 			let mut this = self.clone();
 
+			// The request id is recorded once allocated by `run_subscribe`.
+			let span = tracing::info_span!("subscribe", id = tracing::field::Empty, track = %track.name);
+
 			let path = path.to_owned();
 			let broadcast = broadcast.clone();
-			web_async::spawn(async move {
-				this.run_subscribe(path, broadcast, track).await;
-			});
+			// Forward immediately; the producer is dropped so it never toggles.
+			let forward = kio::Producer::new(true).consume();
+			// A resumed subscription asks for the groups it missed, as with subscribe_from.
+			let start = track.requested_start().map(|group| ietf::Location { group, object: 0 });
+			web_async::spawn(
+				async move {
+					this.run_subscribe(path, broadcast, track, forward, start).await;
+				}
+				.instrument(span),
+			);
 		}
 
 		Ok(())
 	}
 
-	async fn run_subscribe(&mut self, broadcast_path: Path<'_>, broadcast: BroadcastDynamic, mut track: TrackProducer) {
+	/// Subscribe to `track` on an announced broadcast, pre-armed with forward=0.
+	///
+	/// The publisher sets up the subscription but sends no groups. Each change to `forward`
+	/// sends a SUBSCRIBE_UPDATE, so flipping it to true when playback starts skips the
+	/// SUBSCRIBE round-trip on a channel change.
+	pub fn subscribe_armed(
+		&self,
+		broadcast: Path<'_>,
+		track: TrackProducer,
+		forward: kio::Consumer<bool>,
+	) -> Result<(), Error> {
+		self.spawn_subscribe(broadcast, track, forward, None)
+	}
+
+	/// Subscribe to `track` on an announced broadcast, starting at `start`.
+	///
+	/// The publisher skips every group before `start.group` and the objects before
+	/// `start.object` within it, so playback can begin at a keyframe in the middle of a group.
+	/// The skipped objects show up as empty frames so later frames keep their object IDs.
+	pub fn subscribe_from(
+		&self,
+		broadcast: Path<'_>,
+		track: TrackProducer,
+		start: ietf::Location,
+	) -> Result<(), Error> {
+		// Forward immediately; the producer is dropped so it never toggles.
+		let forward = kio::Producer::new(true).consume();
+		self.spawn_subscribe(broadcast, track, forward, Some(start))
+	}
+
+	fn spawn_subscribe(
+		&self,
+		broadcast: Path<'_>,
+		track: TrackProducer,
+		forward: kio::Consumer<bool>,
+		start: Option<ietf::Location>,
+	) -> Result<(), Error> {
+		let dynamic = {
+			let state = self.state.lock();
+			let broadcast = state.broadcasts.get(&broadcast.to_owned()).ok_or(Error::NotFound)?;
+			broadcast.producer.dynamic()
+		};
+
+		let mut this = self.clone();
+		let span = tracing::info_span!("subscribe", id = tracing::field::Empty, track = %track.name);
+		let path = broadcast.to_owned();
+		web_async::spawn(
+			async move {
+				this.run_subscribe(path, dynamic, track, forward, start).await;
+			}
+			.instrument(span),
+		);
+
+		Ok(())
+	}
+
+	async fn run_subscribe(
+		&mut self,
+		broadcast_path: Path<'_>,
+		broadcast: BroadcastDynamic,
+		mut track: TrackProducer,
+		forward: kio::Consumer<bool>,
+		start: Option<ietf::Location>,
+	) {
 		let request_id = match self.control.next_request_id().await {
 			Ok(id) => id,
 			Err(err) => {
@@ -693,8 +839,11 @@ impl<S: web_transport_trait::Session> Subscriber<S> {
 			}
 		};
 
+		let span = tracing::Span::current();
+		span.record("id", tracing::field::display(request_id));
+
 		let mut stream = match Stream::open(&self.session, self.version).await {
-			Ok(s) => s,
+			Ok(s) => self.control.log_stream(s),
 			Err(err) => {
 				tracing::debug!(%err, "failed to open subscribe stream");
 				let _ = track.abort(err);
@@ -719,13 +868,16 @@ impl<S: web_transport_trait::Session> Subscriber<S> {
 					producer: track.clone(),
 					alias: None,
 					stats: track_stats,
+					span,
+					window: self.group_window.map(GroupWindow::new),
 				},
 			);
 		}
 
 		// Write Subscribe message
+		let mut forwarding = *forward.read();
 		if let Err(err) = self
-			.write_subscribe(&mut stream, request_id, &broadcast_path, &track)
+			.write_subscribe(&mut stream, request_id, &broadcast_path, &track, forwarding, start)
 			.await
 		{
 			tracing::debug!(%err, "failed to write subscribe");
@@ -740,7 +892,11 @@ impl<S: web_transport_trait::Session> Subscriber<S> {
 		match self.read_subscribe_response(&mut stream).await {
 			Ok(Some(alias)) => {
 				if let Err(err) = self.register_alias(request_id, alias) {
-					self.session.close(err.to_code(), err.to_string().as_ref());
+					// An alias already routing another track is DUPLICATE_TRACK_ALIAS, fatal to the
+					// session; anything else (e.g. the subscription was dropped meanwhile) is local.
+					if matches!(err, Error::Duplicate) {
+						self.session.close(DUPLICATE_TRACK_ALIAS, "duplicate track alias");
+					}
 					self.remove_subscribe(request_id);
 					let _ = track.abort(err);
 					return;
@@ -760,24 +916,45 @@ impl<S: web_transport_trait::Session> Subscriber<S> {
 		// lifetime. It drops (releasing `broadcasts_closed`) when this fn returns.
 		let _broadcast_sub = self.broadcasts.subscribe(&abs);
 
-		tokio::select! {
-			_ = track.unused() => {
-				tracing::info!(broadcast = %self.origin.as_ref().expect("origin set by start_announce").absolute(&broadcast_path), track = %track.name, "subscribe cancelled");
-				let _ = track.abort(Error::Cancel);
-			}
-			err = broadcast.closed() => {
-				tracing::info!(broadcast = %self.origin.as_ref().expect("origin set by start_announce").absolute(&broadcast_path), track = %track.name, "broadcast closed");
-				let _ = track.abort(err);
-			}
-			res = stream.reader.closed() => {
-				match res {
-					Ok(()) => {
-						tracing::info!(broadcast = %self.origin.as_ref().expect("origin set by start_announce").absolute(&broadcast_path), track = %track.name, "subscribe complete");
-						let _ = track.finish();
+		loop {
+			tokio::select! {
+				_ = track.unused() => {
+					tracing::info!(broadcast = %self.origin.as_ref().expect("origin set by start_announce").absolute(&broadcast_path), track = %track.name, "subscribe cancelled");
+					let _ = track.abort(Error::Cancel);
+					break;
+				}
+				err = broadcast.closed() => {
+					tracing::info!(broadcast = %self.origin.as_ref().expect("origin set by start_announce").absolute(&broadcast_path), track = %track.name, "broadcast closed");
+					let _ = track.abort(err);
+					break;
+				}
+				// Cancel safe: the type id is only consumed once fully decoded.
+				res = stream.reader.decode_maybe::<u64>() => {
+					let res = match res {
+						Ok(type_id) => self.read_publish_done(&mut stream, type_id).await,
+						Err(err) => Err(err),
+					};
+					match res {
+						Ok(()) => {
+							tracing::info!(broadcast = %self.origin.as_ref().expect("origin set by start_announce").absolute(&broadcast_path), track = %track.name, "subscribe complete");
+							let _ = track.finish();
+						}
+						Err(err) => {
+							tracing::debug!(%err, "subscribe stream closed with error");
+							let _ = track.abort(err);
+						}
 					}
-					Err(err) => {
-						tracing::debug!(%err, "subscribe stream closed with error");
+					break;
+				}
+				Some(changed) = kio::wait(|waiter| forward.poll(waiter, |state| match **state != forwarding {
+					true => Poll::Ready(**state),
+					false => Poll::Pending,
+				}).map(|res| res.ok())) => {
+					forwarding = changed;
+					if let Err(err) = self.write_subscribe_update(&mut stream, request_id, track.priority, forwarding).await {
+						tracing::debug!(%err, "failed to write subscribe update");
 						let _ = track.abort(err);
+						break;
 					}
 				}
 			}
@@ -795,6 +972,8 @@ impl<S: web_transport_trait::Session> Subscriber<S> {
 		request_id: RequestId,
 		broadcast: &Path<'_>,
 		track: &TrackProducer,
+		forward: bool,
+		start: Option<ietf::Location>,
 	) -> Result<(), Error> {
 		stream.writer.encode(&ietf::Subscribe::ID).await?;
 		stream
@@ -803,9 +982,42 @@ impl<S: web_transport_trait::Session> Subscriber<S> {
 				request_id,
 				track_namespace: broadcast.to_owned(),
 				track_name: (&track.name).into(),
-				subscriber_priority: track.priority,
+				subscriber_priority: priority::to_wire(track.priority),
 				group_order: GroupOrder::Descending,
-				filter_type: FilterType::LargestObject,
+				filter_type: match start {
+					Some(_) => FilterType::AbsoluteStart,
+					None => FilterType::LargestObject,
+				},
+				start_location: start,
+				end_group: None,
+				forward,
+				delivery_timeout: None,
+			})
+			.await?;
+		Ok(())
+	}
+
+	async fn write_subscribe_update(
+		&self,
+		stream: &mut Stream<S, Version>,
+		request_id: RequestId,
+		priority: u8,
+		forward: bool,
+	) -> Result<(), Error> {
+		let update_id = self.control.next_request_id().await?;
+		stream.writer.encode(&ietf::SubscribeUpdate::ID).await?;
+		stream
+			.writer
+			.encode(&ietf::SubscribeUpdate {
+				request_id: update_id,
+				subscription_request_id: match self.version {
+					Version::Draft14 | Version::Draft15 | Version::Draft16 => Some(request_id),
+					_ => None,
+				},
+				start_location: ietf::Location::default(),
+				end_group: 0,
+				subscriber_priority: priority::to_wire(priority),
+				forward,
 			})
 			.await?;
 		Ok(())
@@ -837,6 +1049,86 @@ impl<S: web_transport_trait::Session> Subscriber<S> {
 		}
 	}
 
+	/// Read the PUBLISH_DONE ending a subscription, given its already decoded type id.
+	///
+	/// Resolves to `Ok` when the publisher ended the track cleanly, so the track can be
+	/// finished: no more groups will ever arrive. A stream closed without PUBLISH_DONE is
+	/// treated the same way.
+	async fn read_publish_done(&self, stream: &mut Stream<S, Version>, type_id: Option<u64>) -> Result<(), Error> {
+		match type_id {
+			None => Ok(()),
+			Some(ietf::PublishDone::ID) => {
+				let msg: ietf::PublishDone = stream.reader.decode().await?;
+				tracing::debug!(message = ?msg, "received publish done");
+				match msg.status_code {
+					200 => stream.reader.closed().await,
+					_ => Err(Error::Dropped),
+				}
+			}
+			Some(_) => Err(Error::UnexpectedMessage),
+		}
+	}
+
+	/// Query the remote publisher for the status of a track.
+	///
+	/// Sends a TRACK_STATUS on its own request stream and resolves once the peer answers.
+	/// v14 replies with TRACK_STATUS_OK, v15+ with REQUEST_OK, which only says the track
+	/// exists and carries no location.
+	pub async fn track_status(&self, broadcast: Path<'_>, track: &str) -> Result<TrackStatus, Error> {
+		let request_id = self.control.next_request_id().await?;
+		let stream = Stream::open(&self.session, self.version).await?;
+		let mut stream = self.control.log_stream(stream);
+
+		stream.writer.encode(&ietf::TrackStatus::ID).await?;
+		stream
+			.writer
+			.encode(&ietf::TrackStatus {
+				request_id,
+				track_namespace: broadcast,
+				track_name: track.into(),
+			})
+			.await?;
+
+		let reply = self.read_track_status_response(&mut stream).await;
+		stream.writer.finish().ok();
+		reply
+	}
+
+	async fn read_track_status_response(&self, stream: &mut Stream<S, Version>) -> Result<TrackStatus, Error> {
+		let type_id: u64 = stream.reader.decode().await?;
+		let size: u16 = stream.reader.decode().await?;
+		let mut data = stream.reader.read_exact(size as usize).await?;
+
+		match type_id {
+			ietf::TrackStatusOk::ID if self.version == Version::Draft14 => {
+				let msg = ietf::TrackStatusOk::decode_msg(&mut data, self.version)?;
+				tracing::debug!(message = ?msg, "received track status");
+				let largest = msg.largest.map(|largest| (largest.group, largest.object));
+				Ok(match msg.status_code {
+					ietf::TrackStatusCode::InProgress => TrackStatus::Active { largest },
+					ietf::TrackStatusCode::Ended => TrackStatus::Ended { largest },
+					ietf::TrackStatusCode::NotFound => TrackStatus::NotFound,
+					ietf::TrackStatusCode::NotAuthorized => TrackStatus::NotAuthorized,
+				})
+			}
+			ietf::RequestOk::ID if self.version != Version::Draft14 => {
+				let msg = ietf::RequestOk::decode_msg(&mut data, self.version)?;
+				tracing::debug!(message = ?msg, "received track status");
+				Ok(TrackStatus::Active { largest: None })
+			}
+			ietf::RequestError::ID => {
+				let msg = ietf::RequestError::decode_msg(&mut data, self.version)?;
+				tracing::debug!(message = ?msg, "track status error");
+				match msg.error_code {
+					ietf::ERROR_NOT_FOUND => Ok(TrackStatus::NotFound),
+					ietf::ERROR_UNAUTHORIZED | ietf::ERROR_FORBIDDEN => Ok(TrackStatus::NotAuthorized),
+					code => Err(u32::try_from(code).map_or(Error::ProtocolViolation, Error::Remote)),
+				}
+			}
+			_ => Err(Error::UnexpectedMessage),
+		}
+	}
+
 	pub async fn recv_group(&mut self, stream: &mut Reader<S::RecvStream, Version>) -> Result<(), Error> {
 		let group: ietf::GroupHeader = stream.decode().await?;
 
@@ -852,35 +1144,77 @@ impl<S: web_transport_trait::Session> Subscriber<S> {
 			tracing::warn!(track_alias = %group.track_alias, "unknown track alias");
 		})?;
 
-		let (mut producer, track, track_stats) = {
+		let (mut producer, mut track, track_stats, span, window) = {
 			let mut state = self.state.lock();
 			let track = state.subscribes.get_mut(&request_id).ok_or(Error::NotFound)?;
 
+			// Refuse a group that is already too far behind, before it's visible to consumers.
+			if let Some(window) = &track.window {
+				window.arrive(group.group_id)?;
+			}
+
 			let group_info = Group {
 				sequence: group.group_id,
+				priority: group
+					.flags
+					.has_priority
+					.then(|| priority::from_wire(group.publisher_priority)),
 			};
 			let producer = track.producer.create_group(group_info)?;
-			(producer, track.producer.clone(), track.stats.clone())
+			let span = tracing::debug_span!(
+				parent: &track.span,
+				"group",
+				sequence = group.group_id,
+				publisher_priority = group.publisher_priority
+			);
+			(
+				producer,
+				track.producer.clone(),
+				track.stats.clone(),
+				span,
+				track.window.clone(),
+			)
 		};
 
 		// Bump groups counter for this incoming group on the subscriber side.
 		track_stats.group();
 
+		let sequence = group.group_id;
+		let expired = async {
+			match &window {
+				Some(window) => window.expired(sequence).await,
+				None => std::future::pending().await,
+			}
+		};
+
 		let res = tokio::select! {
 			err = track.closed() => Err(err),
 			err = producer.closed() => Err(err),
-			res = self.run_group(group, stream, producer.clone(), track_stats.clone()) => res,
+			_ = expired => Err(Error::Old),
+			res = self.run_group(group, stream, producer.clone(), track_stats.clone()).instrument(span.clone()) => res,
 		};
 
 		match res {
 			Err(Error::Cancel) => {
 				let _ = producer.abort(Error::Cancel);
 			}
+			Err(Error::Old) => {
+				// Newer groups arrived; stop the stream rather than finish a stale group.
+				span.in_scope(|| tracing::debug!("group fell behind the window"));
+				stream.abort(&Error::Old);
+				let _ = producer.abort(Error::Old);
+			}
 			Err(err) => {
-				tracing::debug!(%err, group = %producer.sequence, "group error");
+				span.in_scope(|| tracing::debug!(%err, "group error"));
 				let _ = producer.abort(err);
 			}
-			_ => {
+			Ok(true) => {
+				let _ = producer.finish();
+				span.in_scope(|| tracing::debug!("end of track"));
+				// Only possible while this is the newest group; PublishDone still ends the track otherwise.
+				let _ = track.finish_at(sequence);
+			}
+			Ok(false) => {
 				let _ = producer.finish();
 			}
 		}
@@ -888,41 +1222,82 @@ impl<S: web_transport_trait::Session> Subscriber<S> {
 		Ok(())
 	}
 
+	/// Read the objects of a group into `producer`.
+	///
+	/// Returns true if the group ended with an End of Track object, kept as a
+	/// [`Frame::end_of_track`] marker.
 	async fn run_group(
 		&mut self,
 		group: ietf::GroupHeader,
 		stream: &mut Reader<S::RecvStream, Version>,
 		mut producer: GroupProducer,
 		track_stats: Arc<SubscriberTrack>,
-	) -> Result<(), Error> {
+	) -> Result<bool, Error> {
+		let mut first = true;
 		while let Some(id_delta) = stream.decode_maybe::<u64>().await? {
-			if id_delta != 0 {
-				tracing::warn!(id_delta = %id_delta, "object ID delta is not supported, dropping stream");
+			// The first object ID doubles as the subgroup ID, which must be 0 like an explicit one.
+			if std::mem::take(&mut first) && group.flags.has_subgroup_object && id_delta != 0 {
+				tracing::warn!(sub_group_id = %id_delta, "subgroup ID is not supported, dropping stream");
+				return Err(Error::Unsupported);
+			}
+
+			if id_delta > MAX_OBJECT_GAP {
+				tracing::warn!(id_delta = %id_delta, "object ID delta too large, dropping stream");
 				return Err(Error::Unsupported);
 			}
 
+			// Skipped objects (e.g. before a mid-group start) keep their slots as empty gap frames.
+			for _ in 0..id_delta {
+				let mut frame = producer.create_frame(Frame::new(0))?;
+				frame.finish()?;
+			}
+
+			// The capture timestamp is optional; without it, consumers fall back to group/frame order.
+			let mut timestamp = None;
 			if group.flags.has_extensions {
 				let size: usize = stream.decode().await?;
-				stream.skip(size).await?;
+				let mut extensions = stream.read_exact(size).await?;
+				timestamp = super::properties::capture_timestamp(&mut extensions, self.version)?
+					.and_then(|micros| crate::Time::from_micros(micros).ok());
 			}
 
 			let size: u64 = stream.decode().await?;
 			if size == 0 {
 				let status: u64 = stream.decode().await?;
-				if status == 0 {
-					let mut frame = producer.create_frame(Frame { size: 0 })?;
-					track_stats.frame();
-					frame.finish()?;
-				} else if status == 3 && !group.flags.has_end {
-					break;
-				} else {
-					return Err(Error::Unsupported);
+				match ietf::ObjectStatus::try_from(status) {
+					// A missing object still occupies a slot, so keep it as an empty gap frame.
+					Ok(ietf::ObjectStatus::Normal | ietf::ObjectStatus::DoesNotExist) => {
+						let mut frame = producer.create_frame(Frame {
+							timestamp,
+							..Default::default()
+						})?;
+						track_stats.frame();
+						frame.finish()?;
+					}
+					Ok(ietf::ObjectStatus::GroupDoesNotExist) => return Err(Error::NotFound),
+					Ok(ietf::ObjectStatus::EndOfGroup) if !group.flags.has_end => break,
+					Ok(ietf::ObjectStatus::EndOfTrack) => {
+						let mut frame = producer.create_frame(Frame {
+							timestamp,
+							..Frame::end_of_track()
+						})?;
+						frame.finish()?;
+						return Ok(true);
+					}
+					_ => {
+						tracing::warn!(status, "unsupported object status, dropping stream");
+						return Err(Error::Unsupported);
+					}
 				}
 			} else {
 				if size > MAX_FRAME_SIZE {
 					return Err(Error::FrameTooLarge);
 				}
-				let mut frame = producer.create_frame(Frame { size })?;
+				let mut frame = producer.create_frame(Frame {
+					size,
+					timestamp,
+					..Default::default()
+				})?;
 				track_stats.frame();
 
 				if let Err(err) = self.run_frame(stream, frame.clone(), &track_stats).await {
@@ -934,7 +1309,7 @@ impl<S: web_transport_trait::Session> Subscriber<S> {
 			}
 		}
 
-		Ok(())
+		Ok(false)
 	}
 
 	async fn run_frame(
@@ -950,7 +1325,11 @@ impl<S: web_transport_trait::Session> Subscriber<S> {
 				Some(n) if n > 0 => {
 					track_stats.bytes(n as u64);
 				}
-				_ => return Err(Error::WrongSize),
+				_ => {
+					let remaining = bytes::BufMut::remaining_mut(&frame);
+					tracing::debug!(size = frame.size, remaining, "stream ended mid-frame");
+					return Err(Error::WrongSize);
+				}
 			}
 		}
 		Ok(())
@@ -985,6 +1364,20 @@ mod tests {
 		));
 	}
 
+	#[test]
+	fn duplicate_track_alias_is_rejected() {
+		let aliases = TrackAliases::default();
+		insert_track_alias(&aliases, 7, RequestId(11)).unwrap();
+
+		// Re-registering the same mapping is harmless, but a second track can't take the alias.
+		insert_track_alias(&aliases, 7, RequestId(11)).unwrap();
+		assert!(matches!(
+			insert_track_alias(&aliases, 7, RequestId(13)),
+			Err(Error::Duplicate)
+		));
+		assert_eq!(aliases.read().get(&7), Some(&RequestId(11)));
+	}
+
 	#[test]
 	fn removing_old_track_does_not_remove_reused_alias() {
 		let aliases = TrackAliases::default();
@@ -7,7 +7,7 @@ use num_enum::{IntoPrimitive, TryFromPrimitive};
 use crate::{
 	Path,
 	coding::*,
-	ietf::{FilterType, GroupOrder, Parameters, RequestId},
+	ietf::{FilterType, GroupOrder, Location, Parameters, RequestId},
 };
 
 use super::Message;
@@ -102,6 +102,51 @@ impl Decode<Version> for TrackStatusCode {
 	}
 }
 
+/// TrackStatusOk message (0x0e) - v14 only.
+/// v15+ answers a TRACK_STATUS with REQUEST_OK instead.
+#[derive(Clone, Debug)]
+pub struct TrackStatusOk {
+	pub request_id: RequestId,
+	pub status_code: TrackStatusCode,
+	/// The largest location published so far, if any.
+	pub largest: Option<Location>,
+}
+
+impl Message for TrackStatusOk {
+	const ID: u64 = 0x0e;
+
+	fn encode_msg<W: bytes::BufMut>(&self, w: &mut W, version: Version) -> Result<(), EncodeError> {
+		self.request_id.encode(w, version)?;
+		self.status_code.encode(w, version)?;
+		match &self.largest {
+			Some(largest) => {
+				1u8.encode(w, version)?;
+				largest.encode(w, version)?;
+			}
+			None => 0u8.encode(w, version)?,
+		}
+		0u8.encode(w, version)?; // no parameters
+		Ok(())
+	}
+
+	fn decode_msg<R: bytes::Buf>(r: &mut R, version: Version) -> Result<Self, DecodeError> {
+		let request_id = RequestId::decode(r, version)?;
+		let status_code = TrackStatusCode::decode(r, version)?;
+		let largest = match u8::decode(r, version)? {
+			0 => None,
+			1 => Some(Location::decode(r, version)?),
+			_ => return Err(DecodeError::InvalidValue),
+		};
+		let _params = Parameters::decode(r, version)?;
+
+		Ok(Self {
+			request_id,
+			status_code,
+			largest,
+		})
+	}
+}
+
 #[cfg(test)]
 mod tests {
 	use super::*;
@@ -197,4 +242,35 @@ mod tests {
 		assert_eq!(decoded.track_namespace.as_str(), "test/ns");
 		assert_eq!(decoded.track_name, "video");
 	}
+
+	#[test]
+	fn test_track_status_ok_round_trip() {
+		let msg = TrackStatusOk {
+			request_id: RequestId(3),
+			status_code: TrackStatusCode::InProgress,
+			largest: Some(Location { group: 7, object: 2 }),
+		};
+
+		let encoded = encode_message(&msg, Version::Draft14);
+		let decoded: TrackStatusOk = decode_message(&encoded, Version::Draft14).unwrap();
+
+		assert_eq!(decoded.request_id, RequestId(3));
+		assert!(matches!(decoded.status_code, TrackStatusCode::InProgress));
+		assert_eq!(decoded.largest, Some(Location { group: 7, object: 2 }));
+	}
+
+	#[test]
+	fn test_track_status_ok_no_content() {
+		let msg = TrackStatusOk {
+			request_id: RequestId(3),
+			status_code: TrackStatusCode::NotFound,
+			largest: None,
+		};
+
+		let encoded = encode_message(&msg, Version::Draft14);
+		let decoded: TrackStatusOk = decode_message(&encoded, Version::Draft14).unwrap();
+
+		assert!(matches!(decoded.status_code, TrackStatusCode::NotFound));
+		assert_eq!(decoded.largest, None);
+	}
 }
@@ -52,19 +52,34 @@ mod client;
 mod coding;
 mod error;
 mod ietf;
+/// Entry points for fuzz targets, enabled with the `fuzz` feature.
+#[cfg(feature = "fuzz")]
+pub mod fuzz {
+	pub use crate::ietf::{
+		Version,
+		message::{ControlMessage, decode_control},
+	};
+}
+mod limit;
 mod lite;
+#[cfg(any(test, feature = "loopback"))]
+pub mod loopback;
 mod model;
+mod opener;
+mod pacer;
 mod path;
 mod server;
 mod session;
 mod setup;
 mod stats;
 mod version;
+mod window;
 
 pub use client::*;
 pub use coding::{BoundsExceeded, DecodeError, EncodeError};
 pub use error::*;
 pub use model::*;
+pub use pacer::RateLimit;
 pub use path::*;
 pub use server::*;
 pub use session::*;
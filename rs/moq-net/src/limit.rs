@@ -0,0 +1,77 @@
+use std::sync::Arc;
+
+use tokio::sync::{OwnedSemaphorePermit, Semaphore};
+
+/// The default cap on concurrently running group streams.
+///
+/// Well above what multi-track playback needs; it only exists to bound memory
+/// when a peer opens streams faster than we can drain them.
+pub(crate) const DEFAULT_MAX_STREAMS: usize = 4096;
+
+/// Caps the incoming group streams a session processes concurrently.
+///
+/// A permit is taken before each stream is accepted and held until it is drained,
+/// so once the cap is reached QUIC flow control pushes back on the peer. Streams
+/// that turn out not to carry a group drop their permit as soon as they're identified.
+#[derive(Clone)]
+pub(crate) struct StreamLimit {
+	max: usize,
+	permits: Option<Arc<Semaphore>>,
+}
+
+impl StreamLimit {
+	/// Allow up to `max` streams at once, or any number if `max` is zero.
+	pub fn new(max: usize) -> Self {
+		Self {
+			max,
+			permits: (max > 0).then(|| Arc::new(Semaphore::new(max))),
+		}
+	}
+
+	/// Wait for a free slot, for as long as it takes.
+	///
+	/// Returns `None` when there is no limit.
+	pub async fn acquire(&self) -> Option<OwnedSemaphorePermit> {
+		let permits = self.permits.as_ref()?;
+		if let Ok(permit) = permits.clone().try_acquire_owned() {
+			return Some(permit);
+		}
+
+		tracing::debug!(max = self.max, "uni stream limit reached");
+
+		// The semaphore is never closed.
+		permits.clone().acquire_owned().await.ok()
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use std::time::Duration;
+
+	use super::*;
+
+	#[tokio::test(start_paused = true)]
+	async fn waits_for_a_free_slot() {
+		let limit = StreamLimit::new(1);
+		let first = limit.acquire().await;
+		assert!(first.is_some());
+
+		let waiting = tokio::spawn({
+			let limit = limit.clone();
+			async move { limit.acquire().await }
+		});
+
+		// Saturated for a long time is still just backpressure.
+		tokio::time::sleep(Duration::from_secs(60)).await;
+		assert!(!waiting.is_finished());
+		drop(first);
+
+		assert!(waiting.await.unwrap().is_some());
+	}
+
+	#[tokio::test]
+	async fn zero_is_unlimited() {
+		let limit = StreamLimit::new(0);
+		assert!(limit.acquire().await.is_none());
+	}
+}
@@ -16,6 +16,9 @@ use tokio::sync::watch;
 // - On remove from overflow: rebuild heap (rare case, acceptable O(n) cost)
 //
 // Priority ordering: higher track value = higher priority, then higher group value = higher priority
+//
+// The QUIC stream priority is the item's rank in the queue, not a packed (track, group) value,
+// so the full u64 group sequence is compared and there is no sequence at which ordering wraps.
 
 /// A priority composed of a track-level priority and a group sequence number.
 /// Higher `track` is always preferred; `group` only breaks ties within the same track.
@@ -331,6 +334,18 @@ mod tests {
 		assert_eq!(group1.current(), 2);
 	}
 
+	#[test]
+	fn test_group_priority_large_sequence() {
+		let queue = PriorityQueue::default();
+
+		// Sequences past 2^24 (and up to u64::MAX) still order correctly.
+		let mut small = queue.insert(Priority::new(100, 1 << 24));
+		let mut large = queue.insert(Priority::new(100, u64::MAX));
+
+		assert_eq!(large.current(), 0);
+		assert_eq!(small.current(), 1);
+	}
+
 	#[test]
 	fn test_track_priority_overrides_group() {
 		let queue = PriorityQueue::default();
@@ -1,11 +1,11 @@
-use std::time::Duration;
+use std::{task::Poll, time::Duration};
 
 use futures::{FutureExt, StreamExt, stream::FuturesUnordered};
-use web_async::FuturesExt;
+use web_async::{FuturesExt, time::Instant};
 use web_transport_trait::Stats;
 
 use crate::{
-	AsPath, BroadcastRequested, Error, Origin, OriginConsumer, OriginList, StatsHandle as MoqStats, Track,
+	AsPath, BroadcastRequested, Error, Origin, OriginConsumer, OriginList, RateLimit, StatsHandle as MoqStats, Track,
 	TrackConsumer,
 	coding::{Stream, Writer},
 	lite::{
@@ -13,6 +13,8 @@ use crate::{
 		priority::{Priority, PriorityHandle, PriorityQueue},
 	},
 	model::GroupConsumer,
+	opener::Opener,
+	pacer::Pacer,
 };
 
 use super::Version;
@@ -25,9 +27,60 @@ pub(super) struct PublisherConfig<S: web_transport_trait::Session> {
 	/// Stats aggregator for this session's egress. Use [`MoqStats::default`]
 	/// to opt out.
 	pub stats: MoqStats,
+	/// Egress caps for each subscribed track, picked by the track's priority.
+	pub rate_limit: RateLimit,
 	pub version: Version,
 }
 
+/// What `run_subscribe` needs from the session to serve one SUBSCRIBE.
+struct SubscribeParams<S: web_transport_trait::Session> {
+	session: S,
+	/// The requested broadcast, once the origin resolves it.
+	broadcast: kio::Pending<BroadcastRequested>,
+	priority: PriorityQueue,
+	opener: Opener,
+	rate_limit: RateLimit,
+	/// The track guard, which bumps `subscriptions`.
+	track_stats: crate::PublisherTrack,
+	/// The per-session broadcast tracker. Its `broadcasts` sentinel is taken once the
+	/// subscription is validated, and held for its lifetime.
+	broadcasts: crate::SessionBroadcasts,
+	/// The absolute path of the broadcast, for stats.
+	absolute: crate::PathOwned,
+	version: Version,
+}
+
+/// The state of an active subscription, shared by every one of its group streams.
+#[derive(Clone)]
+struct TrackParams<S: web_transport_trait::Session> {
+	session: S,
+	/// The subscribe ID, stamped on every group header.
+	id: u64,
+	priority: PriorityQueue,
+	opener: Opener,
+	stats: std::sync::Arc<crate::PublisherTrack>,
+	/// The subscriber priority, updated by SUBSCRIBE_UPDATE.
+	track_priority: tokio::sync::watch::Receiver<u8>,
+	/// The subscriber's max latency, if its version carries a non-zero one: a non-latest
+	/// group is reset once it is this much older than the latest group, instead of
+	/// retransmitting data that is already too late to be useful.
+	max_latency: Option<Duration>,
+	/// The newest group queued so far, and when it was queued.
+	latest: kio::Consumer<Option<(u64, Instant)>>,
+	/// The track's egress cap, shared by all of its group streams.
+	pacer: Option<Pacer>,
+	version: Version,
+}
+
+/// A group to serve on its own stream.
+struct GroupParams {
+	msg: lite::Group,
+	priority: PriorityHandle,
+	group: GroupConsumer,
+	/// When the group was queued, the start of its wall-clock age.
+	queued: Instant,
+}
+
 pub(super) struct Publisher<S: web_transport_trait::Session> {
 	session: S,
 	origin: OriginConsumer,
@@ -38,6 +91,9 @@ pub(super) struct Publisher<S: web_transport_trait::Session> {
 	broadcasts: crate::SessionBroadcasts,
 	self_origin: Origin,
 	priority: PriorityQueue,
+	/// Opens group streams in priority order once the peer's stream limit is reached.
+	opener: Opener,
+	rate_limit: RateLimit,
 	version: Version,
 }
 
@@ -57,6 +113,8 @@ impl<S: web_transport_trait::Session> Publisher<S> {
 			broadcasts,
 			self_origin,
 			priority: Default::default(),
+			opener: Default::default(),
+			rate_limit: config.rate_limit,
 			version: config.version,
 		}
 	}
@@ -357,30 +415,24 @@ impl<S: web_transport_trait::Session> Publisher<S> {
 
 		// Prefer an announced broadcast, but allow a dynamic origin to serve
 		// unannounced paths such as edge-local dashboard stats.
-		let broadcast = self.origin.request_broadcast(&subscribe.broadcast);
-		let priority = self.priority.clone();
-		let version = self.version;
-
 		// Per-track subscription guard (bumps `subscriptions`). The per-(session,
 		// broadcast) `broadcasts` sentinel that counts viewers is taken inside
 		// `run_subscribe`, only once the subscription is validated and active, so
 		// a stale/invalid SUBSCRIBE isn't counted as a viewer.
-		let track_stats = self.stats.broadcast(&absolute).publisher_track(&track);
-		let broadcasts = self.broadcasts.clone();
+		let params = SubscribeParams {
+			session: self.session.clone(),
+			broadcast: self.origin.request_broadcast(&subscribe.broadcast),
+			priority: self.priority.clone(),
+			opener: self.opener.clone(),
+			rate_limit: self.rate_limit.clone(),
+			track_stats: self.stats.broadcast(&absolute).publisher_track(&track),
+			broadcasts: self.broadcasts.clone(),
+			absolute: absolute.clone(),
+			version: self.version,
+		};
 
-		let session = self.session.clone();
 		web_async::spawn(async move {
-			if let Err(err) = Self::run_subscribe(
-				session,
-				&mut stream,
-				&subscribe,
-				broadcast,
-				priority,
-				(track_stats, broadcasts, absolute.clone()),
-				version,
-			)
-			.await
-			{
+			if let Err(err) = Self::run_subscribe(params, &mut stream, &subscribe).await {
 				match &err {
 					// TODO better classify WebTransport errors.
 					Error::Cancel | Error::Transport(_) => {
@@ -444,29 +496,22 @@ impl<S: web_transport_trait::Session> Publisher<S> {
 	}
 
 	async fn run_subscribe(
-		session: S,
+		params: SubscribeParams<S>,
 		stream: &mut Stream<S, Version>,
 		subscribe: &lite::Subscribe<'_>,
-		consumer: kio::Pending<BroadcastRequested>,
-		priority: PriorityQueue,
-		// The track guard (bumps `subscriptions`), the per-session broadcast
-		// tracker, and the broadcast path. The `broadcasts` sentinel is taken
-		// below, after the subscription is validated, and held for its lifetime.
-		stats: (crate::PublisherTrack, crate::SessionBroadcasts, crate::PathOwned),
-		version: Version,
 	) -> Result<(), Error> {
-		let (track_stats, broadcasts, absolute) = stats;
+		let version = params.version;
 		let track = Track {
 			name: subscribe.track.to_string(),
 			priority: subscribe.priority,
 		};
 
-		let broadcast = consumer.await?;
+		let broadcast = params.broadcast.await?;
 		let mut track = broadcast.subscribe_track(&track)?;
 
 		// Subscription is now active: count this session as a viewer of the
 		// broadcast. Dropping this guard (subscription end) releases it.
-		let _broadcast_sub = broadcasts.subscribe(&absolute);
+		let _broadcast_sub = params.broadcasts.subscribe(&params.absolute);
 
 		// Resolve the absolute start group once: a non-zero request wins, otherwise the
 		// latest group (or 0 for a track with none yet). The same value is advertised in
@@ -504,12 +549,31 @@ impl<S: web_transport_trait::Session> Publisher<S> {
 		// to both run_track (so future groups inherit the new priority) and serve_group
 		// tasks (so in-flight groups update via PriorityHandle::set_track).
 		let (track_priority_tx, track_priority_rx) = tokio::sync::watch::channel(track.priority);
-		let track_stats = std::sync::Arc::new(track_stats);
+		let latest = kio::Producer::default();
+
+		let params = TrackParams {
+			session: params.session,
+			id: subscribe.id,
+			priority: params.priority,
+			opener: params.opener,
+			stats: std::sync::Arc::new(params.track_stats),
+			track_priority: track_priority_rx,
+			// Lite01/02 have no max latency on the wire, and older subscribers send zero, so
+			// either way every group is delivered in full.
+			max_latency: match version {
+				Version::Lite01 | Version::Lite02 => None,
+				_ => Some(subscribe.max_latency).filter(|latency| !latency.is_zero()),
+			},
+			latest: latest.consume(),
+			// One bucket per subscription, capped by the tier of the publisher's priority.
+			pacer: params.rate_limit.rate(track.priority).map(Pacer::new),
+			version,
+		};
 
 		// `Some(last_group)` means the track ended (and we owe a SUBSCRIBE_END); `None`
 		// means the subscriber tore down the stream first, so no end signal is owed.
 		let ended = tokio::select! {
-			res = Self::run_track(session, track, subscribe, priority, track_stats, track_priority_rx, version) => Some(res?),
+			res = Self::run_track(params, track, latest) => Some(res?),
 			res = Self::run_subscribe_updates(&mut stream.reader, &track_priority_tx) => { res?; None }
 		};
 
@@ -539,13 +603,9 @@ impl<S: web_transport_trait::Session> Publisher<S> {
 	}
 
 	async fn run_track(
-		session: S,
+		mut params: TrackParams<S>,
 		mut track: TrackConsumer,
-		subscribe: &lite::Subscribe<'_>,
-		priority: PriorityQueue,
-		track_stats: std::sync::Arc<crate::PublisherTrack>,
-		mut track_priority: tokio::sync::watch::Receiver<u8>,
-		version: Version,
+		latest: kio::Producer<Option<(u64, Instant)>>,
 	) -> Result<Option<u64>, Error> {
 		let mut tasks = FuturesUnordered::new();
 
@@ -555,52 +615,104 @@ impl<S: web_transport_trait::Session> Publisher<S> {
 
 		loop {
 			let group = tokio::select! {
-				// Poll all active group futures; never matches but keeps them running.
-				true = async {
-					while tasks.next().await.is_some() {}
-					false
-				} => unreachable!(),
+				// Poll all active group futures, stopping once one carried the end of the track.
+				Some(end_of_track) = tasks.next() => match end_of_track {
+					true => break,
+					false => continue,
+				},
 				Some(group) = track.recv_group().transpose() => group,
 				else => return Ok(last_sequence),
 			}?;
 
 			let sequence = group.sequence;
 			last_sequence = last_sequence.max(Some(sequence));
-			tracing::debug!(subscribe = %subscribe.id, track = %track.name, sequence, "serving group");
+
+			// Groups may arrive out of order; only a newer group ages the others.
+			let queued = Instant::now();
+			if let Ok(mut latest) = latest.write()
+				&& latest.is_none_or(|(newest, _)| newest < sequence)
+			{
+				*latest = Some((sequence, queued));
+			}
+			tracing::debug!(subscribe = %params.id, track = %track.name, sequence, "serving group");
 
 			let msg = lite::Group {
-				subscribe: subscribe.id,
+				subscribe: params.id,
 				sequence,
 			};
 
 			// Use the latest priority for new groups so SUBSCRIBE_UPDATE applies to them too.
-			let current_priority = *track_priority.borrow_and_update();
-			let handle = priority.insert(Priority::new(current_priority, sequence));
-			tasks.push(
-				Self::serve_group(
-					session.clone(),
-					msg,
-					handle,
-					group,
-					track_stats.clone(),
-					track_priority.clone(),
-					version,
-				)
-				.map(|_| ()),
-			);
+			let current_priority = *params.track_priority.borrow_and_update();
+			let priority = params.priority.insert(Priority::new(current_priority, sequence));
+			let group = GroupParams {
+				msg,
+				priority,
+				group,
+				queued,
+			};
+			tasks.push(Self::serve_group(params.clone(), group).map(|res| matches!(res, Ok(true))));
 		}
+
+		// Let the in-flight groups finish before reporting the end of the track.
+		while tasks.next().await.is_some() {}
+
+		Ok(last_sequence)
 	}
 
-	async fn serve_group(
-		session: S,
-		msg: lite::Group,
-		mut priority: PriorityHandle,
-		mut group: GroupConsumer,
-		track_stats: std::sync::Arc<crate::PublisherTrack>,
-		mut track_priority: tokio::sync::watch::Receiver<u8>,
-		version: Version,
-	) -> Result<(), Error> {
-		let stream = session.open_uni().await.map_err(Error::from_transport)?;
+	/// Serve a group on its own uni stream.
+	///
+	/// Returns true if the group ended with a [`Frame::end_of_track`](crate::Frame::end_of_track)
+	/// marker, which isn't sent.
+	async fn serve_group(params: TrackParams<S>, group: GroupParams) -> Result<bool, Error> {
+		let TrackParams {
+			session,
+			opener,
+			stats: track_stats,
+			mut track_priority,
+			max_latency,
+			latest,
+			pacer,
+			version,
+			..
+		} = params;
+		let GroupParams {
+			msg,
+			mut priority,
+			mut group,
+			queued,
+		} = group;
+
+		// A group is never expired until a newer one is queued. Its age is how much later
+		// the latest group was queued.
+		let expired = async {
+			if let Some(max_latency) = max_latency {
+				let stale = |latest: &kio::Ref<'_, Option<(u64, Instant)>>| match **latest {
+					Some((newest, at))
+						if newest > msg.sequence && at.saturating_duration_since(queued) >= max_latency =>
+					{
+						Poll::Ready(())
+					}
+					_ => Poll::Pending,
+				};
+				// Resolve to whether it expired, so no lock guard is held across the await.
+				let poll = |waiter: &kio::Waiter| latest.poll(waiter, stale).map(|res| res.is_ok());
+				if kio::wait(poll).await {
+					return;
+				}
+			}
+			std::future::pending::<()>().await
+		};
+		tokio::pin!(expired);
+
+		// Queue behind higher-priority groups if the peer's stream limit is reached.
+		let track = *track_priority.borrow();
+		let stream = tokio::select! {
+			stream = opener.open(&session, (track, msg.sequence)) => stream?,
+			_ = &mut expired => {
+				tracing::debug!(sequence = %msg.sequence, "group expired before opening stream");
+				return Err(Error::Old);
+			}
+		};
 
 		let mut stream = Writer::new(stream, version);
 		stream.set_priority(priority.current());
@@ -612,10 +724,17 @@ impl<S: web_transport_trait::Session> Publisher<S> {
 		// zigzag delta from the previous frame (the first frame is a delta from 0).
 		let mut prev_timestamp: i64 = 0;
 
+		let mut end_of_track = false;
+
 		loop {
 			let frame = tokio::select! {
 				biased;
 				_ = stream.closed() => return Err(Error::Cancel),
+				_ = &mut expired => {
+					tracing::debug!(sequence = %msg.sequence, "group expired");
+					stream.abort(&Error::Old);
+					return Err(Error::Old);
+				}
 				frame = group.next_frame() => frame,
 				new_pri = priority.next() => {
 					stream.set_priority(new_pri);
@@ -632,6 +751,12 @@ impl<S: web_transport_trait::Session> Publisher<S> {
 				None => break,
 			};
 
+			// moq-lite has no End of Track object; the subscription ends after this group instead.
+			if frame.is_end_of_track {
+				end_of_track = true;
+				break;
+			}
+
 			if version.has_track_stream() {
 				let now = i64::try_from(crate::Time::now().as_millis()).unwrap_or(i64::MAX);
 				let delta = now - prev_timestamp;
@@ -647,6 +772,11 @@ impl<S: web_transport_trait::Session> Publisher<S> {
 				let chunk = tokio::select! {
 					biased;
 					_ = stream.closed() => return Err(Error::Cancel),
+					_ = &mut expired => {
+						tracing::debug!(sequence = %msg.sequence, "group expired");
+						stream.abort(&Error::Old);
+						return Err(Error::Old);
+					}
 					chunk = frame.read_chunk() => chunk,
 					new_pri = priority.next() => {
 						stream.set_priority(new_pri);
@@ -661,6 +791,17 @@ impl<S: web_transport_trait::Session> Publisher<S> {
 				match chunk? {
 					Some(mut chunk) => {
 						let n = chunk.len() as u64;
+						if let Some(pacer) = &pacer {
+							tokio::select! {
+								biased;
+								_ = pacer.acquire(n) => {}
+								_ = &mut expired => {
+									tracing::debug!(sequence = %msg.sequence, "group expired");
+									stream.abort(&Error::Old);
+									return Err(Error::Old);
+								}
+							}
+						}
 						loop {
 							tokio::select! {
 								biased;
@@ -668,6 +809,11 @@ impl<S: web_transport_trait::Session> Publisher<S> {
 									result?;
 									break;
 								}
+								_ = &mut expired => {
+									tracing::debug!(sequence = %msg.sequence, "group expired");
+									stream.abort(&Error::Old);
+									return Err(Error::Old);
+								}
 								new_pri = priority.next() => {
 									stream.set_priority(new_pri);
 								}
@@ -688,6 +834,6 @@ impl<S: web_transport_trait::Session> Publisher<S> {
 
 		tracing::debug!(sequence = %msg.sequence, "finished group");
 
-		Ok(())
+		Ok(end_of_track)
 	}
 }
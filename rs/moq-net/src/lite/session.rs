@@ -1,26 +1,61 @@
+use std::time::Duration;
+
 use crate::{
-	BandwidthConsumer, BandwidthProducer, Error, OriginConsumer, OriginProducer, StatsHandle, coding::Stream,
-	lite::SessionInfo,
+	BandwidthConsumer, BandwidthProducer, Error, OriginConsumer, OriginProducer, RateLimit, StatsHandle,
+	coding::Stream, limit::StreamLimit, lite::SessionInfo,
 };
 
 use super::{Publisher, PublisherConfig, Setup, Subscriber, SubscriberConfig, Version, send_setup};
+
+/// The default max latency sent in SUBSCRIBE.
+///
+/// Zero disables the deadline, so the default is long enough that only a subscriber
+/// stalled for seconds loses groups.
+pub const DEFAULT_MAX_LATENCY: Duration = Duration::from_secs(10);
+
+/// Options for [`start`], everything besides the transport and the SETUP stream.
+pub struct StartConfig {
+	/// The version of the protocol to use.
+	pub version: Version,
+	/// We will publish any local broadcasts from this origin.
+	pub publish: Option<OriginConsumer>,
+	/// We will consume any remote broadcasts, inserting them into this origin.
+	pub subscribe: Option<OriginProducer>,
+	/// Tier-scoped stats handle. Use [`StatsHandle::default`] to opt out.
+	pub stats: StatsHandle,
+	/// Egress caps for each subscribed track, picked by the track's priority.
+	pub rate_limit: RateLimit,
+	/// The maximum number of incoming group streams processed concurrently, or zero for no limit.
+	pub max_streams: usize,
+	/// Drop incoming groups this far behind the newest one received, per track.
+	pub group_window: Option<u64>,
+	/// Sent in every SUBSCRIBE: how much older than the latest group a group may be
+	/// before the publisher drops it.
+	pub max_latency: Duration,
+	/// The SETUP message to advertise on the Setup stream (moq-lite-05+). Ignored on
+	/// earlier versions, which have no Setup stream.
+	pub setup: Setup,
+}
+
 pub fn start<S: web_transport_trait::Session>(
 	session: S,
 	// The stream used to setup the session, after exchanging setup messages.
 	// NOTE: No longer used in draft-03.
 	setup: Option<Stream<S, Version>>,
-	// We will publish any local broadcasts from this origin.
-	publish: Option<OriginConsumer>,
-	// We will consume any remote broadcasts, inserting them into this origin.
-	subscribe: Option<OriginProducer>,
-	// Tier-scoped stats handle. Pass [`StatsHandle::default`] to opt out.
-	stats: StatsHandle,
-	// The version of the protocol to use.
-	version: Version,
-	// The SETUP message to advertise on the Setup stream (moq-lite-05+). Ignored on
-	// earlier versions, which have no Setup stream.
-	our_setup: Setup,
+	config: StartConfig,
 ) -> Result<Option<BandwidthConsumer>, Error> {
+	let StartConfig {
+		version,
+		publish,
+		subscribe,
+		stats,
+		rate_limit,
+		max_streams,
+		group_window,
+		max_latency,
+		setup: our_setup,
+	} = config;
+
 	let recv_bw = BandwidthProducer::new();
 
 	let recv_bw_consumer = match version {
@@ -42,6 +77,7 @@ pub fn start<S: web_transport_trait::Session>(
 		session: session.clone(),
 		origin: publish,
 		stats: stats.clone(),
+		rate_limit,
 		version,
 	});
 	let subscriber = Subscriber::new(SubscriberConfig {
@@ -49,6 +85,9 @@ pub fn start<S: web_transport_trait::Session>(
 		origin: subscribe,
 		recv_bandwidth: recv_bw_for_sub,
 		stats,
+		max_streams: StreamLimit::new(max_streams),
+		group_window,
+		max_latency,
 		version,
 	});
 
@@ -74,8 +113,8 @@ pub fn start<S: web_transport_trait::Session>(
 		};
 
 		match res {
-			Err(Error::Transport(_)) => {
-				tracing::info!("session terminated");
+			Err(Error::Transport(err)) => {
+				tracing::info!(%err, "session terminated");
 				session.close(1, "");
 			}
 			Err(err) => {
@@ -4,6 +4,12 @@ use super::Version;
 
 use num_enum::{IntoPrimitive, TryFromPrimitive};
 
+/// The first value on every moq-lite bidirectional stream, naming the request it carries.
+///
+/// This doesn't choose between moq-lite and IETF: by the time a stream is opened, the
+/// session already speaks one of them. The protocol is picked by ALPN, or, for the legacy
+/// `moql` ALPN and no ALPN at all, by the version negotiated in a SETUP sent with the IETF
+/// encoding (see [`crate::Client::connect`]).
 #[derive(Debug, PartialEq, Clone, Copy, IntoPrimitive, TryFromPrimitive)]
 #[repr(u64)]
 pub enum ControlType {
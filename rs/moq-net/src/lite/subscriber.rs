@@ -9,8 +9,10 @@ use crate::{
 	AsPath, BandwidthProducer, Broadcast, BroadcastDynamic, Error, Frame, FrameProducer, Group, GroupProducer,
 	MAX_FRAME_SIZE, OriginProducer, Path, PathOwned, StatsHandle, SubscriberStats, SubscriberTrack, TrackProducer,
 	coding::{Reader, Stream},
+	limit::StreamLimit,
 	lite,
 	model::BroadcastProducer,
+	window::GroupWindow,
 };
 
 use super::Version;
@@ -27,6 +29,15 @@ pub(super) struct SubscriberConfig<S: web_transport_trait::Session> {
 	/// Stats aggregator for this session's ingress. Use [`StatsHandle::default`]
 	/// to opt out.
 	pub stats: StatsHandle,
+	/// Caps the group streams processed concurrently.
+	/// Further streams are left unaccepted until a slot frees up.
+	pub max_streams: StreamLimit,
+	/// Drop incoming groups this far behind the newest one of their track.
+	/// None keeps every group.
+	pub group_window: Option<u64>,
+	/// Sent in every SUBSCRIBE: the publisher resets a non-latest group once it is this
+	/// much older than the latest one.
+	pub max_latency: std::time::Duration,
 	pub version: Version,
 }
 
@@ -54,6 +65,9 @@ pub(super) struct Subscriber<S: web_transport_trait::Session> {
 	session_origin: crate::Origin,
 	subscribes: Lock<HashMap<u64, TrackEntry>>,
 	next_id: Arc<atomic::AtomicU64>,
+	max_streams: StreamLimit,
+	group_window: Option<u64>,
+	max_latency: std::time::Duration,
 	version: Version,
 }
 
@@ -61,6 +75,7 @@ pub(super) struct Subscriber<S: web_transport_trait::Session> {
 struct TrackEntry {
 	producer: TrackProducer,
 	stats: Arc<SubscriberTrack>,
+	window: Option<GroupWindow>,
 }
 
 impl<S: web_transport_trait::Session> Subscriber<S> {
@@ -82,6 +97,9 @@ impl<S: web_transport_trait::Session> Subscriber<S> {
 			session_origin: crate::Origin::random(),
 			subscribes: Default::default(),
 			next_id: Default::default(),
+			max_streams: config.max_streams,
+			group_window: config.group_window,
+			max_latency: config.max_latency,
 			version: config.version,
 		}
 	}
@@ -97,25 +115,36 @@ impl<S: web_transport_trait::Session> Subscriber<S> {
 
 	async fn run_uni(self) -> Result<(), Error> {
 		loop {
+			// Wait for a free slot before accepting, so QUIC flow control pushes back on the peer.
+			let permit = self.max_streams.acquire().await;
+
 			let stream = self.session.accept_uni().await.map_err(Error::from_transport)?;
 
 			let stream = Reader::new(stream, self.version);
 			let this = self.clone();
 
 			web_async::spawn(async move {
-				if let Err(err) = this.run_uni_stream(stream).await {
+				if let Err(err) = this.run_uni_stream(stream, permit).await {
 					tracing::debug!(%err, "error running uni stream");
 				}
 			});
 		}
 	}
 
-	async fn run_uni_stream(mut self, mut stream: Reader<S::RecvStream, Version>) -> Result<(), Error> {
+	async fn run_uni_stream(
+		mut self,
+		mut stream: Reader<S::RecvStream, Version>,
+		permit: Option<tokio::sync::OwnedSemaphorePermit>,
+	) -> Result<(), Error> {
 		let kind = stream.decode().await?;
 
 		let res = match kind {
 			lite::DataType::Group => self.recv_group(&mut stream).await,
-			lite::DataType::Setup => self.recv_setup(&mut stream).await,
+			lite::DataType::Setup => {
+				// Not a group, so it doesn't count against the limit.
+				drop(permit);
+				self.recv_setup(&mut stream).await
+			}
 		};
 
 		if let Err(err) = res {
@@ -397,6 +426,7 @@ impl<S: web_transport_trait::Session> Subscriber<S> {
 			TrackEntry {
 				producer: track.clone(),
 				stats: track_stats.clone(),
+				window: self.group_window.map(GroupWindow::new),
 			},
 		);
 
@@ -406,8 +436,8 @@ impl<S: web_transport_trait::Session> Subscriber<S> {
 			track: (&track.name).into(),
 			priority: track.priority,
 			ordered: true,
-			max_latency: std::time::Duration::ZERO,
-			start_group: None,
+			max_latency: self.max_latency,
+			start_group: track.requested_start(),
 			end_group: None,
 		};
 
@@ -491,21 +521,34 @@ impl<S: web_transport_trait::Session> Subscriber<S> {
 	pub async fn recv_group(&mut self, stream: &mut Reader<S::RecvStream, Version>) -> Result<(), Error> {
 		let hdr: lite::Group = stream.decode().await?;
 
-		let (mut group, track, track_stats) = {
+		let (mut group, track, track_stats, window) = {
 			let mut subs = self.subscribes.lock();
 			let entry = subs.get_mut(&hdr.subscribe).ok_or(Error::Cancel)?;
 
-			let group_info = Group { sequence: hdr.sequence };
+			// Refuse a group that is already too far behind, before it's visible to consumers.
+			if let Some(window) = &entry.window {
+				window.arrive(hdr.sequence)?;
+			}
+
+			let group_info = Group::new(hdr.sequence);
 			let group = entry.producer.create_group(group_info)?;
-			(group, entry.producer.clone(), entry.stats.clone())
+			(group, entry.producer.clone(), entry.stats.clone(), entry.window.clone())
 		};
 
 		// Bump groups counter for this incoming group on the subscriber side.
 		track_stats.group();
 
+		let expired = async {
+			match &window {
+				Some(window) => window.expired(hdr.sequence).await,
+				None => std::future::pending().await,
+			}
+		};
+
 		let res = tokio::select! {
 			err = track.closed() => Err(err),
 			err = group.closed() => Err(err),
+			_ = expired => Err(Error::Old),
 			res = self.run_group(stream, group.clone(), track_stats.clone()) => res,
 		};
 
@@ -513,6 +556,12 @@ impl<S: web_transport_trait::Session> Subscriber<S> {
 			Err(Error::Cancel) => {
 				let _ = group.abort(Error::Cancel);
 			}
+			Err(Error::Old) => {
+				// Newer groups arrived; stop the stream rather than finish a stale group.
+				tracing::debug!(group = %group.sequence, "group fell behind the window");
+				stream.abort(&Error::Old);
+				let _ = group.abort(Error::Old);
+			}
 			Err(err) => {
 				tracing::debug!(%err, group = %group.sequence, "group error");
 				let _ = group.abort(err);
@@ -549,7 +598,7 @@ impl<S: web_transport_trait::Session> Subscriber<S> {
 			if size > MAX_FRAME_SIZE {
 				return Err(Error::FrameTooLarge);
 			}
-			let mut frame = group.create_frame(Frame { size })?;
+			let mut frame = group.create_frame(Frame::new(size))?;
 			track_stats.frame();
 
 			if let Err(err) = self.run_frame(stream, &mut frame, &track_stats).await {
@@ -578,7 +627,12 @@ impl<S: web_transport_trait::Session> Subscriber<S> {
 				Some(n) if n > 0 => {
 					track_stats.bytes(n as u64);
 				}
-				_ => return Err(Error::WrongSize),
+				_ => {
+					// Only this group's stream is short; the track carries on with the next group.
+					let remaining = bytes::BufMut::remaining_mut(frame);
+					tracing::debug!(size = frame.size, remaining, "stream ended mid-frame");
+					return Err(Error::WrongSize);
+				}
 			}
 		}
 		Ok(())
@@ -0,0 +1,1507 @@
+//! An in-memory transport implementing the [web_transport_trait] traits.
+//!
+//! [pair] returns two connected sessions whose streams are backed by channels, so tests can
+//! drive a full SETUP, announce and subscribe round-trip without QUIC. Delivery is in order
+//! and lossless; stream priorities are ignored, unless a [Fault] is injected to exercise the
+//! peer's error paths. Closing either end fails any read still waiting for data, as a QUIC
+//! connection close would.
+//!
+//! Enabled with the `loopback` feature. Test-only: never ship it in a production build.
+
+use std::sync::Arc;
+
+use bytes::{Buf, Bytes};
+use tokio::sync::{Mutex, mpsc, watch};
+
+/// Create two connected sessions, each negotiating the given ALPN `protocol`.
+pub fn pair(protocol: Option<&str>) -> (Session, Session) {
+	let protocol: Option<Arc<str>> = protocol.map(Into::into);
+	let closed = Arc::new(watch::channel(None).0);
+
+	let (a_bi, b_bi_rx) = mpsc::unbounded_channel();
+	let (b_bi, a_bi_rx) = mpsc::unbounded_channel();
+	let (a_uni, b_uni_rx) = mpsc::unbounded_channel();
+	let (b_uni, a_uni_rx) = mpsc::unbounded_channel();
+	let (a_datagram, b_datagram_rx) = mpsc::unbounded_channel();
+	let (b_datagram, a_datagram_rx) = mpsc::unbounded_channel();
+
+	let a = Session {
+		protocol: protocol.clone(),
+		fault: Default::default(),
+		bi: a_bi,
+		uni: a_uni,
+		datagram: a_datagram,
+		inbox: Arc::new(Inbox {
+			bi: Mutex::new(a_bi_rx),
+			uni: Mutex::new(a_uni_rx),
+			datagram: Mutex::new(a_datagram_rx),
+		}),
+		closed: closed.clone(),
+	};
+
+	let b = Session {
+		protocol,
+		fault: Default::default(),
+		bi: b_bi,
+		uni: b_uni,
+		datagram: b_datagram,
+		inbox: Arc::new(Inbox {
+			bi: Mutex::new(b_bi_rx),
+			uni: Mutex::new(b_uni_rx),
+			datagram: Mutex::new(b_datagram_rx),
+		}),
+		closed,
+	};
+
+	(a, b)
+}
+
+/// A fault injected into the uni streams a session opens, set with [Session::set_fault].
+///
+/// Offsets count from the start of each stream, so they land at the same place in every group
+/// stream: pick one past the group header to cut a frame short of its size prefix.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum Fault {
+	/// Finish each stream after this many bytes, silently dropping the rest.
+	Truncate(usize),
+
+	/// Reset each stream with `code` after `after` bytes.
+	Reset { after: usize, code: u32 },
+}
+
+/// An error returned by the loopback transport.
+#[derive(thiserror::Error, Debug, Clone)]
+pub enum LoopbackError {
+	#[error("session closed: code={0} reason={1}")]
+	Closed(u32, String),
+
+	#[error("stream reset: code={0}")]
+	Reset(u32),
+
+	#[error("stream stopped")]
+	Stopped,
+}
+
+impl web_transport_trait::Error for LoopbackError {
+	fn session_error(&self) -> Option<(u32, String)> {
+		match self {
+			Self::Closed(code, reason) => Some((*code, reason.clone())),
+			_ => None,
+		}
+	}
+
+	fn stream_error(&self) -> Option<u32> {
+		match self {
+			Self::Reset(code) => Some(*code),
+			_ => None,
+		}
+	}
+}
+
+type Bi = (SendStream, RecvStream);
+
+struct Inbox {
+	bi: Mutex<mpsc::UnboundedReceiver<Bi>>,
+	uni: Mutex<mpsc::UnboundedReceiver<RecvStream>>,
+	datagram: Mutex<mpsc::UnboundedReceiver<Bytes>>,
+}
+
+/// One end of a loopback connection, created by [pair].
+#[derive(Clone)]
+pub struct Session {
+	protocol: Option<Arc<str>>,
+
+	// Shared by every clone of this end, applied to uni streams as they're opened.
+	fault: Arc<std::sync::Mutex<Option<Fault>>>,
+
+	// Streams and datagrams opened towards the peer.
+	bi: mpsc::UnboundedSender<Bi>,
+	uni: mpsc::UnboundedSender<RecvStream>,
+	datagram: mpsc::UnboundedSender<Bytes>,
+
+	// Streams and datagrams opened by the peer.
+	inbox: Arc<Inbox>,
+
+	// Shared by both ends: the close code and reason, once either side closes.
+	closed: Arc<watch::Sender<Option<(u32, String)>>>,
+}
+
+impl Session {
+	/// Inject `fault` into every uni stream this end opens from now on, or stop with `None`.
+	///
+	/// The publisher writes each group on a uni stream, so this corrupts the groups it sends
+	/// without touching the control streams.
+	pub fn set_fault(&self, fault: Option<Fault>) {
+		*self.fault.lock().unwrap() = fault;
+	}
+
+	fn check(&self) -> Result<(), LoopbackError> {
+		match &*self.closed.borrow() {
+			Some((code, reason)) => Err(LoopbackError::Closed(*code, reason.clone())),
+			None => Ok(()),
+		}
+	}
+
+	async fn wait_closed(&self) -> LoopbackError {
+		let mut closed = self.closed.subscribe();
+		match closed.wait_for(Option::is_some).await {
+			Ok(state) => {
+				let (code, reason) = state.clone().unwrap();
+				LoopbackError::Closed(code, reason)
+			}
+			// Unreachable: we hold the sender.
+			Err(_) => LoopbackError::Closed(0, String::new()),
+		}
+	}
+
+	async fn accept<T>(&self, inbox: &Mutex<mpsc::UnboundedReceiver<T>>) -> Result<T, LoopbackError> {
+		self.check()?;
+		let mut inbox = inbox.lock().await;
+		tokio::select! {
+			Some(item) = inbox.recv() => Ok(item),
+			err = self.wait_closed() => Err(err),
+		}
+	}
+}
+
+impl web_transport_trait::Session for Session {
+	type SendStream = SendStream;
+	type RecvStream = RecvStream;
+	type Error = LoopbackError;
+
+	async fn accept_uni(&self) -> Result<Self::RecvStream, Self::Error> {
+		self.accept(&self.inbox.uni).await
+	}
+
+	async fn accept_bi(&self) -> Result<(Self::SendStream, Self::RecvStream), Self::Error> {
+		self.accept(&self.inbox.bi).await
+	}
+
+	async fn open_bi(&self) -> Result<(Self::SendStream, Self::RecvStream), Self::Error> {
+		self.check()?;
+		let (local_send, remote_recv) = stream(self.closed.subscribe());
+		let (remote_send, local_recv) = stream(self.closed.subscribe());
+		self.bi
+			.send((remote_send, remote_recv))
+			.map_err(|_| LoopbackError::Closed(0, String::new()))?;
+		Ok((local_send, local_recv))
+	}
+
+	async fn open_uni(&self) -> Result<Self::SendStream, Self::Error> {
+		self.check()?;
+		let (mut send, recv) = stream(self.closed.subscribe());
+		send.fault = *self.fault.lock().unwrap();
+		self.uni
+			.send(recv)
+			.map_err(|_| LoopbackError::Closed(0, String::new()))?;
+		Ok(send)
+	}
+
+	fn send_datagram(&self, payload: Bytes) -> Result<(), Self::Error> {
+		self.check()?;
+		// Datagrams are unreliable anyway, so a dropped peer is not an error.
+		let _ = self.datagram.send(payload);
+		Ok(())
+	}
+
+	async fn recv_datagram(&self) -> Result<Bytes, Self::Error> {
+		self.accept(&self.inbox.datagram).await
+	}
+
+	fn max_datagram_size(&self) -> usize {
+		1200
+	}
+
+	fn protocol(&self) -> Option<&str> {
+		self.protocol.as_deref()
+	}
+
+	fn close(&self, code: u32, reason: &str) {
+		self.closed.send_if_modified(|state| {
+			if state.is_some() {
+				return false;
+			}
+			*state = Some((code, reason.to_string()));
+			true
+		});
+	}
+
+	async fn closed(&self) -> Self::Error {
+		self.wait_closed().await
+	}
+}
+
+enum Chunk {
+	Data(Bytes),
+	Fin,
+	Reset(u32),
+}
+
+fn stream(closed: watch::Receiver<Option<(u32, String)>>) -> (SendStream, RecvStream) {
+	let (tx, rx) = mpsc::unbounded_channel();
+	(
+		SendStream {
+			tx,
+			done: false,
+			fault: None,
+			written: 0,
+			closed: closed.clone(),
+		},
+		RecvStream {
+			rx,
+			chunk: Bytes::new(),
+			done: false,
+			closed,
+		},
+	)
+}
+
+/// The sending half of a loopback stream.
+pub struct SendStream {
+	tx: mpsc::UnboundedSender<Chunk>,
+	// Set once finished or reset; later writes fail.
+	done: bool,
+	// Applied once `written` reaches the fault's offset, after which writes are swallowed.
+	fault: Option<Fault>,
+	written: usize,
+	// The connection's close, after which nothing more is delivered.
+	closed: watch::Receiver<Option<(u32, String)>>,
+}
+
+impl SendStream {
+	fn check(&self) -> Result<(), LoopbackError> {
+		match &*self.closed.borrow() {
+			Some((code, reason)) => Err(LoopbackError::Closed(*code, reason.clone())),
+			None => Ok(()),
+		}
+	}
+}
+
+impl web_transport_trait::SendStream for SendStream {
+	type Error = LoopbackError;
+
+	async fn write(&mut self, buf: &[u8]) -> Result<usize, Self::Error> {
+		self.check()?;
+
+		let Some(fault) = self.fault else {
+			if self.done {
+				return Err(LoopbackError::Stopped);
+			}
+			self.tx
+				.send(Chunk::Data(Bytes::copy_from_slice(buf)))
+				.map_err(|_| LoopbackError::Stopped)?;
+			return Ok(buf.len());
+		};
+
+		// Pretend the whole buffer went out, so the writer carries on as if nothing happened.
+		let (after, end) = match fault {
+			Fault::Truncate(after) => (after, Chunk::Fin),
+			Fault::Reset { after, code } => (after, Chunk::Reset(code)),
+		};
+		if !self.done {
+			let size = buf.len().min(after.saturating_sub(self.written));
+			self.written += size;
+			if size > 0 {
+				let _ = self.tx.send(Chunk::Data(Bytes::copy_from_slice(&buf[..size])));
+			}
+			if self.written == after {
+				self.done = true;
+				let _ = self.tx.send(end);
+			}
+		}
+		Ok(buf.len())
+	}
+
+	fn set_priority(&mut self, _order: u8) {}
+
+	fn finish(&mut self) -> Result<(), Self::Error> {
+		self.check()?;
+		if self.done && self.fault.is_some() {
+			return Ok(());
+		}
+		if self.done {
+			return Err(LoopbackError::Stopped);
+		}
+		self.done = true;
+		self.tx.send(Chunk::Fin).map_err(|_| LoopbackError::Stopped)
+	}
+
+	fn reset(&mut self, code: u32) {
+		if !self.done {
+			self.done = true;
+			let _ = self.tx.send(Chunk::Reset(code));
+		}
+	}
+
+	async fn closed(&mut self) -> Result<(), Self::Error> {
+		// Resolves once the peer reads the FIN, stops, or drops its end.
+		self.tx.closed().await;
+		Ok(())
+	}
+}
+
+/// The receiving half of a loopback stream.
+pub struct RecvStream {
+	rx: mpsc::UnboundedReceiver<Chunk>,
+	// Unread bytes from the last chunk.
+	chunk: Bytes,
+	// Set once the FIN was received.
+	done: bool,
+	// The connection's close, which fails a read still waiting for data.
+	closed: watch::Receiver<Option<(u32, String)>>,
+}
+
+impl web_transport_trait::RecvStream for RecvStream {
+	type Error = LoopbackError;
+
+	async fn read(&mut self, dst: &mut [u8]) -> Result<Option<usize>, Self::Error> {
+		while self.chunk.is_empty() {
+			if self.done {
+				return Ok(None);
+			}
+
+			let chunk = tokio::select! {
+				biased;
+				chunk = self.rx.recv() => chunk,
+				Ok(closed) = self.closed.wait_for(Option::is_some) => {
+					let (code, reason) = closed.clone().unwrap();
+					return Err(LoopbackError::Closed(code, reason));
+				}
+			};
+
+			match chunk {
+				Some(Chunk::Data(chunk)) => self.chunk = chunk,
+				Some(Chunk::Reset(code)) => return Err(LoopbackError::Reset(code)),
+				Some(Chunk::Fin) => self.fin(),
+				// The peer dropped its end without a FIN, which only the connection closing explains.
+				None => match &*self.closed.borrow() {
+					Some((code, reason)) => return Err(LoopbackError::Closed(*code, reason.clone())),
+					None => self.done = true,
+				},
+			}
+		}
+
+		let size = dst.len().min(self.chunk.len());
+		self.chunk.copy_to_slice(&mut dst[..size]);
+		Ok(Some(size))
+	}
+
+	fn stop(&mut self, _code: u32) {
+		self.rx.close();
+	}
+
+	async fn closed(&mut self) -> Result<(), Self::Error> {
+		while !self.done {
+			match self.rx.recv().await {
+				Some(Chunk::Data(_)) => {}
+				Some(Chunk::Reset(code)) => return Err(LoopbackError::Reset(code)),
+				Some(Chunk::Fin) => self.fin(),
+				None => self.done = true,
+			}
+		}
+		Ok(())
+	}
+}
+
+impl RecvStream {
+	// Everything up to the FIN arrived, so the sender's `closed` resolves, as an acknowledgement would.
+	fn fin(&mut self) {
+		self.done = true;
+		self.rx.close();
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::{Client, CloseReason, Error, Origin, Server, Track};
+	use web_transport_trait::{RecvStream as _, SendStream as _, Session as _};
+
+	#[tokio::test]
+	async fn uni_stream_round_trip() {
+		let (a, b) = pair(None);
+
+		let mut send = a.open_uni().await.unwrap();
+		send.write(b"hello").await.unwrap();
+		send.finish().unwrap();
+
+		let mut recv = b.accept_uni().await.unwrap();
+		let mut buf = [0u8; 16];
+		let n = recv.read(&mut buf).await.unwrap().unwrap();
+		assert_eq!(&buf[..n], b"hello");
+		assert_eq!(recv.read(&mut buf).await.unwrap(), None);
+	}
+
+	#[tokio::test]
+	async fn reset_surfaces_code() {
+		let (a, b) = pair(None);
+
+		let mut send = a.open_uni().await.unwrap();
+		send.reset(7);
+
+		let mut recv = b.accept_uni().await.unwrap();
+		let mut buf = [0u8; 16];
+		assert!(matches!(recv.read(&mut buf).await, Err(LoopbackError::Reset(7))));
+	}
+
+	#[tokio::test]
+	async fn close_wakes_both_ends() {
+		let (a, b) = pair(None);
+		a.close(3, "bye");
+
+		assert!(matches!(b.closed().await, LoopbackError::Closed(3, _)));
+		assert!(b.accept_uni().await.is_err());
+	}
+
+	#[tokio::test]
+	async fn close_reason_tells_a_close_from_a_failure() {
+		let (client_session, server_session) = pair(None);
+		let server = Server::new().with_publish(Origin::random().produce().consume());
+		let client = Client::new().with_consume(Origin::random().produce());
+		let (server, client) = tokio::join!(server.accept(server_session), client.connect(client_session));
+		let (mut server, client) = (server.unwrap(), client.unwrap());
+
+		server.close(Error::Cancel);
+		let reason = tokio::time::timeout(std::time::Duration::from_secs(5), client.close_reason())
+			.await
+			.unwrap();
+		assert_eq!(
+			reason,
+			CloseReason::Closed {
+				code: 0,
+				reason: "cancelled".into()
+			}
+		);
+
+		// An error without a session close code is the connection failing underneath.
+		assert!(matches!(
+			CloseReason::from_transport(LoopbackError::Stopped),
+			CloseReason::Transport(_)
+		));
+	}
+
+	#[tokio::test]
+	async fn ietf_subscribe_round_trip() {
+		let (client_session, server_session) = pair(Some(crate::version::ALPN_14));
+
+		let origin = Origin::random().produce();
+		let mut broadcast = origin.create_broadcast("demo").unwrap();
+		let mut track = broadcast.create_track(Track::new("video")).unwrap();
+
+		let remote = Origin::random().produce();
+		let server = Server::new().with_publish(origin.consume());
+		let client = Client::new().with_consume(remote.clone());
+
+		let (server, client) = tokio::join!(server.accept(server_session), client.connect(client_session));
+		let (_server, _client) = (server.unwrap(), client.unwrap());
+
+		let timeout = std::time::Duration::from_secs(5);
+		let consumer = tokio::time::timeout(timeout, remote.consume().announced_broadcast("demo"))
+			.await
+			.unwrap()
+			.unwrap();
+		let mut subscription = consumer.subscribe_track(&Track::new("video")).unwrap();
+
+		track.write_frame(Bytes::from_static(b"hello")).unwrap();
+
+		let frame = tokio::time::timeout(timeout, subscription.read_frame())
+			.await
+			.unwrap()
+			.unwrap();
+		assert_eq!(frame, Some(Bytes::from_static(b"hello")));
+	}
+
+	#[tokio::test]
+	async fn truncate_fault() {
+		let (a, b) = pair(None);
+		a.set_fault(Some(Fault::Truncate(3)));
+
+		let mut send = a.open_uni().await.unwrap();
+		assert_eq!(send.write(b"hello").await.unwrap(), 5);
+		send.finish().unwrap();
+
+		let mut recv = b.accept_uni().await.unwrap();
+		let mut buf = [0u8; 16];
+		let n = recv.read(&mut buf).await.unwrap().unwrap();
+		assert_eq!(&buf[..n], b"hel");
+		assert_eq!(recv.read(&mut buf).await.unwrap(), None);
+	}
+
+	#[tokio::test]
+	async fn ietf_truncated_frame_aborts_group() {
+		let (client_session, server_session) = pair(Some(crate::version::ALPN_14));
+		// Cut each group stream a few bytes into the frame payload.
+		let faults = server_session.clone();
+		faults.set_fault(Some(Fault::Truncate(32)));
+
+		let origin = Origin::random().produce();
+		let mut broadcast = origin.create_broadcast("demo").unwrap();
+		let mut track = broadcast.create_track(Track::new("video")).unwrap();
+
+		let remote = Origin::random().produce();
+		let server = Server::new().with_publish(origin.consume());
+		let client = Client::new().with_consume(remote.clone());
+
+		let (server, client) = tokio::join!(server.accept(server_session), client.connect(client_session));
+		let (_server, _client) = (server.unwrap(), client.unwrap());
+
+		let timeout = std::time::Duration::from_secs(5);
+		let consumer = tokio::time::timeout(timeout, remote.consume().announced_broadcast("demo"))
+			.await
+			.unwrap()
+			.unwrap();
+		let mut subscription = consumer.subscribe_track(&Track::new("video")).unwrap();
+
+		track.write_frame(Bytes::from(vec![0u8; 1024])).unwrap();
+
+		// The subscriber aborts the group rather than waiting for bytes that never come.
+		let mut group = tokio::time::timeout(timeout, subscription.recv_group())
+			.await
+			.unwrap()
+			.unwrap()
+			.unwrap();
+		let frame = tokio::time::timeout(timeout, group.read_frame()).await.unwrap();
+		assert!(matches!(frame, Err(crate::Error::WrongSize)));
+
+		// Each group has its own stream, so the next one still arrives intact.
+		faults.set_fault(None);
+		track.write_frame(Bytes::from_static(b"hello")).unwrap();
+
+		let mut group = tokio::time::timeout(timeout, subscription.recv_group())
+			.await
+			.unwrap()
+			.unwrap()
+			.unwrap();
+		let frame = tokio::time::timeout(timeout, group.read_frame()).await.unwrap();
+		assert_eq!(frame.unwrap(), Some(Bytes::from_static(b"hello")));
+	}
+
+	#[tokio::test]
+	async fn ietf_track_end_round_trip() {
+		let (client_session, server_session) = pair(Some(crate::version::ALPN_14));
+
+		let origin = Origin::random().produce();
+		let mut broadcast = origin.create_broadcast("demo").unwrap();
+		let mut track = broadcast.create_track(Track::new("video")).unwrap();
+
+		let remote = Origin::random().produce();
+		let server = Server::new().with_publish(origin.consume());
+		let client = Client::new().with_consume(remote.clone());
+
+		let (server, client) = tokio::join!(server.accept(server_session), client.connect(client_session));
+		let (_server, _client) = (server.unwrap(), client.unwrap());
+
+		let timeout = std::time::Duration::from_secs(5);
+		let consumer = tokio::time::timeout(timeout, remote.consume().announced_broadcast("demo"))
+			.await
+			.unwrap()
+			.unwrap();
+		let mut subscription = consumer.subscribe_track(&Track::new("video")).unwrap();
+
+		track.write_frame(Bytes::from_static(b"last")).unwrap();
+		let frame = tokio::time::timeout(timeout, subscription.read_frame())
+			.await
+			.unwrap()
+			.unwrap();
+		assert_eq!(frame, Some(Bytes::from_static(b"last")));
+
+		// Finishing the track reaches the subscriber as an end of stream, not an error.
+		track.finish().unwrap();
+		tokio::time::timeout(timeout, subscription.finished())
+			.await
+			.unwrap()
+			.unwrap();
+	}
+
+	#[tokio::test]
+	async fn ietf_finish_keeps_receiving() {
+		let (client_session, server_session) = pair(Some(crate::version::ALPN_14));
+
+		let origin = Origin::random().produce();
+		let mut broadcast = origin.create_broadcast("demo").unwrap();
+		let mut track = broadcast.create_track(Track::new("video")).unwrap();
+
+		let remote = Origin::random().produce();
+		let server = Server::new().with_publish(origin.consume());
+		let client = Client::new().with_consume(remote.clone());
+
+		let (server, client) = tokio::join!(server.accept(server_session), client.connect(client_session));
+		let (_server, mut client) = (server.unwrap(), client.unwrap());
+
+		let timeout = std::time::Duration::from_secs(5);
+		let consumer = tokio::time::timeout(timeout, remote.consume().announced_broadcast("demo"))
+			.await
+			.unwrap()
+			.unwrap();
+		let mut subscription = consumer.subscribe_track(&Track::new("video")).unwrap();
+
+		track.write_frame(Bytes::from_static(b"last")).unwrap();
+		let frame = tokio::time::timeout(timeout, subscription.read_frame())
+			.await
+			.unwrap()
+			.unwrap();
+		assert_eq!(frame, Some(Bytes::from_static(b"last")));
+
+		// The client stops sending, but the server's PUBLISH_DONE still gets through.
+		client.finish();
+		track.finish().unwrap();
+		tokio::time::timeout(timeout, subscription.finished())
+			.await
+			.unwrap()
+			.unwrap();
+
+		// A new SUBSCRIBE can't be written any more, so it fails rather than hanging.
+		let mut other = consumer.subscribe_track(&Track::new("audio")).unwrap();
+		let res = tokio::time::timeout(timeout, other.recv_group()).await.unwrap();
+		assert!(res.is_err());
+	}
+
+	async fn track_status_case(alpn: &str) {
+		let (client_session, server_session) = pair(Some(alpn));
+
+		let origin = Origin::random().produce();
+		let mut broadcast = origin.create_broadcast("demo").unwrap();
+		let _track = broadcast.create_track(Track::new("video")).unwrap();
+		let mut dynamic = broadcast.dynamic();
+
+		let server = Server::new().with_publish(origin.consume());
+		let client = Client::new().with_consume(Origin::random().produce());
+
+		let (server, client) = tokio::join!(server.accept(server_session), client.connect(client_session));
+		let (_server, client) = (server.unwrap(), client.unwrap());
+
+		let timeout = std::time::Duration::from_secs(5);
+		let status = tokio::time::timeout(timeout, client.track_status("demo", "video"))
+			.await
+			.unwrap()
+			.unwrap();
+		assert_eq!(status, crate::TrackStatus::Active { largest: None });
+
+		let status = tokio::time::timeout(timeout, client.track_status("missing", "video"))
+			.await
+			.unwrap()
+			.unwrap();
+		assert_eq!(status, crate::TrackStatus::NotFound);
+
+		// A probe only looks the track up; it never asks the broadcast to produce it.
+		let status = tokio::time::timeout(timeout, client.track_status("demo", "audio"))
+			.await
+			.unwrap()
+			.unwrap();
+		assert_eq!(status, crate::TrackStatus::NotFound);
+		dynamic.assert_no_request();
+	}
+
+	#[tokio::test]
+	async fn ietf_track_status_draft14() {
+		track_status_case(crate::version::ALPN_14).await;
+	}
+
+	#[tokio::test]
+	async fn ietf_track_status_draft16() {
+		track_status_case(crate::version::ALPN_16).await;
+	}
+
+	#[tokio::test]
+	async fn ietf_track_status_draft17() {
+		track_status_case(crate::version::ALPN_17).await;
+	}
+
+	async fn subscribe_armed_case(alpn: &str) {
+		let (client_session, server_session) = pair(Some(alpn));
+
+		let origin = Origin::random().produce();
+		let mut broadcast = origin.create_broadcast("demo").unwrap();
+		let mut track = broadcast.create_track(Track::new("video")).unwrap();
+
+		let remote = Origin::random().produce();
+		let server = Server::new().with_publish(origin.consume());
+		let client = Client::new().with_consume(remote.clone());
+
+		let (server, client) = tokio::join!(server.accept(server_session), client.connect(client_session));
+		let (_server, client) = (server.unwrap(), client.unwrap());
+
+		let timeout = std::time::Duration::from_secs(5);
+		tokio::time::timeout(timeout, remote.consume().announced_broadcast("demo"))
+			.await
+			.unwrap()
+			.unwrap();
+
+		let (mut armed, forward) = client.subscribe_armed("demo", &Track::new("video")).unwrap();
+
+		// Subscribed, but nothing is forwarded until playback starts.
+		track.write_frame(Bytes::from_static(b"early")).unwrap();
+		let idle = std::time::Duration::from_millis(100);
+		assert!(tokio::time::timeout(idle, armed.recv_group()).await.is_err());
+
+		forward.set_forward(true);
+		track.write_frame(Bytes::from_static(b"live")).unwrap();
+		let group = tokio::time::timeout(timeout, armed.recv_group())
+			.await
+			.unwrap()
+			.unwrap();
+		assert!(group.is_some());
+	}
+
+	#[tokio::test]
+	async fn ietf_subscribe_armed_draft14() {
+		subscribe_armed_case(crate::version::ALPN_14).await;
+	}
+
+	#[tokio::test]
+	async fn ietf_subscribe_armed_draft17() {
+		subscribe_armed_case(crate::version::ALPN_17).await;
+	}
+
+	#[tokio::test]
+	async fn lite_subscribe_armed_unsupported() {
+		let (client_session, server_session) = pair(Some(crate::version::ALPN_LITE_03));
+
+		let server = Server::new().with_publish(Origin::random().produce().consume());
+		let client = Client::new().with_consume(Origin::random().produce());
+		let (server, client) = tokio::join!(server.accept(server_session), client.connect(client_session));
+		let (_server, client) = (server.unwrap(), client.unwrap());
+
+		let res = client.subscribe_armed("demo", &Track::new("video"));
+		assert!(matches!(res, Err(crate::Error::Unsupported)));
+	}
+
+	async fn subscribe_from_case(alpn: &str) {
+		let (client_session, server_session) = pair(Some(alpn));
+
+		let origin = Origin::random().produce();
+		let mut broadcast = origin.create_broadcast("demo").unwrap();
+		let mut track = broadcast.create_track(Track::new("video")).unwrap();
+
+		let remote = Origin::random().produce();
+		let server = Server::new().with_publish(origin.consume());
+		let client = Client::new().with_consume(remote.clone());
+
+		let (server, client) = tokio::join!(server.accept(server_session), client.connect(client_session));
+		let (_server, client) = (server.unwrap(), client.unwrap());
+
+		let timeout = std::time::Duration::from_secs(5);
+		tokio::time::timeout(timeout, remote.consume().announced_broadcast("demo"))
+			.await
+			.unwrap()
+			.unwrap();
+
+		// Two cached groups; playback starts at the keyframe in the middle of the second.
+		track.write_frame(Bytes::from_static(b"before")).unwrap();
+		let mut group = track.append_group().unwrap();
+		for frame in [b"delta", b"key00", b"after"] {
+			group.write_frame(Bytes::from_static(frame)).unwrap();
+		}
+		group.finish().unwrap();
+
+		let mut subscription = client.subscribe_from("demo", &Track::new("video"), (1, 1)).unwrap();
+		let mut group = tokio::time::timeout(timeout, subscription.recv_group())
+			.await
+			.unwrap()
+			.unwrap()
+			.unwrap();
+		assert_eq!(group.sequence, 1);
+
+		// The skipped object keeps its slot as an empty frame.
+		assert_eq!(group.read_frame().await.unwrap(), Some(Bytes::new()));
+		assert_eq!(group.read_frame().await.unwrap(), Some(Bytes::from_static(b"key00")));
+		assert_eq!(group.read_frame().await.unwrap(), Some(Bytes::from_static(b"after")));
+		assert_eq!(group.read_frame().await.unwrap(), None);
+	}
+
+	#[tokio::test]
+	async fn ietf_subscribe_from_draft14() {
+		subscribe_from_case(crate::version::ALPN_14).await;
+	}
+
+	#[tokio::test]
+	async fn ietf_subscribe_from_draft17() {
+		subscribe_from_case(crate::version::ALPN_17).await;
+	}
+
+	async fn resume_case(alpn: &str) {
+		let origin = Origin::random().produce();
+		let mut broadcast = origin.create_broadcast("demo").unwrap();
+		let mut track = broadcast.create_track(Track::new("video")).unwrap();
+
+		let remote = Origin::random().produce();
+		let mut resume = crate::ResumeTrack::new(&remote.consume(), "demo", Track::new("video")).unwrap();
+
+		let connect = || async {
+			let (client_session, server_session) = pair(Some(alpn));
+			let server = Server::new().with_publish(origin.consume());
+			let client = Client::new().with_consume(remote.clone());
+			let (server, client) = tokio::join!(server.accept(server_session), client.connect(client_session));
+			(server.unwrap(), client.unwrap())
+		};
+
+		let timeout = std::time::Duration::from_secs(5);
+		let mut next = async || {
+			let group = tokio::time::timeout(timeout, resume.next_group()).await.unwrap();
+			group.unwrap().unwrap().sequence
+		};
+
+		let session = connect().await;
+		track.write_frame(Bytes::from_static(b"0")).unwrap();
+		assert_eq!(next().await, 0);
+
+		track.write_frame(Bytes::from_static(b"1")).unwrap();
+		assert_eq!(next().await, 1);
+
+		drop(session);
+
+		// Published while disconnected, then recovered from the publisher's cache.
+		track.write_frame(Bytes::from_static(b"2")).unwrap();
+		track.write_frame(Bytes::from_static(b"3")).unwrap();
+
+		let _session = connect().await;
+		assert_eq!(next().await, 2);
+		assert_eq!(next().await, 3);
+	}
+
+	#[tokio::test]
+	async fn ietf_resume_recovers_the_gap() {
+		resume_case(crate::version::ALPN_14).await;
+	}
+
+	#[tokio::test]
+	async fn lite_resume_recovers_the_gap() {
+		resume_case(crate::version::ALPN_LITE_03).await;
+	}
+
+	/// Serve one group, then end the track either by finishing it or with an explicit marker frame.
+	async fn end_of_track_case(marker: bool) {
+		let (client_session, server_session) = pair(Some(crate::version::ALPN_14));
+
+		let origin = Origin::random().produce();
+		let mut broadcast = origin.create_broadcast("demo").unwrap();
+		let mut track = broadcast.create_track(Track::new("video")).unwrap();
+
+		let remote = Origin::random().produce();
+		let server = Server::new().with_publish(origin.consume());
+		let client = Client::new().with_consume(remote.clone());
+
+		let (server, client) = tokio::join!(server.accept(server_session), client.connect(client_session));
+		let (_server, _client) = (server.unwrap(), client.unwrap());
+
+		let timeout = std::time::Duration::from_secs(5);
+		let consumer = tokio::time::timeout(timeout, remote.consume().announced_broadcast("demo"))
+			.await
+			.unwrap()
+			.unwrap();
+		let mut subscription = consumer.subscribe_track(&Track::new("video")).unwrap();
+
+		track.write_frame(Bytes::from_static(b"last")).unwrap();
+		let group = tokio::time::timeout(timeout, subscription.recv_group())
+			.await
+			.unwrap()
+			.unwrap()
+			.unwrap();
+		assert_eq!(group.sequence, 0);
+
+		if marker {
+			let mut group = track.append_group().unwrap();
+			group
+				.create_frame(crate::Frame::end_of_track())
+				.unwrap()
+				.finish()
+				.unwrap();
+			group.finish().unwrap();
+		}
+		track.finish().unwrap();
+
+		// Either way the subscriber gets the End of Track object as a marker in the group after the last.
+		let mut group = tokio::time::timeout(timeout, subscription.recv_group())
+			.await
+			.unwrap()
+			.unwrap()
+			.unwrap();
+		assert_eq!(group.sequence, 1);
+		let frame = group.next_frame().await.unwrap().unwrap();
+		assert!(frame.is_end_of_track);
+		assert_eq!(frame.size, 0);
+		assert!(group.next_frame().await.unwrap().is_none());
+
+		let next = tokio::time::timeout(timeout, subscription.recv_group()).await.unwrap();
+		assert!(next.unwrap().is_none());
+		assert_eq!(subscription.finished().await.unwrap(), 2);
+	}
+
+	#[tokio::test]
+	async fn ietf_publish_update_uses_default_priority() {
+		use crate::coding::{Encode as _, Reader, Writer};
+		use crate::ietf::{self, Message as _};
+
+		let version = ietf::Version::Draft17;
+		let (client_session, server_session) = pair(Some(crate::version::ALPN_17));
+
+		let remote = Origin::random().produce();
+		let _client = Client::new()
+			.with_consume(remote.clone())
+			.connect(client_session)
+			.await
+			.unwrap();
+
+		// Play a raw publisher: SETUP to grant request IDs, then a PUBLISH with forward=0.
+		let mut parameters = ietf::Parameters::default();
+		parameters.set_max_request_id(ietf::RequestId(100));
+		let setup = crate::setup::Setup {
+			parameters: parameters.encode_bytes(version).unwrap(),
+		};
+		let mut control = Writer::new(server_session.open_uni().await.unwrap(), crate::Version::Ietf(version));
+		control.encode(&setup).await.unwrap();
+
+		let (send, recv) = server_session.open_bi().await.unwrap();
+		let (mut writer, mut reader) = (Writer::new(send, version), Reader::new(recv, version));
+		writer.encode(&ietf::Publish::ID).await.unwrap();
+		writer
+			.encode(&ietf::Publish {
+				request_id: ietf::RequestId(1),
+				track_namespace: "demo".into(),
+				track_name: "video".into(),
+				track_alias: 1,
+				group_order: ietf::GroupOrder::Descending,
+				largest_location: None,
+				forward: false,
+			})
+			.await
+			.unwrap();
+
+		// PUBLISH carries no priority, so the update that starts forwarding asks for the default.
+		let timeout = std::time::Duration::from_secs(5);
+		let update = tokio::time::timeout(timeout, async {
+			assert_eq!(reader.decode::<u64>().await.unwrap(), ietf::RequestOk::ID);
+			let _: ietf::RequestOk = reader.decode().await.unwrap();
+			assert_eq!(reader.decode::<u64>().await.unwrap(), ietf::SubscribeUpdate::ID);
+			reader.decode::<ietf::SubscribeUpdate>().await.unwrap()
+		})
+		.await
+		.unwrap();
+		assert!(update.forward);
+		assert_eq!(update.subscriber_priority, 128);
+	}
+
+	#[tokio::test]
+	async fn ietf_duplicate_track_alias_closes_session() {
+		use crate::coding::{Encode as _, Writer};
+		use crate::ietf::{self, Message as _};
+
+		let version = ietf::Version::Draft17;
+		let (client_session, server_session) = pair(Some(crate::version::ALPN_17));
+
+		let remote = Origin::random().produce();
+		let _client = Client::new()
+			.with_consume(remote.clone())
+			.connect(client_session)
+			.await
+			.unwrap();
+
+		let mut parameters = ietf::Parameters::default();
+		parameters.set_max_request_id(ietf::RequestId(100));
+		let setup = crate::setup::Setup {
+			parameters: parameters.encode_bytes(version).unwrap(),
+		};
+		let mut control = Writer::new(server_session.open_uni().await.unwrap(), crate::Version::Ietf(version));
+		control.encode(&setup).await.unwrap();
+
+		// Two PUBLISHes for different tracks that claim the same alias.
+		let mut streams = Vec::new();
+		for (request_id, track_name) in [(1, "video"), (3, "audio")] {
+			let (send, recv) = server_session.open_bi().await.unwrap();
+			let mut writer = Writer::new(send, version);
+			writer.encode(&ietf::Publish::ID).await.unwrap();
+			writer
+				.encode(&ietf::Publish {
+					request_id: ietf::RequestId(request_id),
+					track_namespace: "demo".into(),
+					track_name: track_name.into(),
+					track_alias: 1,
+					group_order: ietf::GroupOrder::Descending,
+					largest_location: None,
+					forward: false,
+				})
+				.await
+				.unwrap();
+			streams.push((writer, recv));
+		}
+
+		let timeout = std::time::Duration::from_secs(5);
+		let err = tokio::time::timeout(timeout, server_session.closed()).await.unwrap();
+		// DUPLICATE_TRACK_ALIAS, rather than the generic Error::Duplicate code.
+		assert!(matches!(err, LoopbackError::Closed(0x5, _)), "unexpected close: {err}");
+	}
+
+	#[tokio::test]
+	async fn ietf_end_of_track_object() {
+		end_of_track_case(false).await;
+	}
+
+	#[tokio::test]
+	async fn ietf_end_of_track_marker() {
+		end_of_track_case(true).await;
+	}
+
+	async fn end_of_track_across_lite_relay(alpn: &str) {
+		let timeout = std::time::Duration::from_secs(5);
+
+		let origin = Origin::random().produce();
+		let mut broadcast = origin.create_broadcast("demo").unwrap();
+		let mut track = broadcast.create_track(Track::new("video")).unwrap();
+
+		// The relay subscribes upstream over IETF and serves its downstream over moq-lite.
+		let relay = Origin::random().produce();
+		let (client_session, server_session) = pair(Some(crate::version::ALPN_14));
+		let server = Server::new().with_publish(origin.consume());
+		let client = Client::new().with_consume(relay.clone());
+		let (server, client) = tokio::join!(server.accept(server_session), client.connect(client_session));
+		let _upstream = (server.unwrap(), client.unwrap());
+
+		let remote = Origin::random().produce();
+		let (client_session, server_session) = pair(Some(alpn));
+		let server = Server::new().with_publish(relay.consume());
+		let client = Client::new().with_consume(remote.clone());
+		let (server, client) = tokio::join!(server.accept(server_session), client.connect(client_session));
+		let _downstream = (server.unwrap(), client.unwrap());
+
+		let consumer = tokio::time::timeout(timeout, remote.consume().announced_broadcast("demo"))
+			.await
+			.unwrap()
+			.unwrap();
+		let mut subscription = consumer.subscribe_track(&Track::new("video")).unwrap();
+
+		track.write_frame(Bytes::from_static(b"last")).unwrap();
+		let mut group = tokio::time::timeout(timeout, subscription.recv_group())
+			.await
+			.unwrap()
+			.unwrap()
+			.unwrap();
+		assert_eq!(group.sequence, 0);
+		assert_eq!(group.read_frame().await.unwrap().unwrap(), Bytes::from_static(b"last"));
+
+		// Only the marker ends the track; the upstream track itself is left open.
+		let mut marker = track.append_group().unwrap();
+		marker
+			.create_frame(crate::Frame::end_of_track())
+			.unwrap()
+			.finish()
+			.unwrap();
+		marker.finish().unwrap();
+
+		// moq-lite gets the marker's group without the marker, then the track ends.
+		let mut group = tokio::time::timeout(timeout, subscription.recv_group())
+			.await
+			.unwrap()
+			.unwrap()
+			.unwrap();
+		assert_eq!(group.sequence, 1);
+		assert!(group.next_frame().await.unwrap().is_none());
+
+		let next = tokio::time::timeout(timeout, subscription.recv_group()).await.unwrap();
+		assert!(next.unwrap().is_none());
+	}
+
+	#[tokio::test]
+	async fn ietf_end_of_track_across_lite03_relay() {
+		end_of_track_across_lite_relay(crate::version::ALPN_LITE_03).await;
+	}
+
+	#[tokio::test]
+	async fn ietf_end_of_track_across_lite04_relay() {
+		end_of_track_across_lite_relay(crate::version::ALPN_LITE_04).await;
+	}
+
+	#[tokio::test]
+	async fn ietf_publisher_priority_across_relay() {
+		let timeout = std::time::Duration::from_secs(5);
+
+		let origin = Origin::random().produce();
+		let mut broadcast = origin.create_broadcast("demo").unwrap();
+		let mut track = broadcast.create_track(Track::new("video").with_priority(200)).unwrap();
+
+		// The relay subscribes upstream and publishes what it receives downstream.
+		let relay = Origin::random().produce();
+		let (client_session, server_session) = pair(Some(crate::version::ALPN_14));
+		let server = Server::new().with_publish(origin.consume());
+		let client = Client::new().with_consume(relay.clone());
+		let (server, client) = tokio::join!(server.accept(server_session), client.connect(client_session));
+		let _upstream = (server.unwrap(), client.unwrap());
+
+		let remote = Origin::random().produce();
+		let (client_session, server_session) = pair(Some(crate::version::ALPN_14));
+		let server = Server::new().with_publish(relay.consume());
+		let client = Client::new().with_consume(remote.clone());
+		let (server, client) = tokio::join!(server.accept(server_session), client.connect(client_session));
+		let _downstream = (server.unwrap(), client.unwrap());
+
+		let consumer = tokio::time::timeout(timeout, remote.consume().announced_broadcast("demo"))
+			.await
+			.unwrap()
+			.unwrap();
+		let mut subscription = consumer.subscribe_track(&Track::new("video")).unwrap();
+
+		track.write_frame(Bytes::from_static(b"hello")).unwrap();
+		let group = tokio::time::timeout(timeout, subscription.recv_group())
+			.await
+			.unwrap()
+			.unwrap()
+			.unwrap();
+		assert_eq!(group.priority, Some(200));
+	}
+
+	async fn capture_timestamp_case(alpn: &str) {
+		let (client_session, server_session) = pair(Some(alpn));
+
+		let origin = Origin::random().produce();
+		let mut broadcast = origin.create_broadcast("demo").unwrap();
+		let mut track = broadcast.create_track(Track::new("video")).unwrap();
+
+		let remote = Origin::random().produce();
+		let server = Server::new().with_publish(origin.consume());
+		let client = Client::new().with_consume(remote.clone());
+
+		let (server, client) = tokio::join!(server.accept(server_session), client.connect(client_session));
+		let (_server, _client) = (server.unwrap(), client.unwrap());
+
+		let timeout = std::time::Duration::from_secs(5);
+		let consumer = tokio::time::timeout(timeout, remote.consume().announced_broadcast("demo"))
+			.await
+			.unwrap()
+			.unwrap();
+		let mut subscription = consumer.subscribe_track(&Track::new("video")).unwrap();
+
+		// A timestamped group, where a later frame may still go without one.
+		let time = crate::Time::from_millis(1_500).unwrap();
+		let mut group = track.append_group().unwrap();
+		let mut frame = group.create_frame(crate::Frame::new(5).with_timestamp(time)).unwrap();
+		frame.write(Bytes::from_static(b"hello")).unwrap();
+		frame.finish().unwrap();
+		group.write_frame(Bytes::from_static(b"plain")).unwrap();
+		group.finish().unwrap();
+
+		let mut group = tokio::time::timeout(timeout, subscription.recv_group())
+			.await
+			.unwrap()
+			.unwrap()
+			.unwrap();
+		let first = group.next_frame().await.unwrap().unwrap();
+		assert_eq!(first.timestamp, Some(time));
+		let second = group.next_frame().await.unwrap().unwrap();
+		assert_eq!(second.timestamp, None);
+		assert_eq!(group.read_frame().await.unwrap(), None);
+
+		// Without a timestamp the objects carry no extensions at all.
+		track.write_frame(Bytes::from_static(b"untimed")).unwrap();
+		let mut group = tokio::time::timeout(timeout, subscription.recv_group())
+			.await
+			.unwrap()
+			.unwrap()
+			.unwrap();
+		let frame = group.next_frame().await.unwrap().unwrap();
+		assert_eq!(frame.timestamp, None);
+	}
+
+	#[tokio::test]
+	async fn ietf_capture_timestamp_draft14() {
+		capture_timestamp_case(crate::version::ALPN_14).await;
+	}
+
+	#[tokio::test]
+	async fn ietf_capture_timestamp_draft17() {
+		capture_timestamp_case(crate::version::ALPN_17).await;
+	}
+
+	async fn rate_limit_case(alpn: &str) {
+		let (client_session, server_session) = pair(Some(alpn));
+
+		let origin = Origin::random().produce();
+		let mut broadcast = origin.create_broadcast("demo").unwrap();
+		let mut low = broadcast.create_track(Track::new("low")).unwrap();
+		let mut high = broadcast.create_track(Track::new("high").with_priority(200)).unwrap();
+
+		// Low priority tracks get 1000 bytes per second; priority 128 and up are uncapped.
+		let limit = crate::RateLimit::new(1000).with_priority(128, 0);
+		let remote = Origin::random().produce();
+		let server = Server::new().with_publish(origin.consume()).with_rate_limit(limit);
+		let client = Client::new().with_consume(remote.clone());
+
+		let (server, client) = tokio::join!(server.accept(server_session), client.connect(client_session));
+		let (_server, _client) = (server.unwrap(), client.unwrap());
+
+		let timeout = std::time::Duration::from_secs(5);
+		let consumer = tokio::time::timeout(timeout, remote.consume().announced_broadcast("demo"))
+			.await
+			.unwrap()
+			.unwrap();
+		let mut low_sub = consumer.subscribe_track(&Track::new("low")).unwrap();
+		let mut high_sub = consumer.subscribe_track(&Track::new("high")).unwrap();
+
+		let start = tokio::time::Instant::now();
+		let payload = Bytes::from(vec![0u8; 3000]);
+		low.write_frame(payload.clone()).unwrap();
+		high.write_frame(payload.clone()).unwrap();
+
+		let frame = high_sub.read_frame().await.unwrap();
+		assert_eq!(frame, Some(payload.clone()));
+		assert!(start.elapsed() < std::time::Duration::from_secs(1));
+
+		// One second of burst, then the remaining 2000 bytes at 1000 per second.
+		let frame = low_sub.read_frame().await.unwrap();
+		assert_eq!(frame, Some(payload));
+		assert!(start.elapsed() >= std::time::Duration::from_secs(2));
+	}
+
+	#[tokio::test(start_paused = true)]
+	async fn lite_rate_limit_by_priority() {
+		rate_limit_case(crate::version::ALPN_LITE_03).await;
+	}
+
+	#[tokio::test(start_paused = true)]
+	async fn ietf_rate_limit_by_priority() {
+		rate_limit_case(crate::version::ALPN_14).await;
+	}
+
+	#[tokio::test(start_paused = true)]
+	async fn ietf_stalled_streams_apply_backpressure() {
+		let (client_session, server_session) = pair(Some(crate::version::ALPN_14));
+		let server_side = server_session.clone();
+
+		let origin = Origin::random().produce();
+		let mut broadcast = origin.create_broadcast("demo").unwrap();
+		let mut track = broadcast.create_track(Track::new("video")).unwrap();
+
+		let remote = Origin::random().produce();
+		let server = Server::new().with_publish(origin.consume());
+		let client = Client::new().with_consume(remote.clone()).with_max_streams(1);
+
+		let (server, client) = tokio::join!(server.accept(server_session), client.connect(client_session));
+		let (_server, _client) = (server.unwrap(), client.unwrap());
+
+		let consumer = remote.consume().announced_broadcast("demo").await.unwrap();
+		let mut subscription = consumer.subscribe_track(&Track::new("video")).unwrap();
+
+		// The first group doesn't finish yet, so its stream holds the only slot.
+		let mut first = track.append_group().unwrap();
+		first.write_frame(Bytes::from_static(b"stuck")).unwrap();
+		let group = subscription.recv_group().await.unwrap().unwrap();
+		assert_eq!(group.sequence, 0);
+
+		track
+			.append_group()
+			.unwrap()
+			.write_frame(Bytes::from_static(b"next"))
+			.unwrap();
+
+		// However long it stays saturated, the next stream just waits.
+		let stalled = std::time::Duration::from_secs(60);
+		assert!(tokio::time::timeout(stalled, subscription.recv_group()).await.is_err());
+		assert!(
+			tokio::time::timeout(std::time::Duration::ZERO, server_side.closed())
+				.await
+				.is_err()
+		);
+
+		first.finish().unwrap();
+		let group = subscription.recv_group().await.unwrap().unwrap();
+		assert_eq!(group.sequence, 1);
+	}
+
+	// A lite session whose subscriber sends the given max latency.
+	struct MaxLatency {
+		track: crate::TrackProducer,
+		subscription: crate::TrackConsumer,
+		_keep: (crate::BroadcastProducer, crate::Session, crate::Session),
+	}
+
+	async fn max_latency_session(latency: std::time::Duration) -> MaxLatency {
+		let (client_session, server_session) = pair(Some(crate::version::ALPN_LITE_03));
+
+		let origin = Origin::random().produce();
+		let mut broadcast = origin.create_broadcast("demo").unwrap();
+		let track = broadcast.create_track(Track::new("audio")).unwrap();
+
+		let remote = Origin::random().produce();
+		let server = Server::new().with_publish(origin.consume());
+		let client = Client::new().with_consume(remote.clone()).with_max_latency(latency);
+
+		let (server, client) = tokio::join!(server.accept(server_session), client.connect(client_session));
+
+		let consumer = remote.consume().announced_broadcast("demo").await.unwrap();
+		let subscription = consumer.subscribe_track(&Track::new("audio")).unwrap();
+		MaxLatency {
+			track,
+			subscription,
+			_keep: (broadcast, server.unwrap(), client.unwrap()),
+		}
+	}
+
+	#[tokio::test(start_paused = true)]
+	async fn lite_max_latency_keeps_latest_group() {
+		let MaxLatency {
+			mut track,
+			mut subscription,
+			_keep,
+		} = max_latency_session(std::time::Duration::from_secs(1)).await;
+
+		let mut group = track.append_group().unwrap();
+		group.write_frame(Bytes::from_static(b"first")).unwrap();
+		let mut remote = subscription.recv_group().await.unwrap().unwrap();
+		assert_eq!(remote.read_frame().await.unwrap(), Some(Bytes::from_static(b"first")));
+
+		// However long it runs, the latest group is never stale.
+		tokio::time::sleep(std::time::Duration::from_secs(30)).await;
+		group.write_frame(Bytes::from_static(b"second")).unwrap();
+		group.finish().unwrap();
+		assert_eq!(remote.read_frame().await.unwrap(), Some(Bytes::from_static(b"second")));
+		assert_eq!(remote.read_frame().await.unwrap(), None);
+	}
+
+	#[tokio::test(start_paused = true)]
+	async fn lite_max_latency_drops_older_group() {
+		let MaxLatency {
+			mut track,
+			mut subscription,
+			_keep,
+		} = max_latency_session(std::time::Duration::from_secs(1)).await;
+
+		let mut old = track.append_group().unwrap();
+		old.write_frame(Bytes::from_static(b"old")).unwrap();
+		let mut remote = subscription.recv_group().await.unwrap().unwrap();
+		assert_eq!(remote.read_frame().await.unwrap(), Some(Bytes::from_static(b"old")));
+
+		// A newer group queued past the max latency makes the unfinished one stale.
+		tokio::time::sleep(std::time::Duration::from_secs(2)).await;
+		track
+			.append_group()
+			.unwrap()
+			.write_frame(Bytes::from_static(b"new"))
+			.unwrap();
+		let mut newer = subscription.recv_group().await.unwrap().unwrap();
+		assert_eq!(newer.sequence, 1);
+		assert_eq!(newer.read_frame().await.unwrap(), Some(Bytes::from_static(b"new")));
+
+		old.write_frame(Bytes::from_static(b"late")).unwrap();
+		assert!(remote.read_frame().await.is_err());
+	}
+
+	#[tokio::test(start_paused = true)]
+	async fn lite_max_latency_zero_keeps_older_group() {
+		let MaxLatency {
+			mut track,
+			mut subscription,
+			_keep,
+		} = max_latency_session(std::time::Duration::ZERO).await;
+
+		let mut old = track.append_group().unwrap();
+		old.write_frame(Bytes::from_static(b"old")).unwrap();
+		let mut remote = subscription.recv_group().await.unwrap().unwrap();
+		assert_eq!(remote.read_frame().await.unwrap(), Some(Bytes::from_static(b"old")));
+
+		// Zero is what subscribers without a deadline send, so a newer group drops nothing.
+		tokio::time::sleep(std::time::Duration::from_secs(2)).await;
+		track
+			.append_group()
+			.unwrap()
+			.write_frame(Bytes::from_static(b"new"))
+			.unwrap();
+		let mut newer = subscription.recv_group().await.unwrap().unwrap();
+		assert_eq!(newer.read_frame().await.unwrap(), Some(Bytes::from_static(b"new")));
+
+		old.write_frame(Bytes::from_static(b"late")).unwrap();
+		old.finish().unwrap();
+		assert_eq!(remote.read_frame().await.unwrap(), Some(Bytes::from_static(b"late")));
+		assert_eq!(remote.read_frame().await.unwrap(), None);
+	}
+
+	/// Records the fields of every event, to check what the wire log emits.
+	#[derive(Clone, Default)]
+	struct Events(Arc<std::sync::Mutex<Vec<String>>>);
+
+	impl tracing::Subscriber for Events {
+		fn enabled(&self, _: &tracing::Metadata<'_>) -> bool {
+			true
+		}
+
+		fn new_span(&self, _: &tracing::span::Attributes<'_>) -> tracing::span::Id {
+			tracing::span::Id::from_u64(1)
+		}
+
+		fn record(&self, _: &tracing::span::Id, _: &tracing::span::Record<'_>) {}
+
+		fn record_follows_from(&self, _: &tracing::span::Id, _: &tracing::span::Id) {}
+
+		fn event(&self, event: &tracing::Event<'_>) {
+			struct Fields(String);
+
+			impl tracing::field::Visit for Fields {
+				fn record_debug(&mut self, field: &tracing::field::Field, value: &dyn std::fmt::Debug) {
+					self.0 += &format!("{}={:?} ", field.name(), value);
+				}
+			}
+
+			let mut fields = Fields(String::new());
+			event.record(&mut fields);
+			self.0.lock().unwrap().push(fields.0);
+		}
+
+		fn enter(&self, _: &tracing::span::Id) {}
+
+		fn exit(&self, _: &tracing::span::Id) {}
+	}
+
+	async fn wire_log_case(alpn: &str) {
+		let events = Events::default();
+		let _guard = tracing::subscriber::set_default(events.clone());
+
+		let (client_session, server_session) = pair(Some(alpn));
+
+		let origin = Origin::random().produce();
+		let mut broadcast = origin.create_broadcast("demo").unwrap();
+		let mut track = broadcast.create_track(Track::new("video")).unwrap();
+
+		let remote = Origin::random().produce();
+		let server = Server::new().with_publish(origin.consume());
+		let client = Client::new().with_consume(remote.clone()).with_wire_log(true);
+
+		let (server, client) = tokio::join!(server.accept(server_session), client.connect(client_session));
+		let (_server, _client) = (server.unwrap(), client.unwrap());
+
+		let timeout = std::time::Duration::from_secs(5);
+		let consumer = tokio::time::timeout(timeout, remote.consume().announced_broadcast("demo"))
+			.await
+			.unwrap()
+			.unwrap();
+		let mut subscription = consumer.subscribe_track(&Track::new("video")).unwrap();
+
+		track.write_frame(Bytes::from_static(b"hello")).unwrap();
+		tokio::time::timeout(timeout, subscription.recv_group())
+			.await
+			.unwrap()
+			.unwrap()
+			.unwrap();
+
+		// Only the client logs: its requests on the way out and the replies on the way in.
+		let events = events.0.lock().unwrap();
+		let logged = |direction: &str, message: &str| {
+			events
+				.iter()
+				.any(|event| event.contains(direction) && event.contains(&format!("message={message}(")))
+		};
+		assert!(logged("control message sent", "Subscribe"));
+		assert!(logged("control message received", "SubscribeOk"));
+		assert!(
+			logged("control message received", "Namespace") || logged("control message received", "PublishNamespace")
+		);
+		assert!(!logged("control message received", "Subscribe"));
+	}
+
+	#[tokio::test]
+	async fn ietf_wire_log_draft14() {
+		wire_log_case(crate::version::ALPN_14).await;
+	}
+
+	#[tokio::test]
+	async fn ietf_wire_log_draft17() {
+		wire_log_case(crate::version::ALPN_17).await;
+	}
+}
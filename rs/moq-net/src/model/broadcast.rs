@@ -46,6 +46,9 @@ struct State {
 
 	// The error that caused the broadcast to be aborted, if any.
 	abort: Option<Error>,
+
+	// Bumped whenever a track is added to or removed from the lookup.
+	epoch: u64,
 }
 
 fn modify(state: &kio::Producer<State>) -> Result<kio::Mut<'_, State>, Error> {
@@ -62,8 +65,28 @@ impl State {
 			return Err(Error::Duplicate);
 		};
 		entry.insert(weak);
+		self.epoch += 1;
 		Ok(())
 	}
+
+	/// Remove a track from the lookup, returning the removed handle.
+	fn remove_track(&mut self, name: &str) -> Option<TrackWeak> {
+		let weak = self.tracks.remove(name)?;
+		self.epoch += 1;
+		Some(weak)
+	}
+
+	/// The names of every live track, sorted for a stable order.
+	fn track_names(&self) -> Vec<String> {
+		let mut names: Vec<String> = self
+			.tracks
+			.iter()
+			.filter(|(_, weak)| !weak.is_closed())
+			.map(|(name, _)| name.clone())
+			.collect();
+		names.sort();
+		names
+	}
 }
 
 /// Manages tracks within a broadcast.
@@ -107,7 +130,7 @@ impl BroadcastProducer {
 	/// Remove a track from the lookup.
 	pub fn remove_track(&mut self, name: &str) -> Result<(), Error> {
 		let mut state = modify(&self.state)?;
-		state.tracks.remove(name).ok_or(Error::NotFound)?;
+		state.remove_track(name).ok_or(Error::NotFound)?;
 		Ok(())
 	}
 
@@ -125,8 +148,13 @@ impl BroadcastProducer {
 	/// Generates names like `0{suffix}`, `1{suffix}`, etc. and picks the first
 	/// one not already used in this broadcast.
 	pub fn unique_track(&mut self, suffix: &str) -> Result<TrackProducer, Error> {
+		self.unique_track_with_priority(suffix, 0)
+	}
+
+	/// Like [`Self::unique_track`], with the given delivery priority instead of `0`.
+	pub fn unique_track_with_priority(&mut self, suffix: &str, priority: u8) -> Result<TrackProducer, Error> {
 		let name = self.unique_name(suffix);
-		self.create_track(Track { name, priority: 0 })
+		self.create_track(Track { name, priority })
 	}
 
 	/// Generate a unique track name from a suffix without creating the track.
@@ -152,6 +180,7 @@ impl BroadcastProducer {
 		BroadcastConsumer {
 			info: self.info.clone(),
 			state: self.state.consume(),
+			epoch: 0,
 		}
 	}
 
@@ -282,15 +311,24 @@ impl BroadcastDynamic {
 	}
 
 	/// Block until a consumer requests a track, returning its producer.
+	///
+	/// Concurrent requests for the same track name are deduplicated, so each producer
+	/// yielded here is shared by every consumer that asked for it.
 	pub async fn requested_track(&mut self) -> Result<TrackProducer, Error> {
 		kio::wait(|waiter| self.poll_requested_track(waiter)).await
 	}
 
+	/// Convert into a [futures::Stream] of requested tracks, as returned by [Self::requested_track].
+	pub fn into_stream(self) -> super::RequestStream {
+		super::RequestStream::new(self)
+	}
+
 	/// Create a consumer that can subscribe to tracks in this broadcast.
 	pub fn consume(&self) -> BroadcastConsumer {
 		BroadcastConsumer {
 			info: self.info.clone(),
 			state: self.state.consume(),
+			epoch: 0,
 		}
 	}
 
@@ -374,6 +412,9 @@ impl BroadcastDynamic {
 pub struct BroadcastConsumer {
 	info: Broadcast,
 	state: kio::Consumer<State>,
+
+	// The track lookup epoch last returned by [Self::tracks_changed].
+	epoch: u64,
 }
 
 impl Deref for BroadcastConsumer {
@@ -392,6 +433,22 @@ impl BroadcastConsumer {
 	/// [`BroadcastDynamic::requested_track`]. Returns [`Error::NotFound`] if the
 	/// broadcast has no dynamic producer to handle requests.
 	pub fn subscribe_track(&self, track: &Track) -> Result<TrackConsumer, Error> {
+		self.subscribe(track, None)
+	}
+
+	/// Subscribe to a track starting at group `start`, even if it's older than the latest.
+	///
+	/// Like [`Self::subscribe_track`], but a new request carries `start` (see
+	/// [`TrackProducer::requested_start`]) so the session serving it asks the upstream
+	/// publisher for the earlier groups it still has. The returned consumer skips any group
+	/// before `start`, including those of a track that was already being published.
+	pub fn subscribe_track_from(&self, track: &Track, start: u64) -> Result<TrackConsumer, Error> {
+		let mut consumer = self.subscribe(track, Some(start))?;
+		consumer.start_at(start);
+		Ok(consumer)
+	}
+
+	fn subscribe(&self, track: &Track, start: Option<u64>) -> Result<TrackConsumer, Error> {
 		// Upgrade to a temporary producer so we can modify the state.
 		let producer = self
 			.state
@@ -404,12 +461,13 @@ impl BroadcastConsumer {
 				return Ok(weak.consume());
 			}
 			// Remove the stale entry
-			state.tracks.remove(&track.name);
+			state.remove_track(&track.name);
 		}
 
 		// Otherwise we have never seen this track before and need to create a new producer.
 		let producer = track.clone().produce();
 		let consumer = producer.consume();
+		producer.set_requested_start(start);
 
 		if state.dynamic == 0 {
 			return Err(Error::NotFound);
@@ -417,7 +475,7 @@ impl BroadcastConsumer {
 
 		// Insert a weak reference for deduplication.
 		let weak = producer.weak();
-		state.tracks.insert(producer.name.clone(), weak.clone());
+		state.insert_track(weak.clone())?;
 		state.requests.push(producer);
 
 		// Remove the track from the lookup when it's unused.
@@ -432,17 +490,59 @@ impl BroadcastConsumer {
 				return;
 			};
 
-			// Remove the entry, but reinsert if it was replaced by a different reference.
-			if let Some(current) = state.tracks.remove(&weak.info.name)
-				&& !current.is_clone(&weak)
+			// Remove the entry, unless it was replaced by a different reference.
+			if state
+				.tracks
+				.get(&weak.info.name)
+				.is_some_and(|current| current.is_clone(&weak))
 			{
-				state.tracks.insert(current.info.name.clone(), current);
+				state.remove_track(&weak.info.name);
 			}
 		});
 
 		Ok(consumer)
 	}
 
+	/// Whether a live track named `name` is already published or subscribed, without requesting it.
+	pub(crate) fn has_track(&self, name: &str) -> bool {
+		self.state.read().tracks.get(name).is_some_and(|weak| !weak.is_closed())
+	}
+
+	/// The names of the tracks currently known to this broadcast, sorted.
+	///
+	/// Includes tracks inserted by the producer and tracks requested by any consumer.
+	pub fn tracks(&self) -> Vec<String> {
+		self.state.read().track_names()
+	}
+
+	/// Poll for a change to the set of tracks, returning the new [Self::tracks] snapshot.
+	///
+	/// The first call returns immediately if any tracks are known.
+	/// Returns an error once the broadcast is closed.
+	pub fn poll_tracks_changed(&mut self, waiter: &kio::Waiter) -> Poll<Result<Vec<String>, Error>> {
+		let epoch = self.epoch;
+		let res = ready!(self.state.poll(waiter, |state| {
+			if state.epoch == epoch {
+				Poll::Pending
+			} else {
+				Poll::Ready((state.epoch, state.track_names()))
+			}
+		}));
+
+		Poll::Ready(match res {
+			Ok((epoch, names)) => {
+				self.epoch = epoch;
+				Ok(names)
+			}
+			Err(state) => Err(state.abort.clone().unwrap_or(Error::Dropped)),
+		})
+	}
+
+	/// Block until the set of tracks changes, returning the new [Self::tracks] snapshot.
+	pub async fn tracks_changed(&mut self) -> Result<Vec<String>, Error> {
+		kio::wait(|waiter| self.poll_tracks_changed(waiter)).await
+	}
+
 	/// Block until the broadcast is closed and return the cause.
 	///
 	/// Returns [`Error::Dropped`] if every producer was dropped without an
@@ -470,6 +570,34 @@ impl BroadcastConsumer {
 	pub fn is_clone(&self, other: &Self) -> bool {
 		self.state.same_channel(&other.state)
 	}
+
+	/// Create a broadcast carrying only `tracks` of this one, e.g. to publish video and audio
+	/// on separate sessions.
+	///
+	/// Each track is subscribed up front and shared, not copied, so the subset serves the same
+	/// groups as this broadcast. Any other track is [`Error::NotFound`] on the subset. Publish
+	/// the subset's [`BroadcastProducer::consume`] on another origin, keeping the producer
+	/// alive for as long as it should stay announced.
+	pub fn subset<'a>(&self, tracks: impl IntoIterator<Item = &'a Track>) -> Result<BroadcastProducer, Error> {
+		let mut subset = self.info.clone().produce();
+
+		let tracks = tracks
+			.into_iter()
+			.map(|track| self.subscribe_track(track))
+			.collect::<Result<Vec<_>, _>>()?;
+		for track in &tracks {
+			subset.insert_track(track.clone())?;
+		}
+
+		// The subset only holds weak handles, so keep the subscriptions alive until it closes.
+		let consumer = subset.consume();
+		web_async::spawn(async move {
+			consumer.closed().await;
+			drop(tracks);
+		});
+
+		Ok(subset)
+	}
 }
 
 #[cfg(test)]
@@ -517,6 +645,68 @@ mod test {
 		track2_consumer.assert_group();
 	}
 
+	#[tokio::test]
+	async fn subset() {
+		let mut producer = Broadcast::new().produce();
+		let mut video = producer.assert_create_track(&Track::new("video"));
+		let _audio = producer.assert_create_track(&Track::new("audio"));
+
+		let subset = producer.consume().subset([&Track::new("video")]).unwrap();
+		let consumer = subset.consume();
+		assert_eq!(consumer.tracks(), ["video"]);
+
+		let mut sub = consumer.assert_subscribe_track(&Track::new("video"));
+		video.append_group().unwrap();
+		sub.assert_group();
+
+		assert!(matches!(
+			consumer.subscribe_track(&Track::new("audio")),
+			Err(Error::NotFound)
+		));
+
+		// A track the source doesn't have can't be carried.
+		assert!(producer.consume().subset([&Track::new("missing")]).is_err());
+	}
+
+	#[tokio::test]
+	async fn tracks() {
+		let mut producer = Broadcast::new().produce();
+		let mut consumer = producer.consume();
+		assert!(consumer.tracks().is_empty());
+		assert!(
+			consumer.tracks_changed().now_or_never().is_none(),
+			"should have blocked"
+		);
+
+		let _video = producer.assert_create_track(&Track::new("video"));
+		let _audio = producer.assert_create_track(&Track::new("audio"));
+		assert_eq!(consumer.tracks(), ["audio", "video"]);
+
+		let changed = consumer
+			.tracks_changed()
+			.now_or_never()
+			.expect("should not have blocked");
+		assert_eq!(changed.unwrap(), ["audio", "video"]);
+		assert!(
+			consumer.tracks_changed().now_or_never().is_none(),
+			"should have blocked"
+		);
+
+		producer.remove_track("video").unwrap();
+		let changed = consumer
+			.tracks_changed()
+			.now_or_never()
+			.expect("should not have blocked");
+		assert_eq!(changed.unwrap(), ["audio"]);
+
+		drop(producer);
+		let changed = consumer
+			.tracks_changed()
+			.now_or_never()
+			.expect("should not have blocked");
+		assert!(changed.is_err());
+	}
+
 	#[tokio::test]
 	async fn closed() {
 		let mut producer = Broadcast::new().produce();
@@ -615,6 +805,26 @@ mod test {
 		assert!(track5.is_err(), "should have errored");
 	}
 
+	#[tokio::test]
+	async fn requests_shared_across_priorities() {
+		let mut producer = Broadcast::new().produce().dynamic();
+		let consumer = producer.consume();
+
+		// Two consumers asking for the same track at different priorities share one request.
+		let track1 = consumer.assert_subscribe_track(&Track::new("track1").with_priority(1));
+		let track2 = consumer.assert_subscribe_track(&Track::new("track1").with_priority(2));
+		track2.assert_is_clone(&track1);
+
+		let request = producer.assert_request();
+		producer.assert_no_request();
+
+		// The shared request stays used until the last consumer drops.
+		drop(track1);
+		assert!(request.unused().now_or_never().is_none(), "track should still be used");
+		drop(track2);
+		assert!(request.unused().now_or_never().is_some(), "track should be unused");
+	}
+
 	#[tokio::test]
 	async fn stale_producer() {
 		let mut broadcast = Broadcast::new().produce().dynamic();
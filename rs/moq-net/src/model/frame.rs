@@ -28,14 +28,48 @@ pub(crate) const MAX_FRAME_SIZE: u64 = 32 * 1024 * 1024;
 ///
 /// Note that this is just the header.
 /// You use [FrameProducer] and [FrameConsumer] to deal with the frame payload, potentially chunked.
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Default)]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Frame {
 	/// Total payload size in bytes. Declared up front so consumers can preallocate.
 	pub size: u64,
+
+	/// Capture timestamp, when the transport carries one alongside the payload.
+	///
+	/// `None` means the timing lives in the payload, if anywhere.
+	pub timestamp: Option<crate::Time>,
+
+	/// An empty frame marking the end of the track: its group is the last one.
+	///
+	/// Carried as an End of Track object over IETF. moq-lite has no such object, so it skips
+	/// the marker and ends the subscription after its group.
+	#[cfg_attr(feature = "serde", serde(default))]
+	pub is_end_of_track: bool,
 }
 
 impl Frame {
+	/// Create a frame header for a payload of `size` bytes.
+	pub fn new(size: u64) -> Self {
+		Self {
+			size,
+			..Default::default()
+		}
+	}
+
+	/// Create the empty frame that ends the track. See [`Self::is_end_of_track`].
+	pub fn end_of_track() -> Self {
+		Self {
+			is_end_of_track: true,
+			..Default::default()
+		}
+	}
+
+	/// Set the capture timestamp, returning `self` for chaining.
+	pub fn with_timestamp(mut self, timestamp: crate::Time) -> Self {
+		self.timestamp = Some(timestamp);
+		self
+	}
+
 	/// Create a new producer for the frame.
 	pub fn produce(self) -> FrameProducer {
 		FrameProducer::new(self)
@@ -44,25 +78,25 @@ impl Frame {
 
 impl From<usize> for Frame {
 	fn from(size: usize) -> Self {
-		Self { size: size as u64 }
+		Self::new(size as u64)
 	}
 }
 
 impl From<u64> for Frame {
 	fn from(size: u64) -> Self {
-		Self { size }
+		Self::new(size)
 	}
 }
 
 impl From<u32> for Frame {
 	fn from(size: u32) -> Self {
-		Self { size: size as u64 }
+		Self::new(size as u64)
 	}
 }
 
 impl From<u16> for Frame {
 	fn from(size: u16) -> Self {
-		Self { size: size as u64 }
+		Self::new(size as u64)
 	}
 }
 
@@ -344,6 +378,10 @@ impl From<Frame> for FrameProducer {
 }
 
 /// Used to consume a frame's worth of data, streaming as bytes arrive.
+///
+/// Chunks are [Bytes] views into the producer's buffer, never copies. A relay that
+/// forwards a track re-encodes only the frame header; the payload goes from the
+/// upstream `read_buf` to the downstream `write_all` untouched, for every subscriber.
 #[derive(Clone)]
 pub struct FrameConsumer {
 	info: Frame,
@@ -474,6 +512,20 @@ impl FrameConsumer {
 	pub async fn read_chunks(&mut self) -> Result<Vec<Bytes>> {
 		kio::wait(|waiter| self.poll_read_chunks(waiter)).await
 	}
+
+	/// The error that terminated the frame, or `None` if it's still open or finished cleanly.
+	///
+	/// Use [Error::reason] to tell a cancellation apart from the publisher going away.
+	pub fn error(&self) -> Option<Error> {
+		let state = self.state.read();
+		if let Some(err) = &state.abort {
+			Some(err.clone())
+		} else if state.is_closed() && !state.fin {
+			Some(Error::Dropped)
+		} else {
+			None
+		}
+	}
 }
 
 #[cfg(test)]
@@ -483,7 +535,7 @@ mod test {
 
 	#[test]
 	fn single_chunk_roundtrip() {
-		let mut producer = Frame { size: 5 }.produce();
+		let mut producer = Frame::new(5).produce();
 		producer.write(Bytes::from_static(b"hello")).unwrap();
 		producer.finish().unwrap();
 
@@ -494,7 +546,7 @@ mod test {
 
 	#[test]
 	fn multi_chunk_read_all() {
-		let mut producer = Frame { size: 10 }.produce();
+		let mut producer = Frame::new(10).produce();
 		producer.write(Bytes::from_static(b"hello")).unwrap();
 		producer.write(Bytes::from_static(b"world")).unwrap();
 		producer.finish().unwrap();
@@ -506,7 +558,7 @@ mod test {
 
 	#[test]
 	fn read_chunk_sequential() {
-		let mut producer = Frame { size: 10 }.produce();
+		let mut producer = Frame::new(10).produce();
 		producer.write(Bytes::from_static(b"hello")).unwrap();
 		// Each read_chunk returns whatever is new since the last call,
 		// which may span multiple writes.
@@ -525,7 +577,7 @@ mod test {
 
 	#[test]
 	fn read_all_chunks() {
-		let mut producer = Frame { size: 10 }.produce();
+		let mut producer = Frame::new(10).produce();
 		producer.write(Bytes::from_static(b"hello")).unwrap();
 		producer.write(Bytes::from_static(b"world")).unwrap();
 		producer.finish().unwrap();
@@ -538,7 +590,7 @@ mod test {
 
 	#[test]
 	fn finish_checks_remaining() {
-		let mut producer = Frame { size: 5 }.produce();
+		let mut producer = Frame::new(5).produce();
 		producer.write(Bytes::from_static(b"hi")).unwrap();
 		let err = producer.finish().unwrap_err();
 		assert!(matches!(err, Error::WrongSize));
@@ -546,24 +598,40 @@ mod test {
 
 	#[test]
 	fn write_too_many_bytes() {
-		let mut producer = Frame { size: 3 }.produce();
+		let mut producer = Frame::new(3).produce();
 		let err = producer.write(Bytes::from_static(b"toolong")).unwrap_err();
 		assert!(matches!(err, Error::WrongSize));
 	}
 
 	#[test]
 	fn abort_propagates() {
-		let mut producer = Frame { size: 5 }.produce();
+		let mut producer = Frame::new(5).produce();
 		let mut consumer = producer.consume();
 		producer.abort(Error::Cancel).unwrap();
 
 		let err = consumer.read_all().now_or_never().unwrap().unwrap_err();
 		assert!(matches!(err, Error::Cancel));
+		assert_eq!(consumer.error().unwrap().reason(), crate::AbortReason::Cancelled);
+	}
+
+	#[tokio::test]
+	async fn read_all_aborted_mid_frame() {
+		let mut producer = Frame::from(10u64).produce();
+		let mut consumer = producer.consume();
+
+		// Half the frame arrives, so read_all keeps waiting for the rest.
+		producer.write(Bytes::from_static(b"hello")).unwrap();
+		let mut read = Box::pin(consumer.read_all());
+		assert!((&mut read).now_or_never().is_none());
+
+		producer.abort(Error::Cancel).unwrap();
+		let err = read.now_or_never().unwrap().unwrap_err();
+		assert!(matches!(err, Error::Cancel));
 	}
 
 	#[test]
 	fn empty_frame() {
-		let mut producer = Frame { size: 0 }.produce();
+		let mut producer = Frame::new(0).produce();
 		producer.finish().unwrap();
 
 		let mut consumer = producer.consume();
@@ -573,7 +641,7 @@ mod test {
 
 	#[tokio::test]
 	async fn pending_then_ready() {
-		let mut producer = Frame { size: 5 }.produce();
+		let mut producer = Frame::new(5).produce();
 		let mut consumer = producer.consume();
 
 		// Consumer blocks because no data yet.
@@ -589,7 +657,7 @@ mod test {
 	#[test]
 	fn buf_mut_roundtrip() {
 		// Exercise the BufMut path that the receive loop uses via `read_buf`.
-		let mut producer = Frame { size: 12 }.produce();
+		let mut producer = Frame::new(12).produce();
 		assert_eq!(producer.remaining_mut(), 12);
 		producer.put_slice(b"hello");
 		assert_eq!(producer.remaining_mut(), 7);
@@ -605,14 +673,14 @@ mod test {
 	#[test]
 	#[should_panic(expected = "advance_mut past frame.size")]
 	fn buf_mut_advance_past_capacity_panics() {
-		let mut producer = Frame { size: 4 }.produce();
+		let mut producer = Frame::new(4).produce();
 		// Safety violation on purpose: cnt > remaining_mut().
 		unsafe { producer.advance_mut(5) };
 	}
 
 	#[test]
 	fn read_chunk_streams_partial_writes() {
-		let mut producer = Frame { size: 6 }.produce();
+		let mut producer = Frame::new(6).produce();
 		let mut consumer = producer.consume();
 
 		producer.write(Bytes::from_static(b"foo")).unwrap();
@@ -632,7 +700,7 @@ mod test {
 
 	#[test]
 	fn cloned_consumer_independent_cursor() {
-		let mut producer = Frame { size: 10 }.produce();
+		let mut producer = Frame::new(10).produce();
 		let mut c1 = producer.consume();
 		producer.write(Bytes::from_static(b"hello")).unwrap();
 
@@ -650,4 +718,18 @@ mod test {
 		let chunk = c2.read_chunk().now_or_never().unwrap().unwrap();
 		assert_eq!(chunk, Some(Bytes::from_static(b"world")));
 	}
+
+	#[test]
+	fn read_chunk_zero_copy() {
+		let mut producer = Frame::from(6u64).produce();
+		let mut c1 = producer.consume();
+		let mut c2 = producer.consume();
+		producer.write(Bytes::from_static(b"foobar")).unwrap();
+		producer.finish().unwrap();
+
+		// Every consumer sees the same memory, so fan-out never copies the payload.
+		let a = c1.read_chunk().now_or_never().unwrap().unwrap().unwrap();
+		let b = c2.read_chunk().now_or_never().unwrap().unwrap().unwrap();
+		assert_eq!(a.as_ptr(), b.as_ptr());
+	}
 }
@@ -33,9 +33,28 @@ pub struct Group {
 	/// Per-track sequence number used to detect ordering and gaps. Higher numbers
 	/// supersede lower ones; consumers may skip late arrivals.
 	pub sequence: u64,
+
+	/// Delivery priority for this group, overriding [`crate::Track::priority`] when set.
+	///
+	/// Set from the publisher's priority on the wire, so a relay forwards it unchanged.
+	pub priority: Option<u8>,
 }
 
 impl Group {
+	/// Create a group with the given sequence number, using the track's priority.
+	pub fn new(sequence: u64) -> Self {
+		Self {
+			sequence,
+			priority: None,
+		}
+	}
+
+	/// Set the delivery priority, returning `self` for chaining.
+	pub fn with_priority(mut self, priority: u8) -> Self {
+		self.priority = Some(priority);
+		self
+	}
+
 	/// Consume this [`Group`] to create a producer that owns its sequence number.
 	pub fn produce(self) -> GroupProducer {
 		GroupProducer::new(self)
@@ -44,31 +63,25 @@ impl Group {
 
 impl From<usize> for Group {
 	fn from(sequence: usize) -> Self {
-		Self {
-			sequence: sequence as u64,
-		}
+		Self::new(sequence as u64)
 	}
 }
 
 impl From<u64> for Group {
 	fn from(sequence: u64) -> Self {
-		Self { sequence }
+		Self::new(sequence)
 	}
 }
 
 impl From<u32> for Group {
 	fn from(sequence: u32) -> Self {
-		Self {
-			sequence: sequence as u64,
-		}
+		Self::new(sequence as u64)
 	}
 }
 
 impl From<u16> for Group {
 	fn from(sequence: u16) -> Self {
-		Self {
-			sequence: sequence as u64,
-		}
+		Self::new(sequence as u64)
 	}
 }
 
@@ -163,6 +176,9 @@ pub struct GroupProducer {
 
 	// The group header containing the sequence number.
 	info: Group,
+
+	// Frames larger than this are rejected before allocating, see [`crate::TrackProducer::set_max_frame_size`].
+	max_frame_size: u64,
 }
 
 impl std::ops::Deref for GroupProducer {
@@ -179,18 +195,23 @@ impl GroupProducer {
 		Self {
 			info,
 			state: kio::Producer::default(),
+			max_frame_size: MAX_FRAME_SIZE,
 		}
 	}
 
+	/// Lower the frame size limit below [`MAX_FRAME_SIZE`].
+	pub(crate) fn with_max_frame_size(mut self, max: u64) -> Self {
+		self.max_frame_size = max.min(MAX_FRAME_SIZE);
+		self
+	}
+
 	/// A helper method to write a frame from a single byte buffer.
 	///
 	/// If you want to write multiple chunks, use [Self::create_frame] to get a frame producer.
 	/// But an upfront size is required.
 	pub fn write_frame<B: Into<Bytes>>(&mut self, frame: B) -> Result<()> {
 		let data = frame.into();
-		let frame = Frame {
-			size: data.len() as u64,
-		};
+		let frame = Frame::new(data.len() as u64);
 		let mut frame = self.create_frame(frame)?;
 		frame.write(data)?;
 		frame.finish()?;
@@ -201,7 +222,7 @@ impl GroupProducer {
 	pub fn create_frame(&mut self, info: Frame) -> Result<FrameProducer> {
 		// Reject before `produce()`: `FrameProducer::new` preallocates `size` bytes, so an oversized
 		// frame must be caught here or it triggers the very allocation the limit exists to prevent.
-		if info.size > MAX_FRAME_SIZE {
+		if info.size > self.max_frame_size {
 			return Err(Error::FrameTooLarge);
 		}
 		let frame = info.produce();
@@ -213,7 +234,7 @@ impl GroupProducer {
 	pub fn append_frame(&mut self, frame: FrameProducer) -> Result<()> {
 		// Backstop for direct callers (the buffer is already allocated by the time we hold a
 		// FrameProducer); `create_frame` is the path that rejects before allocating.
-		if frame.size > MAX_FRAME_SIZE {
+		if frame.size > self.max_frame_size {
 			return Err(Error::FrameTooLarge);
 		}
 		let mut state = modify(&self.state)?;
@@ -255,6 +276,17 @@ impl GroupProducer {
 		Ok(())
 	}
 
+	/// Whether frames may still arrive: neither finished nor aborted.
+	pub(crate) fn is_open(&self) -> bool {
+		let state = self.state.read();
+		!state.fin && state.abort.is_none()
+	}
+
+	/// Whether the group was aborted (e.g. its stream was reset) rather than finished.
+	pub(crate) fn is_aborted(&self) -> bool {
+		self.state.read().abort.is_some()
+	}
+
 	/// Create a new consumer for the group.
 	pub fn consume(&self) -> GroupConsumer {
 		GroupConsumer {
@@ -284,6 +316,7 @@ impl Clone for GroupProducer {
 		Self {
 			info: self.info.clone(),
 			state: self.state.clone(),
+			max_frame_size: self.max_frame_size,
 		}
 	}
 }
@@ -393,6 +426,11 @@ impl GroupConsumer {
 		kio::wait(|waiter| self.poll_read_frame(waiter)).await
 	}
 
+	/// Convert into a [futures::Stream] of frames, as returned by [Self::read_frame].
+	pub fn into_stream(self) -> super::GroupStream {
+		super::GroupStream::new(self)
+	}
+
 	/// Read all of the chunks of the next frame, without blocking.
 	pub fn poll_read_frame_chunks(&mut self, waiter: &kio::Waiter) -> Poll<Result<Option<Vec<Bytes>>>> {
 		let index = self.index;
@@ -420,6 +458,20 @@ impl GroupConsumer {
 	pub async fn finished(&mut self) -> Result<u64> {
 		kio::wait(|waiter| self.poll_finished(waiter)).await
 	}
+
+	/// The error that terminated the group, or `None` if it's still open or finished cleanly.
+	///
+	/// Use [Error::reason] to tell a cancellation apart from the publisher going away.
+	pub fn error(&self) -> Option<Error> {
+		let state = self.state.read();
+		if let Some(err) = &state.abort {
+			Some(err.clone())
+		} else if state.is_closed() && !state.fin {
+			Some(Error::Dropped)
+		} else {
+			None
+		}
+	}
 }
 
 #[cfg(test)]
@@ -429,7 +481,7 @@ mod test {
 
 	#[test]
 	fn basic_frame_reading() {
-		let mut producer = Group { sequence: 0 }.produce();
+		let mut producer = Group::new(0).produce();
 		producer.write_frame(Bytes::from_static(b"frame0")).unwrap();
 		producer.write_frame(Bytes::from_static(b"frame1")).unwrap();
 		producer.finish().unwrap();
@@ -445,7 +497,7 @@ mod test {
 
 	#[test]
 	fn read_frame_all_at_once() {
-		let mut producer = Group { sequence: 0 }.produce();
+		let mut producer = Group::new(0).produce();
 		producer.write_frame(Bytes::from_static(b"hello")).unwrap();
 		producer.finish().unwrap();
 
@@ -456,8 +508,8 @@ mod test {
 
 	#[test]
 	fn read_frame_chunks() {
-		let mut producer = Group { sequence: 0 }.produce();
-		let mut frame = producer.create_frame(Frame { size: 10 }).unwrap();
+		let mut producer = Group::new(0).produce();
+		let mut frame = producer.create_frame(Frame::new(10)).unwrap();
 		frame.write(Bytes::from_static(b"hello")).unwrap();
 		frame.write(Bytes::from_static(b"world")).unwrap();
 		frame.finish().unwrap();
@@ -473,21 +525,19 @@ mod test {
 
 	#[test]
 	fn append_rejects_oversized_frame() {
-		let mut producer = Group { sequence: 0 }.produce();
-		let err = producer.create_frame(Frame {
-			size: MAX_FRAME_SIZE + 1,
-		});
+		let mut producer = Group::new(0).produce();
+		let err = producer.create_frame(Frame::new(MAX_FRAME_SIZE + 1));
 		assert!(
 			matches!(err, Err(Error::FrameTooLarge)),
 			"a frame over the limit is rejected"
 		);
 		// A frame at the limit is still accepted.
-		assert!(producer.create_frame(Frame { size: MAX_FRAME_SIZE }).is_ok());
+		assert!(producer.create_frame(Frame::new(MAX_FRAME_SIZE)).is_ok());
 	}
 
 	#[test]
 	fn get_frame_by_index() {
-		let mut producer = Group { sequence: 0 }.produce();
+		let mut producer = Group::new(0).produce();
 		producer.write_frame(Bytes::from_static(b"a")).unwrap();
 		producer.write_frame(Bytes::from_static(b"bb")).unwrap();
 		producer.finish().unwrap();
@@ -503,7 +553,7 @@ mod test {
 
 	#[test]
 	fn group_finish_returns_none() {
-		let mut producer = Group { sequence: 0 }.produce();
+		let mut producer = Group::new(0).produce();
 		producer.finish().unwrap();
 
 		let mut consumer = producer.consume();
@@ -513,7 +563,7 @@ mod test {
 
 	#[test]
 	fn abort_propagates() {
-		let mut producer = Group { sequence: 0 }.produce();
+		let mut producer = Group::new(0).produce();
 		let mut consumer = producer.consume();
 		producer.abort(crate::Error::Cancel).unwrap();
 
@@ -521,9 +571,52 @@ mod test {
 		assert!(matches!(result, Err(crate::Error::Cancel)));
 	}
 
+	#[test]
+	fn abort_reason() {
+		let mut producer = Group::new(0).produce();
+		let consumer = producer.consume();
+		assert!(consumer.error().is_none());
+
+		producer.abort(crate::Error::Timeout).unwrap();
+		let err = consumer.error().expect("group should be aborted");
+		assert_eq!(err.reason(), crate::AbortReason::Lost);
+	}
+
+	#[test]
+	fn error_remote_reset() {
+		// A publisher reset carrying an application code stays distinguishable from loss.
+		let mut producer = Group::new(0).produce();
+		let consumer = producer.consume();
+		producer
+			.abort(crate::Error::Remote(crate::Error::App(7).to_code()))
+			.unwrap();
+		assert_eq!(consumer.error().unwrap().reason(), crate::AbortReason::App(7));
+
+		let mut producer = Group::new(1).produce();
+		let consumer = producer.consume();
+		producer
+			.abort(crate::Error::Remote(crate::Error::Old.to_code()))
+			.unwrap();
+		assert_eq!(consumer.error().unwrap().reason(), crate::AbortReason::Lost);
+	}
+
+	#[test]
+	fn error_after_drop_and_finish() {
+		let producer = Group::new(0).produce();
+		let consumer = producer.consume();
+		drop(producer);
+		assert_eq!(consumer.error().unwrap().reason(), crate::AbortReason::Gone);
+
+		let mut producer = Group::new(1).produce();
+		let consumer = producer.consume();
+		producer.finish().unwrap();
+		drop(producer);
+		assert!(consumer.error().is_none(), "a finished group has no error");
+	}
+
 	#[test]
 	fn abort_clears_cached_frames() {
-		let mut producer = Group { sequence: 0 }.produce();
+		let mut producer = Group::new(0).produce();
 		producer.write_frame(Bytes::from_static(b"data")).unwrap();
 
 		// A stale consumer that never reads must not pin the cached frames.
@@ -539,7 +632,7 @@ mod test {
 
 	#[test]
 	fn drop_unfinished_clears_cached_frames() {
-		let producer = Group { sequence: 0 }.produce();
+		let producer = Group::new(0).produce();
 		let mut writer = producer.clone();
 		writer.write_frame(Bytes::from_static(b"data")).unwrap();
 
@@ -557,7 +650,7 @@ mod test {
 
 	#[test]
 	fn drop_finished_keeps_cached_frames() {
-		let mut producer = Group { sequence: 0 }.produce();
+		let mut producer = Group::new(0).produce();
 		producer.write_frame(Bytes::from_static(b"data")).unwrap();
 		producer.finish().unwrap();
 
@@ -571,7 +664,7 @@ mod test {
 
 	#[tokio::test]
 	async fn pending_then_ready() {
-		let mut producer = Group { sequence: 0 }.produce();
+		let mut producer = Group::new(0).produce();
 		let mut consumer = producer.consume();
 
 		// Consumer blocks because no frames yet.
@@ -586,7 +679,7 @@ mod test {
 
 	#[test]
 	fn eviction_drops_old_frames() {
-		let mut producer = Group { sequence: 0 }.produce();
+		let mut producer = Group::new(0).produce();
 
 		// Write frames that total more than MAX_GROUP_CACHE.
 		let big = Bytes::from(vec![0u8; MAX_GROUP_CACHE as usize]);
@@ -605,7 +698,7 @@ mod test {
 
 	#[test]
 	fn no_eviction_under_limit() {
-		let mut producer = Group { sequence: 0 }.produce();
+		let mut producer = Group::new(0).produce();
 		producer.write_frame(Bytes::from_static(b"small")).unwrap();
 		producer.write_frame(Bytes::from_static(b"frames")).unwrap();
 		producer.finish().unwrap();
@@ -619,7 +712,7 @@ mod test {
 
 	#[test]
 	fn eviction_by_frame_count() {
-		let mut producer = Group { sequence: 0 }.produce();
+		let mut producer = Group::new(0).produce();
 
 		// Write more than MAX_GROUP_FRAMES frames.
 		for _ in 0..=MAX_GROUP_FRAMES {
@@ -643,7 +736,7 @@ mod test {
 
 	#[test]
 	fn next_frame_returns_cache_full_on_tombstone() {
-		let mut producer = Group { sequence: 0 }.produce();
+		let mut producer = Group::new(0).produce();
 
 		let big = Bytes::from(vec![0u8; MAX_GROUP_CACHE as usize]);
 		producer.write_frame(big.clone()).unwrap();
@@ -657,7 +750,7 @@ mod test {
 
 	#[test]
 	fn clone_consumer_independent() {
-		let mut producer = Group { sequence: 0 }.produce();
+		let mut producer = Group::new(0).produce();
 		producer.write_frame(Bytes::from_static(b"a")).unwrap();
 
 		let mut c1 = producer.consume();
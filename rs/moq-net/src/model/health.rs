@@ -0,0 +1,46 @@
+//! Whether a subscription is keeping up, as a signal for rendition switching.
+
+/// How well a subscribed track is keeping up with its publisher, from [`crate::TrackConsumer::health`].
+///
+/// Derived from the groups the track still caches (the last few seconds): how many are
+/// still arriving at once, and how many were aborted, e.g. because their stream was reset
+/// or they fell out of the subscriber's group window. A live track normally has one group
+/// in flight, the newest; when groups arrive slower than they're produced, new ones open
+/// before the old ones finish. An ABR player polls this to pick a lower or higher rendition.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Health {
+	/// At most one group in flight and none aborted: there's room to switch up.
+	Healthy,
+	/// A backlog is forming or a group was aborted: hold, or prepare to switch down.
+	Degrading,
+	/// Groups pile up or keep getting aborted: switch down.
+	Starved,
+}
+
+impl Health {
+	/// Classify from the number of groups still arriving and the number aborted.
+	pub(crate) fn classify(open: usize, aborted: usize) -> Self {
+		if open >= 3 || aborted >= 2 {
+			Self::Starved
+		} else if open >= 2 || aborted >= 1 {
+			Self::Degrading
+		} else {
+			Self::Healthy
+		}
+	}
+}
+
+#[cfg(test)]
+mod test {
+	use super::*;
+
+	#[test]
+	fn classify() {
+		assert_eq!(Health::classify(0, 0), Health::Healthy);
+		assert_eq!(Health::classify(1, 0), Health::Healthy);
+		assert_eq!(Health::classify(2, 0), Health::Degrading);
+		assert_eq!(Health::classify(1, 1), Health::Degrading);
+		assert_eq!(Health::classify(3, 0), Health::Starved);
+		assert_eq!(Health::classify(0, 2), Health::Starved);
+	}
+}
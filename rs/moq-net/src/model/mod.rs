@@ -2,7 +2,10 @@ mod bandwidth;
 mod broadcast;
 mod frame;
 mod group;
+mod health;
 mod origin;
+mod resume;
+mod stream;
 mod time;
 mod track;
 
@@ -10,6 +13,9 @@ pub use bandwidth::*;
 pub use broadcast::*;
 pub use frame::*;
 pub use group::*;
+pub use health::*;
 pub use origin::*;
+pub use resume::*;
+pub use stream::*;
 pub use time::*;
 pub use track::*;
@@ -581,14 +581,133 @@ impl OriginNode {
 		}
 	}
 
+	// Drop every broadcast published at this path, including backups, and unannounce it.
+	// Returns false if nothing was published there.
+	fn evict(&mut self, full: impl AsPath, relative: impl AsPath) -> bool {
+		let full = full.as_path();
+		let relative = relative.as_path();
+
+		if let Some((dir, relative)) = relative.next_part() {
+			let Some(nested) = self.nested.get(dir).cloned() else {
+				return false;
+			};
+			let mut locked = nested.lock();
+			let evicted = locked.evict(&full, &relative);
+
+			if locked.is_empty() {
+				drop(locked);
+				self.nested.remove(dir);
+			}
+
+			evicted
+		} else {
+			if self.broadcast.take().is_none() {
+				return false;
+			}
+			self.notify.lock().unannounce(full);
+			true
+		}
+	}
+
+	// Returns true if this exact broadcast is published at the path, as the active or a backup.
+	fn contains(&self, rest: impl AsPath, broadcast: &BroadcastConsumer) -> bool {
+		let rest = rest.as_path();
+
+		if let Some((dir, rest)) = rest.next_part() {
+			self.nested
+				.get(dir)
+				.is_some_and(|node| node.lock().contains(&rest, broadcast))
+		} else {
+			self.broadcast.as_ref().is_some_and(|entry| {
+				entry.active.is_clone(broadcast) || entry.backup.iter().any(|b| b.is_clone(broadcast))
+			})
+		}
+	}
+
 	fn is_empty(&self) -> bool {
 		self.broadcast.is_none() && self.nested.is_empty() && self.notify.lock().consumers.is_empty()
 	}
 }
 
+/// Tracks published paths by last use so the least recently used can be evicted.
+///
+/// Shared by every producer and consumer derived from the same [OriginProducer].
+/// Lock order is capacity then node; never take this lock while holding a node.
+#[derive(Default)]
+struct OriginCapacity {
+	// The maximum number of published paths, or None for unbounded.
+	max: Option<usize>,
+	// Monotonic counter stamped on each use.
+	next: u64,
+	entries: HashMap<PathOwned, CapacityEntry>,
+	// Paths ordered by their last use, oldest first.
+	order: BTreeMap<u64, PathOwned>,
+}
+
+struct CapacityEntry {
+	used: u64,
+	node: Lock<OriginNode>,
+	rest: PathOwned,
+}
+
+impl OriginCapacity {
+	fn stamp(&mut self) -> u64 {
+		let used = self.next;
+		self.next += 1;
+		used
+	}
+
+	// Record a publish at `full`, evicting the least recently used paths if over capacity.
+	fn insert(&mut self, full: PathOwned, node: Lock<OriginNode>, rest: PathOwned) {
+		let Some(max) = self.max else {
+			return;
+		};
+
+		let used = self.stamp();
+		if let Some(old) = self.entries.insert(full.clone(), CapacityEntry { used, node, rest }) {
+			self.order.remove(&old.used);
+		}
+		self.order.insert(used, full);
+
+		while self.entries.len() > max {
+			let Some((_, victim)) = self.order.pop_first() else {
+				break;
+			};
+			let Some(entry) = self.entries.remove(&victim) else {
+				continue;
+			};
+
+			tracing::debug!(broadcast = %victim, max, "evicting least recently used broadcast");
+			entry.node.lock().evict(&victim, &entry.rest);
+		}
+	}
+
+	// Mark `full` as recently used, if it is tracked.
+	fn touch(&mut self, full: PathOwned) {
+		if self.max.is_none() {
+			return;
+		}
+
+		let used = self.stamp();
+		if let Some(entry) = self.entries.get_mut(&full) {
+			self.order.remove(&entry.used);
+			entry.used = used;
+			self.order.insert(used, full);
+		}
+	}
+
+	// Stop tracking `full` once nothing is published there.
+	fn forget(&mut self, full: PathOwned) {
+		if let Some(entry) = self.entries.remove(&full) {
+			self.order.remove(&entry.used);
+		}
+	}
+}
+
 #[derive(Clone)]
 struct OriginNodes {
 	nodes: Vec<(PathOwned, Lock<OriginNode>)>,
+	capacity: Lock<OriginCapacity>,
 }
 
 impl OriginNodes {
@@ -616,7 +735,10 @@ impl OriginNodes {
 		if roots.is_empty() {
 			None
 		} else {
-			Some(Self { nodes: roots })
+			Some(Self {
+				nodes: roots,
+				capacity: self.capacity.clone(),
+			})
 		}
 	}
 
@@ -643,7 +765,10 @@ impl OriginNodes {
 		if roots.is_empty() {
 			None
 		} else {
-			Some(Self { nodes: roots })
+			Some(Self {
+				nodes: roots,
+				capacity: self.capacity.clone(),
+			})
 		}
 	}
 
@@ -665,6 +790,7 @@ impl Default for OriginNodes {
 	fn default() -> Self {
 		Self {
 			nodes: vec![("".into(), Lock::new(OriginNode::new(None)))],
+			capacity: Lock::default(),
 		}
 	}
 }
@@ -757,16 +883,73 @@ impl OriginProducer {
 		}
 
 		root.lock().publish(&full, &broadcast, &rest);
+		self.nodes
+			.capacity
+			.lock()
+			.insert(full.to_owned(), root.clone(), rest.clone());
+
 		let root = root.clone();
+		let capacity = self.nodes.capacity.clone();
 
 		web_async::spawn(async move {
 			broadcast.closed().await;
-			root.lock().remove(&full, broadcast, &rest);
+
+			// Hold the capacity lock throughout, so a republish can't slip in between
+			// removing this broadcast and forgetting the path.
+			let mut capacity = capacity.lock();
+			let mut node = root.lock();
+
+			// Skip a broadcast that was already unpublished or evicted; the path may
+			// belong to a newer broadcast by now.
+			if !node.contains(&rest, &broadcast) {
+				return;
+			}
+
+			node.remove(&full, broadcast, &rest);
+			if node.consume_broadcast(&rest).is_none() {
+				capacity.forget(full);
+			}
 		});
 
 		true
 	}
 
+	/// Unpublish every broadcast at `path`, including backups, unannouncing it to all consumers.
+	///
+	/// For a local takedown: sessions forwarding this origin tell their peers the broadcast
+	/// ended. The broadcasts themselves are left open, so existing subscribers keep receiving
+	/// data until the publisher stops. The path can be published again immediately.
+	///
+	/// Returns false if the path is not allowed or nothing is published there.
+	pub fn unpublish(&self, path: impl AsPath) -> bool {
+		let path = path.as_path();
+		let Some((root, rest)) = self.nodes.get(&path) else {
+			return false;
+		};
+
+		let full = self.root.join(&path);
+
+		let mut capacity = self.nodes.capacity.lock();
+		capacity.forget(full.clone());
+
+		root.lock().evict(&full, &rest)
+	}
+
+	/// Limit the number of broadcast paths this origin retains.
+	///
+	/// Once more than `max` paths are published, the least recently used path is evicted:
+	/// every broadcast at that path is unannounced and dropped from the origin. A path is
+	/// used when it is published or looked up via [`OriginConsumer::get_broadcast`] or
+	/// [`OriginConsumer::request_broadcast`]. Evicted broadcasts are not closed, so
+	/// existing subscribers keep receiving data until the publisher stops.
+	///
+	/// The limit is shared with every handle derived from this producer. Only paths
+	/// published after it is set are tracked.
+	pub fn with_max_broadcasts(self, max: usize) -> Self {
+		self.nodes.capacity.lock().max = Some(max);
+		self
+	}
+
 	/// Returns a new OriginProducer restricted to publishing under one of `prefixes`.
 	///
 	/// Returns None if there are no legal prefixes (the requested prefixes are
@@ -935,6 +1118,10 @@ impl OriginConsumer {
 	}
 
 	/// Create another consumer with its own announcement cursor over the same origin.
+	///
+	/// The new cursor starts by replaying every broadcast active right now, however far
+	/// this one has read. Each session publishes from its own clone, so a session that
+	/// replaces a dropped one (e.g. after a relay restart) announces everything again.
 	pub fn consume(&self) -> Self {
 		self.clone()
 	}
@@ -949,8 +1136,9 @@ impl OriginConsumer {
 	pub fn get_broadcast(&self, path: impl AsPath) -> Option<BroadcastConsumer> {
 		let path = path.as_path();
 		let (root, rest) = self.nodes.get(&path)?;
-		let state = root.lock();
-		state.consume_broadcast(&rest)
+		let broadcast = root.lock().consume_broadcast(&rest)?;
+		self.nodes.capacity.lock().touch(self.root.join(&path).to_owned());
+		Some(broadcast)
 	}
 
 	/// Block until a broadcast with the given path is announced and return it.
@@ -2725,4 +2913,163 @@ mod tests {
 			"request should stay pending until served"
 		);
 	}
+
+	#[tokio::test]
+	async fn test_max_broadcasts_evicts_lru() {
+		tokio::time::pause();
+
+		let origin = Origin::random().produce().with_max_broadcasts(2);
+		let broadcast1 = Broadcast::new().produce();
+		let broadcast2 = Broadcast::new().produce();
+		let broadcast3 = Broadcast::new().produce();
+
+		let mut consumer = origin.consume();
+
+		origin.publish_broadcast("test1", broadcast1.consume());
+		origin.publish_broadcast("test2", broadcast2.consume());
+		consumer.assert_next("test1", &broadcast1.consume());
+		consumer.assert_next("test2", &broadcast2.consume());
+
+		// Looking up test1 makes test2 the least recently used.
+		assert!(consumer.get_broadcast("test1").is_some());
+
+		origin.publish_broadcast("test3", broadcast3.consume());
+		consumer.assert_next_none("test2");
+		consumer.assert_next("test3", &broadcast3.consume());
+		consumer.assert_next_wait();
+
+		assert!(consumer.get_broadcast("test1").is_some());
+		assert!(consumer.get_broadcast("test2").is_none());
+
+		// The evicted broadcast stays open, and closing it later is harmless.
+		broadcast2.consume().assert_not_closed();
+		drop(broadcast2);
+		tokio::time::sleep(tokio::time::Duration::from_millis(1)).await;
+		consumer.assert_next_wait();
+	}
+
+	#[tokio::test]
+	async fn test_max_broadcasts_evict_then_republish() {
+		tokio::time::pause();
+
+		let origin = Origin::random().produce().with_max_broadcasts(1);
+		let old = Broadcast::new().produce();
+		let other = Broadcast::new().produce();
+		let new = Broadcast::new().produce();
+
+		let mut consumer = origin.consume();
+
+		origin.publish_broadcast("test", old.consume());
+		consumer.assert_next("test", &old.consume());
+
+		origin.publish_broadcast("other", other.consume());
+		consumer.assert_next("other", &other.consume());
+		consumer.assert_next_none("test");
+		old.consume().assert_not_closed();
+
+		// Republish the evicted path, then close the old broadcast.
+		origin.publish_broadcast("test", new.consume());
+		consumer.assert_next_none("other");
+		consumer.assert_next("test", &new.consume());
+		drop(old);
+
+		// The old close must neither unannounce nor forget the new broadcast.
+		tokio::time::sleep(tokio::time::Duration::from_millis(1)).await;
+		consumer.assert_next_wait();
+		assert!(consumer.get_broadcast("test").is_some());
+		new.consume().assert_not_closed();
+	}
+
+	#[tokio::test]
+	async fn test_consume_replays_active() {
+		tokio::time::pause();
+
+		let origin = Origin::random().produce();
+		let broadcast1 = Broadcast::new().produce();
+		let broadcast2 = Broadcast::new().produce();
+
+		let mut consumer = origin.consume();
+		origin.publish_broadcast("test1", broadcast1.consume());
+		origin.publish_broadcast("test2", broadcast2.consume());
+		consumer.assert_next("test1", &broadcast1.consume());
+		consumer.assert_next("test2", &broadcast2.consume());
+
+		drop(broadcast1);
+		tokio::time::sleep(tokio::time::Duration::from_millis(1)).await;
+		consumer.assert_next_none("test1");
+		consumer.assert_next_wait();
+
+		// A drained consumer hands a new session the active set, not its own position.
+		let mut session = consumer.consume();
+		session.assert_next("test2", &broadcast2.consume());
+		session.assert_next_wait();
+	}
+
+	#[tokio::test]
+	async fn test_unpublish() {
+		tokio::time::pause();
+
+		let origin = Origin::random().produce();
+		let broadcast = Broadcast::new().produce();
+		let backup = Broadcast::new().produce();
+
+		let mut consumer = origin.consume();
+
+		origin.publish_broadcast("test", broadcast.consume());
+		origin.publish_broadcast("test", backup.consume());
+		consumer.assert_next("test", &broadcast.consume());
+
+		// The backup goes too, rather than being promoted.
+		assert!(origin.unpublish("test"));
+		consumer.assert_next_none("test");
+		consumer.assert_next_wait();
+		assert!(consumer.get_broadcast("test").is_none());
+		assert!(!origin.unpublish("test"));
+
+		// Both broadcasts stay open, and closing them later doesn't announce anything further.
+		broadcast.consume().assert_not_closed();
+		backup.consume().assert_not_closed();
+		drop(broadcast);
+		drop(backup);
+		tokio::time::sleep(tokio::time::Duration::from_millis(1)).await;
+		consumer.assert_next_wait();
+
+		// The path can be published again.
+		let broadcast = Broadcast::new().produce();
+		origin.publish_broadcast("test", broadcast.consume());
+		consumer.assert_next("test", &broadcast.consume());
+	}
+
+	#[tokio::test]
+	async fn test_unpublish_then_republish() {
+		tokio::time::pause();
+
+		let origin = Origin::random().produce().with_max_broadcasts(1);
+		let old = Broadcast::new().produce();
+		let new = Broadcast::new().produce();
+
+		let mut consumer = origin.consume();
+
+		origin.publish_broadcast("test", old.consume());
+		consumer.assert_next("test", &old.consume());
+
+		// Republish, then close the old broadcast.
+		assert!(origin.unpublish("test"));
+		origin.publish_broadcast("test", new.consume());
+		consumer.assert_next_none("test");
+		consumer.assert_next("test", &new.consume());
+		drop(old);
+
+		// The old close must neither unannounce nor forget the new broadcast.
+		tokio::time::sleep(tokio::time::Duration::from_millis(1)).await;
+		consumer.assert_next_wait();
+		assert!(consumer.get_broadcast("test").is_some());
+		new.consume().assert_not_closed();
+
+		// Still tracked, so it's the one evicted to make room.
+		let other = Broadcast::new().produce();
+		origin.publish_broadcast("other", other.consume());
+		consumer.assert_next("other", &other.consume());
+		consumer.assert_next_none("test");
+	}
 }
@@ -0,0 +1,210 @@
+//! A track subscription that survives its broadcast going away and coming back.
+//!
+//! When a session drops, every broadcast it announced into the local origin is
+//! unannounced and their tracks are aborted. A reconnect (e.g. `moq-native`'s
+//! `Reconnect`) announces them again into the same origin, but each subscriber would
+//! have to notice, look the broadcast up again and resubscribe. [ResumeTrack] does that
+//! for one track, picking up after the last group it returned.
+
+use std::task::{Poll, ready};
+
+use crate::{AsPath, GroupConsumer, OriginConsumer, PathOwned, Result, Track, TrackConsumer};
+
+/// Reads a track by broadcast path, resubscribing whenever the broadcast is announced again.
+///
+/// Groups are returned in increasing sequence, as with [TrackConsumer::next_group]. A
+/// resubscribe starts after the last group returned (an IETF AbsoluteStart, or a moq-lite start
+/// group, see [crate::BroadcastConsumer::subscribe_track_from]), so a consumer never sees a
+/// group twice, and groups published while the broadcast was gone are recovered as long as the
+/// publisher still has them cached.
+///
+/// A track that is aborted while its broadcast stays announced is only resumed once the
+/// broadcast is announced again.
+pub struct ResumeTrack {
+	// Scoped to the broadcast path, so only its (un)announcements wake us.
+	origin: OriginConsumer,
+	path: PathOwned,
+	track: Track,
+
+	// The live subscription, or `None` while waiting for the broadcast to be announced.
+	current: Option<TrackConsumer>,
+
+	// The sequence of the last group returned.
+	last: Option<u64>,
+}
+
+impl ResumeTrack {
+	/// Follow `track` of the broadcast at `path`, announced on `origin`.
+	///
+	/// Returns `None` if `path` is outside the origin's allowed prefixes.
+	pub fn new(origin: &OriginConsumer, path: impl AsPath, track: Track) -> Option<Self> {
+		let path = path.as_path();
+		let origin = origin.scope(std::slice::from_ref(&path))?;
+
+		// See OriginConsumer::announced_broadcast: a narrower scope can never match the exact path.
+		if !origin.allowed().any(|allowed| path.has_prefix(allowed)) {
+			return None;
+		}
+
+		Some(Self {
+			origin,
+			path: path.to_owned(),
+			track,
+			current: None,
+			last: None,
+		})
+	}
+
+	/// The sequence of the last group returned, which a resubscribe resumes after.
+	pub fn last(&self) -> Option<u64> {
+		self.last
+	}
+
+	/// Poll for the next group, resubscribing across broadcast drops.
+	///
+	/// Returns `Ready(Ok(None))` once the track finishes or the origin is closed.
+	pub fn poll_next_group(&mut self, waiter: &kio::Waiter) -> Poll<Result<Option<GroupConsumer>>> {
+		loop {
+			if let Some(current) = &mut self.current {
+				match ready!(current.poll_next_group(waiter)) {
+					Ok(Some(group)) => {
+						self.last = Some(group.sequence);
+						return Poll::Ready(Ok(Some(group)));
+					}
+					Ok(None) => return Poll::Ready(Ok(None)),
+					Err(err) => {
+						let (broadcast, track) = (&self.path, &self.track.name);
+						tracing::debug!(%broadcast, %track, %err, "track lost, waiting to resubscribe");
+						self.current = None;
+					}
+				}
+			}
+
+			let Some((path, broadcast)) = ready!(self.origin.poll_announced(waiter)) else {
+				return Poll::Ready(Ok(None));
+			};
+			let Some(broadcast) = broadcast.filter(|_| path.as_path() == self.path.as_path()) else {
+				continue;
+			};
+
+			// Ask the publisher for everything after the last group, not just its latest.
+			let track = match self.last {
+				Some(last) => {
+					tracing::debug!(broadcast = %self.path, track = %self.track.name, last, "resubscribing");
+					broadcast.subscribe_track_from(&self.track, last + 1)
+				}
+				None => broadcast.subscribe_track(&self.track),
+			};
+
+			match track {
+				Ok(track) => self.current = Some(track),
+				Err(err) => {
+					let (broadcast, track) = (&self.path, &self.track.name);
+					tracing::debug!(%broadcast, %track, %err, "resubscribe failed, waiting for the next announce");
+				}
+			}
+		}
+	}
+
+	/// Return the next group, resubscribing across broadcast drops.
+	///
+	/// Returns `Ok(None)` once the track finishes or the origin is closed.
+	pub async fn next_group(&mut self) -> Result<Option<GroupConsumer>> {
+		kio::wait(|waiter| self.poll_next_group(waiter)).await
+	}
+}
+
+#[cfg(test)]
+mod test {
+	use super::*;
+	use crate::{Broadcast, Error, Group, Origin};
+
+	use futures::FutureExt;
+
+	fn next(resume: &mut ResumeTrack) -> Option<u64> {
+		let group = resume.next_group().now_or_never()?.unwrap()?;
+		Some(group.sequence)
+	}
+
+	#[tokio::test]
+	async fn resumes_after_the_last_group() {
+		tokio::time::pause();
+
+		let origin = Origin::random().produce();
+		let mut resume = ResumeTrack::new(&origin.consume(), "live", Track::new("video")).unwrap();
+
+		let mut broadcast = Broadcast::new().produce();
+		let mut track = broadcast.create_track(Track::new("video")).unwrap();
+		origin.publish_broadcast("live", broadcast.consume());
+
+		track.create_group(Group::new(0)).unwrap();
+		track.create_group(Group::new(1)).unwrap();
+		assert_eq!(next(&mut resume), Some(0));
+		assert_eq!(next(&mut resume), Some(1));
+		assert_eq!(next(&mut resume), None);
+
+		// The session drops: the track is aborted and the broadcast unannounced.
+		track.abort(Error::Cancel).unwrap();
+		drop(broadcast);
+		tokio::time::sleep(std::time::Duration::from_millis(1)).await;
+		assert_eq!(next(&mut resume), None);
+
+		// A reconnect announces it again, replaying a group we already returned.
+		let mut broadcast = Broadcast::new().produce();
+		let mut track = broadcast.create_track(Track::new("video")).unwrap();
+		origin.publish_broadcast("live", broadcast.consume());
+		for sequence in 1..=3 {
+			track.create_group(Group::new(sequence)).unwrap();
+		}
+
+		assert_eq!(next(&mut resume), Some(2));
+		assert_eq!(next(&mut resume), Some(3));
+		assert_eq!(resume.last(), Some(3));
+	}
+
+	#[tokio::test]
+	async fn resubscribe_requests_the_missed_groups() {
+		tokio::time::pause();
+
+		let origin = Origin::random().produce();
+		let mut resume = ResumeTrack::new(&origin.consume(), "live", Track::new("video")).unwrap();
+
+		let broadcast = Broadcast::new().produce();
+		let mut dynamic = broadcast.dynamic();
+		origin.publish_broadcast("live", broadcast.consume());
+
+		// The first subscription starts wherever the publisher is.
+		assert_eq!(next(&mut resume), None);
+		let mut track = dynamic.assert_request();
+		assert_eq!(track.requested_start(), None);
+		track.create_group(Group::new(0)).unwrap();
+		assert_eq!(next(&mut resume), Some(0));
+
+		track.abort(Error::Cancel).unwrap();
+		drop((broadcast, dynamic));
+		tokio::time::sleep(std::time::Duration::from_millis(1)).await;
+		assert_eq!(next(&mut resume), None);
+
+		// The resubscribe asks for everything after group 0, which the publisher has moved past.
+		let broadcast = Broadcast::new().produce();
+		let mut dynamic = broadcast.dynamic();
+		origin.publish_broadcast("live", broadcast.consume());
+		assert_eq!(next(&mut resume), None);
+
+		let mut track = dynamic.assert_request();
+		assert_eq!(track.requested_start(), Some(1));
+		for sequence in 1..=3 {
+			track.create_group(Group::new(sequence)).unwrap();
+		}
+		assert_eq!(next(&mut resume), Some(1));
+		assert_eq!(next(&mut resume), Some(2));
+		assert_eq!(next(&mut resume), Some(3));
+	}
+
+	#[test]
+	fn disallowed_path() {
+		let origin = Origin::random().produce();
+		let consumer = origin.consume().scope(&["allowed".into()]).unwrap();
+		assert!(ResumeTrack::new(&consumer, "other", Track::new("video")).is_none());
+	}
+}
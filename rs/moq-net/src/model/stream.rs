@@ -0,0 +1,173 @@
+//! [futures::Stream] adapters over track and group consumers, and broadcast track requests.
+//!
+//! The consumers are polled with a [kio::Waiter], which must stay alive between polls to
+//! keep its registration. These adapters own one per stream, so a track, group or stream of
+//! track requests can be driven with `StreamExt` combinators (`take`, `map`, `filter`, ...)
+//! like any other stream.
+
+use std::{
+	pin::Pin,
+	task::{Context, Poll},
+};
+
+use bytes::Bytes;
+
+use super::{BroadcastDynamic, GroupConsumer, TrackConsumer, TrackProducer};
+use crate::Result;
+
+/// A [futures::Stream] of a track's groups, from [TrackConsumer::into_stream].
+///
+/// Yields groups as [TrackConsumer::next_group] returns them: in increasing sequence,
+/// skipping late arrivals. Ends when the track finishes; an abort is yielded as an error.
+pub struct TrackStream {
+	track: TrackConsumer,
+	waiter: Option<kio::Waiter>,
+}
+
+impl TrackStream {
+	pub(super) fn new(track: TrackConsumer) -> Self {
+		Self { track, waiter: None }
+	}
+
+	/// Return the underlying consumer.
+	pub fn into_inner(self) -> TrackConsumer {
+		self.track
+	}
+}
+
+impl futures::Stream for TrackStream {
+	type Item = Result<GroupConsumer>;
+
+	fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+		let this = &mut *self;
+		let waiter = this.waiter.insert(kio::Waiter::new(cx.waker().clone()));
+		this.track.poll_next_group(waiter).map(Result::transpose)
+	}
+}
+
+/// A [futures::Stream] of a group's frames, from [GroupConsumer::into_stream].
+///
+/// Yields each frame's data all at once, as [GroupConsumer::read_frame] does. Ends when
+/// the group finishes; an abort is yielded as an error.
+pub struct GroupStream {
+	group: GroupConsumer,
+	waiter: Option<kio::Waiter>,
+}
+
+impl GroupStream {
+	pub(super) fn new(group: GroupConsumer) -> Self {
+		Self { group, waiter: None }
+	}
+
+	/// Return the underlying consumer.
+	pub fn into_inner(self) -> GroupConsumer {
+		self.group
+	}
+}
+
+impl futures::Stream for GroupStream {
+	type Item = Result<Bytes>;
+
+	fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+		let this = &mut *self;
+		let waiter = this.waiter.insert(kio::Waiter::new(cx.waker().clone()));
+		this.group.poll_read_frame(waiter).map(Result::transpose)
+	}
+}
+
+/// A [futures::Stream] of the tracks consumers request, from [BroadcastDynamic::into_stream].
+///
+/// Yields a [TrackProducer] per request, as [BroadcastDynamic::requested_track] returns them,
+/// for the application to fill, e.g. by starting an encoder only once a rendition is wanted.
+/// A broadcast has no clean end, so once it closes the cause is yielded as an error.
+pub struct RequestStream {
+	dynamic: BroadcastDynamic,
+	waiter: Option<kio::Waiter>,
+}
+
+impl RequestStream {
+	pub(super) fn new(dynamic: BroadcastDynamic) -> Self {
+		Self { dynamic, waiter: None }
+	}
+
+	/// Return the underlying handle.
+	pub fn into_inner(self) -> BroadcastDynamic {
+		self.dynamic
+	}
+}
+
+impl futures::Stream for RequestStream {
+	type Item = Result<TrackProducer>;
+
+	fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+		let this = &mut *self;
+		let waiter = this.waiter.insert(kio::Waiter::new(cx.waker().clone()));
+		this.dynamic.poll_requested_track(waiter).map(Some)
+	}
+}
+
+#[cfg(test)]
+mod test {
+	use futures::{FutureExt, StreamExt};
+
+	use crate::{Broadcast, Error, Group, Track};
+
+	#[test]
+	fn track_groups() {
+		let mut producer = Track::new("test").produce();
+		for sequence in [0, 2, 1, 3] {
+			producer.create_group(Group::new(sequence)).unwrap();
+		}
+		producer.finish().unwrap();
+
+		// The late group 1 is skipped, as with next_group.
+		let stream = producer.consume().into_stream();
+		let sequences: Vec<u64> = stream
+			.map(|group| group.unwrap().sequence)
+			.collect()
+			.now_or_never()
+			.unwrap();
+		assert_eq!(sequences, vec![0, 2, 3]);
+	}
+
+	#[test]
+	fn group_frames() {
+		let mut group = Group::new(0).produce();
+		group.write_frame(bytes::Bytes::from_static(b"a")).unwrap();
+		group.write_frame(bytes::Bytes::from_static(b"b")).unwrap();
+
+		let mut stream = group.consume().into_stream();
+		assert_eq!(stream.next().now_or_never().unwrap().unwrap().unwrap(), "a");
+		assert_eq!(stream.next().now_or_never().unwrap().unwrap().unwrap(), "b");
+		assert!(stream.next().now_or_never().is_none());
+
+		group.abort(Error::Cancel).unwrap();
+		assert!(stream.next().now_or_never().unwrap().unwrap().is_err());
+	}
+
+	#[tokio::test]
+	async fn requested_tracks() {
+		let mut broadcast = Broadcast::new().produce();
+		let mut requests = broadcast.dynamic().into_stream();
+		assert!(requests.next().now_or_never().is_none());
+
+		// Subscribing to a track nobody produced yet asks the application for it.
+		let consumer = broadcast.consume();
+		let _video = consumer.subscribe_track(&Track::new("video")).unwrap();
+		let track = requests.next().now_or_never().unwrap().unwrap().unwrap();
+		assert_eq!(track.name(), "video");
+
+		// The handle can be taken back out, e.g. to await a request directly.
+		let mut dynamic = requests.into_inner();
+		let _audio = consumer.subscribe_track(&Track::new("audio")).unwrap();
+		assert_eq!(dynamic.requested_track().await.unwrap().name(), "audio");
+
+		// Like an aborted track, a closed broadcast yields its cause as an error.
+		let mut requests = dynamic.into_stream();
+		broadcast.abort(Error::Cancel).unwrap();
+		assert!(matches!(
+			requests.next().now_or_never().unwrap().unwrap(),
+			Err(Error::Cancel)
+		));
+	}
+}
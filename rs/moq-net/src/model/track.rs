@@ -14,7 +14,7 @@
 
 use crate::{Error, Result, coding};
 
-use super::{Group, GroupConsumer, GroupProducer};
+use super::{Group, GroupConsumer, GroupProducer, Health};
 
 use std::{
 	collections::{HashSet, VecDeque},
@@ -23,7 +23,7 @@ use std::{
 };
 
 /// Groups older than this are evicted from the track cache (unless they are the max_sequence group).
-// TODO: Replace with a configurable cache size.
+/// Override per track with [TrackProducer::set_cache_age].
 const MAX_GROUP_AGE: Duration = Duration::from_secs(5);
 
 /// A track is a collection of groups, delivered out-of-order until expired.
@@ -66,6 +66,14 @@ struct State {
 	max_sequence: Option<u64>,
 	final_sequence: Option<u64>,
 	abort: Option<Error>,
+	// An opt-in frame size limit below MAX_FRAME_SIZE, applied to new groups.
+	max_frame_size: Option<u64>,
+	// Overrides MAX_GROUP_AGE, set by TrackProducer::set_cache_age.
+	cache_age: Option<Duration>,
+	// An opt-in cap on the number of cached groups, set by TrackProducer::set_cache_groups.
+	cache_groups: Option<usize>,
+	// The first group asked for by the subscriber that requested the track, if not the latest.
+	requested_start: Option<u64>,
 }
 
 impl State {
@@ -168,13 +176,20 @@ impl State {
 		}
 	}
 
-	/// Evict groups older than MAX_GROUP_AGE, never evicting the max_sequence group.
+	/// Evict groups older than the cache age, or beyond the cache's group count, never
+	/// evicting the max_sequence group.
 	///
 	/// Groups are in arrival order, so we can stop early when we hit a non-expired,
-	/// non-max_sequence group (everything after it arrived even later).
-	/// When max_sequence is at the front, we skip past it and tombstone expired groups
-	/// behind it.
+	/// non-max_sequence group once we're within the group count (everything after it
+	/// arrived even later). When max_sequence is at the front, we skip past it and
+	/// tombstone expired groups behind it.
 	fn evict_expired(&mut self, now: web_async::time::Instant) {
+		let max_age = self.cache_age.unwrap_or(MAX_GROUP_AGE);
+		let mut excess = match self.cache_groups {
+			Some(max) => self.groups.iter().flatten().count().saturating_sub(max),
+			None => 0,
+		};
+
 		for slot in self.groups.iter_mut() {
 			let Some((group, created_at)) = slot else { continue };
 
@@ -182,10 +197,11 @@ impl State {
 				continue;
 			}
 
-			if now.duration_since(*created_at) <= MAX_GROUP_AGE {
+			if excess == 0 && now.duration_since(*created_at) <= max_age {
 				break;
 			}
 
+			excess = excess.saturating_sub(1);
 			self.duplicates.remove(&group.sequence);
 			*slot = None;
 		}
@@ -197,6 +213,14 @@ impl State {
 		}
 	}
 
+	/// Apply the frame size limit, if any, to a new group.
+	fn limit(&self, group: GroupProducer) -> GroupProducer {
+		match self.max_frame_size {
+			Some(max) => group.with_max_frame_size(max),
+			None => group,
+		}
+	}
+
 	fn poll_finished(&self) -> Poll<Result<u64>> {
 		if let Some(fin) = self.final_sequence {
 			Poll::Ready(Ok(fin))
@@ -233,9 +257,9 @@ impl TrackProducer {
 
 	/// Create a new group with the given sequence number.
 	pub fn create_group(&mut self, info: Group) -> Result<GroupProducer> {
-		let group = info.produce();
-
 		let mut state = self.modify()?;
+		let group = state.limit(info.produce());
+
 		if let Some(fin) = state.final_sequence
 			&& group.sequence >= fin
 		{
@@ -267,7 +291,7 @@ impl TrackProducer {
 			return Err(Error::Closed);
 		}
 
-		let group = Group { sequence }.produce();
+		let group = state.limit(Group::new(sequence).produce());
 
 		let now = web_async::time::Instant::now();
 		state.duplicates.insert(sequence);
@@ -286,6 +310,53 @@ impl TrackProducer {
 		Ok(())
 	}
 
+	/// The first group the subscriber that requested this track asked for, if any.
+	///
+	/// Set by [`crate::BroadcastConsumer::subscribe_track_from`], so the session serving the
+	/// request can ask its upstream publisher to start there rather than at the latest group.
+	pub fn requested_start(&self) -> Option<u64> {
+		self.state.read().requested_start
+	}
+
+	pub(crate) fn set_requested_start(&self, start: Option<u64>) {
+		if let Ok(mut state) = self.modify() {
+			state.requested_start = start;
+		}
+	}
+
+	/// Keep groups cached for `age` instead of the default 5 seconds.
+	///
+	/// Cached groups are what a new [TrackConsumer] starts with, so a longer age lets a
+	/// late subscriber catch up on recent history immediately, at the cost of memory.
+	/// The newest group is always kept. Applies from the next group created.
+	pub fn set_cache_age(&self, age: Duration) {
+		if let Ok(mut state) = self.modify() {
+			state.cache_age = Some(age);
+		}
+	}
+
+	/// Cache at most `groups` groups, evicting the oldest arrivals first.
+	///
+	/// Bounds memory alongside [Self::set_cache_age] when groups are short. The newest
+	/// group is always kept, even when `groups` is zero. Applies from the next group created.
+	pub fn set_cache_groups(&self, groups: usize) {
+		if let Ok(mut state) = self.modify() {
+			state.cache_groups = Some(groups);
+		}
+	}
+
+	/// Reject frames larger than `max` bytes, before they're allocated.
+	///
+	/// On top of the fixed 32 MiB cap, a player or relay that knows what to expect (ex. from
+	/// the catalog bitrate) can fail groups whose publisher claims an implausible frame size
+	/// with [`Error::FrameTooLarge`], by setting this on the producer it subscribes with.
+	/// Applies from the next group created.
+	pub fn set_max_frame_size(&self, max: u64) {
+		if let Ok(mut state) = self.modify() {
+			state.max_frame_size = Some(max);
+		}
+	}
+
 	/// Mark the track as finished after the last appended group.
 	///
 	/// Sets the final sequence to one past the current max_sequence.
@@ -610,6 +681,11 @@ impl TrackConsumer {
 		kio::wait(|waiter| self.poll_next_group(waiter)).await
 	}
 
+	/// Convert into a [futures::Stream] of groups, as returned by [Self::next_group].
+	pub fn into_stream(self) -> super::TrackStream {
+		super::TrackStream::new(self)
+	}
+
 	/// A helper that calls [`Self::poll_next_group`] and returns its first frame,
 	/// skipping the rest of the group. Intended for single-frame groups (see
 	/// [`TrackProducer::write_frame`]).
@@ -671,7 +747,12 @@ impl TrackConsumer {
 		self.poll(waiter, |state| state.poll_finished())
 	}
 
-	/// Block until the track is finished, returning the total number of groups.
+	/// Block until the producer ends the track for good, returning the total number of groups.
+	///
+	/// No more groups will ever arrive: this is the end of the stream (ex. a VOD finished), not
+	/// the end of a group, so a player can show an end state instead of waiting for the next
+	/// group. Over IETF it follows the publisher's PUBLISH_DONE. Returns an error if the track
+	/// was aborted.
 	pub async fn finished(&mut self) -> Result<u64> {
 		kio::wait(|waiter| self.poll_finished(waiter)).await
 	}
@@ -686,6 +767,19 @@ impl TrackConsumer {
 		self.state.read().max_sequence
 	}
 
+	/// Estimate whether the track is keeping up, to drive rendition switching.
+	///
+	/// Recomputed on every call from the groups still cached, so it reflects groups as they
+	/// arrive and abort. See [`Health`] for what each level means.
+	pub fn health(&self) -> Health {
+		let state = self.state.read();
+		let groups = state.groups.iter().flatten().map(|(group, _)| group);
+		let (open, aborted) = groups.fold((0, 0), |(open, aborted), group| {
+			(open + group.is_open() as usize, aborted + group.is_aborted() as usize)
+		});
+		Health::classify(open, aborted)
+	}
+
 	/// Create a weak reference that doesn't prevent auto-close.
 	pub(crate) fn weak(&self) -> TrackWeak {
 		TrackWeak {
@@ -754,6 +848,45 @@ mod test {
 		state.groups.iter().flatten().next().unwrap().0.sequence
 	}
 
+	#[tokio::test]
+	async fn cache_age_override() {
+		tokio::time::pause();
+
+		let mut producer = Track::new("test").produce();
+		producer.set_cache_age(MAX_GROUP_AGE * 4);
+		producer.append_group().unwrap();
+
+		// Past the default age, but within the override.
+		tokio::time::advance(MAX_GROUP_AGE + Duration::from_secs(1)).await;
+		producer.append_group().unwrap();
+
+		let state = producer.state.read();
+		assert_eq!(live_groups(&state), 2);
+		assert_eq!(first_live_sequence(&state), 0);
+	}
+
+	#[tokio::test]
+	async fn cache_groups_limit() {
+		tokio::time::pause();
+
+		let mut producer = Track::new("test").produce();
+		producer.set_cache_groups(2);
+		for _ in 0..5 {
+			producer.append_group().unwrap();
+		}
+
+		// Only the two newest groups are left for a late subscriber.
+		{
+			let state = producer.state.read();
+			assert_eq!(live_groups(&state), 2);
+			assert_eq!(first_live_sequence(&state), 3);
+			assert_eq!(state.offset, 3);
+		}
+
+		// Evicted sequences can be created again, as with age-based eviction.
+		producer.create_group(Group::new(0)).unwrap();
+	}
+
 	#[tokio::test]
 	async fn evict_expired_groups() {
 		tokio::time::pause();
@@ -852,9 +985,9 @@ mod test {
 		let mut producer = Track::new("test").produce();
 
 		// Arrive out of order: seq 5 first, then 3, then 4.
-		producer.create_group(Group { sequence: 5 }).unwrap();
-		producer.create_group(Group { sequence: 3 }).unwrap();
-		producer.create_group(Group { sequence: 4 }).unwrap();
+		producer.create_group(Group::new(5)).unwrap();
+		producer.create_group(Group::new(3)).unwrap();
+		producer.create_group(Group::new(4)).unwrap();
 
 		// max_sequence = 5, which is at the front of the VecDeque.
 		{
@@ -888,12 +1021,12 @@ mod test {
 		let mut producer = Track::new("test").produce();
 
 		// Arrive: seq 5, then seq 3.
-		producer.create_group(Group { sequence: 5 }).unwrap();
+		producer.create_group(Group::new(5)).unwrap();
 
 		tokio::time::advance(MAX_GROUP_AGE + Duration::from_secs(1)).await;
 
 		// Seq 3 arrives late; max_sequence is still 5 (at front).
-		producer.create_group(Group { sequence: 3 }).unwrap();
+		producer.create_group(Group::new(3)).unwrap();
 
 		// Seq 5 is max_sequence (protected). Seq 3 is not expired (just created).
 		// Nothing should be evicted.
@@ -907,7 +1040,7 @@ mod test {
 		tokio::time::advance(MAX_GROUP_AGE + Duration::from_secs(1)).await;
 
 		// Seq 2 arrives late, triggering eviction.
-		producer.create_group(Group { sequence: 2 }).unwrap();
+		producer.create_group(Group::new(2)).unwrap();
 
 		// Seq 5 is still max_sequence (protected, at front, blocks trim).
 		// Seq 3 is expired → tombstoned.
@@ -1005,10 +1138,48 @@ mod test {
 		assert!(producer.append_group().is_err());
 	}
 
+	#[tokio::test]
+	async fn finished_after_finish() {
+		let mut producer = Track::new("test").produce();
+		let mut consumer = producer.consume();
+
+		producer.append_group().unwrap();
+		assert!(consumer.finished().now_or_never().is_none());
+
+		producer.finish().unwrap();
+		assert!(matches!(consumer.finished().now_or_never(), Some(Ok(1))));
+	}
+
+	#[tokio::test]
+	async fn finished_errors_on_abort() {
+		let mut producer = Track::new("test").produce();
+		let mut consumer = producer.consume();
+
+		producer.abort(Error::Cancel).unwrap();
+		assert!(matches!(consumer.finished().now_or_never(), Some(Err(Error::Cancel))));
+	}
+
+	#[test]
+	fn max_frame_size_rejects_large_frames() {
+		let mut producer = Track::new("test").produce();
+
+		let mut before = producer.append_group().unwrap();
+		producer.set_max_frame_size(100);
+		let mut after = producer.append_group().unwrap();
+
+		assert!(after.create_frame(crate::Frame::from(100u64)).is_ok());
+		assert!(matches!(
+			after.create_frame(crate::Frame::from(101u64)),
+			Err(Error::FrameTooLarge)
+		));
+		// Groups created before the limit keep the default cap.
+		assert!(before.create_frame(crate::Frame::from(101u64)).is_ok());
+	}
+
 	#[test]
 	fn insert_finish_validates_sequence_and_freezes_to_max() {
 		let mut producer = Track::new("test").produce();
-		producer.create_group(Group { sequence: 5 }).unwrap();
+		producer.create_group(Group::new(5)).unwrap();
 
 		assert!(producer.finish_at(4).is_err());
 		assert!(producer.finish_at(10).is_err());
@@ -1020,14 +1191,14 @@ mod test {
 		}
 
 		assert!(producer.finish_at(5).is_err());
-		assert!(producer.create_group(Group { sequence: 4 }).is_ok());
-		assert!(producer.create_group(Group { sequence: 5 }).is_err());
+		assert!(producer.create_group(Group::new(4)).is_ok());
+		assert!(producer.create_group(Group::new(5)).is_err());
 	}
 
 	#[tokio::test]
 	async fn recv_group_finishes_without_waiting_for_gaps() {
 		let mut producer = Track::new("test").produce();
-		producer.create_group(Group { sequence: 1 }).unwrap();
+		producer.create_group(Group::new(1)).unwrap();
 		producer.finish_at(1).unwrap();
 
 		let mut consumer = producer.consume();
@@ -1047,7 +1218,7 @@ mod test {
 		let mut consumer = producer.consume();
 
 		// Seq 5 arrives first.
-		producer.create_group(Group { sequence: 5 }).unwrap();
+		producer.create_group(Group::new(5)).unwrap();
 		let group = consumer
 			.next_group()
 			.now_or_never()
@@ -1057,11 +1228,11 @@ mod test {
 		assert_eq!(group.sequence, 5);
 
 		// Seq 3 arrives late — skipped because 3 <= 5.
-		producer.create_group(Group { sequence: 3 }).unwrap();
+		producer.create_group(Group::new(3)).unwrap();
 		// Seq 4 arrives late — also skipped.
-		producer.create_group(Group { sequence: 4 }).unwrap();
+		producer.create_group(Group::new(4)).unwrap();
 		// Seq 7 arrives — returned.
-		producer.create_group(Group { sequence: 7 }).unwrap();
+		producer.create_group(Group::new(7)).unwrap();
 
 		let group = consumer
 			.next_group()
@@ -1084,8 +1255,8 @@ mod test {
 		let mut consumer = producer.consume();
 
 		// Seq 3 arrives first, then seq 5 — both should be returned in arrival order.
-		producer.create_group(Group { sequence: 3 }).unwrap();
-		producer.create_group(Group { sequence: 5 }).unwrap();
+		producer.create_group(Group::new(3)).unwrap();
+		producer.create_group(Group::new(5)).unwrap();
 
 		let group = consumer
 			.next_group()
@@ -1109,8 +1280,8 @@ mod test {
 		let mut producer = Track::new("test").produce();
 		let mut consumer = producer.consume();
 
-		producer.create_group(Group { sequence: 5 }).unwrap();
-		producer.create_group(Group { sequence: 3 }).unwrap();
+		producer.create_group(Group::new(5)).unwrap();
+		producer.create_group(Group::new(3)).unwrap();
 
 		// Ordered returns seq 5 and advances its internal cursor past it.
 		let group = consumer
@@ -1157,9 +1328,9 @@ mod test {
 		let mut consumer = producer.consume();
 
 		// Seq 3: group open, no frame yet (stalled).
-		let _stalled = producer.create_group(Group { sequence: 3 }).unwrap();
+		let _stalled = producer.create_group(Group::new(3)).unwrap();
 		// Seq 5: fully-written group with a frame.
-		let mut g5 = producer.create_group(Group { sequence: 5 }).unwrap();
+		let mut g5 = producer.create_group(Group::new(5)).unwrap();
 		g5.write_frame(bytes::Bytes::from_static(b"later")).unwrap();
 		g5.finish().unwrap();
 
@@ -1179,7 +1350,7 @@ mod test {
 		let mut consumer = producer.consume();
 
 		// Group 0 has two frames; only the first is returned.
-		let mut g0 = producer.create_group(Group { sequence: 0 }).unwrap();
+		let mut g0 = producer.create_group(Group::new(0)).unwrap();
 		g0.write_frame(bytes::Bytes::from_static(b"one")).unwrap();
 		g0.write_frame(bytes::Bytes::from_static(b"two")).unwrap();
 		g0.finish().unwrap();
@@ -1212,7 +1383,7 @@ mod test {
 		let mut producer = Track::new("test").produce();
 		let mut consumer = producer.consume();
 
-		let mut g0 = producer.create_group(Group { sequence: 0 }).unwrap();
+		let mut g0 = producer.create_group(Group::new(0)).unwrap();
 		producer.finish().unwrap();
 
 		// Track is finished but group 0 has no frame yet — must block, not return None.
@@ -1241,11 +1412,11 @@ mod test {
 		consumer.start_at(5);
 
 		// Seq 3 has a frame but is below min_sequence — must be skipped.
-		let mut g3 = producer.create_group(Group { sequence: 3 }).unwrap();
+		let mut g3 = producer.create_group(Group::new(3)).unwrap();
 		g3.write_frame(bytes::Bytes::from_static(b"skip-me")).unwrap();
 		g3.finish().unwrap();
 
-		let mut g5 = producer.create_group(Group { sequence: 5 }).unwrap();
+		let mut g5 = producer.create_group(Group::new(5)).unwrap();
 		g5.write_frame(bytes::Bytes::from_static(b"keep")).unwrap();
 		g5.finish().unwrap();
 
@@ -1285,7 +1456,7 @@ mod test {
 	#[tokio::test]
 	async fn get_group_finishes_without_waiting_for_gaps() {
 		let mut producer = Track::new("test").produce();
-		producer.create_group(Group { sequence: 1 }).unwrap();
+		producer.create_group(Group::new(1)).unwrap();
 		producer.finish_at(1).unwrap();
 
 		let consumer = producer.consume();
@@ -1315,4 +1486,28 @@ mod test {
 
 		assert!(matches!(producer.append_group(), Err(Error::BoundsExceeded(_))));
 	}
+
+	#[test]
+	fn health_tracks_backlog_and_resets() {
+		let mut producer = Track::new("test").produce();
+		let consumer = producer.consume();
+		assert_eq!(consumer.health(), Health::Healthy);
+
+		// The newest group in flight is normal.
+		let mut first = producer.append_group().unwrap();
+		assert_eq!(consumer.health(), Health::Healthy);
+
+		// A second one opening before the first finishes is a backlog forming.
+		let mut second = producer.append_group().unwrap();
+		assert_eq!(consumer.health(), Health::Degrading);
+		let _third = producer.append_group().unwrap();
+		assert_eq!(consumer.health(), Health::Starved);
+
+		first.finish().unwrap();
+		assert_eq!(consumer.health(), Health::Degrading);
+
+		// A reset stream still counts against the track while its group is cached.
+		second.abort(Error::Cancel).unwrap();
+		assert_eq!(consumer.health(), Health::Degrading);
+	}
 }
@@ -0,0 +1,177 @@
+use std::{
+	collections::BinaryHeap,
+	sync::{Arc, Mutex},
+};
+
+use tokio::sync::oneshot;
+
+use crate::Error;
+
+/// Opens uni streams one at a time per session, highest priority first.
+///
+/// `open_uni` blocks once the peer's stream limit is reached, and whichever caller got
+/// there first would otherwise win the next credit. Queuing the callers here means a
+/// high-priority group gets its stream before a low-priority one that merely arrived
+/// earlier. Priority is `(track, group)`, both higher first, matching stream priority.
+#[derive(Clone, Default)]
+pub(crate) struct Opener {
+	state: Arc<Mutex<State>>,
+}
+
+#[derive(Default)]
+struct State {
+	busy: bool,
+	waiting: BinaryHeap<Waiting>,
+	// Breaks ties between equal priorities in arrival order.
+	count: u64,
+}
+
+struct Waiting {
+	priority: (u8, u64),
+	order: u64,
+	turn: oneshot::Sender<Turn>,
+}
+
+impl Opener {
+	/// Wait for our turn, then open a uni stream.
+	pub async fn open<S: web_transport_trait::Session>(
+		&self,
+		session: &S,
+		priority: (u8, u64),
+	) -> Result<S::SendStream, Error> {
+		let _turn = self.turn(priority).await;
+		session.open_uni().await.map_err(Error::from_transport)
+	}
+
+	async fn turn(&self, priority: (u8, u64)) -> Turn {
+		let rx = {
+			let mut state = self.state.lock().unwrap();
+			if !state.busy {
+				state.busy = true;
+				return Turn::new(self.state.clone());
+			}
+
+			let (tx, rx) = oneshot::channel();
+			let order = state.count;
+			state.count += 1;
+			state.waiting.push(Waiting {
+				priority,
+				order,
+				turn: tx,
+			});
+			rx
+		};
+
+		// The sender is only dropped along with the opener, which we hold a reference to.
+		rx.await.expect("opener dropped")
+	}
+}
+
+/// The right to open the next stream, passed on to the best waiter when dropped.
+struct Turn {
+	// None once handed over (or refused by a cancelled waiter), so drop is a no-op.
+	state: Option<Arc<Mutex<State>>>,
+}
+
+impl Turn {
+	fn new(state: Arc<Mutex<State>>) -> Self {
+		Self { state: Some(state) }
+	}
+}
+
+impl Drop for Turn {
+	fn drop(&mut self) {
+		let Some(state) = self.state.take() else { return };
+
+		loop {
+			let mut guard = state.lock().unwrap();
+			let Some(next) = guard.waiting.pop() else {
+				guard.busy = false;
+				return;
+			};
+			drop(guard);
+
+			// A waiter that was cancelled refuses the turn; try the next one.
+			match next.turn.send(Turn::new(state.clone())) {
+				Ok(()) => return,
+				Err(mut refused) => refused.state = None,
+			}
+		}
+	}
+}
+
+impl PartialEq for Waiting {
+	fn eq(&self, other: &Self) -> bool {
+		self.cmp(other).is_eq()
+	}
+}
+
+impl Eq for Waiting {}
+
+impl PartialOrd for Waiting {
+	fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+		Some(self.cmp(other))
+	}
+}
+
+impl Ord for Waiting {
+	fn cmp(&self, other: &Self) -> std::cmp::Ordering {
+		// Max-heap: the highest priority pops first, then the earliest arrival.
+		self.priority
+			.cmp(&other.priority)
+			.then_with(|| other.order.cmp(&self.order))
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	use futures::FutureExt;
+
+	#[tokio::test]
+	async fn highest_priority_goes_next() {
+		let opener = Opener::default();
+		let first = opener.turn((0, 0)).await;
+
+		let mut low = Box::pin(opener.turn((1, 5)));
+		let mut high = Box::pin(opener.turn((2, 1)));
+		let mut newer = Box::pin(opener.turn((1, 6)));
+		assert!((&mut low).now_or_never().is_none());
+		assert!((&mut high).now_or_never().is_none());
+		assert!((&mut newer).now_or_never().is_none());
+
+		drop(first);
+		let turn = (&mut high).now_or_never().expect("high priority should go next");
+		assert!((&mut low).now_or_never().is_none());
+		assert!((&mut newer).now_or_never().is_none());
+
+		drop(turn);
+		let turn = (&mut newer).now_or_never().expect("newer group should go next");
+		assert!((&mut low).now_or_never().is_none());
+
+		drop(turn);
+		assert!((&mut low).now_or_never().is_some());
+	}
+
+	#[tokio::test]
+	async fn cancelled_waiter_passes_the_turn() {
+		let opener = Opener::default();
+		let first = opener.turn((0, 0)).await;
+
+		let mut cancelled = Box::pin(opener.turn((9, 0)));
+		let mut waiting = Box::pin(opener.turn((1, 0)));
+		assert!((&mut cancelled).now_or_never().is_none());
+		assert!((&mut waiting).now_or_never().is_none());
+		drop(cancelled);
+
+		drop(first);
+		let turn = (&mut waiting)
+			.now_or_never()
+			.expect("turn should skip the cancelled waiter");
+
+		// With nobody left waiting, the next caller goes straight through.
+		drop(turn);
+		assert!(opener.turn((0, 0)).now_or_never().is_some());
+	}
+}
@@ -0,0 +1,149 @@
+use std::{
+	collections::BTreeMap,
+	sync::{Arc, Mutex},
+	time::Duration,
+};
+
+use web_async::time::Instant;
+
+/// Egress caps in bytes per second for each published track, keyed by track priority.
+///
+/// A track is capped by the tier with the highest priority at or below its own, so
+/// `RateLimit::new(500_000).with_priority(128, 4_000_000)` holds tracks below priority
+/// 128 to 500 kB/s and lets the rest use 4 MB/s. A rate of zero leaves a tier uncapped,
+/// as does the default, which has no tiers at all.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct RateLimit {
+	tiers: BTreeMap<u8, u64>,
+}
+
+impl RateLimit {
+	/// Cap every track at `rate` bytes per second, until a higher tier overrides it.
+	pub fn new(rate: u64) -> Self {
+		Self::default().with_priority(0, rate)
+	}
+
+	/// Cap tracks with priority `priority` or higher at `rate` bytes per second.
+	pub fn with_priority(mut self, priority: u8, rate: u64) -> Self {
+		self.tiers.insert(priority, rate);
+		self
+	}
+
+	/// The cap for a track with the given priority, or `None` if it is uncapped.
+	pub fn rate(&self, priority: u8) -> Option<u64> {
+		let (_, rate) = self.tiers.range(..=priority).next_back()?;
+		(*rate > 0).then_some(*rate)
+	}
+}
+
+/// A token bucket capping the bytes per second written for one track.
+///
+/// Created per subscription at the rate [RateLimit] picks for the track's priority, and
+/// cloned into every group stream so the cap holds however many groups are in flight.
+/// The bucket holds up to one second of budget: a burst such as a keyframe goes out at
+/// once, as long as the average stays under the limit. Stream priority still decides
+/// which track's bytes go first within the budget.
+#[derive(Clone)]
+pub(crate) struct Pacer {
+	rate: u64,
+	state: Arc<Mutex<Bucket>>,
+}
+
+struct Bucket {
+	// Goes negative when a write overdraws the budget; the writer sleeps off the debt.
+	tokens: f64,
+	updated: Instant,
+}
+
+impl Pacer {
+	/// Create a pacer allowing `rate` bytes per second, which must be non-zero.
+	pub fn new(rate: u64) -> Self {
+		assert!(rate > 0, "pacer rate must be non-zero");
+
+		Self {
+			rate,
+			state: Arc::new(Mutex::new(Bucket {
+				tokens: rate as f64,
+				updated: Instant::now(),
+			})),
+		}
+	}
+
+	/// Spend `bytes` from the budget, waiting until the track is back under its limit.
+	pub async fn acquire(&self, bytes: u64) {
+		let wait = self.spend(bytes, Instant::now());
+		if !wait.is_zero() {
+			web_async::time::sleep(wait).await;
+		}
+	}
+
+	// Refill the bucket up to `now`, spend `bytes`, and return how long to wait.
+	fn spend(&self, bytes: u64, now: Instant) -> Duration {
+		let rate = self.rate as f64;
+		let mut bucket = self.state.lock().unwrap();
+
+		let elapsed = now.saturating_duration_since(bucket.updated).as_secs_f64();
+		bucket.tokens = (bucket.tokens + elapsed * rate).min(rate);
+		bucket.updated = now;
+		bucket.tokens -= bytes as f64;
+
+		if bucket.tokens >= 0.0 {
+			Duration::ZERO
+		} else {
+			Duration::from_secs_f64(-bucket.tokens / rate)
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn rate_by_priority() {
+		let limit = RateLimit::new(500).with_priority(128, 4000).with_priority(255, 0);
+
+		assert_eq!(limit.rate(0), Some(500));
+		assert_eq!(limit.rate(127), Some(500));
+		assert_eq!(limit.rate(128), Some(4000));
+		assert_eq!(limit.rate(254), Some(4000));
+		assert_eq!(limit.rate(255), None);
+
+		// Tracks below the lowest tier, and everything by default, are uncapped.
+		assert_eq!(RateLimit::default().with_priority(10, 100).rate(9), None);
+		assert_eq!(RateLimit::default().rate(0), None);
+	}
+
+	#[test]
+	fn burst_then_pace() {
+		let pacer = Pacer::new(1000);
+		let start = Instant::now();
+
+		// A full second of budget is available up front.
+		assert_eq!(pacer.spend(1000, start), Duration::ZERO);
+
+		// Anything more is paced at the configured rate.
+		assert_eq!(pacer.spend(500, start), Duration::from_millis(500));
+		assert_eq!(pacer.spend(500, start), Duration::from_secs(1));
+
+		// Debt is repaid over time, and idle time never banks more than one second.
+		assert_eq!(pacer.spend(0, start + Duration::from_secs(1)), Duration::ZERO);
+		assert_eq!(pacer.spend(1000, start + Duration::from_secs(10)), Duration::ZERO);
+		assert_eq!(
+			pacer.spend(100, start + Duration::from_secs(10)),
+			Duration::from_millis(100)
+		);
+	}
+
+	#[tokio::test(start_paused = true)]
+	async fn acquire_sleeps_off_debt() {
+		let pacer = Pacer::new(1000);
+		let start = tokio::time::Instant::now();
+
+		pacer.acquire(1000).await;
+		assert_eq!(start.elapsed(), Duration::ZERO);
+
+		pacer.acquire(250).await;
+		assert_eq!(start.elapsed(), Duration::from_millis(250));
+	}
+}
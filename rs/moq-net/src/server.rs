@@ -1,8 +1,11 @@
 use crate::{
 	ALPN_14, ALPN_15, ALPN_16, ALPN_17, ALPN_18, ALPN_19, ALPN_LITE, ALPN_LITE_03, ALPN_LITE_04, ALPN_LITE_05_WIP,
-	Error, NEGOTIATED, OriginConsumer, OriginProducer, Session, StatsHandle, Version, Versions,
+	Error, NEGOTIATED, OriginConsumer, OriginProducer, RateLimit, Session, StatsHandle, Version, Versions,
 	coding::{Decode, Encode, Stream},
-	ietf, lite, setup,
+	ietf,
+	limit::DEFAULT_MAX_STREAMS,
+	lite, setup,
+	window::DEFAULT_GROUP_WINDOW,
 };
 
 /// A MoQ server session builder.
@@ -12,6 +15,11 @@ pub struct Server {
 	consume: Option<OriginProducer>,
 	stats: StatsHandle,
 	versions: Versions,
+	rate_limit: RateLimit,
+	max_streams: Option<usize>,
+	group_window: Option<u64>,
+	max_latency: Option<std::time::Duration>,
+	wire_log: bool,
 }
 
 impl Server {
@@ -50,6 +58,87 @@ impl Server {
 		self
 	}
 
+	/// Cap the bytes per second written for each track this session publishes.
+	///
+	/// Each subscription gets its own token bucket, refilled at the rate `limit` picks
+	/// for the track's priority and holding up to one second of budget, so one
+	/// high-bitrate track can't starve the others on a shared relay. Uncapped by default.
+	pub fn with_rate_limit(mut self, limit: RateLimit) -> Self {
+		self.rate_limit = limit;
+		self
+	}
+
+	/// Cap the incoming group streams processed at once.
+	///
+	/// Further streams are left unaccepted until one finishes, so QUIC flow control
+	/// pushes back on the peer. Setup and control streams don't count. Defaults to 4096,
+	/// well above what multi-track playback needs; a `max` of zero removes the cap.
+	pub fn with_max_streams(mut self, max: usize) -> Self {
+		self.max_streams = Some(max);
+		self
+	}
+
+	/// Drop incoming groups that fall `size` or more behind the newest group of their track.
+	///
+	/// Stale groups are aborted with [`Error::Old`] rather than reassembled, so a consumer
+	/// that falls behind stays near the live edge. The default of `2` keeps the latest
+	/// two groups, like the publisher; a `size` of zero keeps every group.
+	pub fn with_group_window(mut self, size: u64) -> Self {
+		self.group_window = Some(size);
+		self
+	}
+
+	/// Ask publishers to drop a group once it is `latency` older than the newest group of its track.
+	///
+	/// Sent in every moq-lite SUBSCRIBE. The newest group is never dropped, however long it
+	/// runs, and zero, what older subscribers send, drops nothing. Defaults to ten seconds. Ignored by IETF
+	/// sessions and by moq-lite-01/02, which have no such field.
+	pub fn with_max_latency(mut self, latency: std::time::Duration) -> Self {
+		self.max_latency = Some(latency);
+		self
+	}
+
+	/// Log every IETF control message sent or received after SETUP as hex at TRACE level.
+	///
+	/// Covers the control stream on draft-14 to 16 and each request stream on draft-17+.
+	/// For diagnosing interop failures, where one malformed varint breaks the control stream.
+	/// Off by default, as it formats every message. Ignored by moq-lite.
+	pub fn with_wire_log(mut self, enabled: bool) -> Self {
+		self.wire_log = enabled;
+		self
+	}
+
+	fn into_ietf_config(self, version: ietf::Version, request_id_max: Option<ietf::RequestId>) -> ietf::StartConfig {
+		ietf::StartConfig {
+			version,
+			client: false,
+			request_id_max,
+			parameters: ietf::Parameters::default(),
+			publish: self.publish,
+			subscribe: self.consume,
+			stats: self.stats,
+			rate_limit: self.rate_limit,
+			max_streams: self.max_streams.unwrap_or(DEFAULT_MAX_STREAMS),
+			group_window: Some(self.group_window.unwrap_or(DEFAULT_GROUP_WINDOW)).filter(|&size| size > 0),
+			wire_log: self.wire_log,
+		}
+	}
+
+	fn into_lite_config(self, version: lite::Version) -> lite::StartConfig {
+		lite::StartConfig {
+			version,
+			publish: self.publish,
+			subscribe: self.consume,
+			stats: self.stats,
+			rate_limit: self.rate_limit,
+			max_streams: self.max_streams.unwrap_or(DEFAULT_MAX_STREAMS),
+			group_window: Some(self.group_window.unwrap_or(DEFAULT_GROUP_WINDOW)).filter(|&size| size > 0),
+			max_latency: self.max_latency.unwrap_or(lite::DEFAULT_MAX_LATENCY),
+			// A server never advertises a request path.
+			setup: lite::Setup::default(),
+		}
+	}
+
 	/// Perform the MoQ handshake as a server, returning the established [`Session`].
 	///
 	/// Convenience wrapper over [`accept_request`](Self::accept_request) that completes
@@ -182,9 +271,12 @@ impl Server {
 		let request_id_max = match version {
 			Version::Ietf(v) => {
 				let params = ietf::Parameters::decode(&mut client.parameters, v)?;
-				params
-					.get_varint(ietf::ParameterVarInt::MaxRequestId)
-					.map(ietf::RequestId)
+				tracing::debug!(
+					implementation = ?params.implementation(),
+					has_token = params.authorization_token().is_some(),
+					"client setup"
+				);
+				params.max_request_id()
 			}
 			Version::Lite(_) => None,
 		};
@@ -257,6 +349,31 @@ impl<S: web_transport_trait::Session> Request<S> {
 		self
 	}
 
+	/// Set the per-track egress caps, e.g. per tenant after inspecting [`path`](Self::path).
+	/// Overrides any value from the [`Server`] builder.
+	pub fn with_rate_limit(mut self, limit: RateLimit) -> Self {
+		self.server = self.server.with_rate_limit(limit);
+		self
+	}
+
+	/// Set the incoming stream cap. Overrides any value from the [`Server`] builder.
+	pub fn with_max_streams(mut self, max: usize) -> Self {
+		self.server = self.server.with_max_streams(max);
+		self
+	}
+
+	/// Set the max latency sent in SUBSCRIBE. Overrides any value from the [`Server`] builder.
+	pub fn with_max_latency(mut self, latency: std::time::Duration) -> Self {
+		self.server = self.server.with_max_latency(latency);
+		self
+	}
+
+	/// Set the incoming group window. Overrides any value from the [`Server`] builder.
+	pub fn with_group_window(mut self, size: u64) -> Self {
+		self.server = self.server.with_group_window(size);
+		self
+	}
+
 	/// Accept the session, completing the handshake.
 	pub async fn ok(self) -> Result<Session, Error> {
 		let server = self.server;
@@ -269,42 +386,16 @@ impl<S: web_transport_trait::Session> Request<S> {
 
 		let (session, mut stream, version, request_id_max) = match self.handshake {
 			Handshake::IetfModern { session, version } => {
-				ietf::start(
-					session.clone(),
-					None,
-					None,
-					false,
-					server.publish,
-					server.consume,
-					server.stats,
-					version,
-				)?;
+				let started = ietf::start(session.clone(), None, server.into_ietf_config(version, None))?;
 				tracing::debug!(?version, "connected");
-				return Ok(Session::new(session, version.into(), None));
+				return Ok(Session::new(session, version.into(), None).with_ietf(Some(started)));
 			}
 			Handshake::LiteBare { session, version } => {
-				let recv_bw = lite::start(
-					session.clone(),
-					None,
-					server.publish,
-					server.consume,
-					server.stats,
-					version,
-					lite::Setup::default(),
-				)?;
+				let recv_bw = lite::start(session.clone(), None, server.into_lite_config(version))?;
 				return Ok(Session::new(session, version.into(), recv_bw));
 			}
 			Handshake::Lite05 { session } => {
-				// A server never advertises a request path.
-				let recv_bw = lite::start(
-					session.clone(),
-					None,
-					server.publish,
-					server.consume,
-					server.stats,
-					lite::Version::Lite05Wip,
-					lite::Setup::default(),
-				)?;
+				let recv_bw = lite::start(session.clone(), None, server.into_lite_config(lite::Version::Lite05Wip))?;
 				return Ok(Session::new(session, lite::Version::Lite05Wip.into(), recv_bw));
 			}
 			Handshake::Legacy {
@@ -319,8 +410,8 @@ impl<S: web_transport_trait::Session> Request<S> {
 		let parameters = match version {
 			Version::Ietf(v) => {
 				let mut parameters = ietf::Parameters::default();
-				parameters.set_varint(ietf::ParameterVarInt::MaxRequestId, u32::MAX as u64);
-				parameters.set_bytes(ietf::ParameterBytes::Implementation, b"moq-lite-rs".to_vec());
+				parameters.set_max_request_id(ietf::RequestId(ietf::REQUEST_ID_WINDOW));
+				parameters.set_implementation("moq-lite-rs");
 				parameters.encode_bytes(v)?
 			}
 			Version::Lite(v) => lite::Parameters::default().encode_bytes(v)?,
@@ -332,37 +423,21 @@ impl<S: web_transport_trait::Session> Request<S> {
 		};
 		stream.writer.encode(&server_setup).await?;
 
-		let recv_bw = match version {
+		let (recv_bw, started) = match version {
 			Version::Lite(v) => {
 				let stream = stream.with_version(v);
-				// Pre-lite-05: no Setup stream, so nothing to advertise.
-				lite::start(
-					session.clone(),
-					Some(stream),
-					server.publish,
-					server.consume,
-					server.stats,
-					v,
-					lite::Setup::default(),
-				)?
+				let recv_bw = lite::start(session.clone(), Some(stream), server.into_lite_config(v))?;
+				(recv_bw, None)
 			}
 			Version::Ietf(v) => {
 				let stream = stream.with_version(v);
-				ietf::start(
-					session.clone(),
-					Some(stream),
-					request_id_max,
-					false,
-					server.publish,
-					server.consume,
-					server.stats,
-					v,
-				)?;
-				None
+				let config = server.into_ietf_config(v, request_id_max);
+				let started = ietf::start(session.clone(), Some(stream), config)?;
+				(None, Some(started))
 			}
 		};
 
-		Ok(Session::new(session, version, recv_bw))
+		Ok(Session::new(session, version, recv_bw).with_ietf(started))
 	}
 
 	/// Reject the session, closing the transport with `err`'s wire code.
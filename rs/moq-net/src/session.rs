@@ -3,19 +3,29 @@ use std::{sync::Arc, time::Duration};
 use web_async::MaybeSendBoxFuture;
 use web_transport_trait::Stats;
 
-use crate::{BandwidthConsumer, BandwidthProducer, Error, Version};
+use crate::{
+	AsPath, BandwidthConsumer, BandwidthProducer, Error, PathOwned, Track, TrackConsumer, TrackProducer, Version,
+};
 
 /// A MoQ transport session, wrapping a WebTransport connection.
 ///
 /// Created via:
 /// - [`crate::Client::connect`] for clients.
 /// - [`crate::Server::accept`] for servers.
+///
+/// There's no MoQ-level keepalive: an idle session is kept warm by the transport's own
+/// PINGs and a dead peer is caught by its idle timeout, both configured on the QUIC
+/// endpoint (e.g. `moq-native`'s `--client-quic-keep-alive` and `--client-quic-idle-timeout`).
 #[derive(Clone)]
 pub struct Session {
 	session: Arc<dyn SessionInner>,
 	version: Version,
 	send_bandwidth: Option<BandwidthConsumer>,
 	recv_bandwidth: Option<BandwidthConsumer>,
+	// Half-closes the control stream, for versions that have one.
+	finish: Option<kio::Producer<()>>,
+	// Requests outside of any subscription, for versions that have them.
+	query: Option<Arc<dyn Query>>,
 	closed: bool,
 }
 
@@ -45,11 +55,25 @@ impl Session {
 			version,
 			send_bandwidth,
 			recv_bandwidth,
+			finish: None,
+			query: None,
 			closed: false,
 		}
 	}
 
+	pub(super) fn with_ietf(mut self, started: Option<crate::ietf::Started>) -> Self {
+		if let Some(started) = started {
+			self.finish = Some(started.finish);
+			self.query = Some(started.query);
+		}
+		self
+	}
+
 	/// Returns the negotiated protocol version.
+	///
+	/// This is the outcome of negotiation, by ALPN or the SETUP exchange, so gate
+	/// draft-specific behavior on it (e.g. [`Version::is_lite`]). The SETUP parameters
+	/// carry no extensions to agree on, so there's nothing else to query.
 	pub fn version(&self) -> Version {
 		self.version
 	}
@@ -77,10 +101,110 @@ impl Session {
 		self.session.close(err.to_code(), err.to_string().as_ref());
 	}
 
+	/// Finish sending on the control stream while still receiving on it.
+	///
+	/// With IETF draft-14 through 16, control messages share one bidirectional stream. This
+	/// writes the ones already queued, then finishes the send side without resetting it, so
+	/// the peer's final PUBLISH_DONE or SUBSCRIBE_ERROR still arrive. Requests that try to
+	/// write afterwards fail instead of being dropped. Wait on [`Self::closed`] for the peer
+	/// to close the session.
+	///
+	/// Newer drafts and moq-lite give each request its own stream, so there is nothing to
+	/// finish and this does nothing.
+	pub fn finish(&mut self) {
+		if let Some(finish) = &self.finish {
+			// Finishing twice is a no-op.
+			let _ = finish.close();
+		}
+	}
+
+	/// Ask the peer about a track without subscribing to it.
+	///
+	/// Sends an IETF TRACK_STATUS. moq-lite has no equivalent and fails with
+	/// [`Error::Unsupported`].
+	pub async fn track_status(&self, broadcast: impl AsPath, track: &str) -> Result<TrackStatus, Error> {
+		let query = self.query.as_ref().ok_or(Error::Unsupported)?;
+		query
+			.track_status(broadcast.as_path().to_owned(), track.to_string())
+			.await
+	}
+
+	/// Subscribe to `track` of a broadcast the peer announced, without receiving any groups yet.
+	///
+	/// Sends an IETF SUBSCRIBE with forward=0: the publisher sets up the subscription but opens
+	/// no group streams. Each change made through the returned [`Armed`] sends a
+	/// SUBSCRIBE_UPDATE, so starting to forward when playback starts skips the SUBSCRIBE
+	/// round-trip on a channel change. moq-lite has no equivalent and fails with
+	/// [`Error::Unsupported`].
+	pub fn subscribe_armed(&self, broadcast: impl AsPath, track: &Track) -> Result<(TrackConsumer, Armed), Error> {
+		let query = self.query.as_ref().ok_or(Error::Unsupported)?;
+		let producer = track.clone().produce();
+		let consumer = producer.consume();
+		let forward = kio::Producer::new(false);
+		query.subscribe_armed(broadcast.as_path().to_owned(), producer, forward.consume())?;
+		Ok((consumer, Armed { forward }))
+	}
+
+	/// Subscribe to `track` of a broadcast the peer announced, starting at a (group, object).
+	///
+	/// Sends an IETF SUBSCRIBE with an AbsoluteStart filter: the publisher skips every group
+	/// before `start.0` and the objects before `start.1` within it, so playback can begin at a
+	/// keyframe in the middle of a group. The skipped objects show up as empty frames so later
+	/// frames keep their object IDs. moq-lite has no equivalent and fails with
+	/// [`Error::Unsupported`].
+	pub fn subscribe_from(
+		&self,
+		broadcast: impl AsPath,
+		track: &Track,
+		start: (u64, u64),
+	) -> Result<TrackConsumer, Error> {
+		let query = self.query.as_ref().ok_or(Error::Unsupported)?;
+		let producer = track.clone().produce();
+		let consumer = producer.consume();
+		query.subscribe_from(broadcast.as_path().to_owned(), producer, start)?;
+		Ok(consumer)
+	}
+
 	/// Block until the transport session is closed.
+	///
+	/// Always returns [`Error::Transport`], carrying the transport's description of why the
+	/// connection ended (e.g. an idle timeout, or the peer's close code and reason). Use
+	/// [`Self::close_reason`] to tell those apart.
 	pub async fn closed(&self) -> Result<(), Error> {
-		let err = self.session.closed().await;
-		Err(Error::Transport(err))
+		let reason = self.close_reason().await;
+		Err(Error::Transport(reason.to_string()))
+	}
+
+	/// Block until the transport session is closed, returning why.
+	///
+	/// A close by either side, clean or not, is [`CloseReason::Closed`] with its code and
+	/// reason. A connection that failed underneath, e.g. on an idle timeout, is
+	/// [`CloseReason::Transport`].
+	pub async fn close_reason(&self) -> CloseReason {
+		self.session.closed().await
+	}
+}
+
+/// Why a transport session ended, returned by [`Session::close_reason`].
+#[derive(thiserror::Error, Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum CloseReason {
+	/// Either side closed the session with an application code, 0 for a clean close.
+	#[error("closed: code={code} reason={reason}")]
+	Closed { code: u32, reason: String },
+
+	/// The connection ended without a close, e.g. an idle timeout, a reset or a TLS failure.
+	#[error("{0}")]
+	Transport(String),
+}
+
+impl CloseReason {
+	/// Classify the error a transport session ended with.
+	pub(crate) fn from_transport(err: impl web_transport_trait::Error) -> Self {
+		match err.session_error() {
+			Some((code, reason)) => Self::Closed { code, reason },
+			None => Self::Transport(err.to_string()),
+		}
 	}
 }
 
@@ -136,10 +260,60 @@ async fn run_send_bandwidth_inner<S: web_transport_trait::Session>(session: &S,
 	}
 }
 
+/// Toggles forwarding for a subscription made with [`Session::subscribe_armed`].
+///
+/// Dropping it keeps the subscription with whatever forwarding was last set.
+#[derive(Clone, Debug)]
+pub struct Armed {
+	forward: kio::Producer<bool>,
+}
+
+impl Armed {
+	/// Start or stop receiving groups, sending a SUBSCRIBE_UPDATE when it changes.
+	pub fn set_forward(&self, forward: bool) {
+		if let Ok(mut state) = self.forward.write()
+			&& *state != forward
+		{
+			*state = forward;
+		}
+	}
+}
+
+/// The peer's answer to [`Session::track_status`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TrackStatus {
+	/// The publisher has the track.
+	Active {
+		/// The newest (group, object), on drafts that report it.
+		largest: Option<(u64, u64)>,
+	},
+	/// The track was published but has ended.
+	Ended {
+		/// The final (group, object), on drafts that report it.
+		largest: Option<(u64, u64)>,
+	},
+	/// The publisher doesn't have the track.
+	NotFound,
+	/// The publisher rejected the request as unauthorized.
+	NotAuthorized,
+}
+
+// Requests outside of any subscription, with the protocol's generics erased.
+pub(crate) trait Query: web_transport_trait::MaybeSend + web_transport_trait::MaybeSync {
+	fn track_status(&self, broadcast: PathOwned, track: String) -> MaybeSendBoxFuture<'_, Result<TrackStatus, Error>>;
+	fn subscribe_armed(
+		&self,
+		broadcast: PathOwned,
+		track: TrackProducer,
+		forward: kio::Consumer<bool>,
+	) -> Result<(), Error>;
+	fn subscribe_from(&self, broadcast: PathOwned, track: TrackProducer, start: (u64, u64)) -> Result<(), Error>;
+}
+
 // We use a wrapper type that is dyn-compatible to remove the generic bounds from Session.
 trait SessionInner: web_transport_trait::MaybeSend + web_transport_trait::MaybeSync {
 	fn close(&self, code: u32, reason: &str);
-	fn closed(&self) -> MaybeSendBoxFuture<'_, String>;
+	fn closed(&self) -> MaybeSendBoxFuture<'_, CloseReason>;
 }
 
 impl<S: web_transport_trait::Session> SessionInner for S {
@@ -147,7 +321,7 @@ impl<S: web_transport_trait::Session> SessionInner for S {
 		S::close(self, code, reason);
 	}
 
-	fn closed(&self) -> MaybeSendBoxFuture<'_, String> {
-		Box::pin(async move { S::closed(self).await.to_string() })
+	fn closed(&self) -> MaybeSendBoxFuture<'_, CloseReason> {
+		Box::pin(async move { CloseReason::from_transport(S::closed(self).await) })
 	}
 }
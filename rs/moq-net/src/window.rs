@@ -0,0 +1,91 @@
+use std::task::Poll;
+
+use crate::Error;
+
+/// The default incoming group window, the same two groups the publisher keeps.
+pub(crate) const DEFAULT_GROUP_WINDOW: u64 = 2;
+
+/// The newest groups of one subscribed track, beyond which older groups are dropped.
+///
+/// A subscriber that falls behind (a slow decoder, a stalled render loop) would otherwise
+/// keep reassembling every group it was sent, drifting further from the live edge.
+/// Shared by every group stream of a subscription: a group whose sequence falls `size`
+/// or more behind the newest one received is aborted with [`Error::Old`].
+#[derive(Clone)]
+pub(crate) struct GroupWindow {
+	size: u64,
+	latest: kio::Producer<Option<u64>>,
+}
+
+impl GroupWindow {
+	/// Keep the latest `size` groups, which must be non-zero.
+	pub fn new(size: u64) -> Self {
+		assert!(size > 0, "group window must be non-zero");
+
+		Self {
+			size,
+			latest: kio::Producer::default(),
+		}
+	}
+
+	/// Record the arrival of a group, returning [`Error::Old`] if it is already outside the window.
+	pub fn arrive(&self, sequence: u64) -> Result<(), Error> {
+		if let Ok(mut latest) = self.latest.write()
+			&& latest.is_none_or(|latest| sequence > latest)
+		{
+			*latest = Some(sequence);
+		}
+
+		match self.is_old(*self.latest.read(), sequence) {
+			true => Err(Error::Old),
+			false => Ok(()),
+		}
+	}
+
+	/// Resolve once enough newer groups have arrived to push `sequence` out of the window.
+	pub async fn expired(&self, sequence: u64) {
+		let old = |latest: &kio::Ref<'_, Option<u64>>| match self.is_old(**latest, sequence) {
+			true => Poll::Ready(()),
+			false => Poll::Pending,
+		};
+		// The producer is held by self, so the channel can't close while we wait.
+		kio::wait(|waiter| self.latest.poll_ref(waiter, old).map(|_| ())).await;
+	}
+
+	fn is_old(&self, latest: Option<u64>, sequence: u64) -> bool {
+		latest.is_some_and(|latest| sequence.saturating_add(self.size) <= latest)
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	use futures::FutureExt;
+
+	#[test]
+	fn keeps_the_latest_groups() {
+		let window = GroupWindow::new(2);
+		assert!(window.arrive(5).is_ok());
+		assert!(window.arrive(6).is_ok());
+
+		// Late arrivals inside the window are still accepted.
+		assert!(window.arrive(5).is_ok());
+		assert!(matches!(window.arrive(4), Err(Error::Old)));
+	}
+
+	#[tokio::test]
+	async fn newer_groups_expire_older_ones() {
+		let window = GroupWindow::new(2);
+		window.arrive(1).unwrap();
+
+		let mut expired = Box::pin(window.expired(1));
+		assert!((&mut expired).now_or_never().is_none());
+
+		window.arrive(2).unwrap();
+		assert!((&mut expired).now_or_never().is_none());
+
+		window.arrive(3).unwrap();
+		assert!((&mut expired).now_or_never().is_some());
+	}
+}